@@ -0,0 +1,331 @@
+//! Application Load Balancer events, sent to functions registered as targets of a
+//! target group, and the responses functions return to the load balancer.
+//!
+//! Target groups send headers and query string parameters either with a single value
+//! each, or with all of their values when the `lambda.multi_value_headers.enabled`
+//! attribute is set. The response must use the same mode as the request, otherwise the
+//! load balancer drops its headers, so create responses with
+//! `AlbTargetGroupRequest::response_builder()`.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{apigw::body_bytes, encodings::deserialize_lambda_map};
+
+/// A request received from a load balancer.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbTargetGroupRequest {
+    /// The HTTP method of the request.
+    pub http_method: String,
+    /// The path of the request.
+    pub path: String,
+    /// The headers of the request, with lower-case names. Empty in multi-value mode.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// All values of the headers of the request. Only set in multi-value mode.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub multi_value_headers: HashMap<String, Vec<String>>,
+    /// The query string parameters of the request, as sent by the client: the load
+    /// balancer doesn't decode them. Empty in multi-value mode.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// All values of the query string parameters of the request, not decoded either.
+    /// Only set in multi-value mode.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub multi_value_query_string_parameters: HashMap<String, Vec<String>>,
+    /// Information about the load balancer.
+    pub request_context: AlbTargetGroupRequestContext,
+    /// The body of the request, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AlbTargetGroupRequest {
+    /// Returns whether the target group sends headers in multi-value mode.
+    pub fn is_multi_value(&self) -> bool {
+        !self.multi_value_headers.is_empty()
+    }
+
+    /// Returns the last value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.header_values(name).pop()
+    }
+
+    /// Returns all values of a header, ignoring the case of its name. In single-value
+    /// mode a header has at most one value.
+    pub fn header_values(&self, name: &str) -> Vec<&str> {
+        let multi = self
+            .multi_value_headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, values)| values.iter().map(String::as_str).collect());
+        multi.unwrap_or_else(|| {
+            self.headers
+                .iter()
+                .filter(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+                .collect()
+        })
+    }
+
+    /// Returns the body of the request, decoded from base64 if needed.
+    ///
+    /// # Return
+    /// The body, empty if the request has none, or an error if a base64 encoded body is
+    /// not valid base64.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        body_bytes(self.body.as_deref(), self.is_base64_encoded)
+    }
+
+    /// Returns a builder for the response to this request, that sends headers in the
+    /// same mode as the request.
+    pub fn response_builder(&self) -> AlbTargetGroupResponseBuilder {
+        AlbTargetGroupResponse::builder().multi_value(self.is_multi_value())
+    }
+}
+
+/// Information about the load balancer that sent a request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbTargetGroupRequestContext {
+    /// The load balancer.
+    pub elb: ElbContext,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The load balancer that sent a request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElbContext {
+    /// The ARN of the target group the function is registered with.
+    pub target_group_arn: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A response to a load balancer request.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbTargetGroupResponse {
+    /// The HTTP status code.
+    pub status_code: u16,
+    /// The status line of the response, for example `200 OK`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_description: Option<String>,
+    /// The headers of the response, in single-value mode.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// The headers of the response, in multi-value mode.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub multi_value_headers: HashMap<String, Vec<String>>,
+    /// The body of the response, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded, for binary responses.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for AlbTargetGroupResponse {
+    fn default() -> Self {
+        AlbTargetGroupResponse {
+            status_code: 200,
+            status_description: None,
+            headers: HashMap::new(),
+            multi_value_headers: HashMap::new(),
+            body: None,
+            is_base64_encoded: false,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+impl AlbTargetGroupResponse {
+    /// Returns a builder for a single-value mode response, with status code `200` and no
+    /// headers or body.
+    pub fn builder() -> AlbTargetGroupResponseBuilder {
+        AlbTargetGroupResponseBuilder::default()
+    }
+}
+
+/// Builder for `AlbTargetGroupResponse` objects, created with
+/// `AlbTargetGroupRequest::response_builder()` or `AlbTargetGroupResponse::builder()`.
+///
+/// ```rust
+/// use lambda_events::alb::AlbTargetGroupResponse;
+///
+/// let response = AlbTargetGroupResponse::builder()
+///     .multi_value(true)
+///     .status_code(200)
+///     .header("Set-Cookie", "a=1")
+///     .header("Set-Cookie", "b=2")
+///     .body("ok")
+///     .build();
+/// assert_eq!(response.status_description.as_deref(), Some("200 OK"));
+/// assert_eq!(response.multi_value_headers["Set-Cookie"], vec!["a=1", "b=2"]);
+/// assert!(response.headers.is_empty());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AlbTargetGroupResponseBuilder {
+    response: AlbTargetGroupResponse,
+    multi_value: bool,
+    headers: Vec<(String, String)>,
+}
+
+impl AlbTargetGroupResponseBuilder {
+    /// Sets whether the headers are sent in multi-value mode, which must match the mode
+    /// of the target group.
+    pub fn multi_value(mut self, multi_value: bool) -> Self {
+        self.multi_value = multi_value;
+        self
+    }
+
+    /// Sets the HTTP status code.
+    pub fn status_code(mut self, status_code: u16) -> Self {
+        self.response.status_code = status_code;
+        self
+    }
+
+    /// Sets the status line of the response. Defaults to the status code followed by
+    /// its reason phrase, when the code is a common one.
+    pub fn status_description(mut self, description: impl Into<String>) -> Self {
+        self.response.status_description = Some(description.into());
+        self
+    }
+
+    /// Adds a header. In single-value mode, only the last value of a header is sent.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets a text body.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.response.body = Some(body.into());
+        self.response.is_base64_encoded = false;
+        self
+    }
+
+    /// Sets a binary body, which is sent to the load balancer base64 encoded.
+    pub fn binary_body(mut self, body: impl AsRef<[u8]>) -> Self {
+        self.response.body = Some(base64::encode(body.as_ref()));
+        self.response.is_base64_encoded = true;
+        self
+    }
+
+    /// Returns the response, with the headers in the mode of the builder.
+    pub fn build(self) -> AlbTargetGroupResponse {
+        let mut response = self.response;
+        for (name, value) in self.headers {
+            if self.multi_value {
+                response.multi_value_headers.entry(name).or_default().push(value);
+            } else {
+                response.headers.insert(name, value);
+            }
+        }
+        if response.status_description.is_none() {
+            response.status_description =
+                reason_phrase(response.status_code).map(|reason| format!("{} {}", response.status_code, reason));
+        }
+        response
+    }
+}
+
+/// Returns the reason phrase of the common HTTP status codes.
+pub(crate) fn reason_phrase(status_code: u16) -> Option<&'static str> {
+    let reason = match status_code {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => return None,
+    };
+    Some(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_value_requests_get_single_value_responses() {
+        let input = include_str!("../tests/data/alb_request.json");
+        let request: AlbTargetGroupRequest = serde_json::from_str(input).expect("Could not parse request");
+        assert!(!request.is_multi_value());
+        assert_eq!(
+            request.header("Host"),
+            Some("lambda-846800462-us-east-2.elb.amazonaws.com")
+        );
+        assert!(request
+            .request_context
+            .elb
+            .target_group_arn
+            .ends_with("6d0ecf831eec9f09"));
+
+        let response = request
+            .response_builder()
+            .status_code(404)
+            .header("Content-Type", "text/plain")
+            .build();
+        let expected = serde_json::json!({
+            "statusCode": 404,
+            "statusDescription": "404 Not Found",
+            "headers": {"Content-Type": "text/plain"},
+            "isBase64Encoded": false,
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+    }
+
+    #[test]
+    fn multi_value_requests_get_multi_value_responses() {
+        let input = include_str!("../tests/data/alb_multi_value_request.json");
+        let request: AlbTargetGroupRequest = serde_json::from_str(input).expect("Could not parse request");
+        assert!(request.is_multi_value());
+        assert_eq!(request.header_values("Cookie"), vec!["name1=value1", "name2=value2"]);
+        assert_eq!(
+            request.multi_value_query_string_parameters["myKey"],
+            vec!["val1", "val2"]
+        );
+        assert_eq!(request.body_bytes().unwrap(), b"request_body");
+
+        let response = request.response_builder().header("Content-Type", "text/plain").build();
+        assert!(response.headers.is_empty());
+        assert_eq!(response.multi_value_headers["Content-Type"], vec!["text/plain"]);
+    }
+}
@@ -0,0 +1,647 @@
+//! Alexa Skills Kit events, sent to the functions that implement custom skills, and
+//! the responses that tell the device what to say and show.
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::encodings::deserialize_lambda_map;
+
+/// A request sent to a skill.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::alexa::{AlexaRequestEnvelope, AlexaRequestType, AlexaResponse};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: AlexaRequestEnvelope, _ctx: Context) -> Result<AlexaResponse, HandlerError> {
+///     Ok(match e.request.request_type {
+///         AlexaRequestType::LaunchRequest => {
+///             AlexaResponse::speak("Which sign are you?").reprompt("Tell me your sign.")
+///         }
+///         AlexaRequestType::IntentRequest(ref intent) => match intent.intent.slot_value("Sign") {
+///             Some(sign) => AlexaResponse::speak(format!("Good things are coming, {}.", sign)),
+///             None => AlexaResponse::speak("I didn't get your sign.").reprompt("Which sign are you?"),
+///         },
+///         _ => AlexaResponse::default(),
+///     })
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaRequestEnvelope {
+    /// The version of the request format, for example `1.0`.
+    pub version: String,
+    /// The session, `None` for requests outside of sessions like `AudioPlayer` events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<AlexaSession>,
+    /// The state of the device and of the skill.
+    pub context: AlexaContext,
+    /// The request.
+    pub request: AlexaRequest,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AlexaRequestEnvelope {
+    /// Returns the value of a session attribute.
+    pub fn session_attribute(&self, name: &str) -> Option<&Value> {
+        self.session.as_ref()?.attributes.get(name)
+    }
+}
+
+/// A session, the conversation between a user and a skill.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaSession {
+    /// Whether the request starts the session.
+    pub new: bool,
+    /// The id of the session.
+    pub session_id: String,
+    /// The skill.
+    pub application: AlexaApplication,
+    /// The attributes the skill returned in its previous response.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub attributes: HashMap<String, Value>,
+    /// The user.
+    pub user: AlexaUser,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A skill.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaApplication {
+    /// The id of the skill.
+    pub application_id: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The Amazon account that uses a skill.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaUser {
+    /// The id of the user, unique per skill.
+    pub user_id: String,
+    /// The token of the account the user linked to the skill, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The state of the device and of the skill.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AlexaContext {
+    /// The device and the Alexa APIs.
+    #[serde(rename = "System")]
+    pub system: AlexaSystem,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The device and the Alexa APIs.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaSystem {
+    /// The skill.
+    pub application: AlexaApplication,
+    /// The user.
+    pub user: AlexaUser,
+    /// The device.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<AlexaDevice>,
+    /// The URL of the Alexa APIs, which depends on the region of the user.
+    #[serde(default)]
+    pub api_endpoint: String,
+    /// The token that authorizes calls to the Alexa APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_access_token: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The device that sent a request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaDevice {
+    /// The id of the device.
+    pub device_id: String,
+    /// The interfaces the device supports, such as `AudioPlayer` or `Display`.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub supported_interfaces: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A request, with the fields that depend on its type in `request_type`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaRequest {
+    /// The id of the request.
+    pub request_id: String,
+    /// The time the request was sent, which skills should check to reject old requests.
+    pub timestamp: DateTime<Utc>,
+    /// The locale of the user, for example `en-US`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// The type of the request and its fields.
+    #[serde(flatten)]
+    pub request_type: AlexaRequestType,
+}
+
+/// The type of a request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum AlexaRequestType {
+    /// The user opened the skill without asking for anything.
+    #[default]
+    LaunchRequest,
+    /// The user asked for something.
+    IntentRequest(IntentRequest),
+    /// The session ended, for reasons other than the skill ending it.
+    SessionEndedRequest(SessionEndedRequest),
+    /// A type of request this crate doesn't support yet, such as `AudioPlayer` and
+    /// `Display` events.
+    #[serde(other)]
+    Other,
+}
+
+/// The fields of an `IntentRequest`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentRequest {
+    /// `STARTED`, `IN_PROGRESS`, or `COMPLETED`, for intents with a dialog model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialog_state: Option<String>,
+    /// The intent.
+    pub intent: AlexaIntent,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// What a user asked for.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaIntent {
+    /// The name of the intent.
+    pub name: String,
+    /// `NONE`, `CONFIRMED`, or `DENIED`.
+    #[serde(default)]
+    pub confirmation_status: String,
+    /// The slots of the intent.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub slots: HashMap<String, AlexaSlot>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AlexaIntent {
+    /// Returns the value of a slot, resolved to the value of the slot type if it
+    /// matched one, or as the user said it otherwise.
+    pub fn slot_value(&self, name: &str) -> Option<&str> {
+        let slot = self.slots.get(name)?;
+        slot.resolved_value().or(slot.value.as_deref())
+    }
+}
+
+/// A slot of an intent.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaSlot {
+    /// The name of the slot.
+    pub name: String,
+    /// The value as the user said it, `None` if the user didn't fill the slot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// `NONE`, `CONFIRMED`, or `DENIED`.
+    #[serde(default)]
+    pub confirmation_status: String,
+    /// The values of the slot type the value resolved to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolutions: Option<AlexaResolutions>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AlexaSlot {
+    /// Returns the name of the first value of the slot type the value matched.
+    pub fn resolved_value(&self) -> Option<&str> {
+        self.resolutions
+            .as_ref()?
+            .resolutions_per_authority
+            .iter()
+            .filter(|r| r.status.code == "ER_SUCCESS_MATCH")
+            .flat_map(|r| r.values.first())
+            .map(|v| v.value.name.as_str())
+            .next()
+    }
+}
+
+/// The values a slot resolved to.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaResolutions {
+    /// The resolutions, one per slot type or dynamic entity source.
+    #[serde(default)]
+    pub resolutions_per_authority: Vec<AlexaResolution>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The values a slot resolved to in one source of values.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaResolution {
+    /// The source of the values.
+    pub authority: String,
+    /// Whether the slot matched a value.
+    pub status: AlexaResolutionStatus,
+    /// The values the slot matched.
+    #[serde(default)]
+    pub values: Vec<AlexaResolutionValueWrapper>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Whether a slot matched a value.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AlexaResolutionStatus {
+    /// `ER_SUCCESS_MATCH`, `ER_SUCCESS_NO_MATCH`, `ER_ERROR_TIMEOUT`, or
+    /// `ER_ERROR_EXCEPTION`.
+    pub code: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A value a slot matched.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AlexaResolutionValueWrapper {
+    /// The value.
+    pub value: AlexaResolutionValue,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A value of a slot type.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AlexaResolutionValue {
+    /// The name of the value.
+    pub name: String,
+    /// The id of the value.
+    #[serde(default)]
+    pub id: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The fields of a `SessionEndedRequest`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEndedRequest {
+    /// `USER_INITIATED`, `ERROR`, or `EXCEEDED_MAX_REPROMPTS`.
+    pub reason: String,
+    /// The error, when the reason is `ERROR`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<AlexaError>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An error that ended a session.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AlexaError {
+    /// The type of the error, for example `INVALID_RESPONSE`.
+    #[serde(rename = "type")]
+    pub error_type: String,
+    /// The description of the error.
+    pub message: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The response of a skill.
+///
+/// ```rust
+/// use lambda_events::alexa::{AlexaCard, AlexaResponse};
+///
+/// let response = AlexaResponse::speak("Good things are coming.")
+///     .card(AlexaCard::Simple {
+///         title: String::from("Horoscope"),
+///         content: String::from("Good things are coming."),
+///     })
+///     .session_attribute("lastSign", "virgo");
+/// assert_eq!(response.response.should_end_session, Some(true));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaResponse {
+    /// The version of the response format.
+    pub version: String,
+    /// The attributes the next request of the session will have.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub session_attributes: HashMap<String, Value>,
+    /// What the device says and shows.
+    pub response: AlexaResponseBody,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for AlexaResponse {
+    fn default() -> Self {
+        AlexaResponse {
+            version: String::from("1.0"),
+            session_attributes: HashMap::new(),
+            response: AlexaResponseBody::default(),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+impl AlexaResponse {
+    /// Returns a response that says a text and ends the session.
+    pub fn speak(text: impl Into<String>) -> Self {
+        AlexaResponse::with_speech(AlexaOutputSpeech::PlainText { text: text.into() })
+    }
+
+    /// Returns a response that says an SSML document and ends the session.
+    pub fn speak_ssml(ssml: impl Into<String>) -> Self {
+        AlexaResponse::with_speech(AlexaOutputSpeech::Ssml { ssml: ssml.into() })
+    }
+
+    fn with_speech(speech: AlexaOutputSpeech) -> Self {
+        let mut response = AlexaResponse::default();
+        response.response.output_speech = Some(speech);
+        response.response.should_end_session = Some(true);
+        response
+    }
+
+    /// Sets the text said when the user doesn't answer, and keeps the session open.
+    pub fn reprompt(mut self, text: impl Into<String>) -> Self {
+        self.response.reprompt = Some(AlexaReprompt {
+            output_speech: AlexaOutputSpeech::PlainText { text: text.into() },
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        });
+        self.response.should_end_session = Some(false);
+        self
+    }
+
+    /// Sets the card shown in the Alexa app.
+    pub fn card(mut self, card: AlexaCard) -> Self {
+        self.response.card = Some(card);
+        self
+    }
+
+    /// Sets a session attribute.
+    pub fn session_attribute(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.session_attributes.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets whether the session ends after the response.
+    pub fn end_session(mut self, end: bool) -> Self {
+        self.response.should_end_session = Some(end);
+        self
+    }
+}
+
+/// What a device says and shows.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaResponseBody {
+    /// What the device says.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_speech: Option<AlexaOutputSpeech>,
+    /// The card shown in the Alexa app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card: Option<AlexaCard>,
+    /// What the device says when the user doesn't answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reprompt: Option<AlexaReprompt>,
+    /// Whether the session ends after the response. `None` leaves the session open
+    /// without the microphone on, on devices with a screen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub should_end_session: Option<bool>,
+    /// The directives of the response, such as `AudioPlayer.Play`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directives: Vec<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// What a device says.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum AlexaOutputSpeech {
+    /// A text.
+    PlainText {
+        /// The text.
+        text: String,
+    },
+    /// An SSML document.
+    #[serde(rename = "SSML")]
+    Ssml {
+        /// The document, in a `<speak>` element.
+        ssml: String,
+    },
+}
+
+/// What a device says when the user doesn't answer.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaReprompt {
+    /// What the device says.
+    pub output_speech: AlexaOutputSpeech,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A card shown in the Alexa app.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum AlexaCard {
+    /// A card with a title and a text.
+    Simple {
+        /// The title.
+        title: String,
+        /// The text.
+        content: String,
+    },
+    /// A card with a title, a text, and an image.
+    Standard {
+        /// The title.
+        title: String,
+        /// The text.
+        text: String,
+        /// The image.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        image: Option<AlexaCardImage>,
+    },
+    /// A card that asks the user to link their account.
+    LinkAccount,
+    /// A card that asks the user for permissions.
+    AskForPermissionsConsent {
+        /// The permissions, for example `read::alexa:device:all:address`.
+        permissions: Vec<String>,
+    },
+}
+
+/// The image of a card.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlexaCardImage {
+    /// The URL of the image for small screens, 720x480 pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub small_image_url: Option<String>,
+    /// The URL of the image for large screens, 1200x800 pixels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub large_image_url: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_intent_requests() {
+        let input = include_str!("../tests/data/alexa_intent_request.json");
+        let event: AlexaRequestEnvelope = serde_json::from_str(input).expect("Could not parse Alexa request");
+        assert_eq!(event.session_attribute("visits"), Some(&Value::from(3)));
+        assert_eq!(event.context.system.api_endpoint, "https://api.amazonalexa.com");
+        match event.request.request_type {
+            AlexaRequestType::IntentRequest(ref request) => {
+                assert_eq!(request.intent.name, "GetHoroscope");
+                assert_eq!(request.intent.slots["Sign"].value.as_deref(), Some("virgin"));
+                assert_eq!(request.intent.slot_value("Sign"), Some("virgo"));
+                assert_eq!(request.intent.slot_value("Date"), None);
+            }
+            ref request => panic!("Expected an intent request, got {:?}", request),
+        }
+
+        let json = serde_json::to_string(&event).expect("Could not serialize Alexa request");
+        assert_eq!(serde_json::from_str::<AlexaRequestEnvelope>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn deserializes_other_requests() {
+        let request: AlexaRequest = serde_json::from_value(serde_json::json!({
+            "type": "SessionEndedRequest",
+            "requestId": "amzn1.echo-api.request.0000",
+            "timestamp": "2019-05-13T18:44:16Z",
+            "locale": "en-US",
+            "reason": "ERROR",
+            "error": {"type": "INVALID_RESPONSE", "message": "The response is invalid"}
+        }))
+        .unwrap();
+        match request.request_type {
+            AlexaRequestType::SessionEndedRequest(ref ended) => {
+                assert_eq!(ended.error.as_ref().unwrap().error_type, "INVALID_RESPONSE")
+            }
+            ref request => panic!("Expected a session ended request, got {:?}", request),
+        }
+
+        let request: AlexaRequest = serde_json::from_value(serde_json::json!({
+            "type": "AudioPlayer.PlaybackStarted",
+            "requestId": "amzn1.echo-api.request.0000",
+            "timestamp": "2019-05-13T18:44:16Z",
+            "token": "track-1",
+            "offsetInMilliseconds": 0
+        }))
+        .unwrap();
+        assert_eq!(request.request_type, AlexaRequestType::Other);
+    }
+
+    #[test]
+    fn serializes_responses() {
+        let response = AlexaResponse::speak("Which sign are you?")
+            .reprompt("Tell me your sign.")
+            .card(AlexaCard::Standard {
+                title: String::from("Horoscope"),
+                text: String::from("Which sign are you?"),
+                image: Some(AlexaCardImage {
+                    small_image_url: Some(String::from("https://example.com/small.png")),
+                    large_image_url: None,
+                    #[cfg(feature = "catch-all-fields")]
+                    other: Default::default(),
+                }),
+            })
+            .session_attribute("visits", 4);
+        let expected = serde_json::json!({
+            "version": "1.0",
+            "sessionAttributes": {"visits": 4},
+            "response": {
+                "outputSpeech": {"type": "PlainText", "text": "Which sign are you?"},
+                "card": {
+                    "type": "Standard",
+                    "title": "Horoscope",
+                    "text": "Which sign are you?",
+                    "image": {"smallImageUrl": "https://example.com/small.png"},
+                },
+                "reprompt": {"outputSpeech": {"type": "PlainText", "text": "Tell me your sign."}},
+                "shouldEndSession": false,
+            },
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+
+        let response = AlexaResponse::speak_ssml("<speak>Goodbye</speak>");
+        assert_eq!(
+            serde_json::to_value(&response.response.output_speech).unwrap(),
+            serde_json::json!({"type": "SSML", "ssml": "<speak>Goodbye</speak>"})
+        );
+    }
+}
@@ -0,0 +1,516 @@
+//! Amazon API Gateway REST API events, sent to functions behind a Lambda proxy
+//! integration, WebSocket API route events, and the responses functions return to
+//! API Gateway.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::encodings::{deserialize_lambda_map, MillisecondTimestamp};
+
+/// A request received by a REST API with a Lambda proxy integration.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayProxyRequest {
+    /// The resource path defined in API Gateway, for example `/{proxy+}`.
+    #[serde(default)]
+    pub resource: Option<String>,
+    /// The path of the request.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// The HTTP method of the request.
+    pub http_method: String,
+    /// The headers of the request. Repeated headers only keep their last value, see
+    /// `multi_value_headers`.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// All values of the headers of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub multi_value_headers: HashMap<String, Vec<String>>,
+    /// The query string parameters of the request. Repeated parameters only keep their
+    /// last value, see `multi_value_query_string_parameters`.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// All values of the query string parameters of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub multi_value_query_string_parameters: HashMap<String, Vec<String>>,
+    /// The values of the path parameters of the resource.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub path_parameters: HashMap<String, String>,
+    /// The variables of the deployment stage.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub stage_variables: HashMap<String, String>,
+    /// Information about the request added by API Gateway.
+    pub request_context: ApiGatewayProxyRequestContext,
+    /// The body of the request, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded, which API Gateway does for binary media types.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ApiGatewayProxyRequest {
+    /// Returns the value of a header, ignoring the case of its name. Repeated headers
+    /// return their last value.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, &self.multi_value_headers, name)
+    }
+
+    /// Returns the body of the request, decoded from base64 if needed.
+    ///
+    /// # Return
+    /// The body, empty if the request has none, or an error if a base64 encoded body is
+    /// not valid base64.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        body_bytes(self.body.as_deref(), self.is_base64_encoded)
+    }
+}
+
+/// Information about a request added by API Gateway.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayProxyRequestContext {
+    /// The AWS account id owning the API.
+    pub account_id: String,
+    /// The id of the API.
+    pub api_id: String,
+    /// The id of the resource.
+    pub resource_id: String,
+    /// The resource path defined in API Gateway.
+    pub resource_path: String,
+    /// The deployment stage of the API.
+    pub stage: String,
+    /// The id API Gateway assigned to the request.
+    pub request_id: String,
+    /// The HTTP method of the request.
+    pub http_method: String,
+    /// The full path of the request, including the stage.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// The protocol of the request, for example `HTTP/1.1`.
+    #[serde(default)]
+    pub protocol: Option<String>,
+    /// The domain name the request was sent to.
+    #[serde(default)]
+    pub domain_name: Option<String>,
+    /// The first label of the domain name.
+    #[serde(default)]
+    pub domain_prefix: Option<String>,
+    /// The name of the operation, if one is defined for the method.
+    #[serde(default)]
+    pub operation_name: Option<String>,
+    /// The time of the request in the CLF format, for example
+    /// `04/Mar/2020:19:15:17 +0000`.
+    #[serde(default)]
+    pub request_time: Option<String>,
+    /// The time of the request.
+    #[serde(default)]
+    pub request_time_epoch: Option<MillisecondTimestamp>,
+    /// The caller of the API.
+    #[serde(default)]
+    pub identity: ApiGatewayRequestIdentity,
+    /// The values returned by the authorizer of the method: the principal id, the
+    /// context map, and the claims of Cognito user pool authorizers.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub authorizer: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The caller of an API. Most values are only set for IAM or Cognito authorized methods.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayRequestIdentity {
+    /// The source IP address of the TCP connection the request was sent over.
+    #[serde(default)]
+    pub source_ip: Option<String>,
+    /// The user agent of the caller.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// The Cognito identity id of the caller.
+    #[serde(default)]
+    pub cognito_identity_id: Option<String>,
+    /// The Cognito identity pool id of the caller.
+    #[serde(default)]
+    pub cognito_identity_pool_id: Option<String>,
+    /// The Cognito authentication provider used by the caller.
+    #[serde(default)]
+    pub cognito_authentication_provider: Option<String>,
+    /// The Cognito authentication type used by the caller.
+    #[serde(default)]
+    pub cognito_authentication_type: Option<String>,
+    /// The AWS account id of the caller.
+    #[serde(default)]
+    pub account_id: Option<String>,
+    /// The principal identifier of the caller.
+    #[serde(default)]
+    pub caller: Option<String>,
+    /// The API key used by the caller.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// The id of the API key used by the caller.
+    #[serde(default)]
+    pub api_key_id: Option<String>,
+    /// The access key used to sign the request.
+    #[serde(default)]
+    pub access_key: Option<String>,
+    /// The principal identifier of the user.
+    #[serde(default)]
+    pub user: Option<String>,
+    /// The ARN of the user.
+    #[serde(default)]
+    pub user_arn: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A response to a REST API request with a Lambda proxy integration. Create it with
+/// `ApiGatewayProxyResponse::builder()`.
+///
+/// ```rust
+/// use lambda_events::apigw::ApiGatewayProxyResponse;
+///
+/// let response = ApiGatewayProxyResponse::builder()
+///     .status_code(201)
+///     .header("Content-Type", "application/json")
+///     .multi_value_header("Set-Cookie", "a=1")
+///     .multi_value_header("Set-Cookie", "b=2")
+///     .body(r#"{"created":true}"#)
+///     .build();
+/// assert_eq!(response.status_code, 201);
+/// assert_eq!(response.multi_value_headers["Set-Cookie"], vec!["a=1", "b=2"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayProxyResponse {
+    /// The HTTP status code.
+    pub status_code: u16,
+    /// The headers of the response.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Headers with more than one value. API Gateway merges them with `headers`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub multi_value_headers: HashMap<String, Vec<String>>,
+    /// The body of the response, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded, for binary responses.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for ApiGatewayProxyResponse {
+    fn default() -> Self {
+        ApiGatewayProxyResponse {
+            status_code: 200,
+            headers: HashMap::new(),
+            multi_value_headers: HashMap::new(),
+            body: None,
+            is_base64_encoded: false,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+impl ApiGatewayProxyResponse {
+    /// Returns a builder for a response, with status code `200` and no headers or body.
+    pub fn builder() -> ApiGatewayProxyResponseBuilder {
+        ApiGatewayProxyResponseBuilder::default()
+    }
+}
+
+/// Builder for `ApiGatewayProxyResponse` objects, created with
+/// `ApiGatewayProxyResponse::builder()`.
+#[derive(Debug, Default, Clone)]
+pub struct ApiGatewayProxyResponseBuilder {
+    response: ApiGatewayProxyResponse,
+}
+
+impl ApiGatewayProxyResponseBuilder {
+    /// Sets the HTTP status code.
+    pub fn status_code(mut self, status_code: u16) -> Self {
+        self.response.status_code = status_code;
+        self
+    }
+
+    /// Sets a header, replacing its previous value.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.response.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Adds a value to a header that can have more than one value, such as `Set-Cookie`.
+    pub fn multi_value_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.response
+            .multi_value_headers
+            .entry(name.into())
+            .or_default()
+            .push(value.into());
+        self
+    }
+
+    /// Sets a text body.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.response.body = Some(body.into());
+        self.response.is_base64_encoded = false;
+        self
+    }
+
+    /// Sets a binary body, which is sent to API Gateway base64 encoded. The media type of
+    /// the response must be configured as a binary media type of the API.
+    pub fn binary_body(mut self, body: impl AsRef<[u8]>) -> Self {
+        self.response.body = Some(base64::encode(body.as_ref()));
+        self.response.is_base64_encoded = true;
+        self
+    }
+
+    /// Returns the response.
+    pub fn build(self) -> ApiGatewayProxyResponse {
+        self.response
+    }
+}
+
+/// A request received by a route of a WebSocket API: a `$connect` or `$disconnect`
+/// of a client, or a message sent by a client. Functions respond with an
+/// `ApiGatewayProxyResponse`; for messages, its body is sent back to the client if the
+/// route has a route response.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayWebsocketProxyRequest {
+    /// The headers of the request. Only sent for `$connect` routes.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// All values of the headers of the request. Only sent for `$connect` routes.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub multi_value_headers: HashMap<String, Vec<String>>,
+    /// The query string parameters of the request. Only sent for `$connect` routes.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// All values of the query string parameters of the request. Only sent for
+    /// `$connect` routes.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub multi_value_query_string_parameters: HashMap<String, Vec<String>>,
+    /// The variables of the stage.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub stage_variables: HashMap<String, String>,
+    /// Information about the connection and the request added by API Gateway.
+    pub request_context: ApiGatewayWebsocketProxyRequestContext,
+    /// The message sent by the client, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded, for binary messages.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ApiGatewayWebsocketProxyRequest {
+    /// Returns the type of the event.
+    pub fn event_type(&self) -> WebsocketEventType {
+        self.request_context.event_type
+    }
+
+    /// Returns the id of the connection, used to send messages to the client with the
+    /// `@connections` API.
+    pub fn connection_id(&self) -> &str {
+        &self.request_context.connection_id
+    }
+
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, &self.multi_value_headers, name)
+    }
+
+    /// Returns the message sent by the client, decoded from base64 if needed.
+    ///
+    /// # Return
+    /// The message, empty for connection events, or an error if a base64 encoded
+    /// message is not valid base64.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        body_bytes(self.body.as_deref(), self.is_base64_encoded)
+    }
+}
+
+/// Information about the connection and the request of a WebSocket route event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayWebsocketProxyRequestContext {
+    /// The route that matched the event: `$connect`, `$disconnect`, `$default`, or the
+    /// route selected by the route selection expression of the API.
+    pub route_key: String,
+    /// The type of the event.
+    pub event_type: WebsocketEventType,
+    /// The id of the connection.
+    pub connection_id: String,
+    /// The time the connection was established.
+    pub connected_at: MillisecondTimestamp,
+    /// The id of the message, only set for messages.
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// The direction of the message, always `IN`.
+    #[serde(default)]
+    pub message_direction: Option<String>,
+    /// The close status code sent by the client, only set for `$disconnect` routes.
+    #[serde(default)]
+    pub disconnect_status_code: Option<u16>,
+    /// The close reason sent by the client, only set for `$disconnect` routes.
+    #[serde(default)]
+    pub disconnect_reason: Option<String>,
+    /// The id of the API.
+    pub api_id: String,
+    /// The stage of the API.
+    pub stage: String,
+    /// The domain name the client connected to.
+    pub domain_name: String,
+    /// The id API Gateway assigned to the request.
+    pub request_id: String,
+    /// The extended id API Gateway assigned to the request.
+    #[serde(default)]
+    pub extended_request_id: Option<String>,
+    /// The time of the request in the CLF format.
+    #[serde(default)]
+    pub request_time: Option<String>,
+    /// The time of the request.
+    pub request_time_epoch: MillisecondTimestamp,
+    /// The client.
+    #[serde(default)]
+    pub identity: ApiGatewayRequestIdentity,
+    /// The values returned by the authorizer of the `$connect` route.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub authorizer: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The type of a WebSocket route event.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WebsocketEventType {
+    /// A client is connecting. Responding with an error status code rejects the
+    /// connection.
+    Connect,
+    /// A client sent a message.
+    #[default]
+    Message,
+    /// A client disconnected, or the connection timed out.
+    Disconnect,
+}
+
+/// Looks up a header by name, ignoring case, in the single and multi-value header maps.
+pub(crate) fn header<'a>(
+    headers: &'a HashMap<String, String>,
+    multi_value_headers: &'a HashMap<String, Vec<String>>,
+    name: &str,
+) -> Option<&'a str> {
+    let single = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str());
+    single.or_else(|| {
+        multi_value_headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| v.last())
+            .map(String::as_str)
+    })
+}
+
+/// Returns the bytes of a request body that may be base64 encoded.
+pub(crate) fn body_bytes(body: Option<&str>, is_base64_encoded: bool) -> Result<Vec<u8>, base64::DecodeError> {
+    match body {
+        None => Ok(Vec::new()),
+        Some(body) if is_base64_encoded => base64::decode(body),
+        Some(body) => Ok(body.as_bytes().to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_proxy_request() {
+        let input = include_str!("../tests/data/apigw_proxy_request.json");
+        let request: ApiGatewayProxyRequest = serde_json::from_str(input).expect("Could not parse request");
+        assert_eq!(request.http_method, "POST");
+        assert_eq!(request.header("accept"), Some("*/*"));
+        assert_eq!(
+            request.multi_value_query_string_parameters["multivalueName"],
+            vec!["you", "me"]
+        );
+        assert_eq!(request.path_parameters["proxy"], "hello/world");
+        assert_eq!(request.body_bytes().unwrap(), b"{\r\n\t\"a\": 1\r\n}");
+
+        let context = &request.request_context;
+        assert_eq!(context.stage, "testStage");
+        assert_eq!(
+            context.request_time_epoch.unwrap().timestamp_millis(),
+            1_583_349_317_135
+        );
+        assert_eq!(context.identity.source_ip.as_deref(), Some("192.168.196.186"));
+        assert_eq!(context.identity.caller, None);
+        assert_eq!(context.authorizer["tenant"], "acme");
+
+        let json = serde_json::to_string(&request).expect("Could not serialize request");
+        assert_eq!(serde_json::from_str::<ApiGatewayProxyRequest>(&json).unwrap(), request);
+    }
+
+    #[test]
+    fn serializes_binary_response() {
+        let response = ApiGatewayProxyResponse::builder()
+            .header("Content-Type", "image/png")
+            .binary_body(b"png")
+            .build();
+        let expected = serde_json::json!({
+            "statusCode": 200,
+            "headers": {"Content-Type": "image/png"},
+            "body": "cG5n",
+            "isBase64Encoded": true,
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+    }
+
+    #[test]
+    fn deserializes_websocket_events() {
+        let input = include_str!("../tests/data/apigw_websocket_connect.json");
+        let connect: ApiGatewayWebsocketProxyRequest = serde_json::from_str(input).expect("Could not parse connect");
+        assert_eq!(connect.event_type(), WebsocketEventType::Connect);
+        assert_eq!(connect.request_context.route_key, "$connect");
+        assert_eq!(connect.header("sec-websocket-version"), Some("13"));
+        assert_eq!(connect.query_string_parameters["room"], "lobby");
+        assert!(connect.body_bytes().unwrap().is_empty());
+
+        let input = include_str!("../tests/data/apigw_websocket_message.json");
+        let message: ApiGatewayWebsocketProxyRequest = serde_json::from_str(input).expect("Could not parse message");
+        assert_eq!(message.event_type(), WebsocketEventType::Message);
+        assert_eq!(message.connection_id(), connect.connection_id());
+        assert_eq!(
+            message.request_context.connected_at,
+            connect.request_context.connected_at
+        );
+        assert_eq!(message.request_context.message_id.as_deref(), Some("Ja1rwe8ioAMCJ1A="));
+        assert_eq!(
+            message.body_bytes().unwrap(),
+            br#"{"action":"sendmessage","text":"hello"}"#
+        );
+    }
+}
@@ -0,0 +1,359 @@
+//! AWS AppSync Direct Lambda resolver events, sent to functions that resolve the fields
+//! of a GraphQL API without mapping templates.
+//!
+//! Resolvers with the `Invoke` operation receive one `AppSyncResolverEvent` and return
+//! the value of the field, or an error which AppSync adds to the `errors` of the GraphQL
+//! response. Resolvers with the `BatchInvoke` operation receive an `AppSyncBatchEvent`
+//! and return one `AppSyncBatchResult` per event, in the same order.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::encodings::{deserialize_lambda_map, deserialize_nullable};
+
+/// The resolution of a field, with the arguments of the field deserialized as `A` and
+/// the parent object as `S`.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::appsync::AppSyncResolverEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize)]
+/// struct GetPost {
+///     id: String,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Post {
+///     id: String,
+///     content: String,
+/// }
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: AppSyncResolverEvent<GetPost>, _ctx: Context) -> Result<Post, HandlerError> {
+///     Ok(Post {
+///         id: e.arguments.id,
+///         content: String::from("Hello"),
+///     })
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSyncResolverEvent<A = Value, S = Value> {
+    /// The arguments of the field.
+    pub arguments: A,
+    /// The caller, `None` for API keys.
+    #[serde(default)]
+    pub identity: Option<AppSyncIdentity>,
+    /// The parent object of the field, `None` for the fields of root types.
+    #[serde(default)]
+    pub source: Option<S>,
+    /// The HTTP request of the GraphQL operation.
+    pub request: AppSyncRequest,
+    /// The result of the previous function of a pipeline resolver.
+    #[serde(default)]
+    pub prev: Option<Value>,
+    /// The GraphQL operation.
+    pub info: AppSyncInfo,
+    /// The values the functions of a pipeline resolver stored.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub stash: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The events of a `BatchInvoke` resolver, one per object whose field is resolved.
+pub type AppSyncBatchEvent<A = Value, S = Value> = Vec<AppSyncResolverEvent<A, S>>;
+
+/// The caller of a GraphQL operation, which depends on the authorization mode of the
+/// API.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum AppSyncIdentity {
+    /// A caller authorized with IAM.
+    Iam(AppSyncIamIdentity),
+    /// A caller authorized with a Cognito user pool.
+    Cognito(AppSyncCognitoIdentity),
+    /// A caller authorized with an OpenID Connect provider.
+    Oidc(AppSyncOidcIdentity),
+    /// A caller authorized by a Lambda authorizer.
+    Lambda(AppSyncLambdaIdentity),
+}
+
+/// A caller authorized with IAM.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSyncIamIdentity {
+    /// The AWS account id of the caller.
+    pub account_id: String,
+    /// The ARN of the caller.
+    pub user_arn: String,
+    /// The id of the caller.
+    pub username: String,
+    /// The IP addresses of the caller and the proxies it went through.
+    #[serde(default)]
+    pub source_ip: Vec<String>,
+    /// The Cognito identity pool of the caller, for callers with Cognito credentials.
+    #[serde(default)]
+    pub cognito_identity_pool_id: Option<String>,
+    /// The Cognito identity of the caller, for callers with Cognito credentials.
+    #[serde(default)]
+    pub cognito_identity_id: Option<String>,
+    /// `authenticated` or `unauthenticated`, for callers with Cognito credentials.
+    #[serde(default)]
+    pub cognito_identity_auth_type: Option<String>,
+    /// The provider that authenticated the caller, for callers with Cognito credentials.
+    #[serde(default)]
+    pub cognito_identity_auth_provider: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A caller authorized with a Cognito user pool.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSyncCognitoIdentity {
+    /// The id of the user.
+    pub sub: String,
+    /// The URL of the user pool.
+    pub issuer: String,
+    /// The name of the user.
+    pub username: String,
+    /// The claims of the token of the user.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub claims: HashMap<String, Value>,
+    /// The IP addresses of the caller and the proxies it went through.
+    #[serde(default)]
+    pub source_ip: Vec<String>,
+    /// `ALLOW` or `DENY`, the default action of the user pool configuration.
+    #[serde(default)]
+    pub default_auth_strategy: Option<String>,
+    /// The groups of the user.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub groups: Vec<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A caller authorized with an OpenID Connect provider.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSyncOidcIdentity {
+    /// The subject of the token.
+    pub sub: String,
+    /// The issuer of the token.
+    pub issuer: String,
+    /// The claims of the token.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub claims: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A caller authorized by a Lambda authorizer.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSyncLambdaIdentity {
+    /// The context the authorizer returned.
+    #[serde(default)]
+    pub resolver_context: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The HTTP request of a GraphQL operation.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSyncRequest {
+    /// The headers of the request, with lower-case names.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// The custom domain name of the API, if the request used one.
+    #[serde(default)]
+    pub domain_name: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AppSyncRequest {
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// The GraphQL operation that resolves a field.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSyncInfo {
+    /// The name of the field.
+    pub field_name: String,
+    /// The type of the parent object of the field.
+    pub parent_type_name: String,
+    /// The fields selected in the field, as paths like `author/name`.
+    #[serde(default)]
+    pub selection_set_list: Vec<String>,
+    /// The selection set of the field, as GraphQL.
+    #[serde(rename = "selectionSetGraphQL", default)]
+    pub selection_set_graphql: String,
+    /// The variables of the operation.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub variables: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The result of one event of a `BatchInvoke` resolver: either the value of the field,
+/// or an error that AppSync reports for this field only.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSyncBatchResult<T = Value> {
+    /// The value of the field.
+    #[serde(default)]
+    pub data: Option<T>,
+    /// The message of the error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    /// The type of the error, shown in the `errorType` of the GraphQL error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<T> AppSyncBatchResult<T> {
+    /// Returns the result of a field that was resolved.
+    pub fn data(data: T) -> Self {
+        AppSyncBatchResult {
+            data: Some(data),
+            error_message: None,
+            error_type: None,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+
+    /// Returns the result of a field that could not be resolved.
+    pub fn error(error_type: impl Into<String>, error_message: impl Into<String>) -> Self {
+        AppSyncBatchResult {
+            data: None,
+            error_message: Some(error_message.into()),
+            error_type: Some(error_type.into()),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    struct GetPost {
+        id: String,
+    }
+
+    #[test]
+    fn deserializes_cognito_events() {
+        let input = include_str!("../tests/data/appsync_cognito_event.json");
+        let event: AppSyncResolverEvent<GetPost> = serde_json::from_str(input).expect("Could not parse event");
+        assert_eq!(event.arguments.id, "post-123");
+        assert_eq!(event.source, None);
+        assert_eq!(event.info.field_name, "getPost");
+        assert_eq!(event.info.selection_set_list, vec!["id", "author", "content"]);
+        assert_eq!(event.request.header("Content-Type"), Some("application/json"));
+        match event.identity {
+            Some(AppSyncIdentity::Cognito(ref identity)) => {
+                assert_eq!(identity.username, "jdoe");
+                assert_eq!(identity.groups, vec!["admins"]);
+                assert_eq!(identity.claims["email"], "jdoe@email.com");
+            }
+            ref identity => panic!("Expected a Cognito identity, got {:?}", identity),
+        }
+
+        let json = serde_json::to_string(&event).expect("Could not serialize event");
+        assert_eq!(
+            serde_json::from_str::<AppSyncResolverEvent<GetPost>>(&json).unwrap(),
+            event
+        );
+    }
+
+    #[test]
+    fn distinguishes_identities() {
+        let iam: AppSyncIdentity = serde_json::from_value(serde_json::json!({
+            "accountId": "123456789012",
+            "cognitoIdentityPoolId": null,
+            "cognitoIdentityId": null,
+            "sourceIp": ["1.1.1.1"],
+            "username": "AIDAJ4NZ3EXAMPLE",
+            "userArn": "arn:aws:iam::123456789012:user/jdoe",
+            "cognitoIdentityAuthType": null,
+            "cognitoIdentityAuthProvider": null
+        }))
+        .unwrap();
+        assert!(matches!(iam, AppSyncIdentity::Iam(ref i) if i.account_id == "123456789012"));
+
+        let oidc: AppSyncIdentity = serde_json::from_value(serde_json::json!({
+            "sub": "abc",
+            "issuer": "https://auth.example.com",
+            "claims": {"scope": "read"}
+        }))
+        .unwrap();
+        assert!(matches!(oidc, AppSyncIdentity::Oidc(ref i) if i.claims["scope"] == "read"));
+
+        let lambda: AppSyncIdentity = serde_json::from_value(serde_json::json!({
+            "resolverContext": {"tenant": "acme"}
+        }))
+        .unwrap();
+        assert!(matches!(lambda, AppSyncIdentity::Lambda(ref i) if i.resolver_context["tenant"] == "acme"));
+    }
+
+    #[test]
+    fn serializes_batch_results() {
+        let input = include_str!("../tests/data/appsync_cognito_event.json");
+        let event: AppSyncResolverEvent = serde_json::from_str(input).unwrap();
+        let batch: AppSyncBatchEvent = vec![event.clone(), event];
+        assert_eq!(batch.len(), 2);
+
+        let results = vec![
+            AppSyncBatchResult::data(serde_json::json!({"id": "post-123"})),
+            AppSyncBatchResult::error("NotFound", "Post post-456 not found"),
+        ];
+        assert_eq!(
+            serde_json::to_value(&results).unwrap(),
+            serde_json::json!([
+                {"data": {"id": "post-123"}},
+                {"data": null, "errorMessage": "Post post-456 not found", "errorType": "NotFound"},
+            ])
+        );
+    }
+}
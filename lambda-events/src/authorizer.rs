@@ -0,0 +1,424 @@
+//! Amazon API Gateway Lambda authorizer events, and the responses that allow or deny
+//! access to an API. Build IAM policy responses with `AuthPolicy`.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    apigw::ApiGatewayProxyRequestContext, encodings::deserialize_lambda_map, http_api::ApiGatewayV2HttpRequestContext,
+};
+
+/// The version of the IAM policy language used in authorizer responses.
+const POLICY_VERSION: &str = "2012-10-17";
+
+/// The action of IAM policy statements that allow or deny calls to an API.
+const INVOKE_ACTION: &str = "execute-api:Invoke";
+
+/// The input of a REST API `TOKEN` authorizer.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayTokenAuthorizerRequest {
+    /// Always `TOKEN`.
+    #[serde(rename = "type")]
+    pub authorizer_type: String,
+    /// The value of the token source of the authorizer, usually the `Authorization`
+    /// header.
+    pub authorization_token: String,
+    /// The ARN of the method being called.
+    pub method_arn: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The input of a REST API `REQUEST` authorizer: the request being authorized.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayRequestAuthorizerRequest {
+    /// Always `REQUEST`.
+    #[serde(rename = "type")]
+    pub authorizer_type: String,
+    /// The ARN of the method being called.
+    pub method_arn: String,
+    /// The resource path defined in API Gateway.
+    #[serde(default)]
+    pub resource: Option<String>,
+    /// The path of the request.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// The HTTP method of the request.
+    pub http_method: String,
+    /// The headers of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// All values of the headers of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub multi_value_headers: HashMap<String, Vec<String>>,
+    /// The query string parameters of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// All values of the query string parameters of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub multi_value_query_string_parameters: HashMap<String, Vec<String>>,
+    /// The values of the path parameters of the resource.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub path_parameters: HashMap<String, String>,
+    /// The variables of the deployment stage.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub stage_variables: HashMap<String, String>,
+    /// Information about the request added by API Gateway.
+    pub request_context: ApiGatewayProxyRequestContext,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The input of an HTTP API Lambda authorizer, in the version 2.0 payload format.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayV2AuthorizerRequest {
+    /// The version of the payload format, always `2.0`.
+    pub version: String,
+    /// Always `REQUEST`.
+    #[serde(rename = "type")]
+    pub authorizer_type: String,
+    /// The ARN of the route being called.
+    pub route_arn: String,
+    /// The values of the identity sources of the authorizer.
+    #[serde(default)]
+    pub identity_source: Vec<String>,
+    /// The route that matched the request.
+    pub route_key: String,
+    /// The path of the request, including the stage.
+    pub raw_path: String,
+    /// The query string of the request, without the `?`.
+    #[serde(default)]
+    pub raw_query_string: String,
+    /// The cookies of the request, as `name=value` strings.
+    #[serde(default)]
+    pub cookies: Vec<String>,
+    /// The headers of the request, with lower-case names.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// The query string parameters of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// The values of the path parameters of the route.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub path_parameters: HashMap<String, String>,
+    /// The variables of the stage.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub stage_variables: HashMap<String, String>,
+    /// Information about the request added by API Gateway.
+    pub request_context: ApiGatewayV2HttpRequestContext,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An authorizer response with an IAM policy, for REST APIs and for HTTP APIs that
+/// don't use simple responses.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayCustomAuthorizerResponse {
+    /// The id of the authorized principal.
+    pub principal_id: String,
+    /// The policy that allows or denies the calls of the principal.
+    pub policy_document: PolicyDocument,
+    /// Values passed to the integration in the `authorizer` field of the request context.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub context: HashMap<String, Value>,
+    /// The API key used for the usage plans of the API, when the API key source of the
+    /// API is `AUTHORIZER`. Only supported by REST APIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_identifier_key: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An authorizer response in the simple format of HTTP APIs.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayV2SimpleAuthorizerResponse {
+    /// Whether the request is allowed.
+    pub is_authorized: bool,
+    /// Values passed to the integration in the `authorizer.lambda` field of the request
+    /// context.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub context: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An IAM policy.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyDocument {
+    /// The version of the policy language.
+    pub version: String,
+    /// The statements of the policy.
+    pub statement: Vec<Statement>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A statement of an IAM policy.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Statement {
+    /// The actions the statement applies to.
+    pub action: Vec<String>,
+    /// Whether the statement allows or denies the actions.
+    pub effect: Effect,
+    /// The ARNs of the resources the statement applies to, such as API methods.
+    pub resource: Vec<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Whether a policy statement allows or denies its actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Effect {
+    /// The actions are allowed.
+    Allow,
+    /// The actions are denied. Denials take precedence over allowances.
+    Deny,
+}
+
+/// Builder for the IAM policy responses of authorizers. The policy is created for the
+/// API and stage of the method or route being authorized, and allows or denies calls to
+/// the methods of that stage.
+///
+/// API Gateway caches authorizer responses, by default for all methods of the API, so
+/// policies that only allow the method being called can deny later calls to other
+/// methods that reuse the cached response.
+///
+/// ```rust
+/// use lambda_events::authorizer::AuthPolicy;
+///
+/// let arn = "arn:aws:execute-api:us-east-1:123456789012:abcdef123/prod/GET/pets/42";
+/// let response = AuthPolicy::from_method_arn("user-1", arn)
+///     .expect("Invalid method ARN")
+///     .allow_method("GET", "/pets/*")
+///     .deny_method("*", "/admin/*")
+///     .context("tenant", "acme")
+///     .build();
+/// let statements = &response.policy_document.statement;
+/// assert_eq!(
+///     statements[0].resource,
+///     vec!["arn:aws:execute-api:us-east-1:123456789012:abcdef123/prod/GET/pets/*"]
+/// );
+/// assert_eq!(
+///     statements[1].resource,
+///     vec!["arn:aws:execute-api:us-east-1:123456789012:abcdef123/prod/*/admin/*"]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct AuthPolicy {
+    principal_id: String,
+    arn_prefix: String,
+    allowed: Vec<String>,
+    denied: Vec<String>,
+    context: HashMap<String, Value>,
+    usage_identifier_key: Option<String>,
+}
+
+impl AuthPolicy {
+    /// Creates an empty policy for the API and stage of a method or route ARN, such as
+    /// the `method_arn` of REST API authorizer requests or the `route_arn` of HTTP API
+    /// authorizer requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `principal_id` The id of the authorized principal.
+    /// * `method_arn` The ARN of the method being authorized.
+    ///
+    /// # Return
+    /// The policy, or `None` if `method_arn` is not the ARN of an API method.
+    pub fn from_method_arn(principal_id: impl Into<String>, method_arn: &str) -> Option<AuthPolicy> {
+        let mut parts = method_arn.splitn(6, ':');
+        let (arn, partition, service, region, account_id, resource) = (
+            parts.next()?,
+            parts.next()?,
+            parts.next()?,
+            parts.next()?,
+            parts.next()?,
+            parts.next()?,
+        );
+        if arn != "arn" || service != "execute-api" {
+            return None;
+        }
+        let mut resource = resource.splitn(3, '/');
+        let (api_id, stage) = (resource.next()?, resource.next()?);
+        if api_id.is_empty() || stage.is_empty() {
+            return None;
+        }
+        Some(AuthPolicy {
+            principal_id: principal_id.into(),
+            arn_prefix: format!(
+                "arn:{}:execute-api:{}:{}:{}/{}",
+                partition, region, account_id, api_id, stage
+            ),
+            allowed: Vec::new(),
+            denied: Vec::new(),
+            context: HashMap::new(),
+            usage_identifier_key: None,
+        })
+    }
+
+    /// Allows calls to the methods with the HTTP method `verb` and a path matching
+    /// `path`. Both accept `*` wildcards.
+    pub fn allow_method(mut self, verb: &str, path: &str) -> Self {
+        let arn = self.method_arn(verb, path);
+        self.allowed.push(arn);
+        self
+    }
+
+    /// Denies calls to the methods with the HTTP method `verb` and a path matching
+    /// `path`. Both accept `*` wildcards.
+    pub fn deny_method(mut self, verb: &str, path: &str) -> Self {
+        let arn = self.method_arn(verb, path);
+        self.denied.push(arn);
+        self
+    }
+
+    /// Allows calls to all methods of the stage.
+    pub fn allow_all_methods(self) -> Self {
+        self.allow_method("*", "*")
+    }
+
+    /// Denies calls to all methods of the stage.
+    pub fn deny_all_methods(self) -> Self {
+        self.deny_method("*", "*")
+    }
+
+    /// Adds a value to the context passed to the integration.
+    pub fn context(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.context.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the API key used for the usage plans of a REST API.
+    pub fn usage_identifier_key(mut self, key: impl Into<String>) -> Self {
+        self.usage_identifier_key = Some(key.into());
+        self
+    }
+
+    /// Returns the response with the IAM policy. A policy without any method allowed or
+    /// denied denies all calls.
+    pub fn build(self) -> ApiGatewayCustomAuthorizerResponse {
+        let mut statement = Vec::new();
+        for (effect, resource) in [(Effect::Allow, self.allowed), (Effect::Deny, self.denied)] {
+            if !resource.is_empty() {
+                statement.push(Statement {
+                    action: vec![String::from(INVOKE_ACTION)],
+                    effect,
+                    resource,
+                    #[cfg(feature = "catch-all-fields")]
+                    other: Default::default(),
+                });
+            }
+        }
+        ApiGatewayCustomAuthorizerResponse {
+            principal_id: self.principal_id,
+            policy_document: PolicyDocument {
+                version: String::from(POLICY_VERSION),
+                statement,
+                #[cfg(feature = "catch-all-fields")]
+                other: Default::default(),
+            },
+            context: self.context,
+            usage_identifier_key: self.usage_identifier_key,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+
+    /// Returns the response in the simple format of HTTP APIs, which can't allow only
+    /// some routes: the request is authorized if the policy allows at least one method
+    /// and denies none.
+    pub fn build_simple(self) -> ApiGatewayV2SimpleAuthorizerResponse {
+        ApiGatewayV2SimpleAuthorizerResponse {
+            is_authorized: !self.allowed.is_empty() && self.denied.is_empty(),
+            context: self.context,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+
+    fn method_arn(&self, verb: &str, path: &str) -> String {
+        format!("{}/{}/{}", self.arn_prefix, verb, path.trim_start_matches('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_authorizer_requests() {
+        let input = include_str!("../tests/data/apigw_token_authorizer.json");
+        let token: ApiGatewayTokenAuthorizerRequest = serde_json::from_str(input).expect("Could not parse token");
+        assert_eq!(token.authorization_token, "Bearer allow-token");
+
+        let input = include_str!("../tests/data/apigw_request_authorizer.json");
+        let request: ApiGatewayRequestAuthorizerRequest = serde_json::from_str(input).expect("Could not parse request");
+        assert_eq!(request.authorizer_type, "REQUEST");
+        assert_eq!(request.method_arn, token.method_arn);
+        assert_eq!(request.headers["Authorization"], "Bearer allow-token");
+        assert_eq!(request.request_context.resource_path, "/pets/{id}");
+
+        let input = include_str!("../tests/data/http_api_authorizer.json");
+        let request: ApiGatewayV2AuthorizerRequest = serde_json::from_str(input).expect("Could not parse v2 request");
+        assert_eq!(request.identity_source, vec!["Bearer allow-token"]);
+        assert_eq!(request.request_context.http.method, "POST");
+    }
+
+    #[test]
+    fn policies_are_serialized() {
+        let input = include_str!("../tests/data/http_api_authorizer.json");
+        let request: ApiGatewayV2AuthorizerRequest = serde_json::from_str(input).expect("Could not parse v2 request");
+        let response = AuthPolicy::from_method_arn("user-1", &request.route_arn)
+            .unwrap()
+            .allow_all_methods()
+            .usage_identifier_key("key-1")
+            .build();
+        let expected = serde_json::json!({
+            "principalId": "user-1",
+            "policyDocument": {
+                "Version": "2012-10-17",
+                "Statement": [{
+                    "Action": ["execute-api:Invoke"],
+                    "Effect": "Allow",
+                    "Resource": ["arn:aws:execute-api:us-east-2:123456789012:r3pmxmplak/prod/*/*"],
+                }],
+            },
+            "usageIdentifierKey": "key-1",
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+
+        let empty = AuthPolicy::from_method_arn("user-1", &request.route_arn).unwrap();
+        assert!(empty.clone().build().policy_document.statement.is_empty());
+        assert!(!empty.clone().build_simple().is_authorized);
+        let simple = empty.allow_all_methods().context("tenant", "acme").build_simple();
+        assert_eq!(
+            serde_json::to_value(&simple).unwrap(),
+            serde_json::json!({"isAuthorized": true, "context": {"tenant": "acme"}})
+        );
+
+        assert!(AuthPolicy::from_method_arn("user-1", "arn:aws:lambda:us-east-1:123:function:f").is_none());
+        assert!(AuthPolicy::from_method_arn("user-1", "not an arn").is_none());
+    }
+}
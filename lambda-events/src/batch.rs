@@ -0,0 +1,94 @@
+//! Responses that report partial batch failures to the event source mapping, for
+//! functions that enable `ReportBatchItemFailures`. Lambda then retries only the failed
+//! items instead of the whole batch.
+use serde_derive::{Deserialize, Serialize};
+
+/// An item that failed to process.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemFailure {
+    /// The id of the item: the message id for SQS, the sequence number for Kinesis and
+    /// DynamoDB Streams.
+    pub item_identifier: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The response of a function that reports partial batch failures of a Kinesis or
+/// DynamoDB stream.
+///
+/// Stream records are processed in order, so Lambda checkpoints the stream at the lowest
+/// reported sequence number and retries the batch from that record on. An empty response
+/// checkpoints the whole batch.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamsEventResponse {
+    /// The records that failed.
+    pub batch_item_failures: Vec<BatchItemFailure>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl StreamsEventResponse {
+    /// Creates the response for the results of processing the records of a batch, in
+    /// order. Only the first failed record is reported: the records after it are retried
+    /// anyway, and reporting them could hide the record the batch has to restart from.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` The sequence number of each record with the result of processing it.
+    ///
+    /// # Return
+    /// A response that checkpoints the stream before the first failed record.
+    pub fn from_results<S, I, T, E>(results: I) -> StreamsEventResponse
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = (S, Result<T, E>)>,
+    {
+        let failure = results
+            .into_iter()
+            .find(|(_, result)| result.is_err())
+            .map(|(sequence_number, _)| BatchItemFailure {
+                item_identifier: sequence_number.into(),
+                #[cfg(feature = "catch-all-fields")]
+                other: Default::default(),
+            });
+        StreamsEventResponse {
+            batch_item_failures: failure.into_iter().collect(),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+/// Runs `handler` for the records of a stream batch in order, until one fails.
+pub(crate) fn process_stream<'a, R, F, T, E>(
+    records: &'a [R],
+    sequence_number: fn(&'a R) -> &'a str,
+    mut handler: F,
+) -> StreamsEventResponse
+where
+    F: FnMut(&'a R) -> Result<T, E>,
+{
+    StreamsEventResponse::from_results(records.iter().map(|record| (sequence_number(record), handler(record))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_report_the_first_failure() {
+        let results = vec![("1", Ok(())), ("2", Err("failed")), ("3", Err("failed"))];
+        let response = StreamsEventResponse::from_results(results);
+        let expected = serde_json::json!({"batchItemFailures": [{"itemIdentifier": "2"}]});
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+
+        let response = StreamsEventResponse::from_results(vec![("1", Ok::<(), ()>(()))]);
+        assert!(response.batch_item_failures.is_empty());
+    }
+}
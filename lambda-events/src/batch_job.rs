@@ -0,0 +1,202 @@
+//! The details of the AWS Batch job state changes delivered by EventBridge.
+//!
+//! Not to be confused with the `batch` module, which reports partial failures of the
+//! batches of event source mappings.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    encodings::{deserialize_lambda_map, MillisecondTimestamp},
+    eventbridge::EventBridgeEvent,
+};
+
+/// The `detail_type` of job state changes.
+pub const JOB_STATE_CHANGE_DETAIL_TYPE: &str = "Batch Job State Change";
+
+/// A `Batch Job State Change` event, sent when a job moves to another status.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::batch_job::{BatchJobStateChangeEvent, BatchJobStatus};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: BatchJobStateChangeEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     if e.detail.status == BatchJobStatus::Failed {
+///         println!("{} failed: {:?}", e.detail.job_name, e.detail.status_reason);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub type BatchJobStateChangeEvent = EventBridgeEvent<BatchJobStateChangeDetail>;
+
+/// The detail of a `Batch Job State Change` event, in the format of the
+/// `DescribeJobs` API.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchJobStateChangeDetail {
+    /// The ARN of the job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_arn: Option<String>,
+    /// The name of the job.
+    pub job_name: String,
+    /// The id of the job.
+    pub job_id: String,
+    /// The ARN of the queue of the job.
+    pub job_queue: String,
+    /// The new status of the job.
+    pub status: BatchJobStatus,
+    /// The description of the status.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_reason: Option<String>,
+    /// The attempts to run the job, oldest first.
+    #[serde(default)]
+    pub attempts: Vec<BatchJobAttempt>,
+    /// The ARN of the job definition.
+    pub job_definition: String,
+    /// The parameters of the job.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub parameters: HashMap<String, String>,
+    /// The jobs this job depends on.
+    #[serde(default)]
+    pub depends_on: Vec<Value>,
+    /// The container of the job, for single-node jobs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<Value>,
+    /// The retry strategy of the job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_strategy: Option<Value>,
+    /// The tags of the job.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub tags: HashMap<String, String>,
+    /// The time the job was created.
+    pub created_at: MillisecondTimestamp,
+    /// The time the job started running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<MillisecondTimestamp>,
+    /// The time the job stopped running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped_at: Option<MillisecondTimestamp>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl BatchJobStateChangeDetail {
+    /// Returns the last attempt to run the job.
+    pub fn last_attempt(&self) -> Option<&BatchJobAttempt> {
+        self.attempts.last()
+    }
+}
+
+/// The status of a job.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BatchJobStatus {
+    /// The job was submitted to the queue.
+    #[default]
+    Submitted,
+    /// The job waits for the jobs it depends on.
+    Pending,
+    /// The job waits for resources to run.
+    Runnable,
+    /// The job is being placed on a host.
+    Starting,
+    /// The job is running.
+    Running,
+    /// The job exited with code 0.
+    Succeeded,
+    /// The job failed all its attempts, or was cancelled.
+    Failed,
+}
+
+impl BatchJobStatus {
+    /// Returns whether the job will not change status anymore.
+    pub fn is_terminal(self) -> bool {
+        matches!(self, BatchJobStatus::Succeeded | BatchJobStatus::Failed)
+    }
+}
+
+/// An attempt to run a job.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchJobAttempt {
+    /// The container of the attempt.
+    pub container: BatchAttemptContainer,
+    /// The time the attempt started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<MillisecondTimestamp>,
+    /// The time the attempt stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped_at: Option<MillisecondTimestamp>,
+    /// Why the attempt stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_reason: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The container of an attempt to run a job.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchAttemptContainer {
+    /// The ARN of the ECS container instance the attempt ran on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_instance_arn: Option<String>,
+    /// The ARN of the ECS task of the attempt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_arn: Option<String>,
+    /// The exit code of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Why the container stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The CloudWatch Logs stream of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_stream_name: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_job_state_changes() {
+        let input = include_str!("../tests/data/batch_job_state_change.json");
+        let event: BatchJobStateChangeEvent = serde_json::from_str(input).expect("Could not parse Batch event");
+        assert_eq!(event.detail_type, JOB_STATE_CHANGE_DETAIL_TYPE);
+
+        let job = &event.detail;
+        assert_eq!(job.status, BatchJobStatus::Failed);
+        assert!(job.status.is_terminal());
+        assert_eq!(job.parameters["date"], "2022-01-11");
+        assert_eq!(job.created_at.timestamp_millis(), 1641944160000);
+        let attempt = job.last_attempt().unwrap();
+        assert_eq!(attempt.container.exit_code, Some(1));
+        assert_eq!(attempt.stopped_at.unwrap().timestamp_millis(), 1641944200456);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize Batch event");
+        assert_eq!(serde_json::from_str::<BatchJobStateChangeEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn detects_terminal_statuses() {
+        assert!(!BatchJobStatus::Runnable.is_terminal());
+        assert!(BatchJobStatus::Succeeded.is_terminal());
+    }
+}
@@ -0,0 +1,68 @@
+//! Prints a sample event, to invoke functions locally or to see the payload a type
+//! expects.
+//!
+//! ```text
+//! $ cargo run --bin gen-event -- sqs > event.json
+//! $ cargo run --bin gen-event -- --list
+//! ```
+//!
+//! Events are named after the constants of `lambda_events::samples`, in lowercase.
+//! The `_event` suffix can be left out, and so can the end of the name when only one
+//! sample starts with what was given.
+use std::{env, process};
+
+use lambda_events::samples;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None | Some("-h") | Some("--help") => {
+            eprintln!("Usage: gen-event <EVENT>\n       gen-event --list");
+            process::exit(2);
+        }
+        Some("-l") | Some("--list") => {
+            for name in samples::names() {
+                println!("{}", name);
+            }
+        }
+        Some(name) => match find(name) {
+            Ok(sample) => print!("{}", sample),
+            Err(candidates) if candidates.is_empty() => {
+                eprintln!("Unknown event `{}`, see `gen-event --list`", name);
+                process::exit(1);
+            }
+            Err(candidates) => {
+                eprintln!("Ambiguous event `{}`, one of: {}", name, candidates.join(", "));
+                process::exit(1);
+            }
+        },
+    }
+}
+
+/// Returns the sample named `name`, or the names of the samples it could mean.
+fn find(name: &str) -> Result<&'static str, Vec<&'static str>> {
+    let name = name.replace('-', "_").to_lowercase();
+    if let Some(sample) = samples::get(&name).or_else(|| samples::get(&format!("{}_event", name))) {
+        return Ok(sample);
+    }
+    let candidates: Vec<_> = samples::names().filter(|n| n.starts_with(&name)).collect();
+    match candidates.as_slice() {
+        [only] => Ok(samples::get(only).unwrap()),
+        _ => Err(candidates),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_samples() {
+        assert_eq!(find("sqs"), Ok(samples::SQS_EVENT));
+        assert_eq!(find("s3-event-delete"), Ok(samples::S3_EVENT_DELETE));
+        assert_eq!(find("lex-v2"), Ok(samples::LEX_V2_EVENT));
+        assert_eq!(find("secretsmanager"), Ok(samples::SECRETSMANAGER_ROTATION_EVENT));
+        assert_eq!(find("alb"), Err(vec!["alb_multi_value_request", "alb_request"]));
+        assert_eq!(find("nope"), Err(vec![]));
+    }
+}
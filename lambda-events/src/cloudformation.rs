@@ -0,0 +1,426 @@
+//! AWS CloudFormation custom resource events, sent to the functions that implement a
+//! custom resource when a stack creates, updates, or deletes it.
+//!
+//! Functions don't return their response: they upload it to the pre-signed S3 URL of
+//! the request, and the stack waits until they do, for up to an hour. With the
+//! `custom-resource-responder` feature, `CloudFormationCustomResourceRequest::respond()`
+//! sends the response.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A custom resource request, with the resource properties deserialized as `P`.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::cloudformation::CloudFormationCustomResourceRequest;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: CloudFormationCustomResourceRequest, _ctx: Context) -> Result<(), HandlerError> {
+///     let response = match e {
+///         CloudFormationCustomResourceRequest::Create(_) => e.success("my-resource"),
+///         CloudFormationCustomResourceRequest::Update(_) => e.failure("my-resource can't be updated"),
+///         CloudFormationCustomResourceRequest::Delete(ref r) => e.success(r.physical_resource_id.clone()),
+///     };
+///     // send the response to `e.response_url()`, for example with `e.respond(&response)`
+///     println!("{:?}", response);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "RequestType")]
+pub enum CloudFormationCustomResourceRequest<P = Value> {
+    /// The stack creates the resource.
+    Create(CreateRequest<P>),
+    /// The stack updates the properties of the resource.
+    Update(UpdateRequest<P>),
+    /// The stack deletes the resource.
+    Delete(DeleteRequest<P>),
+}
+
+/// A request to create a resource.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreateRequest<P = Value> {
+    /// The ARN of the function.
+    pub service_token: String,
+    /// The pre-signed S3 URL the response must be uploaded to.
+    #[serde(rename = "ResponseURL")]
+    pub response_url: String,
+    /// The ARN of the stack.
+    pub stack_id: String,
+    /// The unique id of the request.
+    pub request_id: String,
+    /// The name of the resource in the template.
+    pub logical_resource_id: String,
+    /// The type of the resource in the template, for example `Custom::MyResource`.
+    pub resource_type: String,
+    /// The properties of the resource in the template, including `ServiceToken`.
+    pub resource_properties: P,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A request to update a resource.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct UpdateRequest<P = Value> {
+    /// The ARN of the function.
+    pub service_token: String,
+    /// The pre-signed S3 URL the response must be uploaded to.
+    #[serde(rename = "ResponseURL")]
+    pub response_url: String,
+    /// The ARN of the stack.
+    pub stack_id: String,
+    /// The unique id of the request.
+    pub request_id: String,
+    /// The name of the resource in the template.
+    pub logical_resource_id: String,
+    /// The id the function returned when it created the resource.
+    pub physical_resource_id: String,
+    /// The type of the resource in the template, for example `Custom::MyResource`.
+    pub resource_type: String,
+    /// The new properties of the resource.
+    pub resource_properties: P,
+    /// The properties of the resource before the update.
+    pub old_resource_properties: P,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A request to delete a resource.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteRequest<P = Value> {
+    /// The ARN of the function.
+    pub service_token: String,
+    /// The pre-signed S3 URL the response must be uploaded to.
+    #[serde(rename = "ResponseURL")]
+    pub response_url: String,
+    /// The ARN of the stack.
+    pub stack_id: String,
+    /// The unique id of the request.
+    pub request_id: String,
+    /// The name of the resource in the template.
+    pub logical_resource_id: String,
+    /// The id the function returned when it created the resource.
+    pub physical_resource_id: String,
+    /// The type of the resource in the template, for example `Custom::MyResource`.
+    pub resource_type: String,
+    /// The properties of the resource.
+    pub resource_properties: P,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<P> CloudFormationCustomResourceRequest<P> {
+    /// Returns the pre-signed S3 URL the response must be uploaded to.
+    pub fn response_url(&self) -> &str {
+        match self {
+            CloudFormationCustomResourceRequest::Create(r) => &r.response_url,
+            CloudFormationCustomResourceRequest::Update(r) => &r.response_url,
+            CloudFormationCustomResourceRequest::Delete(r) => &r.response_url,
+        }
+    }
+
+    /// Returns the ARN of the stack.
+    pub fn stack_id(&self) -> &str {
+        match self {
+            CloudFormationCustomResourceRequest::Create(r) => &r.stack_id,
+            CloudFormationCustomResourceRequest::Update(r) => &r.stack_id,
+            CloudFormationCustomResourceRequest::Delete(r) => &r.stack_id,
+        }
+    }
+
+    /// Returns the unique id of the request.
+    pub fn request_id(&self) -> &str {
+        match self {
+            CloudFormationCustomResourceRequest::Create(r) => &r.request_id,
+            CloudFormationCustomResourceRequest::Update(r) => &r.request_id,
+            CloudFormationCustomResourceRequest::Delete(r) => &r.request_id,
+        }
+    }
+
+    /// Returns the name of the resource in the template.
+    pub fn logical_resource_id(&self) -> &str {
+        match self {
+            CloudFormationCustomResourceRequest::Create(r) => &r.logical_resource_id,
+            CloudFormationCustomResourceRequest::Update(r) => &r.logical_resource_id,
+            CloudFormationCustomResourceRequest::Delete(r) => &r.logical_resource_id,
+        }
+    }
+
+    /// Returns the id of the resource, `None` for create requests.
+    pub fn physical_resource_id(&self) -> Option<&str> {
+        match self {
+            CloudFormationCustomResourceRequest::Create(_) => None,
+            CloudFormationCustomResourceRequest::Update(r) => Some(&r.physical_resource_id),
+            CloudFormationCustomResourceRequest::Delete(r) => Some(&r.physical_resource_id),
+        }
+    }
+
+    /// Returns the properties of the resource. For update requests, these are the new
+    /// properties.
+    pub fn resource_properties(&self) -> &P {
+        match self {
+            CloudFormationCustomResourceRequest::Create(r) => &r.resource_properties,
+            CloudFormationCustomResourceRequest::Update(r) => &r.resource_properties,
+            CloudFormationCustomResourceRequest::Delete(r) => &r.resource_properties,
+        }
+    }
+
+    /// Returns a response telling the stack the request succeeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `physical_resource_id` The id of the resource. Returning a different id than
+    ///   the one of an update request replaces the resource: the stack then deletes the
+    ///   resource with the old id.
+    pub fn success(&self, physical_resource_id: impl Into<String>) -> CloudFormationCustomResourceResponse {
+        self.response(ResponseStatus::Success, physical_resource_id.into(), None)
+    }
+
+    /// Returns a response telling the stack the request failed, which rolls the stack
+    /// back. The id of the resource is the one of the request, or the request id for
+    /// create requests, since failed responses need one too.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` The reason the request failed, shown in the events of the stack.
+    pub fn failure(&self, reason: impl Into<String>) -> CloudFormationCustomResourceResponse {
+        let physical_resource_id = self.physical_resource_id().unwrap_or_else(|| self.request_id());
+        self.response(
+            ResponseStatus::Failed,
+            physical_resource_id.to_owned(),
+            Some(reason.into()),
+        )
+    }
+
+    fn response(
+        &self,
+        status: ResponseStatus,
+        physical_resource_id: String,
+        reason: Option<String>,
+    ) -> CloudFormationCustomResourceResponse {
+        CloudFormationCustomResourceResponse {
+            status,
+            reason,
+            physical_resource_id,
+            stack_id: self.stack_id().to_owned(),
+            request_id: self.request_id().to_owned(),
+            logical_resource_id: self.logical_resource_id().to_owned(),
+            no_echo: false,
+            data: HashMap::new(),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+
+    /// Uploads a response to the pre-signed S3 URL of the request.
+    ///
+    /// The URL is signed for a request without content type, so the response is sent
+    /// with an empty `Content-Type` header: S3 rejects the upload otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` The response, usually created with `success()` or `failure()`.
+    ///
+    /// # Return
+    /// An error if the response could not be uploaded.
+    #[cfg(feature = "custom-resource-responder")]
+    pub fn respond(&self, response: &CloudFormationCustomResourceResponse) -> Result<(), ResponseError> {
+        ureq::put(self.response_url())
+            .set("Content-Type", "")
+            .send_json(response)
+            .map(|_| ())
+            .map_err(|e| ResponseError(Box::new(e)))
+    }
+}
+
+/// The response to a custom resource request.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CloudFormationCustomResourceResponse {
+    /// Whether the request succeeded.
+    pub status: ResponseStatus,
+    /// The reason the request failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The id of the resource.
+    pub physical_resource_id: String,
+    /// The ARN of the stack, copied from the request.
+    pub stack_id: String,
+    /// The id of the request, copied from the request.
+    pub request_id: String,
+    /// The name of the resource in the template, copied from the request.
+    pub logical_resource_id: String,
+    /// Whether the stack masks `data` when it is retrieved with `Fn::GetAtt`.
+    #[serde(default)]
+    pub no_echo: bool,
+    /// The attributes of the resource, retrieved in the template with `Fn::GetAtt`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub data: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CloudFormationCustomResourceResponse {
+    /// Adds an attribute of the resource.
+    pub fn data(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.data.insert(name.into(), value.into());
+        self
+    }
+
+    /// Masks the attributes of the resource.
+    pub fn no_echo(mut self) -> Self {
+        self.no_echo = true;
+        self
+    }
+}
+
+/// Whether a custom resource request succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ResponseStatus {
+    /// The request succeeded.
+    Success,
+    /// The request failed.
+    Failed,
+}
+
+/// The error returned when a custom resource response could not be uploaded.
+#[cfg(feature = "custom-resource-responder")]
+#[derive(Debug)]
+pub struct ResponseError(Box<ureq::Error>);
+
+#[cfg(feature = "custom-resource-responder")]
+impl std::fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Could not upload custom resource response: {}", self.0)
+    }
+}
+
+#[cfg(feature = "custom-resource-responder")]
+impl std::error::Error for ResponseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct BucketRetention {
+        bucket_name: String,
+        retention: String,
+    }
+
+    #[test]
+    fn deserializes_create_requests() {
+        let input = include_str!("../tests/data/cloudformation_create_request.json");
+        let request: CloudFormationCustomResourceRequest<BucketRetention> =
+            serde_json::from_str(input).expect("Could not parse request");
+        match request {
+            CloudFormationCustomResourceRequest::Create(ref r) => assert_eq!(r.resource_properties.retention, "30"),
+            _ => panic!("Expected a create request, got {:?}", request),
+        }
+        assert_eq!(request.physical_resource_id(), None);
+        assert!(request.response_url().contains("X-Amz-Signature"));
+
+        let json = serde_json::to_string(&request).expect("Could not serialize request");
+        assert_eq!(
+            serde_json::from_str::<CloudFormationCustomResourceRequest<BucketRetention>>(&json).unwrap(),
+            request
+        );
+
+        let response = request.failure("Bucket not found");
+        assert_eq!(response.physical_resource_id, "3ab0ff3e-e9c1-4ab8-b7c4-c4a1c1b2b4a3");
+        let expected = serde_json::json!({
+            "Status": "FAILED",
+            "Reason": "Bucket not found",
+            "PhysicalResourceId": "3ab0ff3e-e9c1-4ab8-b7c4-c4a1c1b2b4a3",
+            "StackId": "arn:aws:cloudformation:us-east-1:123456789012:stack/stack-name/5c5c9c50-b4d5-11e9-9b43-0e8e7b6f6aba",
+            "RequestId": "3ab0ff3e-e9c1-4ab8-b7c4-c4a1c1b2b4a3",
+            "LogicalResourceId": "MyCustomResource",
+            "NoEcho": false,
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+    }
+
+    #[test]
+    fn deserializes_update_requests() {
+        let input = include_str!("../tests/data/cloudformation_update_request.json");
+        let request: CloudFormationCustomResourceRequest =
+            serde_json::from_str(input).expect("Could not parse request");
+        match request {
+            CloudFormationCustomResourceRequest::Update(ref r) => {
+                assert_eq!(r.old_resource_properties["Retention"], "30");
+                assert_eq!(r.resource_properties["Retention"], "60");
+            }
+            _ => panic!("Expected an update request, got {:?}", request),
+        }
+
+        let response = request.success("my-bucket-retention").data("Days", 60);
+        assert_eq!(response.status, ResponseStatus::Success);
+        assert_eq!(response.request_id, "7f3d2a1b-0c9e-4d8f-a6b5-1e2f3a4b5c6d");
+        assert_eq!(serde_json::to_value(&response).unwrap()["Data"]["Days"], 60);
+    }
+
+    #[cfg(feature = "custom-resource-responder")]
+    #[test]
+    fn responses_are_uploaded_without_content_type() {
+        use std::{
+            io::{Read, Write},
+            net::TcpListener,
+            thread,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0; 4096];
+            while !String::from_utf8_lossy(&received).contains("\"LogicalResourceId\"") {
+                let n = stream.read(&mut buf).unwrap();
+                received.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            String::from_utf8(received).unwrap()
+        });
+
+        let input = include_str!("../tests/data/cloudformation_update_request.json");
+        let mut request: CloudFormationCustomResourceRequest = serde_json::from_str(input).unwrap();
+        if let CloudFormationCustomResourceRequest::Update(ref mut r) = request {
+            r.response_url = format!("http://127.0.0.1:{}/stack%7CMyCustomResource?X-Amz-Signature=x", port);
+        }
+        let response = request.success("my-bucket-retention");
+        request.respond(&response).expect("Could not upload response");
+
+        let received = server.join().unwrap();
+        assert!(received.starts_with("PUT /stack%7CMyCustomResource?X-Amz-Signature=x HTTP/1.1\r\n"));
+        assert!(received.to_lowercase().contains("\r\ncontent-type: \r\n"));
+        assert!(received.contains("\"Status\":\"SUCCESS\""));
+    }
+}
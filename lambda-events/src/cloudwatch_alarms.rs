@@ -0,0 +1,244 @@
+//! The details of the CloudWatch alarm state changes delivered by EventBridge.
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{encodings::deserialize_lambda_map, eventbridge::EventBridgeEvent};
+
+/// The `detail_type` of alarm state changes.
+pub const ALARM_STATE_CHANGE_DETAIL_TYPE: &str = "CloudWatch Alarm State Change";
+
+/// A `CloudWatch Alarm State Change` event, sent when a metric or composite alarm
+/// changes state.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::cloudwatch_alarms::{AlarmState, CloudWatchAlarmStateChangeEvent};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: CloudWatchAlarmStateChangeEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     if e.detail.state.value == AlarmState::Alarm {
+///         println!("paging: {} ({})", e.detail.alarm_name, e.detail.state.reason);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub type CloudWatchAlarmStateChangeEvent = EventBridgeEvent<CloudWatchAlarmStateChange>;
+
+/// The detail of a `CloudWatch Alarm State Change` event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudWatchAlarmStateChange {
+    /// The name of the alarm.
+    pub alarm_name: String,
+    /// The configuration of the alarm.
+    pub configuration: CloudWatchAlarmConfiguration,
+    /// The state the alarm left.
+    pub previous_state: CloudWatchAlarmStateValue,
+    /// The state the alarm entered.
+    pub state: CloudWatchAlarmStateValue,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CloudWatchAlarmStateChange {
+    /// Returns whether the alarm went into alarm.
+    pub fn is_alarm(&self) -> bool {
+        self.state.value == AlarmState::Alarm && self.previous_state.value != AlarmState::Alarm
+    }
+
+    /// Returns whether the alarm recovered from alarm.
+    pub fn is_recovery(&self) -> bool {
+        self.previous_state.value == AlarmState::Alarm && self.state.value != AlarmState::Alarm
+    }
+}
+
+/// The configuration of an alarm. Metric alarms have `metrics`, composite alarms have
+/// an `alarm_rule`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudWatchAlarmConfiguration {
+    /// The description of the alarm.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The metrics and math expressions of a metric alarm.
+    #[serde(default)]
+    pub metrics: Vec<CloudWatchAlarmMetric>,
+    /// The rule of a composite alarm, for example `ALARM(CpuTooHigh) OR ALARM(DiskFull)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alarm_rule: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A metric or a math expression of an alarm.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudWatchAlarmMetric {
+    /// The id of the metric, used in math expressions.
+    pub id: String,
+    /// The metric and its statistic, for metrics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metric_stat: Option<CloudWatchMetricStat>,
+    /// The math expression, for math expressions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+    /// The label of the metric.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Whether the alarm is evaluated on this metric, rather than it being an input of
+    /// an expression.
+    #[serde(default)]
+    pub return_data: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A metric and the statistic an alarm evaluates on it.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudWatchMetricStat {
+    /// The metric.
+    pub metric: CloudWatchMetric,
+    /// The period of the statistic, in seconds.
+    pub period: u32,
+    /// The statistic, for example `Average` or `p99`.
+    pub stat: String,
+    /// The unit of the metric, if the alarm filters on it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A metric.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudWatchMetric {
+    /// The namespace of the metric, for example `AWS/EC2`.
+    pub namespace: String,
+    /// The name of the metric.
+    pub name: String,
+    /// The dimensions of the metric.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub dimensions: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A state of an alarm.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudWatchAlarmStateValue {
+    /// The state.
+    pub value: AlarmState,
+    /// The description of why the alarm is in this state.
+    pub reason: String,
+    /// The data that caused the state, as JSON. Use `deserialize_reason_data()` to
+    /// parse it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_data: Option<String>,
+    /// The time the alarm entered the state, for example
+    /// `2019-10-02T17:04:40.989+0000`. Use `timestamp()` to parse it.
+    #[serde(rename = "timestamp")]
+    pub timestamp_string: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CloudWatchAlarmStateValue {
+    /// Returns the time the alarm entered the state, or `None` if it isn't in the
+    /// expected format.
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_str(&self.timestamp_string, "%Y-%m-%dT%H:%M:%S%.f%z")
+            .ok()
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    /// Deserializes the data that caused the state, or returns `None` if the state has
+    /// no data.
+    pub fn deserialize_reason_data<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.reason_data.as_ref().map(|data| serde_json::from_str(data))
+    }
+}
+
+/// The state of an alarm.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AlarmState {
+    /// The alarm's condition is not met.
+    #[default]
+    Ok,
+    /// The alarm's condition is met.
+    Alarm,
+    /// There is not enough data to evaluate the alarm.
+    InsufficientData,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ReasonData {
+        recent_datapoints: Vec<f64>,
+        threshold: f64,
+    }
+
+    #[test]
+    fn deserializes_alarm_state_changes() {
+        let input = include_str!("../tests/data/cloudwatch_alarm_state_change.json");
+        let event: CloudWatchAlarmStateChangeEvent = serde_json::from_str(input).expect("Could not parse alarm event");
+        assert_eq!(event.detail_type, ALARM_STATE_CHANGE_DETAIL_TYPE);
+
+        let change = &event.detail;
+        assert!(change.is_alarm());
+        assert!(!change.is_recovery());
+        let metric = change.configuration.metrics[0].metric_stat.as_ref().unwrap();
+        assert_eq!(metric.metric.dimensions["InstanceId"], "i-12345678901234567");
+        assert_eq!(change.state.timestamp().unwrap().timestamp_millis(), 1_570_035_880_989);
+        let data: ReasonData = change.state.deserialize_reason_data().unwrap().unwrap();
+        assert!(data.recent_datapoints[0] > data.threshold);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize alarm event");
+        assert_eq!(
+            serde_json::from_str::<CloudWatchAlarmStateChangeEvent>(&json).unwrap(),
+            event
+        );
+    }
+
+    #[test]
+    fn deserializes_composite_alarm_configurations() {
+        let configuration: CloudWatchAlarmConfiguration = serde_json::from_value(serde_json::json!({
+            "alarmRule": "ALARM(CpuTooHigh) OR ALARM(DiskFull)",
+            "actionsSuppressor": "MaintenanceWindow"
+        }))
+        .expect("Could not parse configuration");
+        assert!(configuration.metrics.is_empty());
+        assert_eq!(
+            configuration.alarm_rule.as_deref(),
+            Some("ALARM(CpuTooHigh) OR ALARM(DiskFull)")
+        );
+    }
+}
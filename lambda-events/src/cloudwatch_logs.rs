@@ -0,0 +1,185 @@
+//! Amazon CloudWatch Logs subscription events, sent to functions subscribed to a log
+//! group. The log events are gzip compressed and base64 encoded, use
+//! `AwsLogs::decode()` to read them.
+use std::{error::Error, fmt, io::Read};
+
+use flate2::read::GzDecoder;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::encodings::MillisecondTimestamp;
+
+/// The `message_type` of the messages CloudWatch Logs sends to check that the
+/// destination of a subscription is reachable. They don't contain any log events.
+pub const CONTROL_MESSAGE: &str = "CONTROL_MESSAGE";
+
+/// A subscription event.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::cloudwatch_logs::CloudwatchLogsEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: CloudwatchLogsEvent, ctx: Context) -> Result<(), HandlerError> {
+///     let logs = e.aws_logs.decode().map_err(|e| ctx.new_error(&e.to_string()))?;
+///     for event in &logs.log_events {
+///         println!("{}: {}", logs.log_stream, event.message);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CloudwatchLogsEvent {
+    /// The encoded log events.
+    #[serde(rename = "awslogs")]
+    pub aws_logs: AwsLogs,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The encoded log events of a subscription event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AwsLogs {
+    /// The gzip compressed, base64 encoded JSON document with the log events.
+    pub data: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AwsLogs {
+    /// Decodes the log events.
+    ///
+    /// # Return
+    /// The log events, or an error if the data is not valid base64, gzip, or JSON.
+    pub fn decode(&self) -> Result<LogsData, LogsDecodeError> {
+        let compressed = base64::decode(&self.data).map_err(LogsDecodeError::Base64)?;
+        let mut json = Vec::new();
+        GzDecoder::new(&compressed[..])
+            .read_to_end(&mut json)
+            .map_err(LogsDecodeError::Gzip)?;
+        serde_json::from_slice(&json).map_err(LogsDecodeError::Json)
+    }
+}
+
+/// The decoded log events of a subscription event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsData {
+    /// `DATA_MESSAGE` for log events, or `CONTROL_MESSAGE` for reachability checks.
+    pub message_type: String,
+    /// The AWS account id owning the log group.
+    pub owner: String,
+    /// The name of the log group.
+    pub log_group: String,
+    /// The name of the log stream.
+    pub log_stream: String,
+    /// The names of the subscription filters that matched the log events.
+    #[serde(default)]
+    pub subscription_filters: Vec<String>,
+    /// The log events.
+    #[serde(default)]
+    pub log_events: Vec<LogEntry>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LogsData {
+    /// Returns whether this is a reachability check rather than log events.
+    pub fn is_control_message(&self) -> bool {
+        self.message_type == CONTROL_MESSAGE
+    }
+}
+
+/// A log event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LogEntry {
+    /// The unique id of the log event.
+    pub id: String,
+    /// The time of the log event.
+    pub timestamp: MillisecondTimestamp,
+    /// The message of the log event.
+    pub message: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The error returned when the log events of a subscription event can't be decoded.
+#[derive(Debug)]
+pub enum LogsDecodeError {
+    /// The data is not valid base64.
+    Base64(base64::DecodeError),
+    /// The data is not valid gzip.
+    Gzip(std::io::Error),
+    /// The decompressed data is not a valid log events document.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for LogsDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogsDecodeError::Base64(e) => write!(f, "Could not decode log data from base64: {}", e),
+            LogsDecodeError::Gzip(e) => write!(f, "Could not decompress log data: {}", e),
+            LogsDecodeError::Json(e) => write!(f, "Could not parse log data: {}", e),
+        }
+    }
+}
+
+impl Error for LogsDecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LogsDecodeError::Base64(e) => Some(e),
+            LogsDecodeError::Gzip(e) => Some(e),
+            LogsDecodeError::Json(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_log_events() {
+        let input = include_str!("../tests/data/cloudwatch_logs_event.json");
+        let event: CloudwatchLogsEvent = serde_json::from_str(input).expect("Could not parse logs event");
+        let logs = event.aws_logs.decode().expect("Could not decode logs");
+        assert!(!logs.is_control_message());
+        assert_eq!(logs.log_group, "/aws/lambda/orders");
+        assert_eq!(logs.subscription_filters, vec!["errors"]);
+        assert_eq!(logs.log_events.len(), 2);
+        assert_eq!(logs.log_events[0].message, "ERROR order o-123 failed\n");
+        assert_eq!(logs.log_events[1].timestamp.timestamp_millis(), 1_552_518_348_221);
+    }
+
+    #[test]
+    fn invalid_data_is_reported() {
+        let not_base64 = AwsLogs {
+            data: String::from("not base64!"),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        };
+        assert!(matches!(not_base64.decode(), Err(LogsDecodeError::Base64(_))));
+
+        let not_gzip = AwsLogs {
+            data: base64::encode("plain text"),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        };
+        let err = not_gzip.decode().unwrap_err();
+        assert!(matches!(err, LogsDecodeError::Gzip(_)));
+        assert!(err.to_string().starts_with("Could not decompress log data"));
+    }
+}
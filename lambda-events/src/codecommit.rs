@@ -0,0 +1,181 @@
+//! AWS CodeCommit events, sent to functions configured as the target of a repository
+//! trigger.
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+/// A CodeCommit event.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::codecommit::CodeCommitEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: CodeCommitEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     for record in &e.records {
+///         for reference in &record.codecommit.references {
+///             if let Some(branch) = reference.branch() {
+///                 println!("{} pushed {} to {}", record.user_identity_arn, reference.commit, branch);
+///             }
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CodeCommitEvent {
+    /// The records of the event.
+    #[serde(rename = "Records")]
+    pub records: Vec<CodeCommitRecord>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A change that fired a trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeCommitRecord {
+    /// The id of the event.
+    pub event_id: String,
+    /// The version of the event format, for example `1.0`.
+    pub event_version: String,
+    /// The time of the event, for example `2016-01-01T23:59:59.000+0000`. Use
+    /// `event_time()` to parse it.
+    #[serde(rename = "eventTime")]
+    pub event_time_string: String,
+    /// The id of the trigger configuration.
+    pub event_trigger_config_id: String,
+    /// The name of the trigger.
+    pub event_trigger_name: String,
+    /// The number of this record, when the event is split in several invocations.
+    pub event_part_number: u32,
+    /// The number of records the event is split in.
+    pub event_total_parts: u32,
+    /// The type of the event, for example `ReferenceChanges`, or `TriggerEventTest`
+    /// when the trigger is tested from the console.
+    pub event_name: String,
+    /// Always `aws:codecommit`.
+    pub event_source: String,
+    /// The ARN of the repository.
+    #[serde(rename = "eventSourceARN")]
+    pub event_source_arn: String,
+    /// The ARN of the user who made the change.
+    #[serde(rename = "userIdentityARN")]
+    pub user_identity_arn: String,
+    /// The region of the repository.
+    pub aws_region: String,
+    /// The custom data of the trigger, if it has any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_data: Option<String>,
+    /// The change.
+    pub codecommit: CodeCommitChange,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CodeCommitRecord {
+    /// Returns the time of the event, or `None` if it isn't in the expected format.
+    pub fn event_time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_str(&self.event_time_string, "%Y-%m-%dT%H:%M:%S%.f%z")
+            .ok()
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    /// Returns the name of the repository.
+    pub fn repository_name(&self) -> &str {
+        self.event_source_arn.rsplit(':').next().unwrap_or_default()
+    }
+
+    /// Deserializes the custom data of the trigger, for triggers configured with JSON
+    /// custom data.
+    ///
+    /// # Return
+    /// The custom data parsed as `T`, `None` if there is none, or the error if the
+    /// custom data is not valid for `T`.
+    pub fn deserialize_custom_data<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.custom_data.as_deref().map(serde_json::from_str)
+    }
+}
+
+/// The references a change updated.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CodeCommitChange {
+    /// The references.
+    pub references: Vec<CodeCommitReference>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A reference a change updated.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CodeCommitReference {
+    /// The id of the commit the reference points to.
+    pub commit: String,
+    /// The full name of the reference, for example `refs/heads/main`.
+    #[serde(rename = "ref")]
+    pub reference: String,
+    /// Whether the change created the reference.
+    #[serde(default)]
+    pub created: bool,
+    /// Whether the change deleted the reference.
+    #[serde(default)]
+    pub deleted: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CodeCommitReference {
+    /// Returns the name of the branch, if the reference is a branch.
+    pub fn branch(&self) -> Option<&str> {
+        self.reference.strip_prefix("refs/heads/")
+    }
+
+    /// Returns the name of the tag, if the reference is a tag.
+    pub fn tag(&self) -> Option<&str> {
+        self.reference.strip_prefix("refs/tags/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn deserializes_events() {
+        let input = include_str!("../tests/data/codecommit_event.json");
+        let event: CodeCommitEvent = serde_json::from_str(input).expect("Could not parse CodeCommit event");
+        let record = &event.records[0];
+        assert_eq!(record.repository_name(), "my-repo");
+        assert_eq!(
+            record.event_time(),
+            Some(Utc.with_ymd_and_hms(2016, 1, 1, 23, 59, 59).unwrap())
+        );
+        let custom_data: serde_json::Value = record.deserialize_custom_data().unwrap().unwrap();
+        assert_eq!(custom_data["channel"], "#deploys");
+
+        let references = &record.codecommit.references;
+        assert_eq!(references[0].branch(), Some("main"));
+        assert!(references[0].created);
+        assert_eq!(references[1].branch(), None);
+        assert_eq!(references[1].tag(), Some("v1.2.0"));
+        assert!(!references[1].created);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize CodeCommit event");
+        assert_eq!(serde_json::from_str::<CodeCommitEvent>(&json).unwrap(), event);
+    }
+}
@@ -0,0 +1,438 @@
+//! AWS CodePipeline job events, sent to the functions of `Invoke` actions.
+//!
+//! A pipeline waits until the function reports the result of the job with the
+//! `PutJobSuccessResult` or `PutJobFailureResult` API, not until the function returns.
+//! This crate doesn't depend on an AWS SDK: implement `JobResultReporter` with the SDK
+//! of your choice, and report the result with `CodePipelineJob::succeed()` or
+//! `CodePipelineJob::fail()`.
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::encodings::deserialize_lambda_map;
+
+/// A job sent by a pipeline.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::codepipeline::{
+///     CodePipelineEvent, FailureType, JobFailureResult, JobResultReporter, JobSuccessResult,
+/// };
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// struct Reporter;
+///
+/// impl JobResultReporter for Reporter {
+///     type Error = HandlerError;
+///
+///     fn put_job_success_result(&self, result: &JobSuccessResult) -> Result<(), HandlerError> {
+///         // call PutJobSuccessResult with an AWS SDK
+///         Ok(())
+///     }
+///
+///     fn put_job_failure_result(&self, result: &JobFailureResult) -> Result<(), HandlerError> {
+///         // call PutJobFailureResult with an AWS SDK
+///         Ok(())
+///     }
+/// }
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: CodePipelineEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     let job = e.job;
+///     match job.user_parameters() {
+///         Some(_) => job.succeed(&Reporter),
+///         None => job.fail(&Reporter, FailureType::ConfigurationError, "Missing user parameters"),
+///     }
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CodePipelineEvent {
+    /// The job.
+    #[serde(rename = "CodePipeline.job")]
+    pub job: CodePipelineJob,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A job of an `Invoke` action.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePipelineJob {
+    /// The id of the job, which the result must copy.
+    pub id: String,
+    /// The AWS account of the pipeline.
+    pub account_id: String,
+    /// The configuration and the artifacts of the action.
+    pub data: CodePipelineJobData,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CodePipelineJob {
+    /// Returns the user parameters of the action, if they are set.
+    pub fn user_parameters(&self) -> Option<&str> {
+        self.data
+            .action_configuration
+            .configuration
+            .get("UserParameters")
+            .map(String::as_str)
+    }
+
+    /// Deserializes the user parameters of the action, for actions configured with
+    /// JSON parameters.
+    ///
+    /// # Return
+    /// The parameters parsed as `T`, `None` if there are none, or the error if the
+    /// parameters are not valid for `T`.
+    pub fn deserialize_user_parameters<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.user_parameters().map(serde_json::from_str)
+    }
+
+    /// Returns the input artifact with the given name.
+    pub fn input_artifact(&self, name: &str) -> Option<&CodePipelineArtifact> {
+        self.data.input_artifacts.iter().find(|artifact| artifact.name == name)
+    }
+
+    /// Returns the result of a job that succeeded.
+    pub fn success_result(&self) -> JobSuccessResult {
+        JobSuccessResult {
+            job_id: self.id.clone(),
+            ..JobSuccessResult::default()
+        }
+    }
+
+    /// Returns the result of a job that failed.
+    pub fn failure_result(&self, failure_type: FailureType, message: impl Into<String>) -> JobFailureResult {
+        JobFailureResult {
+            job_id: self.id.clone(),
+            failure_details: FailureDetails {
+                failure_type,
+                message: message.into(),
+                external_execution_id: None,
+                #[cfg(feature = "catch-all-fields")]
+                other: Default::default(),
+            },
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+
+    /// Reports that the job succeeded.
+    pub fn succeed<R: JobResultReporter>(&self, reporter: &R) -> Result<(), R::Error> {
+        reporter.put_job_success_result(&self.success_result())
+    }
+
+    /// Reports that the job failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` The reporter that calls the CodePipeline API.
+    /// * `failure_type` The category of the failure.
+    /// * `message` The message shown in the pipeline.
+    pub fn fail<R: JobResultReporter>(
+        &self,
+        reporter: &R,
+        failure_type: FailureType,
+        message: impl Into<String>,
+    ) -> Result<(), R::Error> {
+        reporter.put_job_failure_result(&self.failure_result(failure_type, message))
+    }
+}
+
+/// The configuration and the artifacts of an action.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePipelineJobData {
+    /// The configuration of the action.
+    pub action_configuration: CodePipelineActionConfiguration,
+    /// The artifacts the action reads.
+    #[serde(default)]
+    pub input_artifacts: Vec<CodePipelineArtifact>,
+    /// The artifacts the action must write.
+    #[serde(default)]
+    pub output_artifacts: Vec<CodePipelineArtifact>,
+    /// The temporary credentials that can access the artifacts.
+    pub artifact_credentials: CodePipelineArtifactCredentials,
+    /// The token the function returned in a previous result to continue the job, if
+    /// any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// The key the artifacts are encrypted with, if it isn't the default key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key: Option<CodePipelineEncryptionKey>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The configuration of an action.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CodePipelineActionConfiguration {
+    /// The properties of the action, `FunctionName` and `UserParameters`.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub configuration: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An artifact of an action.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePipelineArtifact {
+    /// The name of the artifact.
+    pub name: String,
+    /// The revision of the artifact, if the source action set one.
+    #[serde(default)]
+    pub revision: Option<String>,
+    /// The location of the artifact.
+    pub location: CodePipelineArtifactLocation,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The location of an artifact.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePipelineArtifactLocation {
+    /// The type of the location, `S3`.
+    #[serde(rename = "type")]
+    pub location_type: String,
+    /// The object of the artifact.
+    pub s3_location: CodePipelineS3Location,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An object in the artifact store of a pipeline.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePipelineS3Location {
+    /// The bucket of the artifact store.
+    pub bucket_name: String,
+    /// The key of the object.
+    pub object_key: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Temporary credentials that can access the artifacts of a job.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodePipelineArtifactCredentials {
+    /// The access key id.
+    pub access_key_id: String,
+    /// The secret access key.
+    pub secret_access_key: String,
+    /// The session token.
+    pub session_token: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The key artifacts are encrypted with.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CodePipelineEncryptionKey {
+    /// The id or ARN of the key.
+    pub id: String,
+    /// The type of the key, `KMS`.
+    #[serde(rename = "type")]
+    pub key_type: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Reports the results of jobs to CodePipeline, usually by calling its API with an
+/// AWS SDK. The results serialize to the requests of the `PutJobSuccessResult` and
+/// `PutJobFailureResult` APIs.
+pub trait JobResultReporter {
+    /// The error returned when the result can't be reported.
+    type Error;
+
+    /// Reports that a job succeeded.
+    fn put_job_success_result(&self, result: &JobSuccessResult) -> Result<(), Self::Error>;
+
+    /// Reports that a job failed.
+    fn put_job_failure_result(&self, result: &JobFailureResult) -> Result<(), Self::Error>;
+}
+
+/// The result of a job that succeeded.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSuccessResult {
+    /// The id of the job.
+    pub job_id: String,
+    /// A token that makes the pipeline invoke the function again with it, for jobs
+    /// that take longer than an invocation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// The variables other actions of the pipeline can use.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub output_variables: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl JobSuccessResult {
+    /// Sets the token that continues the job.
+    pub fn continuation_token(mut self, token: impl Into<String>) -> Self {
+        self.continuation_token = Some(token.into());
+        self
+    }
+
+    /// Sets an output variable.
+    pub fn output_variable(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.output_variables.insert(name.into(), value.into());
+        self
+    }
+}
+
+/// The result of a job that failed.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobFailureResult {
+    /// The id of the job.
+    pub job_id: String,
+    /// The failure.
+    pub failure_details: FailureDetails,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The failure of a job.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailureDetails {
+    /// The category of the failure.
+    #[serde(rename = "type")]
+    pub failure_type: FailureType,
+    /// The message shown in the pipeline.
+    pub message: String,
+    /// The id of the run of the job in an external system, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_execution_id: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The category of the failure of a job.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FailureType {
+    /// The job failed.
+    #[default]
+    JobFailed,
+    /// The action is misconfigured.
+    ConfigurationError,
+    /// The function lacks permissions.
+    PermissionError,
+    /// The revision of an artifact is out of sync.
+    RevisionOutOfSync,
+    /// The revision of an artifact is unavailable.
+    RevisionUnavailable,
+    /// A service the job depends on is unavailable.
+    SystemUnavailable,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+    use std::cell::RefCell;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Parameters {
+        environment: String,
+        smoke_test: bool,
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        results: RefCell<Vec<serde_json::Value>>,
+    }
+
+    impl JobResultReporter for Recorder {
+        type Error = ();
+
+        fn put_job_success_result(&self, result: &JobSuccessResult) -> Result<(), ()> {
+            self.results.borrow_mut().push(serde_json::to_value(result).unwrap());
+            Ok(())
+        }
+
+        fn put_job_failure_result(&self, result: &JobFailureResult) -> Result<(), ()> {
+            self.results.borrow_mut().push(serde_json::to_value(result).unwrap());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn deserializes_jobs() {
+        let input = include_str!("../tests/data/codepipeline_job_event.json");
+        let event: CodePipelineEvent = serde_json::from_str(input).expect("Could not parse job event");
+        let job = &event.job;
+        assert_eq!(job.id, "11111111-abcd-1111-abcd-111111abcdef");
+        assert_eq!(job.input_artifact("ArtifactName").unwrap().location.location_type, "S3");
+        assert_eq!(job.data.encryption_key.as_ref().unwrap().key_type, "KMS");
+
+        let parameters: Parameters = job.deserialize_user_parameters().unwrap().unwrap();
+        assert_eq!(parameters.environment, "staging");
+        assert!(parameters.smoke_test);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize job event");
+        assert_eq!(serde_json::from_str::<CodePipelineEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn reports_results() {
+        let input = include_str!("../tests/data/codepipeline_job_event.json");
+        let event: CodePipelineEvent = serde_json::from_str(input).expect("Could not parse job event");
+        let recorder = Recorder::default();
+
+        event.job.succeed(&recorder).unwrap();
+        event
+            .job
+            .fail(&recorder, FailureType::ConfigurationError, "Unknown environment")
+            .unwrap();
+        assert_eq!(
+            *recorder.results.borrow(),
+            vec![
+                serde_json::json!({"jobId": "11111111-abcd-1111-abcd-111111abcdef"}),
+                serde_json::json!({
+                    "jobId": "11111111-abcd-1111-abcd-111111abcdef",
+                    "failureDetails": {"type": "ConfigurationError", "message": "Unknown environment"},
+                }),
+            ]
+        );
+
+        let result = event.job.success_result().continuation_token("step-2");
+        assert_eq!(result.continuation_token.as_deref(), Some("step-2"));
+    }
+}
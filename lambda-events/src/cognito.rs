@@ -0,0 +1,416 @@
+//! Amazon Cognito user pool trigger events. User pools invoke the function with the
+//! event and use the `response` section of the event the function returns, so handlers
+//! fill in the response of the event they received and return the whole event.
+//!
+//! ```rust
+//! use lambda_events::cognito::CognitoPreSignUpEvent;
+//! use lambda_runtime::{error::HandlerError, Context};
+//!
+//! fn pre_sign_up(mut event: CognitoPreSignUpEvent, _ctx: Context) -> Result<CognitoPreSignUpEvent, HandlerError> {
+//!     let email = event.request.user_attributes.get("email").cloned().unwrap_or_default();
+//!     if email.ends_with("@example.com") {
+//!         event.response.auto_confirm_user = true;
+//!         event.response.auto_verify_email = true;
+//!     }
+//!     Ok(event)
+//! }
+//! ```
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::encodings::{deserialize_lambda_map, deserialize_nullable};
+
+/// A user pool trigger event, with the request and response sections of the trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CognitoUserPoolEvent<Req, Resp> {
+    /// The version of the event format.
+    pub version: String,
+    /// The event that invoked the trigger, for example `PreSignUp_SignUp` or
+    /// `PreSignUp_AdminCreateUser`.
+    pub trigger_source: String,
+    /// The region of the user pool.
+    pub region: String,
+    /// The id of the user pool.
+    pub user_pool_id: String,
+    /// The name of the user.
+    #[serde(default)]
+    pub user_name: Option<String>,
+    /// The client that made the request.
+    #[serde(default)]
+    pub caller_context: CognitoCallerContext,
+    /// The input of the trigger.
+    pub request: Req,
+    /// The output of the trigger, filled in by the function.
+    #[serde(default)]
+    pub response: Resp,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The client that made the request to the user pool.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CognitoCallerContext {
+    /// The version of the AWS SDK used by the client.
+    #[serde(default)]
+    pub aws_sdk_version: String,
+    /// The id of the app client.
+    #[serde(default)]
+    pub client_id: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The response of triggers that don't return anything.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EmptyResponse {
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Invoked before a user is signed up, to validate or confirm the user.
+pub type CognitoPreSignUpEvent = CognitoUserPoolEvent<PreSignUpRequest, PreSignUpResponse>;
+
+/// Invoked after a user confirmed their account.
+pub type CognitoPostConfirmationEvent = CognitoUserPoolEvent<PostConfirmationRequest, EmptyResponse>;
+
+/// Invoked when a user signs in, to reject the sign in with an error.
+pub type CognitoPreAuthenticationEvent = CognitoUserPoolEvent<PreAuthenticationRequest, EmptyResponse>;
+
+/// Invoked after a user signed in.
+pub type CognitoPostAuthenticationEvent = CognitoUserPoolEvent<PostAuthenticationRequest, EmptyResponse>;
+
+/// Invoked before tokens are generated, to customize their claims.
+pub type CognitoPreTokenGenerationEvent = CognitoUserPoolEvent<PreTokenGenerationRequest, PreTokenGenerationResponse>;
+
+/// Invoked before a verification or invitation message is sent, to customize it.
+pub type CognitoCustomMessageEvent = CognitoUserPoolEvent<CustomMessageRequest, CustomMessageResponse>;
+
+/// Invoked during custom authentication flows, to pick the next challenge.
+pub type CognitoDefineAuthChallengeEvent =
+    CognitoUserPoolEvent<DefineAuthChallengeRequest, DefineAuthChallengeResponse>;
+
+/// The input of the pre sign-up trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreSignUpRequest {
+    /// The attributes of the user.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub user_attributes: HashMap<String, String>,
+    /// The validation data sent by the client.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub validation_data: HashMap<String, String>,
+    /// The client metadata sent by the client.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub client_metadata: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The output of the pre sign-up trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreSignUpResponse {
+    /// Confirms the user without a confirmation code.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub auto_confirm_user: bool,
+    /// Marks the email address of the user as verified.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub auto_verify_email: bool,
+    /// Marks the phone number of the user as verified.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub auto_verify_phone: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The input of the post confirmation trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostConfirmationRequest {
+    /// The attributes of the user.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub user_attributes: HashMap<String, String>,
+    /// The client metadata sent by the client.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub client_metadata: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The input of the pre authentication trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreAuthenticationRequest {
+    /// The attributes of the user.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub user_attributes: HashMap<String, String>,
+    /// The validation data sent by the client.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub validation_data: HashMap<String, String>,
+    /// Whether the user doesn't exist, when the user pool invokes the trigger for
+    /// unknown users.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub user_not_found: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The input of the post authentication trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostAuthenticationRequest {
+    /// Whether the user signed in from a new device.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub new_device_used: bool,
+    /// The attributes of the user.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub user_attributes: HashMap<String, String>,
+    /// The client metadata sent by the client.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub client_metadata: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The input of the pre token generation trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreTokenGenerationRequest {
+    /// The attributes of the user.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub user_attributes: HashMap<String, String>,
+    /// The groups and roles of the user.
+    #[serde(default)]
+    pub group_configuration: GroupConfiguration,
+    /// The client metadata sent by the client.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub client_metadata: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The groups and IAM roles of a user.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupConfiguration {
+    /// The groups of the user.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub groups_to_override: Vec<String>,
+    /// The IAM roles of the groups of the user.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub iam_roles_to_override: Vec<String>,
+    /// The preferred IAM role of the user.
+    #[serde(default)]
+    pub preferred_role: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The output of the pre token generation trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreTokenGenerationResponse {
+    /// The changes to the claims of the tokens, `None` to keep them as they are.
+    #[serde(default)]
+    pub claims_override_details: Option<ClaimsOverrideDetails>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The changes to the claims of the tokens generated for a user.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimsOverrideDetails {
+    /// Claims to add to the ID token, or to replace in it.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub claims_to_add_or_override: HashMap<String, String>,
+    /// Claims to remove from the ID token.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub claims_to_suppress: Vec<String>,
+    /// The groups and roles to put in the tokens instead of the ones of the user.
+    #[serde(default)]
+    pub group_override_details: Option<GroupConfiguration>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The input of the custom message trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomMessageRequest {
+    /// The attributes of the user.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub user_attributes: HashMap<String, String>,
+    /// The placeholder the message must contain for the verification code, for example
+    /// `{####}`.
+    #[serde(default)]
+    pub code_parameter: Option<String>,
+    /// The placeholder the message must contain for the user name, in invitation
+    /// messages.
+    #[serde(default)]
+    pub username_parameter: Option<String>,
+    /// The client metadata sent by the client.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub client_metadata: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The output of the custom message trigger. Messages that are `None` use the default
+/// message of the user pool.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomMessageResponse {
+    /// The text of SMS messages.
+    #[serde(default)]
+    pub sms_message: Option<String>,
+    /// The body of email messages.
+    #[serde(default)]
+    pub email_message: Option<String>,
+    /// The subject of email messages.
+    #[serde(default)]
+    pub email_subject: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The input of the define auth challenge trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefineAuthChallengeRequest {
+    /// The attributes of the user.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub user_attributes: HashMap<String, String>,
+    /// The challenges answered so far in the authentication flow.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub session: Vec<ChallengeResult>,
+    /// The client metadata sent by the client.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub client_metadata: HashMap<String, String>,
+    /// Whether the user doesn't exist.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub user_not_found: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A challenge answered in an authentication flow.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeResult {
+    /// The type of the challenge, for example `SRP_A` or `CUSTOM_CHALLENGE`.
+    pub challenge_name: String,
+    /// Whether the challenge was answered correctly.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub challenge_result: bool,
+    /// The metadata the create auth challenge trigger attached to custom challenges.
+    #[serde(default)]
+    pub challenge_metadata: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The output of the define auth challenge trigger.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefineAuthChallengeResponse {
+    /// The next challenge to present to the user.
+    #[serde(default)]
+    pub challenge_name: Option<String>,
+    /// Ends the flow successfully and issues tokens to the user.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub issue_tokens: bool,
+    /// Ends the flow with an error.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub fail_authentication: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pre_sign_up_response_is_echoed() {
+        let input = include_str!("../tests/data/cognito_pre_signup.json");
+        let mut event: CognitoPreSignUpEvent = serde_json::from_str(input).expect("Could not parse event");
+        assert_eq!(event.trigger_source, "PreSignUp_SignUp");
+        assert_eq!(event.request.user_attributes["email"], "jane@example.com");
+        assert!(event.request.validation_data.is_empty());
+
+        event.response.auto_confirm_user = true;
+        let output = serde_json::to_value(&event).expect("Could not serialize event");
+        assert_eq!(output["response"]["autoConfirmUser"], true);
+        assert_eq!(output["userPoolId"], "us-east-1_EXAMPLE");
+    }
+
+    #[test]
+    #[cfg(feature = "catch-all-fields")]
+    fn unknown_fields_are_echoed() {
+        let mut input: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/data/cognito_pre_signup.json")).unwrap();
+        input["request"]["userNotFound"] = serde_json::json!(false);
+        input["clientMetadata"] = serde_json::json!({"source": "web"});
+        let event: CognitoPreSignUpEvent = serde_json::from_value(input).expect("Could not parse event");
+        assert_eq!(event.request.other["userNotFound"], false);
+
+        let output = serde_json::to_value(&event).expect("Could not serialize event");
+        assert_eq!(output["request"]["userNotFound"], false);
+        assert_eq!(output["clientMetadata"]["source"], "web");
+    }
+
+    #[test]
+    fn null_responses_are_defaulted() {
+        let input = include_str!("../tests/data/cognito_define_auth_challenge.json");
+        let mut event: CognitoDefineAuthChallengeEvent = serde_json::from_str(input).expect("Could not parse event");
+        assert_eq!(event.request.session.len(), 2);
+        assert!(event.request.session.iter().all(|c| c.challenge_result));
+        assert_eq!(event.request.client_metadata["source"], "web");
+        assert_eq!(event.response, DefineAuthChallengeResponse::default());
+
+        event.response.issue_tokens = true;
+        let output = serde_json::to_value(&event).expect("Could not serialize event");
+        assert_eq!(
+            output["response"],
+            serde_json::json!({"challengeName": null, "issueTokens": true, "failAuthentication": false})
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! AWS Config events, sent to the functions of custom rules.
+//!
+//! Config sends the change that triggered the evaluation and the parameters of the
+//! rule as JSON strings inside the event. The event keeps them as sent, use
+//! `ConfigEvent::invoking_event()` and `ConfigEvent::deserialize_rule_parameters()` to
+//! parse them.
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::encodings::{deserialize_lambda_map, deserialize_nullable};
+
+/// An evaluation of a custom rule.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::config::ConfigEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: ConfigEvent, ctx: Context) -> Result<(), HandlerError> {
+///     let invoking_event = e.invoking_event().map_err(|err| ctx.new_error(&err.to_string()))?;
+///     if let Some(item) = invoking_event.configuration_item {
+///         println!("evaluating {} {}", item.resource_type, item.resource_id);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigEvent {
+    /// The version of the event format, for example `1.0`.
+    pub version: String,
+    /// The change or the schedule that triggered the evaluation, as JSON.
+    pub invoking_event: String,
+    /// The parameters of the rule, as JSON, if the rule has any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_parameters: Option<String>,
+    /// The token to send with the evaluations of the resources.
+    pub result_token: String,
+    /// Whether the resource left the scope of the rule, in which case its evaluation
+    /// should be `NOT_APPLICABLE`.
+    #[serde(default)]
+    pub event_left_scope: bool,
+    /// The role of the rule.
+    pub execution_role_arn: String,
+    /// The ARN of the rule.
+    pub config_rule_arn: String,
+    /// The name of the rule.
+    pub config_rule_name: String,
+    /// The id of the rule.
+    pub config_rule_id: String,
+    /// The AWS account of the rule.
+    pub account_id: String,
+    /// `DETECTIVE` for evaluations of existing resources, `PROACTIVE` for
+    /// evaluations of resources before they are deployed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evaluation_mode: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ConfigEvent {
+    /// Parses the change or the schedule that triggered the evaluation.
+    pub fn invoking_event(&self) -> Result<ConfigInvokingEvent, serde_json::Error> {
+        serde_json::from_str(&self.invoking_event)
+    }
+
+    /// Deserializes the parameters of the rule.
+    ///
+    /// # Return
+    /// The parameters parsed as `T`, `None` if there are none, or the error if the
+    /// parameters are not valid for `T`.
+    pub fn deserialize_rule_parameters<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.rule_parameters.as_deref().map(serde_json::from_str)
+    }
+}
+
+/// What triggered an evaluation.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigInvokingEvent {
+    /// `ConfigurationItemChangeNotification`,
+    /// `OversizedConfigurationItemChangeNotification`, or
+    /// `ScheduledNotification`.
+    pub message_type: String,
+    /// The time of the notification.
+    pub notification_creation_time: DateTime<Utc>,
+    /// The version of the configuration item format, for change notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_version: Option<String>,
+    /// The resource that changed, for `ConfigurationItemChangeNotification`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub configuration_item: Option<ConfigurationItem>,
+    /// The summary of the resource that changed, for
+    /// `OversizedConfigurationItemChangeNotification`. Get the item with the
+    /// `GetResourceConfigHistory` API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub configuration_item_summary: Option<Value>,
+    /// The AWS account of the rule, for `ScheduledNotification`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aws_account_id: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ConfigInvokingEvent {
+    /// Returns whether the evaluation was triggered by a schedule rather than by a
+    /// change.
+    pub fn is_scheduled(&self) -> bool {
+        self.message_type == "ScheduledNotification"
+    }
+}
+
+/// The configuration of a resource at a point in time.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigurationItem {
+    /// The version of the item format, for example `1.3`.
+    pub configuration_item_version: String,
+    /// The time the configuration was recorded.
+    pub configuration_item_capture_time: DateTime<Utc>,
+    /// `OK`, `ResourceDiscovered`, `ResourceNotRecorded`, `ResourceDeleted`, or
+    /// `ResourceDeletedNotRecorded`.
+    pub configuration_item_status: String,
+    /// The version of the configuration, increasing with each change.
+    #[serde(default)]
+    pub configuration_state_id: u64,
+    /// The AWS account of the resource.
+    pub aws_account_id: String,
+    /// The region of the resource.
+    pub aws_region: String,
+    /// The availability zone of the resource, if it has one.
+    #[serde(default)]
+    pub availability_zone: Option<String>,
+    /// The type of the resource, for example `AWS::EC2::Instance`.
+    pub resource_type: String,
+    /// The id of the resource.
+    pub resource_id: String,
+    /// The name of the resource, if it has one.
+    #[serde(default)]
+    pub resource_name: Option<String>,
+    /// The ARN of the resource.
+    #[serde(rename = "ARN", default)]
+    pub arn: Option<String>,
+    /// The time the resource was created.
+    #[serde(default)]
+    pub resource_creation_time: Option<DateTime<Utc>>,
+    /// The tags of the resource.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub tags: HashMap<String, String>,
+    /// The CloudTrail events of the change.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub related_events: Vec<String>,
+    /// The resources related to the resource.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub relationships: Vec<ConfigRelationship>,
+    /// The configuration of the resource, in the format of the `Describe` API of its
+    /// service. Use `deserialize_configuration()` to parse it.
+    #[serde(default)]
+    pub configuration: Value,
+    /// The configuration of the resource that its `Describe` API doesn't return,
+    /// such as the policy of an S3 bucket.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub supplementary_configuration: HashMap<String, Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ConfigurationItem {
+    /// Returns whether the resource was deleted.
+    pub fn is_deleted(&self) -> bool {
+        self.configuration_item_status.starts_with("ResourceDeleted")
+    }
+
+    /// Deserializes the configuration of the resource.
+    ///
+    /// # Return
+    /// The configuration parsed as `T`, or the error if it's not valid for `T`.
+    pub fn deserialize_configuration<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.configuration.clone())
+    }
+}
+
+/// A resource related to another resource.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRelationship {
+    /// The type of the relationship, for example `Is attached to Volume`.
+    pub name: String,
+    /// The type of the related resource.
+    pub resource_type: String,
+    /// The id of the related resource.
+    pub resource_id: String,
+    /// The name of the related resource, if it has one.
+    #[serde(default)]
+    pub resource_name: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RuleParameters {
+        desired_instance_type: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Instance {
+        instance_type: String,
+    }
+
+    #[test]
+    fn deserializes_change_events() {
+        let input = include_str!("../tests/data/config_rule_event.json");
+        let event: ConfigEvent = serde_json::from_str(input).expect("Could not parse Config event");
+        assert_eq!(event.config_rule_name, "change-triggered-config-rule");
+        assert!(!event.event_left_scope);
+
+        let parameters: RuleParameters = event.deserialize_rule_parameters().unwrap().unwrap();
+        assert_eq!(parameters.desired_instance_type, "t2.micro");
+
+        let invoking_event = event.invoking_event().expect("Could not parse invoking event");
+        assert!(!invoking_event.is_scheduled());
+        let item = invoking_event.configuration_item.unwrap();
+        assert_eq!(item.resource_type, "AWS::EC2::Instance");
+        assert_eq!(item.tags["team"], "payments");
+        assert_eq!(item.relationships[0].resource_type, "AWS::EC2::Volume");
+        assert!(!item.is_deleted());
+        let instance: Instance = item.deserialize_configuration().unwrap();
+        assert_eq!(instance.instance_type, "t2.micro");
+
+        let json = serde_json::to_string(&event).expect("Could not serialize Config event");
+        assert_eq!(serde_json::from_str::<ConfigEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn deserializes_scheduled_events() {
+        let event = ConfigEvent {
+            invoking_event: String::from(
+                r#"{"awsAccountId":"123456789012","notificationCreationTime":"2016-02-17T01:36:34.043Z","messageType":"ScheduledNotification"}"#,
+            ),
+            ..ConfigEvent::default()
+        };
+        let invoking_event = event.invoking_event().unwrap();
+        assert!(invoking_event.is_scheduled());
+        assert_eq!(invoking_event.configuration_item, None);
+        assert!(event.deserialize_rule_parameters::<Value>().is_none());
+    }
+}
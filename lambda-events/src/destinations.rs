@@ -0,0 +1,214 @@
+//! Lambda Destinations records, sent to functions configured as the on-success or
+//! on-failure destination of the asynchronous invocations of another function.
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The record of an asynchronous invocation, with the event of the invocation
+/// deserialized as `Req` and the response of the function as `Resp`.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::destinations::DestinationRecord;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: DestinationRecord, _ctx: Context) -> Result<(), HandlerError> {
+///     if let Some(error) = e.function_error() {
+///         println!("{} failed: {}", e.request_context.request_id, error.error_message);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DestinationRecord<Req = Value, Resp = Value> {
+    /// The version of the record format, for example `1.0`.
+    pub version: String,
+    /// The time the record was sent.
+    pub timestamp: DateTime<Utc>,
+    /// The invocation.
+    pub request_context: DestinationRequestContext,
+    /// The event of the invocation.
+    pub request_payload: Req,
+    /// The outcome of the last attempt, `None` if the function was never invoked, for
+    /// example when the event expired in the queue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_context: Option<DestinationResponseContext>,
+    /// The response of the last attempt, or the error it returned. `None` if the
+    /// function was never invoked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_payload: Option<Resp>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<Req, Resp> DestinationRecord<Req, Resp> {
+    /// Returns whether the invocation succeeded.
+    pub fn is_success(&self) -> bool {
+        self.request_context.condition == DestinationCondition::Success
+    }
+}
+
+impl<Req> DestinationRecord<Req, Value> {
+    /// Returns the error the function returned, when the last attempt failed with an
+    /// error.
+    pub fn function_error(&self) -> Option<InvocationError> {
+        self.response_context.as_ref()?.function_error.as_ref()?;
+        self.response_payload
+            .as_ref()
+            .and_then(|payload| serde_json::from_value(payload.clone()).ok())
+    }
+
+    /// Deserializes the response of the function, for records of successful
+    /// invocations.
+    ///
+    /// # Return
+    /// The response parsed as `T`, `None` if there is none, or the error if the
+    /// response is not valid for `T`.
+    pub fn deserialize_response<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.response_payload
+            .as_ref()
+            .map(|payload| serde_json::from_value(payload.clone()))
+    }
+}
+
+/// An asynchronous invocation.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DestinationRequestContext {
+    /// The id of the invocation.
+    pub request_id: String,
+    /// The ARN of the function, with the version or alias that was invoked.
+    pub function_arn: String,
+    /// Why the record was sent.
+    pub condition: DestinationCondition,
+    /// The number of times the function was invoked.
+    pub approximate_invoke_count: u32,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Why a destination record was sent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DestinationCondition {
+    /// The invocation succeeded.
+    #[default]
+    Success,
+    /// All attempts failed.
+    RetriesExhausted,
+    /// The event expired before it could be processed.
+    EventAgeExceeded,
+}
+
+/// The outcome of the last attempt of an asynchronous invocation.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DestinationResponseContext {
+    /// The status code of the invocation, `200` even when the function returned an
+    /// error.
+    pub status_code: u16,
+    /// The version of the function that was invoked.
+    pub executed_version: String,
+    /// `Handled` or `Unhandled` when the function returned an error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_error: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An error returned by a function, as sent in the response payload of destination
+/// records.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvocationError {
+    /// The message of the error.
+    pub error_message: String,
+    /// The type of the error.
+    #[serde(default)]
+    pub error_type: String,
+    /// The stack trace of the error, if the runtime sent one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stack_trace: Vec<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        order_id: String,
+        quantity: u32,
+    }
+
+    #[test]
+    fn deserializes_failure_records() {
+        let input = include_str!("../tests/data/destination_on_failure.json");
+        let record: DestinationRecord<Order> = serde_json::from_str(input).expect("Could not parse record");
+        assert!(!record.is_success());
+        assert_eq!(record.request_context.condition, DestinationCondition::RetriesExhausted);
+        assert_eq!(record.request_context.approximate_invoke_count, 3);
+        assert_eq!(record.request_payload.order_id, "o-123");
+
+        let error = record.function_error().expect("Expected a function error");
+        assert_eq!(error.error_type, "Runtime.ExitError");
+
+        let json = serde_json::to_string(&record).expect("Could not serialize record");
+        assert_eq!(serde_json::from_str::<DestinationRecord<Order>>(&json).unwrap(), record);
+    }
+
+    #[test]
+    fn deserializes_success_records() {
+        let record: DestinationRecord = serde_json::from_value(serde_json::json!({
+            "version": "1.0",
+            "timestamp": "2019-11-24T23:08:25.651Z",
+            "requestContext": {
+                "requestId": "c2a6f2ae-7dbb-4d22-8782-d0485c9877e2",
+                "functionArn": "arn:aws:lambda:us-east-2:123456789012:function:my-function:$LATEST",
+                "condition": "Success",
+                "approximateInvokeCount": 1
+            },
+            "requestPayload": {"orderId": "o-123", "quantity": 2},
+            "responseContext": {"statusCode": 200, "executedVersion": "$LATEST"},
+            "responsePayload": {"orderId": "o-123", "quantity": 3}
+        }))
+        .expect("Could not parse record");
+        assert!(record.is_success());
+        assert_eq!(record.function_error(), None);
+        assert_eq!(record.deserialize_response::<Order>().unwrap().unwrap().quantity, 3);
+
+        let expired: DestinationRecord = serde_json::from_value(serde_json::json!({
+            "version": "1.0",
+            "timestamp": "2019-11-24T23:08:25.651Z",
+            "requestContext": {
+                "requestId": "c2a6f2ae-7dbb-4d22-8782-d0485c9877e2",
+                "functionArn": "arn:aws:lambda:us-east-2:123456789012:function:my-function:$LATEST",
+                "condition": "EventAgeExceeded",
+                "approximateInvokeCount": 0
+            },
+            "requestPayload": {}
+        }))
+        .expect("Could not parse record");
+        assert_eq!(expired.response_context, None);
+        assert_eq!(expired.function_error(), None);
+    }
+}
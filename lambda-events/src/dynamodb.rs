@@ -0,0 +1,418 @@
+//! Amazon DynamoDB Streams events, sent to functions that process the changes made to
+//! the items of a table.
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, ser::Error as SerError};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    batch::{process_stream, StreamsEventResponse},
+    encodings::{deserialize_lambda_map, Base64Data, SecondTimestamp},
+};
+
+/// The attributes of an item, by name.
+pub type Item = HashMap<String, AttributeValue>;
+
+/// A batch of changes read from a stream.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DynamodbEvent {
+    /// The change records, in the order of their sequence numbers.
+    #[serde(rename = "Records")]
+    pub records: Vec<DynamodbEventRecord>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl DynamodbEvent {
+    /// Runs `handler` for each record of the batch in order and returns the response
+    /// that reports the first failed record, for functions whose event source mapping
+    /// enables `ReportBatchItemFailures`. The records after a failed record are not
+    /// passed to `handler`, as Lambda retries them with the failed record.
+    pub fn process<F, T, E>(&self, handler: F) -> StreamsEventResponse
+    where
+        F: FnMut(&DynamodbEventRecord) -> Result<T, E>,
+    {
+        process_stream(&self.records, |record| &record.dynamodb.sequence_number, handler)
+    }
+}
+
+/// A change made to an item of the table.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamodbEventRecord {
+    /// The unique id of the record.
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    /// The type of the change.
+    pub event_name: EventName,
+    /// The version of the record format, for example `1.1`.
+    pub event_version: String,
+    /// Always `aws:dynamodb`.
+    pub event_source: String,
+    /// The region of the table.
+    pub aws_region: String,
+    /// The change.
+    pub dynamodb: StreamRecord,
+    /// The ARN of the stream.
+    #[serde(rename = "eventSourceARN")]
+    pub event_source_arn: String,
+    /// The principal that made the change. Only set for items deleted by the time to
+    /// live process.
+    #[serde(default)]
+    pub user_identity: Option<UserIdentity>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The type of a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EventName {
+    /// A new item was added to the table.
+    Insert,
+    /// One or more attributes of an existing item were changed.
+    Modify,
+    /// An item was deleted from the table.
+    Remove,
+}
+
+/// The principal that made a change.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserIdentity {
+    /// The type of the principal, `Service` for the time to live process.
+    #[serde(rename = "type")]
+    pub identity_type: String,
+    /// The id of the principal, `dynamodb.amazonaws.com` for the time to live process.
+    pub principal_id: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The images of the changed item. Which images are included depends on the stream
+/// view type of the stream.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StreamRecord {
+    /// The time the change was made, rounded down to the nearest second.
+    #[serde(default)]
+    pub approximate_creation_date_time: Option<SecondTimestamp>,
+    /// The primary key attributes of the item.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub keys: Item,
+    /// The item after the change, empty for removals and for streams that don't include
+    /// new images.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub new_image: Item,
+    /// The item before the change, empty for insertions and for streams that don't
+    /// include old images.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub old_image: Item,
+    /// The sequence number of the record. Sequence numbers are decimal numbers that can
+    /// be larger than 64 bits, so they are kept as strings.
+    pub sequence_number: String,
+    /// The size of the record in bytes.
+    pub size_bytes: u64,
+    /// The images included in the records of the stream.
+    pub stream_view_type: StreamViewType,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl StreamRecord {
+    /// Converts the item after the change into `T` with `from_item()`.
+    ///
+    /// # Return
+    /// `None` if the record has no new image, otherwise the result of the conversion.
+    pub fn deserialize_new_image<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        image_as(&self.new_image)
+    }
+
+    /// Converts the item before the change into `T` with `from_item()`.
+    ///
+    /// # Return
+    /// `None` if the record has no old image, otherwise the result of the conversion.
+    pub fn deserialize_old_image<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        image_as(&self.old_image)
+    }
+}
+
+fn image_as<T: DeserializeOwned>(image: &Item) -> Option<Result<T, serde_json::Error>> {
+    if image.is_empty() {
+        None
+    } else {
+        Some(from_item(image))
+    }
+}
+
+/// The images a stream includes in its records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StreamViewType {
+    /// Only the key attributes of the item.
+    KeysOnly,
+    /// The item after the change.
+    NewImage,
+    /// The item before the change.
+    OldImage,
+    /// The item before and after the change.
+    NewAndOldImages,
+}
+
+/// The value of an attribute, tagged with its DynamoDB data type. Numbers are kept as
+/// strings, as DynamoDB supports more precision than the Rust number types.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum AttributeValue {
+    /// A string.
+    #[serde(rename = "S")]
+    S(String),
+    /// A number.
+    #[serde(rename = "N")]
+    N(String),
+    /// Binary data.
+    #[serde(rename = "B")]
+    B(Base64Data),
+    /// A boolean.
+    #[serde(rename = "BOOL")]
+    Bool(bool),
+    /// A null value. The flag is always `true`.
+    #[serde(rename = "NULL")]
+    Null(bool),
+    /// A set of strings.
+    #[serde(rename = "SS")]
+    Ss(Vec<String>),
+    /// A set of numbers.
+    #[serde(rename = "NS")]
+    Ns(Vec<String>),
+    /// A set of binary values.
+    #[serde(rename = "BS")]
+    Bs(Vec<Base64Data>),
+    /// A list of values.
+    #[serde(rename = "L")]
+    L(Vec<AttributeValue>),
+    /// A map of values.
+    #[serde(rename = "M")]
+    M(Item),
+}
+
+/// Converts an item into a user type, using the `Deserialize` implementation of the
+/// type. The item is read like a JSON object: numbers become JSON numbers, except for
+/// integers that don't fit into 64 bits, which stay strings to keep their precision,
+/// binary values become base64 strings that can be read with `Base64Data`, sets and
+/// lists become arrays, and maps become objects.
+///
+/// ```rust
+/// use lambda_events::dynamodb::{from_item, AttributeValue, Item};
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Order {
+///     id: u64,
+///     tags: Vec<String>,
+/// }
+///
+/// let mut item = Item::new();
+/// item.insert(String::from("id"), AttributeValue::N(String::from("101")));
+/// item.insert(String::from("tags"), AttributeValue::Ss(vec![String::from("new")]));
+/// let order: Order = from_item(&item).expect("Could not convert item");
+/// assert_eq!(order.id, 101);
+/// assert_eq!(order.tags, vec!["new"]);
+/// ```
+pub fn from_item<T: DeserializeOwned>(item: &Item) -> Result<T, serde_json::Error> {
+    serde_json::from_value(map_to_json(item))
+}
+
+/// Converts a user type into an item, using the `Serialize` implementation of the type.
+/// The value must serialize to a map. Strings, numbers, booleans, and unit values become
+/// `S`, `N`, `BOOL`, and `NULL` attributes, sequences become lists, and maps become maps.
+/// Sets and binary values can't be told apart from lists and strings, so they are never
+/// produced.
+///
+/// # Return
+/// The item, or an error if `value` can't be serialized or is not a map.
+pub fn to_item<T: serde::Serialize>(value: &T) -> Result<Item, serde_json::Error> {
+    match serde_json::to_value(value)? {
+        Value::Object(map) => Ok(object_to_item(map)),
+        other => Err(serde_json::Error::custom(format!(
+            "An item must be a map, but the value serialized to {}",
+            other
+        ))),
+    }
+}
+
+fn map_to_json(item: &Item) -> Value {
+    Value::Object(item.iter().map(|(k, v)| (k.clone(), attribute_to_json(v))).collect())
+}
+
+fn attribute_to_json(value: &AttributeValue) -> Value {
+    match value {
+        AttributeValue::S(s) => Value::String(s.clone()),
+        AttributeValue::N(n) => number_to_json(n),
+        AttributeValue::B(b) => Value::String(base64::encode(&b.0)),
+        AttributeValue::Bool(b) => Value::Bool(*b),
+        AttributeValue::Null(_) => Value::Null,
+        AttributeValue::Ss(values) => values.iter().cloned().map(Value::String).collect(),
+        AttributeValue::Ns(values) => values.iter().map(|n| number_to_json(n)).collect(),
+        AttributeValue::Bs(values) => values.iter().map(|b| Value::String(base64::encode(&b.0))).collect(),
+        AttributeValue::L(values) => values.iter().map(attribute_to_json).collect(),
+        AttributeValue::M(map) => map_to_json(map),
+    }
+}
+
+fn number_to_json(n: &str) -> Value {
+    let number = if n.contains(['.', 'e', 'E']) {
+        n.parse::<f64>().ok().and_then(Number::from_f64)
+    } else {
+        n.parse::<i64>()
+            .map(Number::from)
+            .or_else(|_| n.parse::<u64>().map(Number::from))
+            .ok()
+    };
+    number
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(String::from(n)))
+}
+
+fn json_to_attribute(value: Value) -> AttributeValue {
+    match value {
+        Value::Null => AttributeValue::Null(true),
+        Value::Bool(b) => AttributeValue::Bool(b),
+        Value::Number(n) => AttributeValue::N(n.to_string()),
+        Value::String(s) => AttributeValue::S(s),
+        Value::Array(values) => AttributeValue::L(values.into_iter().map(json_to_attribute).collect()),
+        Value::Object(map) => AttributeValue::M(object_to_item(map)),
+    }
+}
+
+fn object_to_item(map: Map<String, Value>) -> Item {
+    map.into_iter().map(|(k, v)| (k, json_to_attribute(v))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[test]
+    fn deserializes_stream_records() {
+        let input = include_str!("../tests/data/dynamodb_event.json");
+        let event: DynamodbEvent = serde_json::from_str(input).expect("Could not parse DynamoDB event");
+
+        let insert = &event.records[0];
+        assert_eq!(insert.event_name, EventName::Insert);
+        assert_eq!(insert.dynamodb.stream_view_type, StreamViewType::NewAndOldImages);
+        assert_eq!(insert.dynamodb.sequence_number, "4421584500000000017450439091");
+        assert_eq!(
+            insert.dynamodb.approximate_creation_date_time.unwrap().timestamp(),
+            1_428_537_600
+        );
+        assert_eq!(insert.dynamodb.keys["Id"], AttributeValue::N(String::from("101")));
+        assert!(insert.dynamodb.old_image.is_empty());
+        assert!(insert.user_identity.is_none());
+
+        let image = &insert.dynamodb.new_image;
+        assert_eq!(image["Message"], AttributeValue::S(String::from("New item!")));
+        assert_eq!(image["Thumbnail"], AttributeValue::B(Base64Data(b"image".to_vec())));
+        assert_eq!(image["Active"], AttributeValue::Bool(true));
+        assert_eq!(image["Discount"], AttributeValue::Null(true));
+        assert_eq!(
+            image["Tags"],
+            AttributeValue::Ss(vec![String::from("new"), String::from("sale")])
+        );
+        match &image["Dimensions"] {
+            AttributeValue::M(dimensions) => assert_eq!(
+                dimensions["Sizes"],
+                AttributeValue::L(vec![
+                    AttributeValue::N(String::from("1")),
+                    AttributeValue::S(String::from("large"))
+                ])
+            ),
+            other => panic!("Unexpected dimensions: {:?}", other),
+        }
+
+        let remove = &event.records[1];
+        assert_eq!(remove.event_name, EventName::Remove);
+        assert!(remove.dynamodb.new_image.is_empty());
+        assert_eq!(remove.user_identity.as_ref().unwrap().identity_type, "Service");
+
+        let json = serde_json::to_string(&event).expect("Could not serialize DynamoDB event");
+        assert_eq!(serde_json::from_str::<DynamodbEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn failed_records_checkpoint_the_stream() {
+        let input = include_str!("../tests/data/dynamodb_event.json");
+        let event: DynamodbEvent = serde_json::from_str(input).expect("Could not parse DynamoDB event");
+        let response = event.process(|record| match record.event_name {
+            EventName::Remove => Err("Could not remove item"),
+            _ => Ok(()),
+        });
+        assert_eq!(response.batch_item_failures.len(), 1);
+        assert_eq!(
+            response.batch_item_failures[0].item_identifier,
+            "4421584500000000017450439093"
+        );
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct Message {
+        id: u64,
+        message: String,
+        tags: Vec<String>,
+        thumbnail: Base64Data,
+        active: bool,
+        discount: Option<f64>,
+        dimensions: Dimensions,
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    struct Dimensions {
+        width: f64,
+        sizes: Vec<Value>,
+    }
+
+    #[test]
+    fn images_convert_to_user_types() {
+        let input = include_str!("../tests/data/dynamodb_event.json");
+        let event: DynamodbEvent = serde_json::from_str(input).expect("Could not parse DynamoDB event");
+        let record = &event.records[0].dynamodb;
+        assert!(record.deserialize_old_image::<Message>().is_none());
+
+        let message: Message = record
+            .deserialize_new_image()
+            .unwrap()
+            .expect("Could not convert new image");
+        assert_eq!(message.id, 101);
+        assert_eq!(message.tags, vec!["new", "sale"]);
+        assert_eq!(&*message.thumbnail, b"image");
+        assert_eq!(message.discount, None);
+        assert_eq!(message.dimensions.width, 10.5);
+        assert_eq!(message.dimensions.sizes, vec![Value::from(1), Value::from("large")]);
+
+        let item = to_item(&message).expect("Could not convert message");
+        assert_eq!(item["Id"], AttributeValue::N(String::from("101")));
+        assert_eq!(item["Discount"], AttributeValue::Null(true));
+        assert_eq!(item["Thumbnail"], AttributeValue::S(String::from("aW1hZ2U=")));
+        assert_eq!(from_item::<Message>(&item).unwrap(), message);
+        assert!(to_item(&"not a map").is_err());
+
+        let mut item = Item::new();
+        item.insert(
+            String::from("n"),
+            AttributeValue::N(String::from("123456789012345678901234567890")),
+        );
+        let value: Value = from_item(&item).unwrap();
+        assert_eq!(value["n"], "123456789012345678901234567890");
+    }
+}
@@ -0,0 +1,219 @@
+//! The details of the Amazon ECS task state changes delivered by EventBridge.
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::eventbridge::EventBridgeEvent;
+
+/// The `detail_type` of task state changes.
+pub const TASK_STATE_CHANGE_DETAIL_TYPE: &str = "ECS Task State Change";
+
+/// An `ECS Task State Change` event, sent when the status of a task or of one of its
+/// containers changes.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::ecs::EcsTaskStateChangeEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: EcsTaskStateChangeEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     if e.detail.is_stopped() {
+///         for container in e.detail.failed_containers() {
+///             println!("{} exited with {:?}", container.name, container.exit_code);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub type EcsTaskStateChangeEvent = EventBridgeEvent<EcsTaskStateChangeDetail>;
+
+/// The detail of an `ECS Task State Change` event, in the format of the
+/// `DescribeTasks` API.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EcsTaskStateChangeDetail {
+    /// The ARN of the cluster.
+    pub cluster_arn: String,
+    /// The ARN of the task.
+    pub task_arn: String,
+    /// The ARN of the task definition.
+    pub task_definition_arn: String,
+    /// The status of the task, for example `PENDING`, `RUNNING`, or `STOPPED`.
+    pub last_status: String,
+    /// The status the task is moving to.
+    pub desired_status: String,
+    /// The containers of the task.
+    #[serde(default)]
+    pub containers: Vec<EcsContainer>,
+    /// `EC2`, `FARGATE`, or `EXTERNAL`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_type: Option<String>,
+    /// The number of CPU units of the task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<String>,
+    /// The memory of the task, in MiB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    /// The group of the task, for example `service:my-service` or `family:my-family`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// What started the task, for example the deployment of a service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_by: Option<String>,
+    /// The availability zone of the task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability_zone: Option<String>,
+    /// The network interfaces and other resources attached to the task.
+    #[serde(default)]
+    pub attachments: Vec<EcsAttachment>,
+    /// The overrides of the task definition the task was run with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<Value>,
+    /// Why the task stopped, for example `EssentialContainerExited` or `SpotInterruption`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_code: Option<String>,
+    /// The description of why the task stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped_reason: Option<String>,
+    /// The time the task was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// The time the task started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    /// The time the task started stopping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopping_at: Option<DateTime<Utc>>,
+    /// The time the task stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stopped_at: Option<DateTime<Utc>>,
+    /// The time of the change.
+    pub updated_at: DateTime<Utc>,
+    /// The version of the task, incremented on every change. Events can arrive out of
+    /// order: ignore events with a lower version than the last one seen.
+    pub version: u64,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl EcsTaskStateChangeDetail {
+    /// Returns whether the task stopped.
+    pub fn is_stopped(&self) -> bool {
+        self.last_status == "STOPPED"
+    }
+
+    /// Returns the container with the given name.
+    pub fn container(&self, name: &str) -> Option<&EcsContainer> {
+        self.containers.iter().find(|c| c.name == name)
+    }
+
+    /// Returns the containers that exited with a non-zero code.
+    pub fn failed_containers(&self) -> impl Iterator<Item = &EcsContainer> {
+        self.containers
+            .iter()
+            .filter(|c| c.exit_code.is_some_and(|code| code != 0))
+    }
+}
+
+/// A container of a task.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EcsContainer {
+    /// The ARN of the container.
+    pub container_arn: String,
+    /// The ARN of the task of the container.
+    pub task_arn: String,
+    /// The name of the container, from the task definition.
+    pub name: String,
+    /// The status of the container.
+    pub last_status: String,
+    /// The exit code of the container, once it stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Why the container stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The image of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// The digest of the image of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_digest: Option<String>,
+    /// The id of the container in the container runtime.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_id: Option<String>,
+    /// The number of CPU units of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<String>,
+    /// The network interfaces of the container.
+    #[serde(default)]
+    pub network_interfaces: Vec<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A resource attached to a task, for example an elastic network interface.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EcsAttachment {
+    /// The id of the attachment.
+    pub id: String,
+    /// The type of the attachment, for example `eni`.
+    #[serde(rename = "type")]
+    pub attachment_type: String,
+    /// The status of the attachment.
+    pub status: String,
+    /// The details of the attachment, for example the id of the subnet.
+    #[serde(default)]
+    pub details: Vec<EcsKeyValuePair>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A name and a value.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EcsKeyValuePair {
+    /// The name.
+    pub name: String,
+    /// The value.
+    pub value: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_task_state_changes() {
+        let input = include_str!("../tests/data/ecs_task_state_change.json");
+        let event: EcsTaskStateChangeEvent = serde_json::from_str(input).expect("Could not parse ECS event");
+        assert_eq!(event.detail_type, TASK_STATE_CHANGE_DETAIL_TYPE);
+
+        let task = &event.detail;
+        assert!(task.is_stopped());
+        assert_eq!(task.version, 4);
+        assert_eq!(task.stop_code.as_deref(), Some("EssentialContainerExited"));
+        assert_eq!(task.attachments[0].details[0].value, "subnet-abcd1234");
+        assert_eq!(task.container("FargateApp").unwrap().exit_code, Some(137));
+        assert_eq!(task.failed_containers().count(), 1);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize ECS event");
+        assert_eq!(serde_json::from_str::<EcsTaskStateChangeEvent>(&json).unwrap(), event);
+    }
+}
@@ -0,0 +1,179 @@
+//! Types for the encodings AWS services use for values inside event payloads, such as
+//! base64 encoded binary data and timestamps in seconds or milliseconds since the epoch.
+use std::{collections::HashMap, fmt, hash::Hash, ops::Deref};
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{
+    de::{Error as DeError, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// Binary data sent as a base64 encoded string, decoded when the event is deserialized.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Base64Data(pub Vec<u8>);
+
+impl Deref for Base64Data {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Serialize for Base64Data {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Base64Data {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base64::decode(&encoded).map(Base64Data).map_err(DeError::custom)
+    }
+}
+
+/// A timestamp sent as the number of seconds since the epoch, with an optional
+/// fractional part, as used by Kinesis and DynamoDB Streams.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SecondTimestamp(pub DateTime<Utc>);
+
+impl Deref for SecondTimestamp {
+    type Target = DateTime<Utc>;
+
+    fn deref(&self) -> &DateTime<Utc> {
+        &self.0
+    }
+}
+
+impl Serialize for SecondTimestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0.timestamp_millis() as f64 / 1000.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecondTimestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let millis = deserializer.deserialize_any(EpochVisitor { scale: 1000.0 })?;
+        from_millis(millis).map(SecondTimestamp)
+    }
+}
+
+/// A timestamp sent as the number of milliseconds since the epoch, as used by
+/// CloudWatch Logs and SQS.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MillisecondTimestamp(pub DateTime<Utc>);
+
+impl Deref for MillisecondTimestamp {
+    type Target = DateTime<Utc>;
+
+    fn deref(&self) -> &DateTime<Utc> {
+        &self.0
+    }
+}
+
+impl Serialize for MillisecondTimestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.0.timestamp_millis())
+    }
+}
+
+impl<'de> Deserialize<'de> for MillisecondTimestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let millis = deserializer.deserialize_any(EpochVisitor { scale: 1.0 })?;
+        from_millis(millis).map(MillisecondTimestamp)
+    }
+}
+
+fn from_millis<E: DeError>(millis: f64) -> Result<DateTime<Utc>, E> {
+    Utc.timestamp_millis_opt(millis.round() as i64)
+        .single()
+        .ok_or_else(|| E::custom(format!("Timestamp out of range: {}", millis)))
+}
+
+/// Reads a number, or a string containing a number, and converts it to milliseconds.
+struct EpochVisitor {
+    scale: f64,
+}
+
+impl<'de> Visitor<'de> for EpochVisitor {
+    type Value = f64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a timestamp since the epoch")
+    }
+
+    fn visit_f64<E: DeError>(self, value: f64) -> Result<f64, E> {
+        Ok(value * self.scale)
+    }
+
+    fn visit_i64<E: DeError>(self, value: i64) -> Result<f64, E> {
+        Ok(value as f64 * self.scale)
+    }
+
+    fn visit_u64<E: DeError>(self, value: u64) -> Result<f64, E> {
+        Ok(value as f64 * self.scale)
+    }
+
+    fn visit_str<E: DeError>(self, value: &str) -> Result<f64, E> {
+        value.parse::<f64>().map(|v| v * self.scale).map_err(DeError::custom)
+    }
+}
+
+/// Deserializes a map that AWS sends as `null` when it is empty. Use it with
+/// `#[serde(deserialize_with = "deserialize_lambda_map")]` in custom event types.
+pub fn deserialize_lambda_map<'de, D, K, V>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+{
+    Ok(Option::<HashMap<K, V>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Deserializes a value that AWS sends as `null` when it isn't set, such as lists,
+/// as the default value of its type.
+pub fn deserialize_nullable<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[test]
+    fn base64_data_is_decoded() {
+        let data: Base64Data = serde_json::from_str("\"aGVsbG8=\"").expect("Could not parse data");
+        assert_eq!(&*data, b"hello");
+        assert_eq!(serde_json::to_string(&data).unwrap(), "\"aGVsbG8=\"");
+        assert!(serde_json::from_str::<Base64Data>("\"not base64!\"").is_err());
+    }
+
+    #[test]
+    fn timestamps_are_parsed() {
+        let seconds: SecondTimestamp = serde_json::from_str("1428537600.123").expect("Could not parse seconds");
+        assert_eq!(seconds.timestamp_millis(), 1_428_537_600_123);
+        let millis: MillisecondTimestamp = serde_json::from_str("1428537600123").expect("Could not parse millis");
+        assert_eq!(millis.0, seconds.0);
+        let millis: MillisecondTimestamp = serde_json::from_str("\"1428537600123\"").expect("Could not parse string");
+        assert_eq!(millis.0, seconds.0);
+        assert_eq!(serde_json::to_string(&seconds).unwrap(), "1428537600.123");
+    }
+
+    #[test]
+    fn null_maps_are_empty() {
+        #[derive(Deserialize)]
+        struct Event {
+            #[serde(deserialize_with = "deserialize_lambda_map")]
+            attributes: HashMap<String, String>,
+        }
+
+        let event: Event = serde_json::from_str(r#"{"attributes":null}"#).expect("Could not parse event");
+        assert!(event.attributes.is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+//! Amazon EventBridge and CloudWatch Events events, sent to functions that are the
+//! target of a rule, including the events of scheduled rules.
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// An event matched by a rule. The `detail` is specific to the source and type of the
+/// event: use a struct that matches it, or the default `serde_json::Value`.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::eventbridge::EventBridgeEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct StateChange {
+///     #[serde(rename = "instance-id")]
+///     instance_id: String,
+///     state: String,
+/// }
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: EventBridgeEvent<StateChange>, _ctx: Context) -> Result<(), HandlerError> {
+///     println!("{} is {}", e.detail.instance_id, e.detail.state);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EventBridgeEvent<T = Value> {
+    /// The version of the event format, always `0`.
+    pub version: String,
+    /// The unique id of the event.
+    pub id: String,
+    /// The type of the event, for example `EC2 Instance State-change Notification`.
+    pub detail_type: String,
+    /// The service or application that sent the event, for example `aws.ec2`.
+    pub source: String,
+    /// The AWS account id of the event bus.
+    pub account: String,
+    /// The time the event occurred, as reported by its source.
+    pub time: DateTime<Utc>,
+    /// The region of the event bus.
+    pub region: String,
+    /// The ARNs of the resources the event is about.
+    #[serde(default)]
+    pub resources: Vec<String>,
+    /// The name of the replay, for events sent again by a replay of an archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_name: Option<String>,
+    /// The details of the event.
+    pub detail: T,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The `detail_type` of the events sent by scheduled rules.
+pub const SCHEDULED_EVENT_DETAIL_TYPE: &str = "Scheduled Event";
+
+/// An event sent by a scheduled rule. The details of scheduled events are always empty,
+/// and the ARN of the rule is the only resource.
+///
+/// ```rust
+/// use lambda_events::eventbridge::ScheduledEvent;
+///
+/// fn report_name(event: &ScheduledEvent) -> &str {
+///     event.rule_name().unwrap_or("unknown")
+/// }
+/// ```
+pub type ScheduledEvent = EventBridgeEvent<Value>;
+
+impl<T> EventBridgeEvent<T> {
+    /// Returns whether the event was sent by a scheduled rule.
+    pub fn is_scheduled(&self) -> bool {
+        self.source == "aws.events" && self.detail_type == SCHEDULED_EVENT_DETAIL_TYPE
+    }
+
+    /// Returns the ARN of the rule that sent the event, for events whose resources
+    /// include the rule, such as scheduled events.
+    pub fn rule_arn(&self) -> Option<&str> {
+        self.resources
+            .iter()
+            .map(String::as_str)
+            .find(|arn| arn.starts_with("arn:") && arn.split(':').nth(5).is_some_and(|r| r.starts_with("rule/")))
+    }
+
+    /// Returns the name of the rule that sent the event. Rules of custom event buses
+    /// have ARNs like `rule/<bus>/<name>`, only the name is returned.
+    pub fn rule_name(&self) -> Option<&str> {
+        self.rule_arn()?.rsplit('/').next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    struct StateChange {
+        #[serde(rename = "instance-id")]
+        instance_id: String,
+        state: String,
+    }
+
+    #[test]
+    fn deserializes_typed_details() {
+        let input = include_str!("../tests/data/eventbridge_event.json");
+        let event: EventBridgeEvent<StateChange> = serde_json::from_str(input).expect("Could not parse event");
+        assert_eq!(event.detail_type, "EC2 Instance State-change Notification");
+        assert_eq!(event.time.timestamp(), 1_447_277_394);
+        assert_eq!(event.detail.state, "pending");
+        assert_eq!(event.replay_name, None);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize event");
+        assert_eq!(
+            serde_json::from_str::<EventBridgeEvent<StateChange>>(&json).unwrap(),
+            event
+        );
+
+        let untyped: EventBridgeEvent = serde_json::from_str(input).expect("Could not parse event");
+        assert_eq!(untyped.detail["instance-id"], "i-abcd1111");
+    }
+
+    #[test]
+    fn scheduled_events_have_rule_names() {
+        let input = include_str!("../tests/data/scheduled_event.json");
+        let mut event: ScheduledEvent = serde_json::from_str(input).expect("Could not parse event");
+        assert!(event.is_scheduled());
+        assert_eq!(
+            event.rule_arn(),
+            Some("arn:aws:events:us-east-1:123456789012:rule/nightly-report")
+        );
+        assert_eq!(event.rule_name(), Some("nightly-report"));
+
+        event.resources = vec![String::from(
+            "arn:aws:events:us-east-1:123456789012:rule/orders-bus/on-order",
+        )];
+        assert_eq!(event.rule_name(), Some("on-order"));
+
+        let input = include_str!("../tests/data/eventbridge_event.json");
+        let event: EventBridgeEvent = serde_json::from_str(input).expect("Could not parse event");
+        assert!(!event.is_scheduled());
+        assert_eq!(event.rule_name(), None);
+    }
+}
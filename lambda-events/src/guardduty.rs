@@ -0,0 +1,228 @@
+//! The details of the Amazon GuardDuty findings delivered by EventBridge.
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::eventbridge::EventBridgeEvent;
+
+/// The `detail_type` of GuardDuty findings.
+pub const GUARDDUTY_FINDING_DETAIL_TYPE: &str = "GuardDuty Finding";
+
+/// A `GuardDuty Finding` event, sent when a finding is created or updated.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::guardduty::{GuardDutyFindingEvent, SeverityLevel};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: GuardDutyFindingEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     if e.detail.severity_level() >= SeverityLevel::High {
+///         println!("paging: {}", e.detail.title);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub type GuardDutyFindingEvent = EventBridgeEvent<GuardDutyFinding>;
+
+/// A GuardDuty finding.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardDutyFinding {
+    /// The version of the finding format, for example `2.0`.
+    pub schema_version: String,
+    /// The AWS account of the resource.
+    pub account_id: String,
+    /// The region of the resource.
+    pub region: String,
+    /// The partition of the resource, for example `aws`.
+    pub partition: String,
+    /// The id of the finding.
+    pub id: String,
+    /// The ARN of the finding.
+    pub arn: String,
+    /// The type of the finding, for example `UnauthorizedAccess:EC2/SSHBruteForce`.
+    #[serde(rename = "type")]
+    pub finding_type: String,
+    /// The resource the finding is about.
+    pub resource: GuardDutyResource,
+    /// The activity that caused the finding.
+    pub service: GuardDutyService,
+    /// The severity of the finding, from 1 to 10. Use `severity_level()` to get the
+    /// level shown in the console.
+    pub severity: f64,
+    /// The time the finding was created.
+    pub created_at: DateTime<Utc>,
+    /// The time the finding was last updated.
+    pub updated_at: DateTime<Utc>,
+    /// The title of the finding.
+    pub title: String,
+    /// The description of the finding.
+    pub description: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl GuardDutyFinding {
+    /// Returns the level of the severity of the finding.
+    pub fn severity_level(&self) -> SeverityLevel {
+        SeverityLevel::from_severity(self.severity)
+    }
+
+    /// Returns whether the finding is a sample generated from the console or the API.
+    pub fn is_sample(&self) -> bool {
+        self.service.additional_info.get("sample") == Some(&Value::Bool(true))
+    }
+}
+
+/// The level of the severity of a finding.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum SeverityLevel {
+    /// A severity from 1.0 to 3.9.
+    #[default]
+    Low,
+    /// A severity from 4.0 to 6.9.
+    Medium,
+    /// A severity from 7.0 to 8.9.
+    High,
+    /// A severity from 9.0.
+    Critical,
+}
+
+impl SeverityLevel {
+    /// Returns the level of a severity.
+    pub fn from_severity(severity: f64) -> Self {
+        if severity >= 9.0 {
+            SeverityLevel::Critical
+        } else if severity >= 7.0 {
+            SeverityLevel::High
+        } else if severity >= 4.0 {
+            SeverityLevel::Medium
+        } else {
+            SeverityLevel::Low
+        }
+    }
+}
+
+/// The resource a finding is about. Only the details of the type of the resource are
+/// set.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardDutyResource {
+    /// The type of the resource, for example `Instance`, `AccessKey`, `S3Bucket`, or
+    /// `EKSCluster`.
+    pub resource_type: String,
+    /// The access key, for findings about IAM credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_key_details: Option<GuardDutyAccessKeyDetails>,
+    /// The EC2 instance, in the format of the `DescribeInstances` API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_details: Option<Value>,
+    /// The S3 buckets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub s3_bucket_details: Option<Value>,
+    /// The EKS cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eks_cluster_details: Option<Value>,
+    /// The Kubernetes workload and user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kubernetes_details: Option<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The access key a finding is about.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardDutyAccessKeyDetails {
+    /// The id of the access key.
+    pub access_key_id: String,
+    /// The id of the principal of the access key.
+    pub principal_id: String,
+    /// The name of the user of the access key.
+    pub user_name: String,
+    /// The type of the user, for example `IAMUser` or `AssumedRole`.
+    pub user_type: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The activity that caused a finding.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuardDutyService {
+    /// Always `guardduty`.
+    pub service_name: String,
+    /// The id of the detector that created the finding.
+    pub detector_id: String,
+    /// The activity, for example an API call or a network connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<Value>,
+    /// Whether the resource was the `TARGET` or the `ACTOR` of the activity.
+    #[serde(default)]
+    pub resource_role: String,
+    /// Additional information about the activity.
+    #[serde(default)]
+    pub additional_info: Value,
+    /// The time the activity was first seen.
+    pub event_first_seen: DateTime<Utc>,
+    /// The time the activity was last seen.
+    pub event_last_seen: DateTime<Utc>,
+    /// Whether the finding is archived.
+    #[serde(default)]
+    pub archived: bool,
+    /// The number of times the activity was seen.
+    pub count: u64,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_findings() {
+        let input = include_str!("../tests/data/guardduty_finding_event.json");
+        let event: GuardDutyFindingEvent = serde_json::from_str(input).expect("Could not parse GuardDuty event");
+        assert_eq!(event.detail_type, GUARDDUTY_FINDING_DETAIL_TYPE);
+
+        let finding = &event.detail;
+        assert_eq!(
+            finding.finding_type,
+            "UnauthorizedAccess:IAMUser/InstanceCredentialExfiltration.OutsideAWS"
+        );
+        assert_eq!(finding.severity_level(), SeverityLevel::High);
+        assert!(finding.is_sample());
+        assert_eq!(
+            finding.resource.access_key_details.as_ref().unwrap().user_type,
+            "AssumedRole"
+        );
+        assert_eq!(finding.service.count, 2);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize GuardDuty event");
+        assert_eq!(serde_json::from_str::<GuardDutyFindingEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn maps_severities_to_levels() {
+        assert_eq!(SeverityLevel::from_severity(2.0), SeverityLevel::Low);
+        assert_eq!(SeverityLevel::from_severity(4.0), SeverityLevel::Medium);
+        assert_eq!(SeverityLevel::from_severity(8.9), SeverityLevel::High);
+        assert_eq!(SeverityLevel::from_severity(9.0), SeverityLevel::Critical);
+        assert!(SeverityLevel::Critical > SeverityLevel::Medium);
+    }
+}
@@ -0,0 +1,206 @@
+//! The details of the AWS Health events delivered by EventBridge.
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{encodings::deserialize_lambda_map, eventbridge::EventBridgeEvent};
+
+/// The `detail_type` of Health events.
+pub const HEALTH_EVENT_DETAIL_TYPE: &str = "AWS Health Event";
+
+/// An `AWS Health Event` event, sent when AWS publishes or updates an event that
+/// affects the account, such as an outage or a scheduled maintenance.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::health::{HealthEvent, HealthEventCategory};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: HealthEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     if e.detail.event_type_category == HealthEventCategory::Issue {
+///         for entity in &e.detail.affected_entities {
+///             println!("{} is affected by {}", entity.entity_value, e.detail.event_type_code);
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub type HealthEvent = EventBridgeEvent<HealthEventDetail>;
+
+/// The detail of an `AWS Health Event` event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthEventDetail {
+    /// The ARN of the event.
+    pub event_arn: String,
+    /// The affected service, for example `EC2`.
+    pub service: String,
+    /// The type of the event, for example `AWS_EC2_OPERATIONAL_ISSUE`.
+    pub event_type_code: String,
+    /// The category of the event.
+    pub event_type_category: HealthEventCategory,
+    /// `PUBLIC` for events of a whole region, `ACCOUNT_SPECIFIC` for events of
+    /// resources of the account, or `NONE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_scope_code: Option<String>,
+    /// The id of the communication, shared by the pages of the event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub communication_id: Option<String>,
+    /// The time the event started, for example `Fri, 27 Jan 2023 06:02:51 GMT`. Use
+    /// `start_time()` to parse it.
+    #[serde(rename = "startTime")]
+    pub start_time_string: String,
+    /// The time the event ended, if it did. Use `end_time()` to parse it.
+    #[serde(rename = "endTime", default, skip_serializing_if = "Option::is_none")]
+    pub end_time_string: Option<String>,
+    /// The time the event was last updated. Use `last_updated_time()` to parse it.
+    #[serde(rename = "lastUpdatedTime", default, skip_serializing_if = "Option::is_none")]
+    pub last_updated_time_string: Option<String>,
+    /// The status of the event.
+    pub status_code: HealthEventStatus,
+    /// The affected region.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_region: Option<String>,
+    /// The descriptions of the event, one per language.
+    #[serde(default)]
+    pub event_description: Vec<HealthEventDescription>,
+    /// The affected resources.
+    #[serde(default)]
+    pub affected_entities: Vec<HealthAffectedEntity>,
+    /// The affected account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub affected_account: Option<String>,
+    /// The number of this page, when the affected entities are split in several events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+    /// The number of pages the affected entities are split in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl HealthEventDetail {
+    /// Returns the time the event started, or `None` if it isn't in the expected format.
+    pub fn start_time(&self) -> Option<DateTime<Utc>> {
+        parse_time(&self.start_time_string)
+    }
+
+    /// Returns the time the event ended, or `None` if it didn't end or the time isn't in
+    /// the expected format.
+    pub fn end_time(&self) -> Option<DateTime<Utc>> {
+        self.end_time_string.as_deref().and_then(parse_time)
+    }
+
+    /// Returns the time the event was last updated, or `None` if it isn't set or isn't
+    /// in the expected format.
+    pub fn last_updated_time(&self) -> Option<DateTime<Utc>> {
+        self.last_updated_time_string.as_deref().and_then(parse_time)
+    }
+
+    /// Returns the latest description of the event in a language, for example `en_US`.
+    pub fn description(&self, language: &str) -> Option<&str> {
+        self.event_description
+            .iter()
+            .find(|d| d.language == language)
+            .map(|d| d.latest_description.as_str())
+    }
+}
+
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// The category of a Health event.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthEventCategory {
+    /// A problem with a service.
+    #[default]
+    Issue,
+    /// A change to the account or its resources, such as an expiring certificate.
+    AccountNotification,
+    /// An upcoming change, such as a maintenance.
+    ScheduledChange,
+    /// A problem AWS is investigating.
+    Investigation,
+}
+
+/// The status of a Health event.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthEventStatus {
+    /// The event is ongoing.
+    #[default]
+    Open,
+    /// The event ended.
+    Closed,
+    /// The event is scheduled.
+    Upcoming,
+}
+
+/// A description of a Health event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthEventDescription {
+    /// The language of the description, for example `en_US`.
+    pub language: String,
+    /// The description.
+    pub latest_description: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A resource affected by a Health event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthAffectedEntity {
+    /// The id of the resource, for example an instance id.
+    pub entity_value: String,
+    /// The tags of the resource.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub tags: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_health_events() {
+        let input = include_str!("../tests/data/health_event.json");
+        let event: HealthEvent = serde_json::from_str(input).expect("Could not parse Health event");
+        assert_eq!(event.detail_type, HEALTH_EVENT_DETAIL_TYPE);
+
+        let detail = &event.detail;
+        assert_eq!(detail.event_type_category, HealthEventCategory::Issue);
+        assert_eq!(detail.status_code, HealthEventStatus::Open);
+        assert_eq!(detail.start_time().unwrap().to_rfc3339(), "2023-01-27T06:02:51+00:00");
+        assert_eq!(detail.end_time(), None);
+        assert_eq!(
+            detail.description("en_US"),
+            Some("A description of the event will be provided here")
+        );
+        assert_eq!(detail.affected_entities[0].tags["stage"], "prod");
+
+        let json = serde_json::to_string(&event).expect("Could not serialize Health event");
+        assert_eq!(serde_json::from_str::<HealthEvent>(&json).unwrap(), event);
+    }
+}
@@ -0,0 +1,343 @@
+//! Amazon API Gateway HTTP API events in the version 2.0 payload format, and the
+//! responses functions return to HTTP APIs.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    apigw::body_bytes,
+    encodings::{deserialize_lambda_map, MillisecondTimestamp},
+};
+
+/// A request received by an HTTP API, in the version 2.0 payload format.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayV2HttpRequest {
+    /// The version of the payload format, always `2.0`.
+    pub version: String,
+    /// The route that matched the request, for example `POST /orders/{id}`.
+    pub route_key: String,
+    /// The path of the request, including the stage.
+    pub raw_path: String,
+    /// The query string of the request, without the `?`.
+    #[serde(default)]
+    pub raw_query_string: String,
+    /// The cookies of the request, as `name=value` strings. The `Cookie` header is
+    /// removed from `headers`.
+    #[serde(default)]
+    pub cookies: Vec<String>,
+    /// The headers of the request, with lower-case names. Repeated headers are joined
+    /// with commas.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// The query string parameters of the request. Repeated parameters are joined with
+    /// commas, parse `raw_query_string` to tell them apart.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// The values of the path parameters of the route.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub path_parameters: HashMap<String, String>,
+    /// The variables of the stage.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub stage_variables: HashMap<String, String>,
+    /// Information about the request added by API Gateway.
+    pub request_context: ApiGatewayV2HttpRequestContext,
+    /// The body of the request, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ApiGatewayV2HttpRequest {
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns the value of a cookie of the request.
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .filter_map(|cookie| cookie.split_once('='))
+            .find(|(n, _)| n.trim() == name)
+            .map(|(_, value)| value.trim())
+    }
+
+    /// Returns the body of the request, decoded from base64 if needed.
+    ///
+    /// # Return
+    /// The body, empty if the request has none, or an error if a base64 encoded body is
+    /// not valid base64.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        body_bytes(self.body.as_deref(), self.is_base64_encoded)
+    }
+}
+
+/// Information about a request added by API Gateway.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayV2HttpRequestContext {
+    /// The AWS account id owning the API.
+    pub account_id: String,
+    /// The id of the API.
+    pub api_id: String,
+    /// The domain name the request was sent to.
+    pub domain_name: String,
+    /// The first label of the domain name.
+    pub domain_prefix: String,
+    /// The HTTP details of the request.
+    pub http: ApiGatewayV2HttpDescription,
+    /// The id API Gateway assigned to the request.
+    pub request_id: String,
+    /// The route that matched the request.
+    pub route_key: String,
+    /// The stage of the API.
+    pub stage: String,
+    /// The time of the request in the CLF format, for example
+    /// `10/Mar/2020:05:16:23 +0000`.
+    pub time: String,
+    /// The time of the request.
+    pub time_epoch: MillisecondTimestamp,
+    /// The result of the authorizer of the route, if it has one.
+    #[serde(default)]
+    pub authorizer: Option<ApiGatewayV2Authorizer>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The HTTP details of a request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayV2HttpDescription {
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The path of the request, including the stage.
+    pub path: String,
+    /// The protocol of the request, for example `HTTP/1.1`.
+    pub protocol: String,
+    /// The source IP address of the TCP connection the request was sent over.
+    pub source_ip: String,
+    /// The user agent of the caller.
+    #[serde(default)]
+    pub user_agent: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The result of the authorizer of a route. Only the field of the authorizer type of
+/// the route is set.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayV2Authorizer {
+    /// The validated token of a JWT authorizer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwt: Option<ApiGatewayV2JwtAuthorizer>,
+    /// The context returned by a Lambda authorizer.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub lambda: HashMap<String, Value>,
+    /// The caller of an IAM authorized route, with the same fields as the identity of
+    /// REST API requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iam: Option<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The validated token of a JWT authorizer.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayV2JwtAuthorizer {
+    /// The claims of the token. API Gateway sends all claims as strings.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub claims: HashMap<String, String>,
+    /// The scopes of the token that matched the scopes of the route.
+    #[serde(default)]
+    pub scopes: Option<Vec<String>>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A response to an HTTP API request, in the version 2.0 payload format. Create it
+/// with `ApiGatewayV2HttpResponse::builder()`.
+///
+/// ```rust
+/// use lambda_events::http_api::ApiGatewayV2HttpResponse;
+///
+/// let response = ApiGatewayV2HttpResponse::builder()
+///     .status_code(302)
+///     .header("Location", "/home")
+///     .header("Set-Cookie", "session=abc123; Secure")
+///     .build();
+/// assert_eq!(response.cookies, vec!["session=abc123; Secure"]);
+/// assert!(!response.headers.contains_key("Set-Cookie"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiGatewayV2HttpResponse {
+    /// The HTTP status code.
+    pub status_code: u16,
+    /// The headers of the response. Repeated headers are joined with commas.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// The cookies to set, as `Set-Cookie` header values. API Gateway sends one
+    /// `Set-Cookie` header per cookie.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cookies: Vec<String>,
+    /// The body of the response, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded, for binary responses.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for ApiGatewayV2HttpResponse {
+    fn default() -> Self {
+        ApiGatewayV2HttpResponse {
+            status_code: 200,
+            headers: HashMap::new(),
+            cookies: Vec::new(),
+            body: None,
+            is_base64_encoded: false,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+impl ApiGatewayV2HttpResponse {
+    /// Returns a builder for a response, with status code `200` and no headers or body.
+    pub fn builder() -> ApiGatewayV2HttpResponseBuilder {
+        ApiGatewayV2HttpResponseBuilder::default()
+    }
+}
+
+/// Builder for `ApiGatewayV2HttpResponse` objects, created with
+/// `ApiGatewayV2HttpResponse::builder()`.
+#[derive(Debug, Default, Clone)]
+pub struct ApiGatewayV2HttpResponseBuilder {
+    response: ApiGatewayV2HttpResponse,
+}
+
+impl ApiGatewayV2HttpResponseBuilder {
+    /// Sets the HTTP status code.
+    pub fn status_code(mut self, status_code: u16) -> Self {
+        self.response.status_code = status_code;
+        self
+    }
+
+    /// Adds a header. `Set-Cookie` headers are added to the cookies of the response,
+    /// as HTTP APIs drop all but one of them from the headers; other headers that are
+    /// added more than once are joined with commas.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        if name.eq_ignore_ascii_case("set-cookie") {
+            self.response.cookies.push(value);
+            return self;
+        }
+        self.response
+            .headers
+            .entry(name)
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+        self
+    }
+
+    /// Adds a cookie, as the value of a `Set-Cookie` header.
+    pub fn cookie(mut self, cookie: impl Into<String>) -> Self {
+        self.response.cookies.push(cookie.into());
+        self
+    }
+
+    /// Sets a text body.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.response.body = Some(body.into());
+        self.response.is_base64_encoded = false;
+        self
+    }
+
+    /// Sets a binary body, which is sent to API Gateway base64 encoded.
+    pub fn binary_body(mut self, body: impl AsRef<[u8]>) -> Self {
+        self.response.body = Some(base64::encode(body.as_ref()));
+        self.response.is_base64_encoded = true;
+        self
+    }
+
+    /// Returns the response.
+    pub fn build(self) -> ApiGatewayV2HttpResponse {
+        self.response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_request() {
+        let input = include_str!("../tests/data/http_api_request.json");
+        let request: ApiGatewayV2HttpRequest = serde_json::from_str(input).expect("Could not parse request");
+        assert_eq!(request.route_key, "POST /orders/{id}");
+        assert_eq!(request.raw_query_string, "expand=items&expand=customer&dryRun");
+        assert_eq!(request.header("Content-Type"), Some("application/json"));
+        assert_eq!(request.cookie("theme"), Some("dark"));
+        assert_eq!(request.cookie("missing"), None);
+        assert_eq!(request.body_bytes().unwrap(), br#"{"quantity":2}"#);
+
+        let context = &request.request_context;
+        assert_eq!(context.http.method, "POST");
+        assert_eq!(context.http.source_ip, "205.255.255.176");
+        assert_eq!(context.time_epoch.timestamp_millis(), 1_583_817_383_220);
+        let jwt = context.authorizer.as_ref().and_then(|a| a.jwt.as_ref()).unwrap();
+        assert_eq!(jwt.claims["sub"], "user-1");
+        assert_eq!(jwt.scopes, Some(vec![String::from("orders/write")]));
+
+        let json = serde_json::to_string(&request).expect("Could not serialize request");
+        assert_eq!(serde_json::from_str::<ApiGatewayV2HttpRequest>(&json).unwrap(), request);
+    }
+
+    #[test]
+    fn cookies_are_moved_out_of_headers() {
+        let response = ApiGatewayV2HttpResponse::builder()
+            .header("set-cookie", "a=1")
+            .cookie("b=2")
+            .header("Vary", "Accept")
+            .header("Vary", "Origin")
+            .body("ok")
+            .build();
+        let expected = serde_json::json!({
+            "statusCode": 200,
+            "headers": {"Vary": "Accept, Origin"},
+            "cookies": ["a=1", "b=2"],
+            "body": "ok",
+            "isBase64Encoded": false,
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+    }
+}
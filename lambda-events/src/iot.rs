@@ -0,0 +1,341 @@
+//! AWS IoT Core events: the messages rules send to functions with the Lambda action,
+//! and the requests and responses of custom authorizers.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    authorizer::PolicyDocument,
+    encodings::{deserialize_lambda_map, Base64Data, MillisecondTimestamp},
+};
+
+/// A message sent by a rule. Rules send the result of their `SELECT` statement as is,
+/// so the fields of the message depend on the rule: this type deserializes them as `T`,
+/// and the metadata rules conventionally add with
+/// `SELECT *, topic() AS topic, clientid() AS clientId, timestamp() AS timestamp`
+/// separately when it is present.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::iot::IotRuleEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Reading {
+///     temperature: f64,
+/// }
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: IotRuleEvent<Reading>, _ctx: Context) -> Result<(), HandlerError> {
+///     println!("{:?}: {}", e.client_id, e.payload.temperature);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotRuleEvent<T = Value> {
+    /// The topic the message was published to, selected with `topic()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// The client that published the message, selected with `clientid()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// The time the rule processed the message, selected with `timestamp()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<MillisecondTimestamp>,
+    /// The other fields of the message.
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+impl<T> IotRuleEvent<T> {
+    /// Returns the levels of the topic, for example `["devices", "42", "telemetry"]`
+    /// for `devices/42/telemetry`, or `None` if the rule didn't select the topic.
+    pub fn topic_levels(&self) -> Option<Vec<&str>> {
+        self.topic.as_ref().map(|topic| topic.split('/').collect())
+    }
+}
+
+/// A request to authenticate a device connecting to IoT Core.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotCustomAuthorizerRequest {
+    /// The token the device sent, `None` when the device sent MQTT credentials instead.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Whether IoT Core verified the signature of the token.
+    #[serde(default)]
+    pub signature_verified: bool,
+    /// The protocols of the connection, for example `["tls", "mqtt"]`.
+    #[serde(default)]
+    pub protocols: Vec<String>,
+    /// The information the device sent, per protocol.
+    pub protocol_data: IotProtocolData,
+    /// The connection.
+    pub connection_metadata: IotConnectionMetadata,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The information a device sent when connecting, per protocol.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotProtocolData {
+    /// The TLS handshake.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<IotTlsContext>,
+    /// The HTTP request, for HTTP and MQTT over WebSocket connections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<IotHttpContext>,
+    /// The MQTT `CONNECT` packet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mqtt: Option<IotMqttContext>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The TLS handshake of a connection.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotTlsContext {
+    /// The server name indication the device sent.
+    pub server_name: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The HTTP request of a connection.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotHttpContext {
+    /// The headers of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// The query string of the request, including the leading `?`.
+    #[serde(default)]
+    pub query_string: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The MQTT `CONNECT` packet of a connection.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotMqttContext {
+    /// The user name the device sent.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// The password the device sent, which can be binary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<Base64Data>,
+    /// The client id the device sent.
+    pub client_id: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A connection to IoT Core.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotConnectionMetadata {
+    /// The id of the connection, which appears in the logs of IoT Core.
+    pub id: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The response of a custom authorizer.
+///
+/// ```rust
+/// use lambda_events::{
+///     authorizer::{Effect, PolicyDocument, Statement},
+///     iot::IotCustomAuthorizerResponse,
+/// };
+///
+/// let policy = PolicyDocument {
+///     version: String::from("2012-10-17"),
+///     statement: vec![Statement {
+///         action: vec![String::from("iot:Connect")],
+///         effect: Effect::Allow,
+///         resource: vec![String::from("arn:aws:iot:us-east-1:123456789012:client/thermostat-42")],
+/// #       #[cfg(feature = "catch-all-fields")]
+/// #       other: Default::default(),
+///     }],
+/// #   #[cfg(feature = "catch-all-fields")]
+/// #   other: Default::default(),
+/// };
+/// let response = IotCustomAuthorizerResponse::authenticated("thermostat42", policy).refresh_after(600);
+/// assert!(response.is_authenticated);
+/// assert_eq!(response.disconnect_after_in_seconds, 86400);
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotCustomAuthorizerResponse {
+    /// Whether the device is authenticated.
+    pub is_authenticated: bool,
+    /// The id of the device, alphanumeric and at most 128 characters long.
+    pub principal_id: String,
+    /// How long the connection can stay open, from 300 to 86400 seconds.
+    pub disconnect_after_in_seconds: u32,
+    /// How long before IoT Core invokes the authorizer again to refresh the policies,
+    /// from 300 to 86400 seconds.
+    pub refresh_after_in_seconds: u32,
+    /// The policies of the device.
+    pub policy_documents: Vec<PolicyDocument>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl IotCustomAuthorizerResponse {
+    /// Returns the response for an authenticated device, with a policy and the longest
+    /// connection duration and shortest refresh interval.
+    pub fn authenticated(principal_id: impl Into<String>, policy: PolicyDocument) -> Self {
+        IotCustomAuthorizerResponse {
+            is_authenticated: true,
+            principal_id: principal_id.into(),
+            disconnect_after_in_seconds: 86_400,
+            refresh_after_in_seconds: 300,
+            policy_documents: vec![policy],
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+
+    /// Returns the response for a device that could not be authenticated.
+    pub fn unauthenticated(principal_id: impl Into<String>) -> Self {
+        IotCustomAuthorizerResponse {
+            is_authenticated: false,
+            principal_id: principal_id.into(),
+            disconnect_after_in_seconds: 300,
+            refresh_after_in_seconds: 300,
+            policy_documents: Vec::new(),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+
+    /// Adds a policy.
+    pub fn policy(mut self, policy: PolicyDocument) -> Self {
+        self.policy_documents.push(policy);
+        self
+    }
+
+    /// Sets how long the connection can stay open.
+    pub fn disconnect_after(mut self, seconds: u32) -> Self {
+        self.disconnect_after_in_seconds = seconds;
+        self
+    }
+
+    /// Sets how long before the policies are refreshed.
+    pub fn refresh_after(mut self, seconds: u32) -> Self {
+        self.refresh_after_in_seconds = seconds;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorizer::{Effect, Statement};
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    struct Reading {
+        temperature: f64,
+    }
+
+    #[test]
+    fn deserializes_rule_events() {
+        let input = serde_json::json!({
+            "temperature": 21.5,
+            "topic": "devices/42/telemetry",
+            "clientId": "thermostat-42",
+            "timestamp": 1_545_084_650_987_u64,
+        });
+        let event: IotRuleEvent<Reading> = serde_json::from_value(input.clone()).expect("Could not parse event");
+        assert_eq!(event.payload.temperature, 21.5);
+        assert_eq!(event.topic_levels(), Some(vec!["devices", "42", "telemetry"]));
+        assert_eq!(event.client_id.as_deref(), Some("thermostat-42"));
+        assert_eq!(serde_json::to_value(&event).unwrap(), input);
+
+        let event: IotRuleEvent = serde_json::from_value(serde_json::json!({"temperature": 21.5})).unwrap();
+        assert_eq!(event.topic, None);
+        assert_eq!(event.payload, serde_json::json!({"temperature": 21.5}));
+    }
+
+    #[test]
+    fn deserializes_authorizer_requests() {
+        let input = include_str!("../tests/data/iot_custom_authorizer_request.json");
+        let request: IotCustomAuthorizerRequest = serde_json::from_str(input).expect("Could not parse request");
+        assert_eq!(request.token.as_deref(), Some("aToken"));
+        let mqtt = request.protocol_data.mqtt.as_ref().unwrap();
+        assert_eq!(mqtt.password.as_ref().map(|p| &p[..]), Some(&b"myPassword"[..]));
+        assert_eq!(
+            request.protocol_data.http.as_ref().unwrap().headers["x-device-type"],
+            "thermostat"
+        );
+        assert_eq!(request.connection_metadata.id, "e56f08c3-c559-490f-aa9f-7e8427d0f57b");
+
+        let json = serde_json::to_string(&request).expect("Could not serialize request");
+        assert_eq!(
+            serde_json::from_str::<IotCustomAuthorizerRequest>(&json).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn serializes_authorizer_responses() {
+        let policy = PolicyDocument {
+            version: String::from("2012-10-17"),
+            statement: vec![Statement {
+                action: vec![String::from("iot:Connect")],
+                effect: Effect::Allow,
+                resource: vec![String::from("arn:aws:iot:us-east-1:123456789012:client/thermostat-42")],
+                #[cfg(feature = "catch-all-fields")]
+                other: Default::default(),
+            }],
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        };
+        let response = IotCustomAuthorizerResponse::authenticated("thermostat42", policy).disconnect_after(3600);
+        let expected = serde_json::json!({
+            "isAuthenticated": true,
+            "principalId": "thermostat42",
+            "disconnectAfterInSeconds": 3600,
+            "refreshAfterInSeconds": 300,
+            "policyDocuments": [{
+                "Version": "2012-10-17",
+                "Statement": [{
+                    "Action": ["iot:Connect"],
+                    "Effect": "Allow",
+                    "Resource": ["arn:aws:iot:us-east-1:123456789012:client/thermostat-42"],
+                }],
+            }],
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+
+        let response = IotCustomAuthorizerResponse::unauthenticated("unknown");
+        assert!(response.policy_documents.is_empty());
+    }
+}
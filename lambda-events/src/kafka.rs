@@ -0,0 +1,235 @@
+//! Amazon MSK and self-managed Apache Kafka events, sent to functions that process the
+//! records of Kafka topics in batches. Both sources send the same event, except that
+//! self-managed clusters have no ARN.
+use std::{
+    collections::HashMap,
+    str::{self, Utf8Error},
+};
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::encodings::{deserialize_lambda_map, Base64Data, MillisecondTimestamp};
+
+/// The `event_source` of the events of self-managed clusters.
+pub const SELF_MANAGED_KAFKA_EVENT_SOURCE: &str = "SelfManagedKafka";
+
+/// A batch of Kafka records, from an MSK or a self-managed cluster.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::kafka::KafkaEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: KafkaEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     for record in e.all_records() {
+///         println!("{}@{}: {:?}", record.topic, record.offset, record.value_as_str());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaEvent {
+    /// `aws:kafka` for MSK clusters, `SelfManagedKafka` for self-managed clusters.
+    pub event_source: String,
+    /// The ARN of the cluster, `None` for self-managed clusters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_source_arn: Option<String>,
+    /// The comma-separated `host:port` addresses of the brokers of the cluster.
+    #[serde(default)]
+    pub bootstrap_servers: Option<String>,
+    /// The records, grouped by topic partition, with keys like `<topic>-<partition>`.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub records: HashMap<String, Vec<KafkaRecord>>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl KafkaEvent {
+    /// Returns whether the records come from a self-managed cluster.
+    pub fn is_self_managed(&self) -> bool {
+        self.event_source == SELF_MANAGED_KAFKA_EVENT_SOURCE
+    }
+
+    /// Returns the `host:port` addresses of the brokers of the cluster.
+    pub fn bootstrap_server_list(&self) -> Vec<&str> {
+        self.bootstrap_servers
+            .iter()
+            .flat_map(|servers| servers.split(','))
+            .map(str::trim)
+            .filter(|server| !server.is_empty())
+            .collect()
+    }
+
+    /// Returns the records of all topic partitions. The records of a partition are in
+    /// offset order, but the partitions are in no particular order.
+    pub fn all_records(&self) -> impl Iterator<Item = &KafkaRecord> {
+        self.records.values().flatten()
+    }
+}
+
+/// A Kafka record.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaRecord {
+    /// The topic of the record.
+    pub topic: String,
+    /// The partition of the record.
+    pub partition: i32,
+    /// The offset of the record in its partition.
+    pub offset: i64,
+    /// The timestamp of the record, whose meaning depends on `timestamp_type`.
+    pub timestamp: MillisecondTimestamp,
+    /// `CREATE_TIME` when the timestamp was set by the producer, or `LOG_APPEND_TIME`
+    /// when it was set by the broker.
+    pub timestamp_type: String,
+    /// The key of the record, `None` for records without key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<Base64Data>,
+    /// The value of the record, `None` for tombstones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Base64Data>,
+    /// The headers of the record, as sent by Lambda: one map per header, with the bytes
+    /// of the value as numbers. Use `headers()` to read them.
+    #[serde(default)]
+    pub headers: Vec<HashMap<String, Vec<i16>>>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl KafkaRecord {
+    /// Returns the key of the record as text.
+    ///
+    /// # Return
+    /// The key, `None` if the record has none, or an error if the key is not valid UTF-8.
+    pub fn key_as_str(&self) -> Option<Result<&str, Utf8Error>> {
+        self.key.as_ref().map(|key| str::from_utf8(key))
+    }
+
+    /// Returns the value of the record as text.
+    ///
+    /// # Return
+    /// The value, `None` if the record has none, or an error if the value is not valid
+    /// UTF-8.
+    pub fn value_as_str(&self) -> Option<Result<&str, Utf8Error>> {
+        self.value.as_ref().map(|value| str::from_utf8(value))
+    }
+
+    /// Deserializes the JSON value of the record.
+    ///
+    /// # Return
+    /// The value parsed as `T`, `None` if the record has no value, or the error if the
+    /// value is not valid JSON for `T`.
+    pub fn deserialize_value<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.value.as_ref().map(|value| serde_json::from_slice(value))
+    }
+
+    /// Returns the headers of the record, in order. Kafka allows repeated header names.
+    pub fn headers(&self) -> Vec<(&str, Vec<u8>)> {
+        self.headers
+            .iter()
+            .flatten()
+            .map(|(name, value)| (name.as_str(), header_bytes(value)))
+            .collect()
+    }
+
+    /// Returns the value of the first header with the given name.
+    pub fn header(&self, name: &str) -> Option<Vec<u8>> {
+        self.headers
+            .iter()
+            .flatten()
+            .find(|(n, _)| n.as_str() == name)
+            .map(|(_, value)| header_bytes(value))
+    }
+}
+
+/// Converts the numbers Lambda sends for the bytes of header values, which are signed
+/// for some sources, to bytes.
+fn header_bytes(value: &[i16]) -> Vec<u8> {
+    value.iter().map(|&b| b as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        order_id: String,
+        quantity: u32,
+    }
+
+    #[test]
+    fn deserializes_records() {
+        let input = include_str!("../tests/data/msk_event.json");
+        let event: KafkaEvent = serde_json::from_str(input).expect("Could not parse MSK event");
+        assert_eq!(event.all_records().count(), 3);
+        assert!(!event.is_self_managed());
+        assert!(event.event_source_arn.as_deref().unwrap().contains(":cluster/orders/"));
+        assert_eq!(event.bootstrap_server_list().len(), 2);
+
+        let record = &event.records["orders-0"][0];
+        assert_eq!(record.offset, 15);
+        assert_eq!(record.timestamp.timestamp_millis(), 1_545_084_650_987);
+        assert_eq!(record.key_as_str(), Some(Ok("o-123")));
+        assert_eq!(
+            record.deserialize_value::<Order>().unwrap().unwrap(),
+            Order {
+                order_id: String::from("o-123"),
+                quantity: 2,
+            }
+        );
+        assert_eq!(record.header("content-type"), Some(b"application/json".to_vec()));
+        assert_eq!(
+            record.headers(),
+            vec![
+                ("content-type", b"application/json".to_vec()),
+                ("trace-id", b"abc".to_vec())
+            ]
+        );
+
+        let record = &event.records["orders-0"][1];
+        assert_eq!(record.key, None);
+        assert_eq!(record.value_as_str(), Some(Ok("not json")));
+        assert!(record.deserialize_value::<Order>().unwrap().is_err());
+        assert_eq!(record.header("content-type"), None);
+
+        let record = &event.records["orders-1"][0];
+        assert_eq!(record.header("signed"), Some(vec![255, 127]));
+
+        let json = serde_json::to_string(&event).expect("Could not serialize MSK event");
+        assert_eq!(serde_json::from_str::<KafkaEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn deserializes_self_managed_events() {
+        let input = include_str!("../tests/data/self_managed_kafka_event.json");
+        let event: KafkaEvent = serde_json::from_str(input).expect("Could not parse Kafka event");
+        assert!(event.is_self_managed());
+        assert_eq!(event.event_source_arn, None);
+        assert_eq!(
+            event.bootstrap_server_list(),
+            vec!["kafka-1.internal.example.com:9092", "kafka-2.internal.example.com:9092"]
+        );
+
+        let record = &event.records["payments-0"][0];
+        assert_eq!(record.key_as_str(), Some(Ok("p-123")));
+        assert_eq!(record.header("source"), Some(b"pos".to_vec()));
+
+        let json = serde_json::to_value(&event).expect("Could not serialize Kafka event");
+        assert!(json.get("eventSourceArn").is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! Amazon Kinesis Data Streams events, sent to functions that process the records of a
+//! stream in batches.
+use std::str::{self, Utf8Error};
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    batch::{process_stream, StreamsEventResponse},
+    encodings::{Base64Data, SecondTimestamp},
+};
+
+/// A batch of records read from a shard of a stream.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct KinesisEvent {
+    /// The records, in the order of their sequence numbers.
+    #[serde(rename = "Records")]
+    pub records: Vec<KinesisEventRecord>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl KinesisEvent {
+    /// Runs `handler` for each record of the batch in order and returns the response
+    /// that reports the first failed record, for functions whose event source mapping
+    /// enables `ReportBatchItemFailures`. The records after a failed record are not
+    /// passed to `handler`, as Lambda retries them with the failed record.
+    pub fn process<F, T, E>(&self, handler: F) -> StreamsEventResponse
+    where
+        F: FnMut(&KinesisEventRecord) -> Result<T, E>,
+    {
+        process_stream(&self.records, |record| &record.kinesis.sequence_number, handler)
+    }
+}
+
+/// A record read from a stream.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KinesisEventRecord {
+    /// The id of the record, made of the shard id and the sequence number.
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    /// Always `aws:kinesis:record`.
+    pub event_name: String,
+    /// Always `aws:kinesis`.
+    pub event_source: String,
+    /// The ARN of the stream.
+    #[serde(rename = "eventSourceARN")]
+    pub event_source_arn: String,
+    /// The version of the event format, for example `1.0`.
+    pub event_version: String,
+    /// The ARN of the role used to read the stream.
+    pub invoke_identity_arn: String,
+    /// The region of the stream.
+    pub aws_region: String,
+    /// The record.
+    pub kinesis: KinesisRecord,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The data and metadata of a record.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KinesisRecord {
+    /// The version of the record format, for example `1.0`.
+    pub kinesis_schema_version: String,
+    /// The partition key the producer put the record with.
+    pub partition_key: String,
+    /// The sequence number of the record in its shard. Sequence numbers are decimal
+    /// numbers that can be larger than 64 bits, so they are kept as strings.
+    pub sequence_number: String,
+    /// The data of the record, decoded from base64.
+    pub data: Base64Data,
+    /// The time the record was added to the stream.
+    pub approximate_arrival_timestamp: SecondTimestamp,
+    /// The server-side encryption of the record, `KMS` for encrypted streams.
+    #[serde(default)]
+    pub encryption_type: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl KinesisRecord {
+    /// Returns the data of the record as text.
+    ///
+    /// # Return
+    /// The data, or an error if it is not valid UTF-8.
+    pub fn data_as_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(&self.data)
+    }
+
+    /// Deserializes the JSON data of the record.
+    ///
+    /// ```rust
+    /// use lambda_events::kinesis::KinesisEvent;
+    /// use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Click {
+    ///     page: String,
+    /// }
+    ///
+    /// fn pages(event: &KinesisEvent) -> Vec<String> {
+    ///     event
+    ///         .records
+    ///         .iter()
+    ///         .filter_map(|record| record.kinesis.deserialize_data::<Click>().ok())
+    ///         .map(|click| click.page)
+    ///         .collect()
+    /// }
+    /// ```
+    pub fn deserialize_data<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        order_id: String,
+        quantity: u32,
+    }
+
+    #[test]
+    fn deserializes_records() {
+        let input = include_str!("../tests/data/kinesis_event.json");
+        let event: KinesisEvent = serde_json::from_str(input).expect("Could not parse Kinesis event");
+
+        let record = &event.records[0].kinesis;
+        assert_eq!(record.partition_key, "order-123");
+        assert_eq!(
+            record.approximate_arrival_timestamp.timestamp_millis(),
+            1_545_084_650_987
+        );
+        assert_eq!(record.encryption_type, None);
+        assert_eq!(
+            record.deserialize_data::<Order>().unwrap(),
+            Order {
+                order_id: String::from("o123"),
+                quantity: 2,
+            }
+        );
+
+        let record = &event.records[1].kinesis;
+        assert_eq!(record.data_as_str(), Ok("not json"));
+        assert!(record.deserialize_data::<Order>().is_err());
+        assert_eq!(record.encryption_type.as_deref(), Some("KMS"));
+
+        let json = serde_json::to_string(&event).expect("Could not serialize Kinesis event");
+        assert_eq!(serde_json::from_str::<KinesisEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn processing_stops_at_the_first_failure() {
+        let input = include_str!("../tests/data/kinesis_event.json");
+        let mut event: KinesisEvent = serde_json::from_str(input).expect("Could not parse Kinesis event");
+        event.records.push(event.records[0].clone());
+
+        let mut processed = 0;
+        let response = event.process(|record| {
+            processed += 1;
+            record.kinesis.deserialize_data::<Order>()
+        });
+        assert_eq!(processed, 2);
+        assert_eq!(response.batch_item_failures.len(), 1);
+        assert_eq!(
+            response.batch_item_failures[0].item_identifier,
+            event.records[1].kinesis.sequence_number
+        );
+    }
+}
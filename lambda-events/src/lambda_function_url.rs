@@ -0,0 +1,302 @@
+//! Lambda function URL events, sent to functions invoked through their HTTPS endpoint,
+//! and the responses functions return to the endpoint.
+//!
+//! Function URLs use the shape of the version 2.0 payload format of API Gateway HTTP
+//! APIs, without routes or stages, and with the caller of `AWS_IAM` URLs in the
+//! request context.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    apigw::body_bytes,
+    encodings::{deserialize_lambda_map, MillisecondTimestamp},
+    http_api::ApiGatewayV2HttpResponse,
+};
+
+/// A request received by a function URL.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::{
+///     http_api::ApiGatewayV2HttpResponse,
+///     lambda_function_url::{LambdaFunctionUrlRequest, LambdaFunctionUrlResponse},
+/// };
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: LambdaFunctionUrlRequest, _ctx: Context) -> Result<LambdaFunctionUrlResponse, HandlerError> {
+///     let caller = e.request_context.iam().map(|iam| iam.user_arn.as_str()).unwrap_or("anonymous");
+///     Ok(ApiGatewayV2HttpResponse::builder()
+///         .header("Content-Type", "text/plain")
+///         .body(format!("Hello {}", caller))
+///         .build()
+///         .into())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LambdaFunctionUrlRequest {
+    /// The version of the payload format, always `2.0`.
+    pub version: String,
+    /// The path of the request.
+    pub raw_path: String,
+    /// The query string of the request, without the `?`.
+    #[serde(default)]
+    pub raw_query_string: String,
+    /// The cookies of the request, as `name=value` strings. The `Cookie` header is
+    /// removed from `headers`.
+    #[serde(default)]
+    pub cookies: Vec<String>,
+    /// The headers of the request, with lower-case names. Repeated headers are joined
+    /// with commas.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// The query string parameters of the request. Repeated parameters are joined with
+    /// commas, parse `raw_query_string` to tell them apart.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// Information about the request added by Lambda.
+    pub request_context: LambdaFunctionUrlRequestContext,
+    /// The body of the request, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LambdaFunctionUrlRequest {
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns the value of a cookie of the request.
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .filter_map(|cookie| cookie.split_once('='))
+            .find(|(n, _)| n.trim() == name)
+            .map(|(_, value)| value.trim())
+    }
+
+    /// Returns the body of the request, decoded from base64 if needed.
+    ///
+    /// # Return
+    /// The body, empty if the request has none, or an error if a base64 encoded body is
+    /// not valid base64.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        body_bytes(self.body.as_deref(), self.is_base64_encoded)
+    }
+}
+
+/// Information about a request added by Lambda.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LambdaFunctionUrlRequestContext {
+    /// The AWS account id owning the function.
+    pub account_id: String,
+    /// The id of the function URL, the first label of its domain name.
+    pub api_id: String,
+    /// The domain name of the function URL.
+    pub domain_name: String,
+    /// The first label of the domain name.
+    pub domain_prefix: String,
+    /// The HTTP details of the request.
+    pub http: LambdaFunctionUrlHttp,
+    /// The id Lambda assigned to the request.
+    pub request_id: String,
+    /// The time of the request in the CLF format, for example
+    /// `03/Nov/2021:14:24:18 +0000`.
+    pub time: String,
+    /// The time of the request.
+    pub time_epoch: MillisecondTimestamp,
+    /// The caller, for function URLs with the `AWS_IAM` auth type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorizer: Option<LambdaFunctionUrlAuthorizer>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LambdaFunctionUrlRequestContext {
+    /// Returns the IAM caller, for function URLs with the `AWS_IAM` auth type.
+    pub fn iam(&self) -> Option<&LambdaFunctionUrlIamIdentity> {
+        self.authorizer.as_ref()?.iam.as_ref()
+    }
+}
+
+/// The HTTP details of a request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LambdaFunctionUrlHttp {
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The path of the request.
+    pub path: String,
+    /// The protocol of the request, for example `HTTP/1.1`.
+    pub protocol: String,
+    /// The source IP address of the TCP connection the request was sent over.
+    pub source_ip: String,
+    /// The user agent of the caller.
+    #[serde(default)]
+    pub user_agent: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The authorization of a request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LambdaFunctionUrlAuthorizer {
+    /// The IAM caller.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iam: Option<LambdaFunctionUrlIamIdentity>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The IAM caller of a request, identified by its SigV4 signature.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LambdaFunctionUrlIamIdentity {
+    /// The access key the request was signed with.
+    pub access_key: String,
+    /// The AWS account of the caller.
+    pub account_id: String,
+    /// The id of the caller.
+    pub caller_id: String,
+    /// The Cognito identity of the caller, for callers with Cognito credentials.
+    #[serde(default)]
+    pub cognito_identity: Option<Value>,
+    /// The AWS Organizations id of the account of the caller.
+    #[serde(default)]
+    pub principal_org_id: Option<String>,
+    /// The ARN of the caller.
+    pub user_arn: String,
+    /// The id of the user of the caller.
+    pub user_id: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A response to a function URL request. Function URLs accept the same responses as
+/// HTTP APIs, create them with `ApiGatewayV2HttpResponse::builder()` and convert them
+/// with `into()`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LambdaFunctionUrlResponse {
+    /// The HTTP status code.
+    pub status_code: u16,
+    /// The headers of the response. Repeated headers are joined with commas.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// The cookies to set, as `Set-Cookie` header values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cookies: Vec<String>,
+    /// The body of the response, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded, for binary responses.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for LambdaFunctionUrlResponse {
+    fn default() -> Self {
+        ApiGatewayV2HttpResponse::default().into()
+    }
+}
+
+impl From<ApiGatewayV2HttpResponse> for LambdaFunctionUrlResponse {
+    fn from(response: ApiGatewayV2HttpResponse) -> Self {
+        LambdaFunctionUrlResponse {
+            status_code: response.status_code,
+            headers: response.headers,
+            cookies: response.cookies,
+            body: response.body,
+            is_base64_encoded: response.is_base64_encoded,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_iam_requests() {
+        let input = include_str!("../tests/data/lambda_function_url_request.json");
+        let request: LambdaFunctionUrlRequest = serde_json::from_str(input).expect("Could not parse request");
+        assert_eq!(request.raw_path, "/orders/42");
+        assert_eq!(request.header("Content-Type"), Some("application/json"));
+        assert_eq!(request.cookie("theme"), Some("dark"));
+        assert_eq!(request.body_bytes().unwrap(), br#"{"quantity":2}"#);
+
+        let context = &request.request_context;
+        assert_eq!(context.http.method, "POST");
+        assert_eq!(context.time_epoch.timestamp_millis(), 1_635_949_458_000);
+        let iam = context.iam().expect("Expected an IAM caller");
+        assert_eq!(iam.user_arn, "arn:aws:iam::111122223333:user/jdoe");
+        assert_eq!(iam.principal_org_id.as_deref(), Some("o-abcdefghij"));
+
+        let json = serde_json::to_string(&request).expect("Could not serialize request");
+        assert_eq!(
+            serde_json::from_str::<LambdaFunctionUrlRequest>(&json).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn deserializes_public_requests() {
+        let input = include_str!("../tests/data/lambda_function_url_request.json");
+        let mut json: Value = serde_json::from_str(input).unwrap();
+        json["requestContext"].as_object_mut().unwrap().remove("authorizer");
+        let request: LambdaFunctionUrlRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(request.request_context.iam(), None);
+    }
+
+    #[test]
+    fn converts_http_api_responses() {
+        let response: LambdaFunctionUrlResponse = ApiGatewayV2HttpResponse::builder()
+            .status_code(201)
+            .header("Set-Cookie", "session=abc123")
+            .body("created")
+            .build()
+            .into();
+        let expected = serde_json::json!({
+            "statusCode": 201,
+            "cookies": ["session=abc123"],
+            "body": "created",
+            "isBase64Encoded": false,
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+    }
+}
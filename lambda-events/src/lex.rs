@@ -0,0 +1,447 @@
+//! Amazon Lex V2 events, sent to the functions bots use as dialog and fulfillment code
+//! hooks, and the responses that tell the bot what to do next.
+use std::collections::HashMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::encodings::{deserialize_lambda_map, deserialize_nullable};
+
+/// A code hook invocation.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::lex::{IntentState, LexV2Event, LexV2Response};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: LexV2Event, _ctx: Context) -> Result<LexV2Response, HandlerError> {
+///     Ok(match e.slot_value("size") {
+///         None => e.elicit_slot("size", "What size do you want?"),
+///         Some(size) => {
+///             let message = format!("Your {} pizza is on its way", size);
+///             e.close(IntentState::Fulfilled, message)
+///         }
+///     })
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2Event {
+    /// The version of the event format, for example `1.0`.
+    pub message_version: String,
+    /// Whether the function is invoked as the dialog or fulfillment code hook.
+    pub invocation_source: InvocationSource,
+    /// `Text`, `Speech`, or `DTMF`.
+    pub input_mode: String,
+    /// The MIME type of the messages the bot responds with.
+    pub response_content_type: String,
+    /// The id of the conversation.
+    pub session_id: String,
+    /// The text the user typed or said.
+    #[serde(default)]
+    pub input_transcript: String,
+    /// The bot.
+    pub bot: LexV2Bot,
+    /// The intents the input may match, the most likely first.
+    #[serde(default)]
+    pub interpretations: Vec<LexV2Interpretation>,
+    /// The next action Lex proposes when it intends to elicit a slot, for dialog code
+    /// hooks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposed_next_state: Option<Value>,
+    /// The attributes the client sent with the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub request_attributes: HashMap<String, String>,
+    /// The state of the conversation.
+    pub session_state: LexV2SessionState,
+    /// The transcriptions of speech input, the most likely first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transcriptions: Vec<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LexV2Event {
+    /// Returns the interpreted value of a slot of the current intent.
+    pub fn slot_value(&self, name: &str) -> Option<&str> {
+        self.session_state.intent.as_ref()?.slot_value(name)
+    }
+
+    /// Returns a response that ends the current intent.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` The final state of the intent, usually `Fulfilled` or `Failed`.
+    /// * `message` The message the bot sends to the user.
+    pub fn close(&self, state: IntentState, message: impl Into<String>) -> LexV2Response {
+        let mut response = self.response(DialogActionType::Close, Some(message.into()));
+        if let Some(intent) = response.session_state.intent.as_mut() {
+            intent.state = state;
+        }
+        response
+    }
+
+    /// Returns a response that asks the user for the value of a slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` The name of the slot.
+    /// * `message` The message the bot sends to the user.
+    pub fn elicit_slot(&self, slot: impl Into<String>, message: impl Into<String>) -> LexV2Response {
+        let mut response = self.response(DialogActionType::ElicitSlot, Some(message.into()));
+        response.session_state.dialog_action.as_mut().unwrap().slot_to_elicit = Some(slot.into());
+        response
+    }
+
+    /// Returns a response that lets the bot choose the next action.
+    pub fn delegate(&self) -> LexV2Response {
+        self.response(DialogActionType::Delegate, None)
+    }
+
+    fn response(&self, action: DialogActionType, message: Option<String>) -> LexV2Response {
+        let mut session_state = self.session_state.clone();
+        session_state.dialog_action = Some(LexV2DialogAction {
+            dialog_action_type: action,
+            slot_to_elicit: None,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        });
+        LexV2Response {
+            session_state,
+            messages: message.map(LexV2Message::plain_text).into_iter().collect(),
+            request_attributes: HashMap::new(),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+/// Whether a function is invoked as the dialog or fulfillment code hook.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum InvocationSource {
+    /// The function validates the input and chooses the next step of the dialog.
+    #[default]
+    DialogCodeHook,
+    /// The function fulfills the intent.
+    FulfillmentCodeHook,
+}
+
+/// A bot.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2Bot {
+    /// The id of the bot.
+    pub id: String,
+    /// The name of the bot.
+    pub name: String,
+    /// The id of the alias the client used.
+    pub alias_id: String,
+    /// The name of the alias the client used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_name: Option<String>,
+    /// The locale of the bot, for example `en_US`.
+    pub locale_id: String,
+    /// The version of the bot, for example `DRAFT`.
+    pub version: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An intent the input may match.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2Interpretation {
+    /// The intent.
+    pub intent: LexV2Intent,
+    /// How likely the input matches the intent, from 0 to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nlu_confidence: Option<LexV2NluConfidence>,
+    /// The sentiment of the input, when sentiment analysis is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment_response: Option<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// How likely an input matches an intent.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LexV2NluConfidence {
+    /// The score, from 0 to 1.
+    pub score: f64,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An intent and the values of its slots.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2Intent {
+    /// The name of the intent.
+    pub name: String,
+    /// The slots of the intent, `None` for the slots without value.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub slots: HashMap<String, Option<LexV2Slot>>,
+    /// The state of the intent.
+    pub state: IntentState,
+    /// Whether the user confirmed the intent.
+    pub confirmation_state: ConfirmationState,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LexV2Intent {
+    /// Returns the interpreted value of a slot.
+    pub fn slot_value(&self, name: &str) -> Option<&str> {
+        let slot = self.slots.get(name)?.as_ref()?;
+        slot.value.as_ref()?.interpreted_value.as_deref()
+    }
+}
+
+/// The state of an intent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum IntentState {
+    /// The intent failed.
+    Failed,
+    /// The intent was fulfilled.
+    Fulfilled,
+    /// The fulfillment of the intent is in progress.
+    FulfillmentInProgress,
+    /// The bot is eliciting the slots of the intent.
+    #[default]
+    InProgress,
+    /// All slots are elicited, the intent is ready to be fulfilled.
+    ReadyForFulfillment,
+    /// The bot is waiting for the user.
+    Waiting,
+}
+
+/// Whether the user confirmed an intent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ConfirmationState {
+    /// The user confirmed the intent.
+    Confirmed,
+    /// The user denied the intent.
+    Denied,
+    /// The user wasn't asked to confirm the intent yet.
+    #[default]
+    None,
+}
+
+/// The value of a slot. Slots that allow multiple values have the `List` shape, with
+/// each value in `values`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2Slot {
+    /// `Scalar` or `List`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<String>,
+    /// The value of the slot.
+    #[serde(default)]
+    pub value: Option<LexV2SlotValue>,
+    /// The values of `List` slots.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<LexV2Slot>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A value of a slot.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2SlotValue {
+    /// The value as the user said it.
+    #[serde(default)]
+    pub original_value: String,
+    /// The value Lex chose from the resolved values.
+    #[serde(default)]
+    pub interpreted_value: Option<String>,
+    /// The values of the slot type the value may resolve to.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub resolved_values: Vec<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The state of a conversation.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2SessionState {
+    /// The contexts of the conversation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub active_contexts: Vec<Value>,
+    /// The attributes the bot and the functions stored in the session.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub session_attributes: HashMap<String, String>,
+    /// The hints that improve the recognition of speech.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_hints: Option<Value>,
+    /// The next action of the bot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialog_action: Option<LexV2DialogAction>,
+    /// The current intent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent: Option<LexV2Intent>,
+    /// The id of the request that started the current turn of the conversation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub originating_request_id: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The next action of a bot.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2DialogAction {
+    /// The action.
+    #[serde(rename = "type")]
+    pub dialog_action_type: DialogActionType,
+    /// The slot to ask the user for, for `ElicitSlot` actions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot_to_elicit: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The next action of a bot.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DialogActionType {
+    /// End the intent, with the state of the intent in the response.
+    Close,
+    /// Ask the user to confirm the intent.
+    ConfirmIntent,
+    /// Let the bot choose the next action.
+    #[default]
+    Delegate,
+    /// Ask the user for an intent.
+    ElicitIntent,
+    /// Ask the user for the value of a slot.
+    ElicitSlot,
+}
+
+/// The response of a code hook.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2Response {
+    /// The new state of the conversation, including the next action of the bot.
+    pub session_state: LexV2SessionState,
+    /// The messages the bot sends to the user.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<LexV2Message>,
+    /// The attributes returned to the client.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub request_attributes: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A message a bot sends to the user.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexV2Message {
+    /// `PlainText`, `SSML`, `CustomPayload`, or `ImageResponseCard`.
+    pub content_type: String,
+    /// The text of the message, for all content types but `ImageResponseCard`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// The card, for `ImageResponseCard` messages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_response_card: Option<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LexV2Message {
+    /// Returns a plain text message.
+    pub fn plain_text(content: impl Into<String>) -> Self {
+        LexV2Message {
+            content_type: String::from("PlainText"),
+            content: Some(content.into()),
+            image_response_card: None,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_events() {
+        let input = include_str!("../tests/data/lex_v2_event.json");
+        let event: LexV2Event = serde_json::from_str(input).expect("Could not parse Lex event");
+        assert_eq!(event.invocation_source, InvocationSource::DialogCodeHook);
+        assert_eq!(event.bot.name, "PizzaBot");
+        assert_eq!(event.interpretations[0].nlu_confidence.as_ref().unwrap().score, 0.92);
+        assert_eq!(event.interpretations[0].intent.slots["toppings"], None);
+        assert_eq!(event.slot_value("size"), Some("large"));
+        assert_eq!(event.slot_value("crust"), None);
+
+        let intent = event.session_state.intent.as_ref().unwrap();
+        let toppings = intent.slots["toppings"].as_ref().unwrap();
+        let values: Vec<_> = toppings
+            .values
+            .iter()
+            .filter_map(|v| v.value.as_ref()?.interpreted_value.as_deref())
+            .collect();
+        assert_eq!(values, vec!["ham", "pineapple"]);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize Lex event");
+        assert_eq!(serde_json::from_str::<LexV2Event>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn builds_responses() {
+        let input = include_str!("../tests/data/lex_v2_event.json");
+        let event: LexV2Event = serde_json::from_str(input).expect("Could not parse Lex event");
+
+        let response = event.close(IntentState::Fulfilled, "Your pizza is on its way");
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json["sessionState"]["dialogAction"],
+            serde_json::json!({"type": "Close"})
+        );
+        assert_eq!(json["sessionState"]["intent"]["state"], "Fulfilled");
+        assert_eq!(json["sessionState"]["sessionAttributes"]["customerId"], "c-42");
+        assert_eq!(
+            json["messages"],
+            serde_json::json!([{"contentType": "PlainText", "content": "Your pizza is on its way"}])
+        );
+
+        let response = event.elicit_slot("crust", "Which crust?");
+        let action = response.session_state.dialog_action.unwrap();
+        assert_eq!(action.dialog_action_type, DialogActionType::ElicitSlot);
+        assert_eq!(action.slot_to_elicit.as_deref(), Some("crust"));
+
+        assert!(event.delegate().messages.is_empty());
+    }
+}
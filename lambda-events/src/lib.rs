@@ -0,0 +1,85 @@
+#![warn(missing_docs)]
+//! Typed definitions of the events that AWS services send to Lambda functions. The
+//! types deserialize the JSON payloads of the event sources and can be used directly
+//! as the event type of a `lambda_runtime` handler, so functions don't have to define
+//! partial structs for the events they receive.
+//!
+//! Each event source has its own module. Fields are named after the Rust convention and
+//! renamed to the names used in the payloads, and values that AWS encodes inside the
+//! JSON, such as base64 data and epoch timestamps, use the types of the `encodings`
+//! module.
+//!
+//! Fields that the types don't define are dropped. With the `catch-all-fields` feature,
+//! they are kept in the `other` field of each type instead, so fields that AWS adds are
+//! available before the types define them, and events that functions return modified,
+//! such as Cognito triggers, are sent back with all their fields.
+//!
+//! The types of custom events can be generated from the schemas of the EventBridge
+//! schema registry with the `schema-codegen` feature, see the `schema_codegen` module.
+//!
+//! ```rust,no_run
+//! #[macro_use]
+//! extern crate lambda_runtime;
+//!
+//! use lambda_events::s3::S3Event;
+//! use lambda_runtime::{error::HandlerError, Context};
+//!
+//! fn main() {
+//!     lambda!(my_handler);
+//! }
+//!
+//! fn my_handler(e: S3Event, _ctx: Context) -> Result<(), HandlerError> {
+//!     for record in &e.records {
+//!         println!("{} {}", record.event_name, record.object_key());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+// The tests of `schema_codegen` compile generated types, which refer to this crate by name.
+#[cfg(all(test, feature = "schema-codegen"))]
+extern crate self as lambda_events;
+
+pub mod alb;
+pub mod alexa;
+pub mod apigw;
+pub mod appsync;
+pub mod authorizer;
+pub mod batch;
+pub mod batch_job;
+pub mod cloudformation;
+pub mod cloudwatch_alarms;
+pub mod cloudwatch_logs;
+pub mod codecommit;
+pub mod codepipeline;
+pub mod cognito;
+pub mod config;
+pub mod destinations;
+pub mod dynamodb;
+pub mod ecs;
+pub mod encodings;
+pub mod eventbridge;
+pub mod guardduty;
+pub mod health;
+pub mod http_api;
+pub mod iot;
+pub mod kafka;
+pub mod kinesis;
+pub mod lambda_function_url;
+pub mod lex;
+pub mod mq;
+pub mod pipes;
+pub mod s3;
+pub mod s3_batch;
+pub mod s3_eventbridge;
+pub mod s3_object_lambda;
+pub mod samples;
+#[cfg(feature = "schema-codegen")]
+pub mod schema_codegen;
+pub mod secretsmanager;
+pub mod securityhub;
+pub mod ses;
+pub mod sns;
+pub mod sqs;
+pub mod stepfunctions;
+pub mod vpc_lattice;
@@ -0,0 +1,332 @@
+//! Amazon MQ events, sent to functions that process the messages of ActiveMQ and
+//! RabbitMQ brokers in batches.
+use std::{
+    collections::HashMap,
+    str::{self, Utf8Error},
+};
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::encodings::{deserialize_lambda_map, Base64Data, MillisecondTimestamp};
+
+/// A batch of messages from an ActiveMQ broker.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::mq::ActiveMqEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: ActiveMqEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     for message in &e.messages {
+///         println!("{}: {:?}", message.destination.physical_name, message.data_as_str());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveMqEvent {
+    /// Always `aws:mq`.
+    pub event_source: String,
+    /// The ARN of the broker.
+    pub event_source_arn: String,
+    /// The messages.
+    pub messages: Vec<ActiveMqMessage>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A message from an ActiveMQ broker.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveMqMessage {
+    /// The id of the message.
+    #[serde(rename = "messageID")]
+    pub message_id: String,
+    /// The JMS type of the message, for example `jms/text-message`.
+    pub message_type: String,
+    /// The time the message was sent.
+    pub timestamp: MillisecondTimestamp,
+    /// `1` for non-persistent messages, `2` for persistent messages.
+    #[serde(default)]
+    pub delivery_mode: u8,
+    /// The correlation id the producer set.
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// The destination the consumer should reply to.
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    /// The queue or topic the message was sent to.
+    pub destination: ActiveMqDestination,
+    /// Whether the message was delivered before.
+    #[serde(default)]
+    pub redelivered: bool,
+    /// The `JMSType` the producer set.
+    #[serde(rename = "type", default)]
+    pub jms_type: Option<String>,
+    /// The time the message expires, in milliseconds, `0` if it never expires.
+    #[serde(default)]
+    pub expiration: i64,
+    /// The priority of the message, from `0` to `9`.
+    #[serde(default)]
+    pub priority: u8,
+    /// The body of the message.
+    pub data: Base64Data,
+    /// The time the broker received the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broker_in_time: Option<MillisecondTimestamp>,
+    /// The time the broker sent the message to Lambda.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub broker_out_time: Option<MillisecondTimestamp>,
+    /// The properties the producer set.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub properties: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ActiveMqMessage {
+    /// Returns the body of the message as text.
+    ///
+    /// # Return
+    /// The body, or an error if it is not valid UTF-8.
+    pub fn data_as_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(&self.data)
+    }
+
+    /// Deserializes the JSON body of the message.
+    ///
+    /// # Return
+    /// The body parsed as `T`, or the error if the body is not valid JSON for `T`.
+    pub fn deserialize_data<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.data)
+    }
+}
+
+/// The queue or topic an ActiveMQ message was sent to.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveMqDestination {
+    /// The name of the queue or topic.
+    pub physical_name: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A batch of messages from a RabbitMQ broker.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RabbitMqEvent {
+    /// Always `aws:rmq`.
+    pub event_source: String,
+    /// The ARN of the broker.
+    pub event_source_arn: String,
+    /// The messages, grouped by queue, with keys like `<queue>::<virtual host>`.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub rmq_messages_by_queue: HashMap<String, Vec<RabbitMqMessage>>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl RabbitMqEvent {
+    /// Returns the messages of each queue, with the name and virtual host of the queue.
+    pub fn queues(&self) -> impl Iterator<Item = (&str, &str, &[RabbitMqMessage])> {
+        self.rmq_messages_by_queue.iter().map(|(key, messages)| {
+            let (queue, virtual_host) = key.rsplit_once("::").unwrap_or((key, "/"));
+            (queue, virtual_host, messages.as_slice())
+        })
+    }
+}
+
+/// A message from a RabbitMQ broker.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RabbitMqMessage {
+    /// The properties of the message.
+    pub basic_properties: RabbitMqBasicProperties,
+    /// Whether the message was delivered before.
+    #[serde(default)]
+    pub redelivered: bool,
+    /// The body of the message.
+    pub data: Base64Data,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl RabbitMqMessage {
+    /// Returns the body of the message as text.
+    ///
+    /// # Return
+    /// The body, or an error if it is not valid UTF-8.
+    pub fn data_as_str(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(&self.data)
+    }
+
+    /// Deserializes the JSON body of the message.
+    ///
+    /// # Return
+    /// The body parsed as `T`, or the error if the body is not valid JSON for `T`.
+    pub fn deserialize_data<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.data)
+    }
+}
+
+/// The properties of a RabbitMQ message. The properties the producer didn't set are
+/// `None`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RabbitMqBasicProperties {
+    /// The MIME type of the body.
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// The encoding of the body.
+    #[serde(default)]
+    pub content_encoding: Option<String>,
+    /// The headers of the message. Lambda sends text and binary values as objects with
+    /// the bytes of the value, use `header_bytes()` to read them.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, Value>,
+    /// `1` for non-persistent messages, `2` for persistent messages.
+    #[serde(default)]
+    pub delivery_mode: u8,
+    /// The priority of the message.
+    #[serde(default)]
+    pub priority: u8,
+    /// The correlation id the producer set.
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// The queue the consumer should reply to.
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    /// The time the message expires, in milliseconds.
+    #[serde(default)]
+    pub expiration: Option<String>,
+    /// The id of the message.
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// The time the message was sent, formatted by Lambda, for example
+    /// `Jan 1, 1970, 12:33:41 AM`.
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    /// The type of the message.
+    #[serde(rename = "type", default)]
+    pub message_type: Option<String>,
+    /// The user that sent the message.
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// The application that sent the message.
+    #[serde(default)]
+    pub app_id: Option<String>,
+    /// The id of the cluster.
+    #[serde(default)]
+    pub cluster_id: Option<String>,
+    /// The size of the body, in bytes.
+    #[serde(default)]
+    pub body_size: u64,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl RabbitMqBasicProperties {
+    /// Returns the bytes of a text or binary header.
+    ///
+    /// # Return
+    /// The bytes, or `None` if the message has no such header or its value is not text
+    /// or binary, like numbers.
+    pub fn header_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        match self.headers.get(name)? {
+            Value::String(s) => Some(s.clone().into_bytes()),
+            Value::Object(o) => o
+                .get("bytes")?
+                .as_array()?
+                .iter()
+                .map(|b| b.as_i64().map(|b| b as u8))
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        order_id: String,
+        quantity: u32,
+    }
+
+    #[test]
+    fn deserializes_activemq_messages() {
+        let input = include_str!("../tests/data/activemq_event.json");
+        let event: ActiveMqEvent = serde_json::from_str(input).expect("Could not parse ActiveMQ event");
+
+        let message = &event.messages[0];
+        assert_eq!(message.destination.physical_name, "ordersQueue");
+        assert_eq!(message.timestamp.timestamp_millis(), 1_598_827_811_958);
+        assert_eq!(message.reply_to, None);
+        assert_eq!(message.properties["myCustomProperty"], "value");
+        assert_eq!(
+            message.deserialize_data::<Order>().unwrap(),
+            Order {
+                order_id: String::from("o-123"),
+                quantity: 2,
+            }
+        );
+
+        let message = &event.messages[1];
+        assert!(message.redelivered);
+        assert!(message.data_as_str().is_err());
+        assert!(message.properties.is_empty());
+
+        let json = serde_json::to_string(&event).expect("Could not serialize ActiveMQ event");
+        assert_eq!(serde_json::from_str::<ActiveMqEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn deserializes_rabbitmq_messages() {
+        let input = include_str!("../tests/data/rabbitmq_event.json");
+        let event: RabbitMqEvent = serde_json::from_str(input).expect("Could not parse RabbitMQ event");
+
+        let queues: Vec<_> = event.queues().collect();
+        assert_eq!(queues.len(), 1);
+        let (queue, virtual_host, messages) = queues[0];
+        assert_eq!((queue, virtual_host), ("pizzaQueue", "/"));
+
+        let properties = &messages[0].basic_properties;
+        assert_eq!(properties.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(properties.header_bytes("header1"), Some(b"value1".to_vec()));
+        assert_eq!(properties.header_bytes("numberInHeader"), None);
+        assert_eq!(properties.headers["numberInHeader"], 10);
+        assert_eq!(
+            messages[0].data_as_str(),
+            Ok(r#"{"timeout":0,"data":"CZrmf0Gw8Ov4bqLQxD4E"}"#)
+        );
+
+        let json = serde_json::to_string(&event).expect("Could not serialize RabbitMQ event");
+        assert_eq!(serde_json::from_str::<RabbitMqEvent>(&json).unwrap(), event);
+    }
+}
@@ -0,0 +1,183 @@
+//! Amazon EventBridge Pipes events, sent to functions used as the enrichment or the
+//! target of a pipe.
+//!
+//! Pipes invoke functions with a JSON array of records, without the `Records` object
+//! of event source mappings. Without an input transformer, the records of SQS and
+//! DynamoDB sources have the same shape as in `SqsEvent` and `DynamodbEvent`, and the
+//! records of Kinesis sources are `PipesKinesisRecord`s. With an input transformer, or
+//! for targets after an enrichment, the records are whatever the previous step
+//! produced: use `PipesBatch<T>` with your own type.
+//!
+//! Enrichment functions must return an array too, with the records to send to the
+//! target. The array can be shorter than the batch, to filter records out.
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{dynamodb::DynamodbEventRecord, kinesis::KinesisRecord, sqs::SqsMessage};
+
+/// A batch of records sent by a pipe.
+///
+/// Pipes send batches as JSON arrays rather than objects, so batches have no fields of
+/// their own to keep with the `catch-all-fields` feature: the fields of each record that
+/// its type doesn't define are kept in the record's `other` field.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::pipes::{PipesEnrichmentResponse, PipesSqsBatch};
+/// use lambda_runtime::{error::HandlerError, Context};
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Order {
+///     id: String,
+///     customer_id: String,
+/// }
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: PipesSqsBatch, _ctx: Context) -> Result<PipesEnrichmentResponse<Order>, HandlerError> {
+///     Ok(e.enrich(|message| message.deserialize_body::<Order>().ok()))
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct PipesBatch<T = Value> {
+    /// The records, in the order of the source.
+    pub records: Vec<T>,
+}
+
+impl<T> PipesBatch<T> {
+    /// Returns the response of an enrichment function, with the records `enrich`
+    /// returns. Records for which it returns `None` are not sent to the target.
+    pub fn enrich<U, F>(&self, enrich: F) -> PipesEnrichmentResponse<U>
+    where
+        F: FnMut(&T) -> Option<U>,
+    {
+        self.records.iter().filter_map(enrich).collect()
+    }
+}
+
+/// The response of an enrichment function: the records to send to the target.
+pub type PipesEnrichmentResponse<T = Value> = Vec<T>;
+
+/// A batch of messages from an SQS source.
+pub type PipesSqsBatch = PipesBatch<SqsMessage>;
+
+/// A batch of records from a Kinesis source.
+pub type PipesKinesisBatch = PipesBatch<PipesKinesisRecord>;
+
+/// A batch of records from a DynamoDB Streams source.
+pub type PipesDynamodbBatch = PipesBatch<DynamodbEventRecord>;
+
+/// A record from a Kinesis source. Unlike `KinesisEventRecord`, pipes send the data
+/// and the metadata of the record at the top level of the record.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipesKinesisRecord {
+    /// The id of the record, made of the shard id and the sequence number.
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    /// Always `aws:kinesis:record`.
+    pub event_name: String,
+    /// Always `aws:kinesis`.
+    pub event_source: String,
+    /// The ARN of the stream.
+    #[serde(rename = "eventSourceARN")]
+    pub event_source_arn: String,
+    /// The version of the event format, for example `1.0`.
+    pub event_version: String,
+    /// The ARN of the role of the pipe.
+    pub invoke_identity_arn: String,
+    /// The region of the stream.
+    pub aws_region: String,
+    /// The data and metadata of the record.
+    #[serde(flatten)]
+    pub kinesis: KinesisRecord,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Order {
+        id: String,
+    }
+
+    #[test]
+    fn enriches_sqs_batches() {
+        let batch: PipesSqsBatch = serde_json::from_value(serde_json::json!([
+            {
+                "messageId": "059f36b4-87a3-44ab-83d2-661975830a7d",
+                "receiptHandle": "AQEBwJnKyrHigUMZj6rYigCgxlaS3SLy0a",
+                "body": "{\"id\": \"o-1\"}",
+                "attributes": {"ApproximateReceiveCount": "1"},
+                "messageAttributes": {},
+                "md5OfBody": "e4e68fb7bd0e697a0ae8f1bb342846b3",
+                "eventSource": "aws:sqs",
+                "eventSourceARN": "arn:aws:sqs:us-east-2:123456789012:orders",
+                "awsRegion": "us-east-2"
+            },
+            {
+                "messageId": "2e1424d4-f796-459a-8184-9c92662be6da",
+                "receiptHandle": "AQEBzWwaftRI0KuVm4tP+/7q1rGgNqicHq",
+                "body": "not json",
+                "attributes": {},
+                "messageAttributes": {},
+                "md5OfBody": "e4e68fb7bd0e697a0ae8f1bb342846b3",
+                "eventSource": "aws:sqs",
+                "eventSourceARN": "arn:aws:sqs:us-east-2:123456789012:orders",
+                "awsRegion": "us-east-2"
+            }
+        ]))
+        .expect("Could not parse SQS batch");
+        assert_eq!(batch.records.len(), 2);
+
+        let response = batch.enrich(|message| message.deserialize_body::<Order>().ok());
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!([{"id": "o-1"}])
+        );
+    }
+
+    #[test]
+    fn deserializes_kinesis_batches() {
+        let input = serde_json::json!([{
+            "eventSource": "aws:kinesis",
+            "eventVersion": "1.0",
+            "eventID": "shardId-000000000000:49590338271490256608559692538361571095921575989136588898",
+            "eventName": "aws:kinesis:record",
+            "invokeIdentityArn": "arn:aws:iam::123456789012:role/pipe-role",
+            "awsRegion": "us-east-1",
+            "eventSourceARN": "arn:aws:kinesis:us-east-1:123456789012:stream/orders",
+            "kinesisSchemaVersion": "1.0",
+            "partitionKey": "1",
+            "sequenceNumber": "49590338271490256608559692538361571095921575989136588898",
+            "data": "eyJpZCI6ICJvLTIifQ==",
+            "approximateArrivalTimestamp": 1545084650.987
+        }]);
+        let batch: PipesKinesisBatch = serde_json::from_value(input).expect("Could not parse Kinesis batch");
+        let record = &batch.records[0];
+        assert_eq!(record.kinesis.partition_key, "1");
+        assert_eq!(record.kinesis.deserialize_data::<Order>().unwrap().id, "o-2");
+
+        let json = serde_json::to_string(&batch).expect("Could not serialize Kinesis batch");
+        assert_eq!(serde_json::from_str::<PipesKinesisBatch>(&json).unwrap(), batch);
+    }
+
+    #[test]
+    fn deserializes_transformed_batches() {
+        let batch: PipesBatch<Order> = serde_json::from_value(serde_json::json!([{"id": "o-3"}])).unwrap();
+        assert_eq!(
+            batch.records,
+            vec![Order {
+                id: String::from("o-3")
+            }]
+        );
+    }
+}
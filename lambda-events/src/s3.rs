@@ -0,0 +1,200 @@
+//! Amazon S3 event notifications, sent when objects are created, removed, restored, or
+//! replicated in a bucket.
+use std::{borrow::Cow, collections::HashMap};
+
+use chrono::{DateTime, Utc};
+use percent_encoding::percent_decode_str;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::encodings::deserialize_lambda_map;
+
+/// An S3 event notification, containing one record per affected object.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct S3Event {
+    /// The notification records.
+    #[serde(rename = "Records")]
+    pub records: Vec<S3EventRecord>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The notification for a single object.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3EventRecord {
+    /// The version of the notification format, for example `2.1`.
+    pub event_version: String,
+    /// Always `aws:s3`.
+    pub event_source: String,
+    /// The region of the bucket.
+    pub aws_region: String,
+    /// The time S3 finished processing the request.
+    pub event_time: DateTime<Utc>,
+    /// The type of the event, for example `ObjectCreated:Put`.
+    pub event_name: String,
+    /// The principal that made the request.
+    pub user_identity: S3UserIdentity,
+    /// Parameters of the request that caused the event.
+    pub request_parameters: S3RequestParameters,
+    /// Response headers of the request, such as `x-amz-request-id`.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub response_elements: HashMap<String, String>,
+    /// The bucket and object the event is about.
+    pub s3: S3Entity,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl S3EventRecord {
+    /// Returns the URL-decoded key of the object, see `S3Object::decoded_key()`.
+    pub fn object_key(&self) -> Cow<'_, str> {
+        self.s3.object.decoded_key()
+    }
+}
+
+/// The principal that made the request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3UserIdentity {
+    /// The id of the principal.
+    pub principal_id: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Parameters of the request that caused the event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct S3RequestParameters {
+    /// The IP address the request was made from.
+    #[serde(rename = "sourceIPAddress")]
+    pub source_ip_address: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The bucket and object an event is about.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Entity {
+    /// The version of the `s3` section, for example `1.0`.
+    #[serde(rename = "s3SchemaVersion")]
+    pub schema_version: String,
+    /// The id of the notification configuration of the bucket.
+    pub configuration_id: String,
+    /// The bucket.
+    pub bucket: S3Bucket,
+    /// The object.
+    pub object: S3Object,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The bucket an event is about.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Bucket {
+    /// The name of the bucket.
+    pub name: String,
+    /// The owner of the bucket.
+    pub owner_identity: S3UserIdentity,
+    /// The ARN of the bucket.
+    pub arn: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The object an event is about.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Object {
+    /// The key of the object, URL-encoded. Use `decoded_key()` to get the actual key.
+    pub key: String,
+    /// The size of the object in bytes. Missing for removal events.
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// The ETag of the object. Missing for removal events.
+    #[serde(default, rename = "eTag")]
+    pub e_tag: Option<String>,
+    /// The version of the object, if the bucket is versioned.
+    #[serde(default)]
+    pub version_id: Option<String>,
+    /// A value that orders the events for the same key.
+    pub sequencer: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl S3Object {
+    /// Returns the key of the object. S3 encodes the keys in notifications like HTML form
+    /// values: spaces are sent as `+` and other special characters are percent-encoded,
+    /// so the key in the notification cannot be passed to the S3 APIs as is.
+    ///
+    /// ```rust
+    /// use lambda_events::s3::S3Object;
+    ///
+    /// let object = S3Object {
+    ///     key: String::from("summer+trip/beach%281%29.jpg"),
+    ///     ..S3Object::default()
+    /// };
+    /// assert_eq!(object.decoded_key(), "summer trip/beach(1).jpg");
+    /// ```
+    pub fn decoded_key(&self) -> Cow<'_, str> {
+        decode_key(&self.key)
+    }
+}
+
+/// Decodes a key encoded like an HTML form value, as S3 sends them to functions.
+pub(crate) fn decode_key(key: &str) -> Cow<'_, str> {
+    if !key.contains(['+', '%']) {
+        return Cow::Borrowed(key);
+    }
+    let key = key.replace('+', " ");
+    Cow::Owned(percent_decode_str(&key).decode_utf8_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_put_event() {
+        let input = include_str!("../tests/data/s3_event.json");
+        let event: S3Event = serde_json::from_str(input).expect("Could not parse S3 event");
+        let record = &event.records[0];
+        assert_eq!(record.event_name, "ObjectCreated:Put");
+        assert_eq!(record.event_time.timestamp(), 1_563_309_821);
+        assert_eq!(record.s3.bucket.name, "lambda-artifacts-deafc19498e3f2df");
+        assert_eq!(record.s3.object.size, Some(1_305_107));
+        assert_eq!(record.object_key(), "photos/summer trip/beach(1) été.jpg");
+        assert_eq!(record.response_elements["x-amz-request-id"], "D82B88E5F771F645");
+    }
+
+    #[test]
+    fn deserializes_delete_event() {
+        let input = include_str!("../tests/data/s3_event_delete.json");
+        let event: S3Event = serde_json::from_str(input).expect("Could not parse S3 event");
+        let record = &event.records[0];
+        assert_eq!(record.event_name, "ObjectRemoved:Delete");
+        assert_eq!(record.event_time.timestamp_subsec_millis(), 123);
+        assert!(record.response_elements.is_empty());
+        assert_eq!(record.s3.object.size, None);
+        assert!(matches!(record.object_key(), Cow::Borrowed("reports/2019/q2.csv")));
+
+        let json = serde_json::to_string(&event).expect("Could not serialize S3 event");
+        assert_eq!(serde_json::from_str::<S3Event>(&json).unwrap(), event);
+    }
+}
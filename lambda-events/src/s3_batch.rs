@@ -0,0 +1,245 @@
+//! Amazon S3 Batch Operations events, sent to functions invoked by the jobs that run
+//! the `LambdaInvoke` operation, and the task results functions return to the job.
+use std::{borrow::Cow, collections::HashMap};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{encodings::deserialize_lambda_map, s3::decode_key};
+
+/// An invocation of a function by a batch job, with the objects to process. Jobs
+/// currently send one task per invocation.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::s3_batch::{S3BatchJobEvent, S3BatchJobResponse};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: S3BatchJobEvent, _ctx: Context) -> Result<S3BatchJobResponse, HandlerError> {
+///     Ok(e.process(|task| match task.bucket_name() {
+///         Some(bucket) => task.succeeded(format!("processed {}/{}", bucket, task.decoded_key())),
+///         None => task.permanent_failure("no bucket"),
+///     }))
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchJobEvent {
+    /// The version of the event format, `1.0` or `2.0`.
+    pub invocation_schema_version: String,
+    /// The id of the invocation, which the response must copy.
+    pub invocation_id: String,
+    /// The job.
+    pub job: S3BatchJob,
+    /// The tasks to process.
+    pub tasks: Vec<S3BatchJobTask>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl S3BatchJobEvent {
+    /// Returns the response with the results of the tasks.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` The results of the tasks, usually created with `succeeded()`,
+    ///   `temporary_failure()`, or `permanent_failure()` on the tasks.
+    pub fn response<I>(&self, results: I) -> S3BatchJobResponse
+    where
+        I: IntoIterator<Item = S3BatchJobTaskResult>,
+    {
+        S3BatchJobResponse {
+            invocation_schema_version: self.invocation_schema_version.clone(),
+            treat_missing_keys_as: S3BatchResultCode::PermanentFailure,
+            invocation_id: self.invocation_id.clone(),
+            results: results.into_iter().collect(),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+
+    /// Calls a handler with each task, and returns the response with their results.
+    pub fn process<F>(&self, handler: F) -> S3BatchJobResponse
+    where
+        F: FnMut(&S3BatchJobTask) -> S3BatchJobTaskResult,
+    {
+        self.response(self.tasks.iter().map(handler))
+    }
+}
+
+/// The batch job that invoked a function.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchJob {
+    /// The id of the job.
+    pub id: String,
+    /// The arguments set when the job was created. Only sent with version `2.0`.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub user_arguments: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An object to process.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchJobTask {
+    /// The id of the task, which the result must copy.
+    pub task_id: String,
+    /// The key of the object, URL-encoded. Use `decoded_key()` to get the actual key.
+    pub s3_key: String,
+    /// The version of the object, if the bucket is versioned.
+    #[serde(default)]
+    pub s3_version_id: Option<String>,
+    /// The ARN of the bucket. Only sent with version `1.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub s3_bucket_arn: Option<String>,
+    /// The name of the bucket. Only sent with version `2.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub s3_bucket: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl S3BatchJobTask {
+    /// Returns the key of the object, decoded like `S3Object::decoded_key()`.
+    pub fn decoded_key(&self) -> Cow<'_, str> {
+        decode_key(&self.s3_key)
+    }
+
+    /// Returns the name of the bucket, for both versions of the event format.
+    pub fn bucket_name(&self) -> Option<&str> {
+        self.s3_bucket
+            .as_deref()
+            .or_else(|| self.s3_bucket_arn.as_deref()?.strip_prefix("arn:aws:s3:::"))
+    }
+
+    /// Returns the result of a task that succeeded.
+    pub fn succeeded(&self, result: impl Into<String>) -> S3BatchJobTaskResult {
+        self.result(S3BatchResultCode::Succeeded, result.into())
+    }
+
+    /// Returns the result of a task that failed, which the job retries.
+    pub fn temporary_failure(&self, reason: impl Into<String>) -> S3BatchJobTaskResult {
+        self.result(S3BatchResultCode::TemporaryFailure, reason.into())
+    }
+
+    /// Returns the result of a task that failed, which the job doesn't retry.
+    pub fn permanent_failure(&self, reason: impl Into<String>) -> S3BatchJobTaskResult {
+        self.result(S3BatchResultCode::PermanentFailure, reason.into())
+    }
+
+    fn result(&self, result_code: S3BatchResultCode, result_string: String) -> S3BatchJobTaskResult {
+        S3BatchJobTaskResult {
+            task_id: self.task_id.clone(),
+            result_code,
+            result_string: Some(result_string),
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+/// The response of a function to a batch job.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchJobResponse {
+    /// The version of the event format, copied from the event.
+    pub invocation_schema_version: String,
+    /// The result of the tasks missing from `results`.
+    pub treat_missing_keys_as: S3BatchResultCode,
+    /// The id of the invocation, copied from the event.
+    pub invocation_id: String,
+    /// The results of the tasks.
+    pub results: Vec<S3BatchJobTaskResult>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The result of a task, shown in the completion report of the job.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchJobTaskResult {
+    /// The id of the task, copied from the task.
+    pub task_id: String,
+    /// Whether the task succeeded.
+    pub result_code: S3BatchResultCode,
+    /// A description of the result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_string: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Whether a task succeeded, and whether the job retries it otherwise.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum S3BatchResultCode {
+    /// The task succeeded.
+    Succeeded,
+    /// The task failed, and the job retries it.
+    TemporaryFailure,
+    /// The task failed, and the job doesn't retry it.
+    #[default]
+    PermanentFailure,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_jobs() {
+        let input = include_str!("../tests/data/s3_batch_job_event.json");
+        let event: S3BatchJobEvent = serde_json::from_str(input).expect("Could not parse job event");
+        assert_eq!(event.job.user_arguments["storageClass"], "GLACIER");
+
+        let task = &event.tasks[0];
+        assert_eq!(task.bucket_name(), Some("my-photos"));
+        assert_eq!(task.decoded_key(), "photos/summer trip/beach(1).jpg");
+
+        let task = S3BatchJobTask {
+            s3_bucket_arn: Some(String::from("arn:aws:s3:::my-archive")),
+            ..S3BatchJobTask::default()
+        };
+        assert_eq!(task.bucket_name(), Some("my-archive"));
+
+        let json = serde_json::to_string(&event).expect("Could not serialize job event");
+        assert_eq!(serde_json::from_str::<S3BatchJobEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn aggregates_task_results() {
+        let input = include_str!("../tests/data/s3_batch_job_event.json");
+        let event: S3BatchJobEvent = serde_json::from_str(input).expect("Could not parse job event");
+
+        let response = event.process(|task| task.temporary_failure("SlowDown"));
+        let expected = serde_json::json!({
+            "invocationSchemaVersion": "2.0",
+            "treatMissingKeysAs": "PermanentFailure",
+            "invocationId": "YXNkbGZqYWRmaiBhc2RmdW9hZHNmZGpmaGFzbGtkaGZza2RmaAo",
+            "results": [
+                {
+                    "taskId": "dGFza2lkZ29lc2hlcmUK",
+                    "resultCode": "TemporaryFailure",
+                    "resultString": "SlowDown",
+                }
+            ],
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+    }
+}
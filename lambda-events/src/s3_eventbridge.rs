@@ -0,0 +1,196 @@
+//! The details of the Amazon S3 events delivered by EventBridge, for buckets with
+//! EventBridge notifications enabled.
+//!
+//! These events are `EventBridgeEvent`s, with a different shape than the notifications
+//! of the `s3` module: one object per event, kebab-case fields, and keys that are not
+//! URL-encoded.
+use serde_derive::{Deserialize, Serialize};
+
+use crate::eventbridge::EventBridgeEvent;
+
+/// The source of S3 events.
+pub const S3_EVENT_SOURCE: &str = "aws.s3";
+
+/// An `Object Created` event.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::s3_eventbridge::S3ObjectCreatedEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: S3ObjectCreatedEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     println!("{} created {}/{}", e.detail.requester, e.detail.bucket.name, e.detail.object.key);
+///     Ok(())
+/// }
+/// ```
+pub type S3ObjectCreatedEvent = EventBridgeEvent<S3ObjectCreatedDetail>;
+
+/// An `Object Deleted` event.
+pub type S3ObjectDeletedEvent = EventBridgeEvent<S3ObjectDeletedDetail>;
+
+/// The detail of an `Object Created` event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct S3ObjectCreatedDetail {
+    /// The version of the detail format, `0`.
+    pub version: String,
+    /// The bucket.
+    pub bucket: S3EventBridgeBucket,
+    /// The object.
+    pub object: S3EventBridgeObject,
+    /// The id of the request that created the object.
+    pub request_id: String,
+    /// The AWS account or service principal that made the request.
+    pub requester: String,
+    /// The IP address the request came from.
+    #[serde(default)]
+    pub source_ip_address: Option<String>,
+    /// The API that created the object: `PutObject`, `POST Object`, `CopyObject`, or
+    /// `CompleteMultipartUpload`.
+    pub reason: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The detail of an `Object Deleted` event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct S3ObjectDeletedDetail {
+    /// The version of the detail format, `0`.
+    pub version: String,
+    /// The bucket.
+    pub bucket: S3EventBridgeBucket,
+    /// The object.
+    pub object: S3EventBridgeObject,
+    /// The id of the request that deleted the object.
+    pub request_id: String,
+    /// The AWS account or service principal that made the request, `s3.amazonaws.com`
+    /// for lifecycle expirations.
+    pub requester: String,
+    /// The IP address the request came from.
+    #[serde(default)]
+    pub source_ip_address: Option<String>,
+    /// `DeleteObject` or `Lifecycle Expiration`.
+    pub reason: String,
+    /// `Permanently Deleted` or `Delete Marker Created`.
+    pub deletion_type: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl S3ObjectDeletedDetail {
+    /// Returns whether the object was only hidden behind a delete marker, in a
+    /// versioned bucket.
+    pub fn is_delete_marker(&self) -> bool {
+        self.deletion_type == "Delete Marker Created"
+    }
+}
+
+/// The bucket of an event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct S3EventBridgeBucket {
+    /// The name of the bucket.
+    pub name: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The object of an event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct S3EventBridgeObject {
+    /// The key of the object, not encoded.
+    pub key: String,
+    /// The size of the object in bytes. Missing for deletions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// The ETag of the object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// The version of the object, if the bucket is versioned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    /// A value that orders the events for the same key. Compare the sequencers of two
+    /// events with `is_after()`.
+    pub sequencer: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl S3EventBridgeObject {
+    /// Returns whether the event of this object happened after the event of another
+    /// object with the same key. Sequencers are hexadecimal strings of varying lengths,
+    /// so they must be padded to be compared.
+    pub fn is_after(&self, other: &S3EventBridgeObject) -> bool {
+        let width = self.sequencer.len().max(other.sequencer.len());
+        format!("{:0>width$}", self.sequencer, width = width) > format!("{:0>width$}", other.sequencer, width = width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_object_created_events() {
+        let input = include_str!("../tests/data/s3_eventbridge_object_created.json");
+        let event: S3ObjectCreatedEvent = serde_json::from_str(input).expect("Could not parse S3 event");
+        assert_eq!(event.source, S3_EVENT_SOURCE);
+        assert_eq!(event.detail_type, "Object Created");
+        assert_eq!(event.detail.object.key, "reports/2021 q3.csv");
+        assert_eq!(event.detail.object.size, Some(5));
+        assert_eq!(event.detail.reason, "PutObject");
+
+        let json = serde_json::to_string(&event).expect("Could not serialize S3 event");
+        assert_eq!(serde_json::from_str::<S3ObjectCreatedEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn deserializes_object_deleted_details() {
+        let detail: S3ObjectDeletedDetail = serde_json::from_value(serde_json::json!({
+            "version": "0",
+            "bucket": {"name": "DOC-EXAMPLE-BUCKET1"},
+            "object": {
+                "key": "reports/2021 q3.csv",
+                "version-id": "1QW9g1Z99LUNbvaaYVpW9xDlOLU.qxgF",
+                "sequencer": "617f0837b476e463"
+            },
+            "request-id": "0BH729840619AG5K",
+            "requester": "123456789012",
+            "source-ip-address": "1.2.3.4",
+            "reason": "DeleteObject",
+            "deletion-type": "Delete Marker Created"
+        }))
+        .expect("Could not parse detail");
+        assert!(detail.is_delete_marker());
+        assert_eq!(detail.object.size, None);
+    }
+
+    #[test]
+    fn compares_sequencers() {
+        let first = S3EventBridgeObject {
+            sequencer: String::from("0A1B2C"),
+            ..S3EventBridgeObject::default()
+        };
+        let second = S3EventBridgeObject {
+            sequencer: String::from("0A1B2C3D"),
+            ..S3EventBridgeObject::default()
+        };
+        assert!(second.is_after(&first));
+        assert!(!first.is_after(&second));
+    }
+}
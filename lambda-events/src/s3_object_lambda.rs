@@ -0,0 +1,204 @@
+//! Amazon S3 Object Lambda events, sent to the functions that transform the objects
+//! read through an Object Lambda access point.
+//!
+//! For `GetObject` requests, the function downloads the original object from
+//! `input_s3_url` and sends the transformed object with the `WriteGetObjectResponse`
+//! API, using the route and the token of the event. This crate doesn't depend on an AWS
+//! SDK, make that call with the SDK of your choice.
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::encodings::deserialize_lambda_map;
+
+/// A request received by an Object Lambda access point. Only the context of the
+/// operation of the request is set.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::s3_object_lambda::S3ObjectLambdaEvent;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: S3ObjectLambdaEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     if let Some(context) = e.get_object_context {
+///         // download context.input_s3_url, transform the object, and call
+///         // WriteGetObjectResponse with context.output_route and context.output_token
+///         println!("transforming {}", e.user_request.url);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3ObjectLambdaEvent {
+    /// The id of the request.
+    pub x_amz_request_id: String,
+    /// The context of `GetObject` requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub get_object_context: Option<S3ObjectLambdaGetObjectContext>,
+    /// The context of `HeadObject` requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_object_context: Option<S3ObjectLambdaInputContext>,
+    /// The context of `ListObjects` requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_objects_context: Option<S3ObjectLambdaInputContext>,
+    /// The context of `ListObjectsV2` requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_objects_v2_context: Option<S3ObjectLambdaInputContext>,
+    /// The configuration of the access point.
+    pub configuration: S3ObjectLambdaConfiguration,
+    /// The request sent to the access point.
+    pub user_request: S3ObjectLambdaUserRequest,
+    /// The caller of the access point.
+    pub user_identity: S3ObjectLambdaUserIdentity,
+    /// The version of the event format, for example `1.00`.
+    pub protocol_version: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The context of a `GetObject` request.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3ObjectLambdaGetObjectContext {
+    /// The presigned URL of the original object in the supporting access point.
+    pub input_s3_url: String,
+    /// The route to send with `WriteGetObjectResponse`.
+    pub output_route: String,
+    /// The token to send with `WriteGetObjectResponse`.
+    pub output_token: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The context of a `HeadObject`, `ListObjects`, or `ListObjectsV2` request, which
+/// the function answers in its response rather than with an API call.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3ObjectLambdaInputContext {
+    /// The presigned URL of the original request in the supporting access point.
+    pub input_s3_url: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The configuration of an Object Lambda access point.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3ObjectLambdaConfiguration {
+    /// The ARN of the Object Lambda access point.
+    pub access_point_arn: String,
+    /// The ARN of the access point the original objects are read from.
+    pub supporting_access_point_arn: String,
+    /// The payload of the transformation, if the access point sets one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl S3ObjectLambdaConfiguration {
+    /// Deserializes the payload of the transformation, for access points configured
+    /// with a JSON payload.
+    ///
+    /// # Return
+    /// The payload parsed as `T`, `None` if there is none, or the error if the payload
+    /// is not valid for `T`.
+    pub fn deserialize_payload<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.payload.as_deref().map(serde_json::from_str)
+    }
+}
+
+/// The request sent to an Object Lambda access point.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3ObjectLambdaUserRequest {
+    /// The URL of the request.
+    pub url: String,
+    /// The headers of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl S3ObjectLambdaUserRequest {
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// The caller of an Object Lambda access point.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3ObjectLambdaUserIdentity {
+    /// The type of the caller, for example `IAMUser` or `AssumedRole`.
+    #[serde(rename = "type")]
+    pub identity_type: String,
+    /// The id of the caller.
+    pub principal_id: String,
+    /// The ARN of the caller.
+    pub arn: String,
+    /// The AWS account of the caller.
+    pub account_id: String,
+    /// The access key the request was signed with.
+    #[serde(default)]
+    pub access_key_id: String,
+    /// The session of callers with temporary credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_context: Option<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Payload {
+        redact: Vec<String>,
+    }
+
+    #[test]
+    fn deserializes_get_object_events() {
+        let input = include_str!("../tests/data/s3_object_lambda_event.json");
+        let event: S3ObjectLambdaEvent = serde_json::from_str(input).expect("Could not parse Object Lambda event");
+        let context = event.get_object_context.as_ref().expect("Expected a GetObject context");
+        assert_eq!(context.output_route, "io-iad-cell001");
+        assert_eq!(event.head_object_context, None);
+        assert_eq!(event.user_request.header("accept-encoding"), Some("identity"));
+        assert_eq!(event.user_identity.identity_type, "AssumedRole");
+
+        let payload: Payload = event.configuration.deserialize_payload().unwrap().unwrap();
+        assert_eq!(payload.redact, vec!["email", "phone"]);
+
+        let json = serde_json::to_string(&event).expect("Could not serialize Object Lambda event");
+        assert_eq!(serde_json::from_str::<S3ObjectLambdaEvent>(&json).unwrap(), event);
+    }
+}
@@ -0,0 +1,308 @@
+//! Sample events, as sent by AWS services, to test functions with.
+//!
+//! Every sample is deserialized into its type and serialized back by the tests of this
+//! module, so a sample added here with a new type is enough to check that the type
+//! matches the payloads of the service.
+//!
+//! ```rust
+//! use lambda_events::{samples, sqs::SqsEvent};
+//!
+//! let event: SqsEvent = serde_json::from_str(samples::SQS_EVENT).unwrap();
+//! assert_eq!(event.records.len(), 2);
+//! ```
+//!
+//! The samples can also be printed with the `gen-event` binary, for example with
+//! `cargo run --bin gen-event -- sqs` to invoke a function locally.
+
+macro_rules! samples {
+    ($($(#[$doc:meta])* $name:ident: $ty:ty = $file:literal;)*) => {
+        $(
+            $(#[$doc])*
+            pub const $name: &str = include_str!(concat!("../tests/data/", $file));
+        )*
+
+        /// The file names and the payloads of the samples, sorted by name.
+        const SAMPLES: &[(&str, &str)] = &[$(($file, $name)),*];
+
+        /// The types of the samples, as written in `samples!`.
+        #[cfg(test)]
+        const SAMPLE_TYPES: &[&str] = &[$(stringify!($ty)),*];
+
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod round_trip {
+            $(
+                #[test]
+                fn $name() {
+                    super::tests::assert_round_trip::<$ty>($file, super::$name);
+                }
+            )*
+        }
+    };
+}
+
+samples! {
+    /// An Amazon MQ for ActiveMQ event.
+    ACTIVEMQ_EVENT: crate::mq::ActiveMqEvent = "activemq_event.json";
+    /// An Application Load Balancer request, with multi-value headers.
+    ALB_MULTI_VALUE_REQUEST: crate::alb::AlbTargetGroupRequest = "alb_multi_value_request.json";
+    /// An Application Load Balancer request.
+    ALB_REQUEST: crate::alb::AlbTargetGroupRequest = "alb_request.json";
+    /// An Alexa Skills Kit intent request.
+    ALEXA_INTENT_REQUEST: crate::alexa::AlexaRequestEnvelope = "alexa_intent_request.json";
+    /// An Alexa Skills Kit response, with a card and a reprompt.
+    ALEXA_RESPONSE: crate::alexa::AlexaResponse = "alexa_response.json";
+    /// An API Gateway custom authorizer response, with an IAM policy.
+    APIGW_CUSTOM_AUTHORIZER_RESPONSE: crate::authorizer::ApiGatewayCustomAuthorizerResponse =
+        "apigw_custom_authorizer_response.json";
+    /// An API Gateway REST API proxy request.
+    APIGW_PROXY_REQUEST: crate::apigw::ApiGatewayProxyRequest = "apigw_proxy_request.json";
+    /// An API Gateway REST API proxy response.
+    APIGW_PROXY_RESPONSE: crate::apigw::ApiGatewayProxyResponse = "apigw_proxy_response.json";
+    /// An API Gateway request authorizer request.
+    APIGW_REQUEST_AUTHORIZER: crate::authorizer::ApiGatewayRequestAuthorizerRequest =
+        "apigw_request_authorizer.json";
+    /// An API Gateway token authorizer request.
+    APIGW_TOKEN_AUTHORIZER: crate::authorizer::ApiGatewayTokenAuthorizerRequest = "apigw_token_authorizer.json";
+    /// An API Gateway WebSocket `$connect` request.
+    APIGW_WEBSOCKET_CONNECT: crate::apigw::ApiGatewayWebsocketProxyRequest = "apigw_websocket_connect.json";
+    /// An API Gateway WebSocket `$disconnect` request.
+    APIGW_WEBSOCKET_DISCONNECT: crate::apigw::ApiGatewayWebsocketProxyRequest = "apigw_websocket_disconnect.json";
+    /// An API Gateway WebSocket message.
+    APIGW_WEBSOCKET_MESSAGE: crate::apigw::ApiGatewayWebsocketProxyRequest = "apigw_websocket_message.json";
+    /// An AppSync resolver event, from a user authenticated with Cognito.
+    APPSYNC_COGNITO_EVENT: crate::appsync::AppSyncResolverEvent = "appsync_cognito_event.json";
+    /// An AWS Batch `Batch Job State Change` event.
+    BATCH_JOB_STATE_CHANGE: crate::batch_job::BatchJobStateChangeEvent = "batch_job_state_change.json";
+    /// A CloudFormation custom resource `Create` request.
+    CLOUDFORMATION_CREATE_REQUEST: crate::cloudformation::CloudFormationCustomResourceRequest =
+        "cloudformation_create_request.json";
+    /// A CloudFormation custom resource `Update` request.
+    CLOUDFORMATION_UPDATE_REQUEST: crate::cloudformation::CloudFormationCustomResourceRequest =
+        "cloudformation_update_request.json";
+    /// A CloudWatch `CloudWatch Alarm State Change` event.
+    CLOUDWATCH_ALARM_STATE_CHANGE: crate::cloudwatch_alarms::CloudWatchAlarmStateChangeEvent =
+        "cloudwatch_alarm_state_change.json";
+    /// A CloudWatch Logs subscription event.
+    CLOUDWATCH_LOGS_EVENT: crate::cloudwatch_logs::CloudwatchLogsEvent = "cloudwatch_logs_event.json";
+    /// A CodeCommit trigger event.
+    CODECOMMIT_EVENT: crate::codecommit::CodeCommitEvent = "codecommit_event.json";
+    /// A CodePipeline job event.
+    CODEPIPELINE_JOB_EVENT: crate::codepipeline::CodePipelineEvent = "codepipeline_job_event.json";
+    /// A Cognito user pool custom message trigger.
+    COGNITO_CUSTOM_MESSAGE: crate::cognito::CognitoCustomMessageEvent = "cognito_custom_message.json";
+    /// A Cognito user pool define auth challenge trigger.
+    COGNITO_DEFINE_AUTH_CHALLENGE: crate::cognito::CognitoDefineAuthChallengeEvent =
+        "cognito_define_auth_challenge.json";
+    /// A Cognito user pool post authentication trigger.
+    COGNITO_POST_AUTHENTICATION: crate::cognito::CognitoPostAuthenticationEvent = "cognito_post_authentication.json";
+    /// A Cognito user pool post confirmation trigger.
+    COGNITO_POST_CONFIRMATION: crate::cognito::CognitoPostConfirmationEvent = "cognito_post_confirmation.json";
+    /// A Cognito user pool pre authentication trigger.
+    COGNITO_PRE_AUTHENTICATION: crate::cognito::CognitoPreAuthenticationEvent = "cognito_pre_authentication.json";
+    /// A Cognito user pool pre sign-up trigger.
+    COGNITO_PRE_SIGNUP: crate::cognito::CognitoPreSignUpEvent = "cognito_pre_signup.json";
+    /// A Cognito user pool pre token generation trigger, with claims overridden.
+    COGNITO_PRE_TOKEN_GENERATION: crate::cognito::CognitoPreTokenGenerationEvent = "cognito_pre_token_generation.json";
+    /// An AWS Config custom rule event.
+    CONFIG_RULE_EVENT: crate::config::ConfigEvent = "config_rule_event.json";
+    /// An on-failure destination record of an asynchronous invocation.
+    DESTINATION_ON_FAILURE: crate::destinations::DestinationRecord = "destination_on_failure.json";
+    /// A DynamoDB Streams event.
+    DYNAMODB_EVENT: crate::dynamodb::DynamodbEvent = "dynamodb_event.json";
+    /// An Amazon ECS `ECS Task State Change` event.
+    ECS_TASK_STATE_CHANGE: crate::ecs::EcsTaskStateChangeEvent = "ecs_task_state_change.json";
+    /// An EventBridge event.
+    EVENTBRIDGE_EVENT: crate::eventbridge::EventBridgeEvent = "eventbridge_event.json";
+    /// A GuardDuty finding.
+    GUARDDUTY_FINDING_EVENT: crate::guardduty::GuardDutyFindingEvent = "guardduty_finding_event.json";
+    /// An `AWS Health Event` event.
+    HEALTH_EVENT: crate::health::HealthEvent = "health_event.json";
+    /// An API Gateway HTTP API authorizer request.
+    HTTP_API_AUTHORIZER: crate::authorizer::ApiGatewayV2AuthorizerRequest = "http_api_authorizer.json";
+    /// An API Gateway HTTP API request, in the 2.0 payload format.
+    HTTP_API_REQUEST: crate::http_api::ApiGatewayV2HttpRequest = "http_api_request.json";
+    /// An API Gateway HTTP API authorizer response, in the simple format.
+    HTTP_API_SIMPLE_AUTHORIZER_RESPONSE: crate::authorizer::ApiGatewayV2SimpleAuthorizerResponse =
+        "http_api_simple_authorizer_response.json";
+    /// An AWS IoT Core custom authorizer request.
+    IOT_CUSTOM_AUTHORIZER_REQUEST: crate::iot::IotCustomAuthorizerRequest = "iot_custom_authorizer_request.json";
+    /// An AWS IoT Core custom authorizer response.
+    IOT_CUSTOM_AUTHORIZER_RESPONSE: crate::iot::IotCustomAuthorizerResponse = "iot_custom_authorizer_response.json";
+    /// An AWS IoT Core rule event, with the topic, client id and timestamp selected.
+    IOT_RULE_EVENT: crate::iot::IotRuleEvent = "iot_rule_event.json";
+    /// A Kinesis Data Streams event.
+    KINESIS_EVENT: crate::kinesis::KinesisEvent = "kinesis_event.json";
+    /// A Lambda function URL request.
+    LAMBDA_FUNCTION_URL_REQUEST: crate::lambda_function_url::LambdaFunctionUrlRequest =
+        "lambda_function_url_request.json";
+    /// An Amazon Lex V2 event.
+    LEX_V2_EVENT: crate::lex::LexV2Event = "lex_v2_event.json";
+    /// An Amazon Lex V2 code hook response.
+    LEX_V2_RESPONSE: crate::lex::LexV2Response = "lex_v2_response.json";
+    /// An Amazon MSK event.
+    MSK_EVENT: crate::kafka::KafkaEvent = "msk_event.json";
+    /// A batch of Kinesis records sent by an EventBridge pipe.
+    PIPES_KINESIS_BATCH: crate::pipes::PipesKinesisBatch = "pipes_kinesis_batch.json";
+    /// A batch of SQS messages sent by an EventBridge pipe.
+    PIPES_SQS_BATCH: crate::pipes::PipesSqsBatch = "pipes_sqs_batch.json";
+    /// An Amazon MQ for RabbitMQ event.
+    RABBITMQ_EVENT: crate::mq::RabbitMqEvent = "rabbitmq_event.json";
+    /// An S3 Batch Operations job event.
+    S3_BATCH_JOB_EVENT: crate::s3_batch::S3BatchJobEvent = "s3_batch_job_event.json";
+    /// An S3 `ObjectCreated:Put` notification.
+    S3_EVENT: crate::s3::S3Event = "s3_event.json";
+    /// An S3 `Object Created` event delivered by EventBridge.
+    S3_EVENTBRIDGE_OBJECT_CREATED: crate::s3_eventbridge::S3ObjectCreatedEvent =
+        "s3_eventbridge_object_created.json";
+    /// An S3 `ObjectRemoved:Delete` notification.
+    S3_EVENT_DELETE: crate::s3::S3Event = "s3_event_delete.json";
+    /// An S3 Object Lambda event.
+    S3_OBJECT_LAMBDA_EVENT: crate::s3_object_lambda::S3ObjectLambdaEvent = "s3_object_lambda_event.json";
+    /// A scheduled EventBridge event.
+    SCHEDULED_EVENT: crate::eventbridge::ScheduledEvent = "scheduled_event.json";
+    /// A Secrets Manager rotation event.
+    SECRETSMANAGER_ROTATION_EVENT: crate::secretsmanager::SecretsManagerRotationEvent =
+        "secretsmanager_rotation_event.json";
+    /// A Security Hub `Security Hub Findings - Imported` event.
+    SECURITYHUB_FINDINGS_IMPORTED: crate::securityhub::SecurityHubFindingsEvent =
+        "securityhub_findings_imported.json";
+    /// A self-managed Apache Kafka event.
+    SELF_MANAGED_KAFKA_EVENT: crate::kafka::KafkaEvent = "self_managed_kafka_event.json";
+    /// An SES receipt rule event.
+    SES_EVENT: crate::ses::SimpleEmailEvent = "ses_event.json";
+    /// An SNS event.
+    SNS_EVENT: crate::sns::SnsEvent = "sns_event.json";
+    /// The response of an SQS function that reports a partial batch failure.
+    SQS_BATCH_RESPONSE: crate::sqs::SqsBatchResponse = "sqs_batch_response.json";
+    /// An SQS event.
+    SQS_EVENT: crate::sqs::SqsEvent = "sqs_event.json";
+    /// The payload of a Step Functions task with a task token.
+    STEPFUNCTIONS_TASK_TOKEN_PAYLOAD: crate::stepfunctions::TaskTokenPayload = "stepfunctions_task_token_payload.json";
+    /// The response of a stream function that reports a partial batch failure.
+    STREAMS_EVENT_RESPONSE: crate::batch::StreamsEventResponse = "streams_event_response.json";
+    /// A VPC Lattice request, in the 2.0 event format.
+    VPC_LATTICE_V2_REQUEST: crate::vpc_lattice::VpcLatticeRequestV2 = "vpc_lattice_v2_request.json";
+}
+
+/// Returns the sample with the given name, which is the name of its constant in
+/// lowercase, for example `sqs_event` for `SQS_EVENT`.
+pub fn get(name: &str) -> Option<&'static str> {
+    SAMPLES
+        .iter()
+        .find(|(file, _)| file.trim_end_matches(".json").eq_ignore_ascii_case(name))
+        .map(|(_, sample)| *sample)
+}
+
+/// Returns the names of the samples, in alphabetical order.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    SAMPLES.iter().map(|(file, _)| file.trim_end_matches(".json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fmt::Debug, fs};
+
+    use serde::{de::DeserializeOwned, Serialize};
+    use serde_json::Value;
+
+    /// Deserializes a sample into `T`, serializes it back, and checks that nothing was
+    /// lost and that every field `T` serializes, apart from defaults, is a field of the
+    /// sample, which catches misnamed fields. With `catch-all-fields`, also checks that
+    /// every field of the sample is serialized back.
+    pub(super) fn assert_round_trip<T>(file: &str, sample: &str)
+    where
+        T: DeserializeOwned + Serialize + PartialEq + Debug,
+    {
+        let original: Value = serde_json::from_str(sample).unwrap_or_else(|e| panic!("{} is not JSON: {}", file, e));
+        let event: T = serde_json::from_str(sample).unwrap_or_else(|e| panic!("Could not parse {}: {}", file, e));
+        let json = serde_json::to_value(&event).unwrap_or_else(|e| panic!("Could not serialize {}: {}", file, e));
+        assert_fields_in(file, "", &json, &original, "is serialized but not in the sample");
+        #[cfg(feature = "catch-all-fields")]
+        assert_fields_in(file, "", &original, &json, "is in the sample but not serialized");
+
+        let reparsed: T =
+            serde_json::from_value(json).unwrap_or_else(|e| panic!("Could not parse serialized {}: {}", file, e));
+        assert_eq!(reparsed, event, "{} changed in a round trip", file);
+    }
+
+    /// Checks that every field of `value`, apart from defaults, is a field of `other`.
+    fn assert_fields_in(file: &str, path: &str, value: &Value, other: &Value, missing: &str) {
+        match (value, other) {
+            (Value::Object(fields), Value::Object(other_fields)) => {
+                for (name, value) in fields {
+                    if is_default(value) {
+                        continue;
+                    }
+                    let path = format!("{}.{}", path, name);
+                    match other_fields.get(name) {
+                        Some(other) => assert_fields_in(file, &path, value, other, missing),
+                        None => panic!("{}: {} {}", file, path, missing),
+                    }
+                }
+            }
+            (Value::Array(items), Value::Array(other_items)) => {
+                for (i, (item, other)) in items.iter().zip(other_items).enumerate() {
+                    assert_fields_in(file, &format!("{}[{}]", path, i), item, other, missing);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns whether a value is the default of its type, which the types serialize for
+    /// fields that are missing from the payloads.
+    fn is_default(value: &Value) -> bool {
+        match value {
+            Value::Null => true,
+            Value::Bool(b) => !b,
+            Value::Number(n) => n.as_f64() == Some(0.0),
+            Value::String(s) => s.is_empty(),
+            Value::Array(items) => items.is_empty(),
+            Value::Object(fields) => fields.is_empty(),
+        }
+    }
+
+    #[test]
+    fn covers_every_sample() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data");
+        for entry in fs::read_dir(dir).unwrap() {
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            assert!(
+                super::SAMPLES.iter().any(|(file, _)| *file == name),
+                "{} is not in `samples!`",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn covers_every_module() {
+        // modules of helpers rather than of event sources
+        const NOT_EVENTS: &[&str] = &["encodings", "lib", "samples", "schema_codegen"];
+
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+        for entry in fs::read_dir(dir).unwrap() {
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            let module = match name.strip_suffix(".rs") {
+                Some(module) if !NOT_EVENTS.contains(&module) => module,
+                _ => continue,
+            };
+            assert!(
+                super::SAMPLE_TYPES
+                    .iter()
+                    .any(|ty| ty.split("::").nth(1).map(str::trim) == Some(module)),
+                "{} has no sample in `samples!`",
+                module
+            );
+        }
+    }
+
+    #[test]
+    fn gets_samples_by_name() {
+        assert_eq!(super::get("sqs_event"), Some(super::SQS_EVENT));
+        assert_eq!(super::get("SQS_EVENT"), Some(super::SQS_EVENT));
+        assert_eq!(super::get("sqs"), None);
+        assert!(super::names().any(|name| name == "s3_event_delete"));
+    }
+}
@@ -0,0 +1,433 @@
+//! Generates the `detail` types of custom events from the schemas of the EventBridge
+//! schema registry, with the `schema-codegen` feature.
+//!
+//! Export the schema of an event from the registry, in the OpenAPI 3 or the JSON Schema
+//! Draft 4 format, and generate its types from the build script of the function:
+//!
+//! ```rust,no_run
+//! // build.rs
+//! use std::{env, path::Path};
+//!
+//! fn main() {
+//!     let out = Path::new(&env::var("OUT_DIR").unwrap()).join("order_created.rs");
+//!     lambda_events::schema_codegen::generate_file("schemas/OrderCreated.json", out).unwrap();
+//! }
+//! ```
+//!
+//! Then include the generated types in a module of the function:
+//!
+//! ```rust,ignore
+//! mod order_created {
+//!     include!(concat!(env!("OUT_DIR"), "/order_created.rs"));
+//! }
+//!
+//! fn my_handler(e: order_created::OrderCreatedEvent, _ctx: Context) -> Result<(), HandlerError> {
+//!     println!("order {} created", e.detail.order_id);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The generated module has a struct per object of the schema, the `DETAIL_TYPE` and
+//! `SOURCE` constants of the event, and an alias of `EventBridgeEvent` for the event.
+//! Its types use `serde_derive`, `serde_json`, and `chrono`, which the function must
+//! depend on. Properties that are not required are `Option`s, and properties whose
+//! type the generator doesn't know are `serde_json::Value`s.
+use std::{
+    collections::{BTreeSet, VecDeque},
+    error::Error,
+    fmt, fs, io,
+    path::Path,
+};
+
+use serde_json::{Map, Value};
+
+/// The error returned when types could not be generated from a schema.
+#[derive(Debug)]
+pub enum CodegenError {
+    /// The schema could not be read, or the types could not be written.
+    Io(io::Error),
+    /// The schema is not valid JSON.
+    Json(serde_json::Error),
+    /// The schema is not a schema of the registry.
+    Schema(String),
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodegenError::Io(e) => write!(f, "Could not read schema or write types: {}", e),
+            CodegenError::Json(e) => write!(f, "Could not parse schema: {}", e),
+            CodegenError::Schema(e) => write!(f, "Invalid schema: {}", e),
+        }
+    }
+}
+
+impl Error for CodegenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CodegenError::Io(e) => Some(e),
+            CodegenError::Json(e) => Some(e),
+            CodegenError::Schema(_) => None,
+        }
+    }
+}
+
+/// Generates the types of the schema at `schema` into the file at `out`, and tells Cargo
+/// to run the build script again when the schema changes.
+///
+/// # Arguments
+///
+/// * `schema` The path of the schema, exported from the registry
+/// * `out` The path of the Rust file to write, usually in `OUT_DIR`
+pub fn generate_file<P: AsRef<Path>, Q: AsRef<Path>>(schema: P, out: Q) -> Result<(), CodegenError> {
+    println!("cargo:rerun-if-changed={}", schema.as_ref().display());
+    let schema = fs::read_to_string(schema).map_err(CodegenError::Io)?;
+    fs::write(out, generate(&schema)?).map_err(CodegenError::Io)
+}
+
+/// Generates the types of a schema.
+///
+/// # Arguments
+///
+/// * `schema` The schema, exported from the registry in the OpenAPI 3 or the JSON
+///   Schema Draft 4 format
+///
+/// # Return
+/// The Rust source of the types.
+pub fn generate(schema: &str) -> Result<String, CodegenError> {
+    let document: Value = serde_json::from_str(schema).map_err(CodegenError::Json)?;
+    let empty = Map::new();
+    // OpenAPI documents define the event as the `AWSEvent` component, JSON Schema
+    // documents are the event themselves.
+    let (event, definitions, prefix) = match document.pointer("/components/schemas").and_then(Value::as_object) {
+        Some(schemas) => (
+            schemas
+                .get("AWSEvent")
+                .ok_or_else(|| CodegenError::Schema(String::from("no `AWSEvent` schema")))?,
+            schemas,
+            "#/components/schemas/",
+        ),
+        None => (
+            &document,
+            document.get("definitions").and_then(Value::as_object).unwrap_or(&empty),
+            "#/definitions/",
+        ),
+    };
+    let detail = event
+        .pointer("/properties/detail")
+        .ok_or_else(|| CodegenError::Schema(String::from("no `detail` property")))?;
+    let detail_type = event.get("x-amazon-events-detail-type").and_then(Value::as_str);
+    let source = event.get("x-amazon-events-source").and_then(Value::as_str);
+
+    let mut generator = Generator {
+        definitions,
+        prefix,
+        names: BTreeSet::new(),
+        pending: VecDeque::new(),
+    };
+    let detail_name = match detail.get("$ref").and_then(Value::as_str) {
+        Some(reference) => generator.reference(reference)?,
+        None => {
+            let name = generator.new_name(&pascal_case(detail_type.unwrap_or("Detail")));
+            generator.pending.push_back((name.clone(), detail));
+            name
+        }
+    };
+    let mut alias = format!("{}Event", pascal_case(detail_type.unwrap_or(&detail_name)));
+    if generator.names.contains(&alias) {
+        alias = format!("{}EventBridgeEvent", detail_name);
+    }
+
+    let mut out = String::from("// Generated by lambda_events::schema_codegen. Do not edit.\n");
+    if let Some(detail_type) = detail_type {
+        out.push_str(&format!(
+            "\n/// The `detail_type` of the events.\npub const DETAIL_TYPE: &str = {:?};\n",
+            detail_type
+        ));
+    }
+    if let Some(source) = source {
+        out.push_str(&format!(
+            "\n/// The source of the events.\npub const SOURCE: &str = {:?};\n",
+            source
+        ));
+    }
+    out.push_str(&format!(
+        "\n/// An event of the schema.\npub type {} = lambda_events::eventbridge::EventBridgeEvent<{}>;\n",
+        alias, detail_name
+    ));
+    while let Some((name, schema)) = generator.pending.pop_front() {
+        out.push('\n');
+        out.push_str(&generator.structure(&name, schema)?);
+    }
+    Ok(out)
+}
+
+struct Generator<'a> {
+    definitions: &'a Map<String, Value>,
+    prefix: &'static str,
+    /// The names of the structs, generated or pending.
+    names: BTreeSet<String>,
+    /// The structs to generate, with their schemas.
+    pending: VecDeque<(String, &'a Value)>,
+}
+
+impl<'a> Generator<'a> {
+    /// Returns the name of the struct of a definition, queueing the struct the first
+    /// time the definition is referenced.
+    fn reference(&mut self, reference: &str) -> Result<String, CodegenError> {
+        let definition = reference
+            .strip_prefix(self.prefix)
+            .ok_or_else(|| CodegenError::Schema(format!("unsupported reference `{}`", reference)))?;
+        let schema = self
+            .definitions
+            .get(definition)
+            .ok_or_else(|| CodegenError::Schema(format!("undefined reference `{}`", reference)))?;
+        let name = pascal_case(definition);
+        if !self.pending.iter().any(|(pending, _)| *pending == name) && self.names.insert(name.clone()) {
+            self.pending.push_back((name.clone(), schema));
+        }
+        Ok(name)
+    }
+
+    /// Returns a struct name that is not used yet, based on `name`, and reserves it.
+    fn new_name(&mut self, name: &str) -> String {
+        let mut candidate = name.to_string();
+        let mut i = 2;
+        while self.names.contains(&candidate) {
+            candidate = format!("{}{}", name, i);
+            i += 1;
+        }
+        self.names.insert(candidate.clone());
+        candidate
+    }
+
+    fn structure(&mut self, name: &str, schema: &'a Value) -> Result<String, CodegenError> {
+        let required: BTreeSet<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let mut out = doc_comment(schema, "");
+        out.push_str(
+            "#[derive(Debug, Default, Clone, PartialEq, serde_derive::Deserialize, serde_derive::Serialize)]\n",
+        );
+        out.push_str(&format!("pub struct {} {{\n", name));
+        let mut fields = BTreeSet::new();
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (property, property_schema) in properties {
+                let mut field = snake_case(property);
+                let mut i = 2;
+                while !fields.insert(field.clone()) {
+                    field = format!("{}_{}", snake_case(property), i);
+                    i += 1;
+                }
+                let ty = self.rust_type(name, property, property_schema)?;
+                out.push_str(&doc_comment(property_schema, "    "));
+                let mut attributes = Vec::new();
+                if field != *property {
+                    attributes.push(format!("rename = {:?}", property));
+                }
+                let ty = if required.contains(property.as_str()) {
+                    ty
+                } else {
+                    attributes.push(String::from("default, skip_serializing_if = \"Option::is_none\""));
+                    format!("Option<{}>", ty)
+                };
+                if !attributes.is_empty() {
+                    out.push_str(&format!("    #[serde({})]\n", attributes.join(", ")));
+                }
+                out.push_str(&format!("    pub {}: {},\n", identifier(&field), ty));
+            }
+        }
+        out.push_str("}\n");
+        Ok(out)
+    }
+
+    fn rust_type(&mut self, parent: &str, property: &str, schema: &'a Value) -> Result<String, CodegenError> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            return self.reference(reference);
+        }
+        let ty = match schema.get("type").and_then(Value::as_str) {
+            Some("string") if schema.get("format").and_then(Value::as_str) == Some("date-time") => {
+                String::from("chrono::DateTime<chrono::Utc>")
+            }
+            Some("string") => String::from("String"),
+            Some("integer") => String::from("i64"),
+            Some("number") => String::from("f64"),
+            Some("boolean") => String::from("bool"),
+            Some("array") => match schema.get("items") {
+                Some(items) => format!("Vec<{}>", self.rust_type(parent, property, items)?),
+                None => String::from("Vec<serde_json::Value>"),
+            },
+            Some("object") if schema.get("properties").is_some() => {
+                let name = self.new_name(&format!("{}{}", parent, pascal_case(property)));
+                self.pending.push_back((name.clone(), schema));
+                name
+            }
+            Some("object") => match schema.get("additionalProperties") {
+                Some(values) if values.is_object() => format!(
+                    "std::collections::HashMap<String, {}>",
+                    self.rust_type(parent, property, values)?
+                ),
+                _ => String::from("serde_json::Value"),
+            },
+            _ => String::from("serde_json::Value"),
+        };
+        Ok(ty)
+    }
+}
+
+/// Returns the description of a schema as a doc comment, or nothing if it has none.
+fn doc_comment(schema: &Value, indent: &str) -> String {
+    match schema.get("description").and_then(Value::as_str) {
+        Some(description) => description
+            .lines()
+            .map(|line| format!("{}/// {}\n", indent, line).replace("/// \n", "///\n"))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Splits a name into its words, on separators and case changes.
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && !word.is_empty() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if previous.is_ascii_lowercase() || previous.is_ascii_digit() || next_is_lower {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn snake_case(name: &str) -> String {
+    let snake = words(name)
+        .iter()
+        .map(|word| word.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    match snake.chars().next() {
+        None => String::from("field"),
+        Some(c) if c.is_ascii_digit() => format!("_{}", snake),
+        Some(_) => snake,
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    let pascal: String = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars).collect::<String>()
+        })
+        .collect();
+    match pascal.chars().next() {
+        None => String::from("Detail"),
+        Some(c) if c.is_ascii_digit() => format!("T{}", pascal),
+        Some(_) => pascal,
+    }
+}
+
+/// Returns a field name as an identifier, escaping keywords.
+fn identifier(field: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+        "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod",
+        "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type",
+        "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+    ];
+    if KEYWORDS.contains(&field) {
+        format!("r#{}", field)
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    mod order_created {
+        include!("../tests/schemas/order_created.rs");
+    }
+
+    const GENERATED: &str = include_str!("../tests/schemas/order_created.rs");
+
+    #[test]
+    fn generates_openapi_schemas() {
+        let schema = include_str!("../tests/schemas/order_created_openapi.json");
+        assert_eq!(generate(schema).unwrap(), GENERATED);
+    }
+
+    #[test]
+    fn generates_json_schemas() {
+        let schema = include_str!("../tests/schemas/order_created_jsonschema.json");
+        assert_eq!(generate(schema).unwrap(), GENERATED);
+    }
+
+    #[test]
+    fn generated_types_deserialize_events() {
+        let event: order_created::OrderCreatedEvent = serde_json::from_value(serde_json::json!({
+            "version": "0",
+            "id": "6a7e8feb-b491-4cf7-a9f1-bf3703467718",
+            "detail-type": order_created::DETAIL_TYPE,
+            "source": order_created::SOURCE,
+            "account": "123456789012",
+            "time": "2023-03-01T12:00:00Z",
+            "region": "us-east-1",
+            "resources": [],
+            "detail": {
+                "orderId": "o-1",
+                "customer": {"customerId": "c-1", "address": {"city": "Seattle"}},
+                "items": [{"sku": "B00X4WHP5E", "quantity": 2, "unitPrice": 9.99}],
+                "createdAt": "2023-03-01T11:59:58Z",
+                "attributes": {"channel": "web"},
+                "type": "standard"
+            }
+        }))
+        .expect("Could not parse event");
+        let detail = &event.detail;
+        assert_eq!(detail.order_id, "o-1");
+        assert_eq!(
+            detail.customer.address.as_ref().unwrap().city.as_deref(),
+            Some("Seattle")
+        );
+        assert_eq!(detail.items[0].quantity, 2);
+        assert_eq!(detail.r#type.as_deref(), Some("standard"));
+        assert_eq!(detail.attributes.as_ref().unwrap()["channel"], "web");
+    }
+
+    #[test]
+    fn converts_names() {
+        assert_eq!(snake_case("orderId"), "order_id");
+        assert_eq!(snake_case("userARN"), "user_arn");
+        assert_eq!(snake_case("HTTPStatus"), "http_status");
+        assert_eq!(snake_case("detail-type"), "detail_type");
+        assert_eq!(snake_case("2fa"), "_2fa");
+        assert_eq!(pascal_case("order_created"), "OrderCreated");
+        assert_eq!(pascal_case("aws.partner/saas"), "AwsPartnerSaas");
+    }
+
+    #[test]
+    fn rejects_schemas_without_detail() {
+        let error = generate(r#"{"type": "object", "properties": {"id": {"type": "string"}}}"#).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid schema: no `detail` property");
+    }
+}
@@ -0,0 +1,82 @@
+//! AWS Secrets Manager events, sent to the functions that rotate secrets.
+use serde_derive::{Deserialize, Serialize};
+
+/// A step of the rotation of a secret. Secrets Manager invokes the rotation function
+/// once per step, in order, with the same token.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::secretsmanager::{RotationStep, SecretsManagerRotationEvent};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: SecretsManagerRotationEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     match e.step {
+///         RotationStep::CreateSecret => println!("creating version {}", e.client_request_token),
+///         RotationStep::SetSecret => println!("setting the password in the database"),
+///         RotationStep::TestSecret => println!("logging in with the new password"),
+///         RotationStep::FinishSecret => println!("moving AWSCURRENT to the new version"),
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SecretsManagerRotationEvent {
+    /// The step to run.
+    pub step: RotationStep,
+    /// The ARN of the secret.
+    pub secret_id: String,
+    /// The id of the version of the secret the rotation creates.
+    pub client_request_token: String,
+    /// The token that proves the function was invoked by Secrets Manager, for secrets
+    /// whose rotation function is in another account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation_token: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A step of the rotation of a secret.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RotationStep {
+    /// Creates the new version of the secret, with the `AWSPENDING` label.
+    #[default]
+    CreateSecret,
+    /// Sets the new credentials in the database or service.
+    SetSecret,
+    /// Tests the new credentials.
+    TestSecret,
+    /// Moves the `AWSCURRENT` label to the new version.
+    FinishSecret,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_rotation_events() {
+        let input = serde_json::json!({
+            "Step": "setSecret",
+            "SecretId": "arn:aws:secretsmanager:us-east-1:123456789012:secret:db-password-a1b2c3",
+            "ClientRequestToken": "3c8c5ba0-0b6e-4a51-b4a6-0d1ba4e5bd46"
+        });
+        let event: SecretsManagerRotationEvent =
+            serde_json::from_value(input.clone()).expect("Could not parse rotation event");
+        assert_eq!(event.step, RotationStep::SetSecret);
+        assert_eq!(event.rotation_token, None);
+        assert_eq!(serde_json::to_value(&event).unwrap(), input);
+
+        let step: Result<RotationStep, _> = serde_json::from_value(serde_json::json!("rotateSecret"));
+        assert!(step.is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! The details of the AWS Security Hub findings delivered by EventBridge, in the AWS
+//! Security Finding Format (ASFF).
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{encodings::deserialize_lambda_map, eventbridge::EventBridgeEvent};
+
+/// The `detail_type` of the findings Security Hub imports automatically.
+pub const FINDINGS_IMPORTED_DETAIL_TYPE: &str = "Security Hub Findings - Imported";
+
+/// The `detail_type` of the findings sent to a custom action.
+pub const FINDINGS_CUSTOM_ACTION_DETAIL_TYPE: &str = "Security Hub Findings - Custom Action";
+
+/// An event with Security Hub findings, either imported or sent to a custom action.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::securityhub::{SecurityHubFindingsEvent, SeverityLabel};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: SecurityHubFindingsEvent, _ctx: Context) -> Result<(), HandlerError> {
+///     for finding in &e.detail.findings {
+///         if finding.severity.label >= SeverityLabel::High {
+///             if let Some(url) = finding.remediation_url() {
+///                 println!("{}: see {}", finding.title, url);
+///             }
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+pub type SecurityHubFindingsEvent = EventBridgeEvent<SecurityHubFindingsDetail>;
+
+/// The detail of an event with Security Hub findings.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityHubFindingsDetail {
+    /// The name of the custom action, for custom action events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_name: Option<String>,
+    /// The description of the custom action, for custom action events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_description: Option<String>,
+    /// The findings.
+    pub findings: Vec<AwsSecurityFinding>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A finding in the AWS Security Finding Format.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AwsSecurityFinding {
+    /// The version of the format, for example `2018-10-08`.
+    pub schema_version: String,
+    /// The id of the finding.
+    pub id: String,
+    /// The ARN of the product that created the finding.
+    pub product_arn: String,
+    /// The name of the product that created the finding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product_name: Option<String>,
+    /// The name of the company of the product.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub company_name: Option<String>,
+    /// The region the finding was created in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// The id of the rule or control that created the finding.
+    pub generator_id: String,
+    /// The AWS account the finding is about.
+    pub aws_account_id: String,
+    /// The types of the finding, as `namespace/category/classifier` paths.
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// The time the issue was first observed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_observed_at: Option<DateTime<Utc>>,
+    /// The time the issue was last observed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_observed_at: Option<DateTime<Utc>>,
+    /// The time the finding was created.
+    pub created_at: DateTime<Utc>,
+    /// The time the finding was last updated.
+    pub updated_at: DateTime<Utc>,
+    /// The severity of the finding.
+    pub severity: AsffSeverity,
+    /// The title of the finding.
+    pub title: String,
+    /// The description of the finding.
+    pub description: String,
+    /// How to fix the issue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<AsffRemediation>,
+    /// The fields specific to the product.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub product_fields: HashMap<String, String>,
+    /// The resources the finding is about.
+    pub resources: Vec<AsffResource>,
+    /// The result of the control, for findings of security standards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compliance: Option<AsffCompliance>,
+    /// The status of the investigation of the finding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<AsffWorkflow>,
+    /// `ACTIVE`, or `ARCHIVED` when the issue is resolved.
+    pub record_state: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl AwsSecurityFinding {
+    /// Returns the URL of the remediation of the finding, if it has one.
+    pub fn remediation_url(&self) -> Option<&str> {
+        self.remediation.as_ref()?.recommendation.as_ref()?.url.as_deref()
+    }
+
+    /// Returns whether the finding failed a control of a security standard.
+    pub fn is_failed(&self) -> bool {
+        self.compliance.as_ref().is_some_and(|c| c.status == "FAILED")
+    }
+}
+
+/// The severity of a finding.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffSeverity {
+    /// The level of the severity.
+    pub label: SeverityLabel,
+    /// The severity from 0 to 100. Deprecated in favor of `label`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalized: Option<u8>,
+    /// The severity as reported by the product.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The level of the severity of a finding.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SeverityLabel {
+    /// No issue was found.
+    #[default]
+    Informational,
+    /// The issue doesn't need action on its own.
+    Low,
+    /// The issue must be addressed, but not urgently.
+    Medium,
+    /// The issue must be addressed as a priority.
+    High,
+    /// The issue must be remediated immediately.
+    Critical,
+}
+
+/// How to fix the issue of a finding.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffRemediation {
+    /// The recommendation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<AsffRecommendation>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A recommendation to fix the issue of a finding.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffRecommendation {
+    /// The description of the recommendation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// The URL of the documentation of the recommendation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A resource a finding is about.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffResource {
+    /// The type of the resource, for example `AwsS3Bucket`.
+    #[serde(rename = "Type")]
+    pub resource_type: String,
+    /// The id of the resource, usually its ARN.
+    pub id: String,
+    /// The partition of the resource.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition: Option<String>,
+    /// The region of the resource.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// The tags of the resource.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub tags: HashMap<String, String>,
+    /// The details of the resource, keyed by its type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The result of a control.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffCompliance {
+    /// `PASSED`, `WARNING`, `FAILED`, or `NOT_AVAILABLE`.
+    pub status: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The status of the investigation of a finding.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AsffWorkflow {
+    /// `NEW`, `NOTIFIED`, `SUPPRESSED`, or `RESOLVED`.
+    pub status: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_imported_findings() {
+        let input = include_str!("../tests/data/securityhub_findings_imported.json");
+        let event: SecurityHubFindingsEvent = serde_json::from_str(input).expect("Could not parse Security Hub event");
+        assert_eq!(event.detail_type, FINDINGS_IMPORTED_DETAIL_TYPE);
+        assert_eq!(event.detail.action_name, None);
+
+        let finding = &event.detail.findings[0];
+        assert_eq!(finding.severity.label, SeverityLabel::Medium);
+        assert_eq!(finding.severity.normalized, Some(40));
+        assert_eq!(
+            finding.remediation_url(),
+            Some("https://docs.aws.amazon.com/console/securityhub/S3.1/remediation")
+        );
+        assert!(finding.is_failed());
+        assert_eq!(finding.product_fields["ControlId"], "S3.1");
+        assert_eq!(finding.resources[0].resource_type, "AwsAccount");
+        assert_eq!(finding.resources[0].tags["team"], "security");
+
+        let json = serde_json::to_string(&event).expect("Could not serialize Security Hub event");
+        assert_eq!(serde_json::from_str::<SecurityHubFindingsEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn orders_severity_labels() {
+        let label: SeverityLabel = serde_json::from_value(serde_json::json!("CRITICAL")).unwrap();
+        assert_eq!(label, SeverityLabel::Critical);
+        assert!(SeverityLabel::High > SeverityLabel::Low);
+    }
+}
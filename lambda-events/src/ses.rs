@@ -0,0 +1,325 @@
+//! Amazon SES receipt events, sent to functions invoked by a Lambda action of a receipt
+//! rule, and the disposition functions invoked synchronously return to control the
+//! rest of the rule set.
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+
+/// An SES receipt event. SES invokes functions with one message per event.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::ses::{Disposition, SimpleEmailDisposition, SimpleEmailEvent};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: SimpleEmailEvent, _ctx: Context) -> Result<SimpleEmailDisposition, HandlerError> {
+///     let passed = e.records.iter().all(|r| r.ses.receipt.passed_checks());
+///     let disposition = if passed { Disposition::Continue } else { Disposition::StopRuleSet };
+///     Ok(disposition.into())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SimpleEmailEvent {
+    /// The message records.
+    #[serde(rename = "Records")]
+    pub records: Vec<SimpleEmailRecord>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The receipt of a message by a receipt rule.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleEmailRecord {
+    /// Always `aws:ses`.
+    pub event_source: String,
+    /// The version of the event format, for example `1.0`.
+    pub event_version: String,
+    /// The message and its receipt.
+    pub ses: SimpleEmailService,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A message received by SES.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleEmailService {
+    /// The message.
+    pub mail: SimpleEmailMessage,
+    /// How the message was received.
+    pub receipt: SimpleEmailReceipt,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The envelope and headers of a message. Lambda actions don't receive the content of
+/// the message: store it in S3 with an earlier action to read it.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleEmailMessage {
+    /// The time the message was received.
+    pub timestamp: DateTime<Utc>,
+    /// The envelope sender of the message (`MAIL FROM`).
+    pub source: String,
+    /// The id SES assigned to the message, also the name of the object when the message
+    /// is stored in S3.
+    pub message_id: String,
+    /// The envelope recipients of the message (`RCPT TO`).
+    #[serde(default)]
+    pub destination: Vec<String>,
+    /// Whether headers were left out of `headers` because they were too large.
+    #[serde(default)]
+    pub headers_truncated: bool,
+    /// The headers of the message, in their original order.
+    #[serde(default)]
+    pub headers: Vec<SimpleEmailHeader>,
+    /// The values of the most common headers, parsed.
+    pub common_headers: SimpleEmailCommonHeaders,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SimpleEmailMessage {
+    /// Returns the first value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value.as_str())
+    }
+}
+
+/// A header of a message.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SimpleEmailHeader {
+    /// The name of the header.
+    pub name: String,
+    /// The value of the header.
+    pub value: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The most common headers of a message. The headers missing from the message are
+/// `None` or empty.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleEmailCommonHeaders {
+    /// The `Return-Path` header.
+    #[serde(default)]
+    pub return_path: Option<String>,
+    /// The addresses of the `From` header.
+    #[serde(default)]
+    pub from: Vec<String>,
+    /// The address of the `Sender` header.
+    #[serde(default)]
+    pub sender: Option<String>,
+    /// The addresses of the `Reply-To` header.
+    #[serde(default)]
+    pub reply_to: Vec<String>,
+    /// The `Date` header, as sent.
+    #[serde(default)]
+    pub date: Option<String>,
+    /// The addresses of the `To` header.
+    #[serde(default)]
+    pub to: Vec<String>,
+    /// The addresses of the `Cc` header.
+    #[serde(default)]
+    pub cc: Vec<String>,
+    /// The addresses of the `Bcc` header.
+    #[serde(default)]
+    pub bcc: Vec<String>,
+    /// The `Message-ID` header.
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// The `Subject` header.
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// How a message was received, and the results of the checks SES ran on it.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleEmailReceipt {
+    /// The time the receipt rule was applied.
+    pub timestamp: DateTime<Utc>,
+    /// The time SES took to process the message, in milliseconds.
+    pub processing_time_millis: u64,
+    /// The recipients the receipt rule matched.
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    /// Whether the message is spam.
+    pub spam_verdict: SimpleEmailVerdict,
+    /// Whether the message contains a virus.
+    pub virus_verdict: SimpleEmailVerdict,
+    /// The result of the Sender Policy Framework check.
+    pub spf_verdict: SimpleEmailVerdict,
+    /// The result of the DomainKeys Identified Mail check.
+    pub dkim_verdict: SimpleEmailVerdict,
+    /// The result of the DMARC check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dmarc_verdict: Option<SimpleEmailVerdict>,
+    /// The DMARC policy of the sending domain, when the DMARC check failed: `none`,
+    /// `quarantine`, or `reject`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dmarc_policy: Option<String>,
+    /// The action that invoked the function.
+    pub action: SimpleEmailReceiptAction,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SimpleEmailReceipt {
+    /// Returns whether the spam, virus, SPF, and DKIM checks all passed.
+    pub fn passed_checks(&self) -> bool {
+        [
+            &self.spam_verdict,
+            &self.virus_verdict,
+            &self.spf_verdict,
+            &self.dkim_verdict,
+        ]
+        .iter()
+        .all(|v| v.status == VerdictStatus::Pass)
+    }
+}
+
+/// The result of a check SES ran on a message.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SimpleEmailVerdict {
+    /// The status of the check.
+    pub status: VerdictStatus,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The status of a check SES ran on a message.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VerdictStatus {
+    /// The check passed.
+    Pass,
+    /// The check failed.
+    Fail,
+    /// The check was inconclusive.
+    #[default]
+    Gray,
+    /// SES could not run the check.
+    ProcessingFailed,
+    /// The check is disabled for the receipt rule.
+    Disabled,
+}
+
+/// The receipt rule action that invoked the function.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleEmailReceiptAction {
+    /// Always `Lambda`.
+    #[serde(rename = "type")]
+    pub action_type: String,
+    /// The ARN of the function.
+    pub function_arn: String,
+    /// `RequestResponse` when SES waits for the disposition of the function, or `Event`.
+    pub invocation_type: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The response of a function invoked with the `RequestResponse` invocation type, that
+/// decides how SES handles the rest of the receipt rules.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SimpleEmailDisposition {
+    /// What SES does next.
+    pub disposition: Disposition,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<Disposition> for SimpleEmailDisposition {
+    fn from(disposition: Disposition) -> Self {
+        SimpleEmailDisposition {
+            disposition,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+/// What SES does after a function returns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Disposition {
+    /// Continue with the next action of the receipt rule.
+    #[default]
+    Continue,
+    /// Skip the remaining actions of the receipt rule.
+    StopRule,
+    /// Skip the remaining actions and receipt rules of the rule set.
+    StopRuleSet,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_receipt_events() {
+        let input = include_str!("../tests/data/ses_event.json");
+        let event: SimpleEmailEvent = serde_json::from_str(input).expect("Could not parse SES event");
+        let record = &event.records[0];
+        let mail = &record.ses.mail;
+        assert_eq!(mail.source, "prvs=144d0cba7=sender@example.com");
+        assert_eq!(mail.header("subject"), Some("This is a test"));
+        assert_eq!(mail.common_headers.from, vec!["sender@example.com"]);
+        assert_eq!(mail.common_headers.cc, Vec::<String>::new());
+
+        let receipt = &record.ses.receipt;
+        assert_eq!(receipt.spam_verdict.status, VerdictStatus::Pass);
+        assert_eq!(receipt.dkim_verdict.status, VerdictStatus::Gray);
+        assert_eq!(receipt.dmarc_policy.as_deref(), Some("reject"));
+        assert!(!receipt.passed_checks());
+
+        let json = serde_json::to_string(&event).expect("Could not serialize SES event");
+        assert_eq!(serde_json::from_str::<SimpleEmailEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn serializes_dispositions() {
+        let response = SimpleEmailDisposition::from(Disposition::StopRuleSet);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({"disposition": "STOP_RULE_SET"})
+        );
+        assert_eq!(
+            serde_json::to_value(SimpleEmailDisposition::default()).unwrap(),
+            serde_json::json!({"disposition": "CONTINUE"})
+        );
+    }
+}
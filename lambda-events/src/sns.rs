@@ -0,0 +1,191 @@
+//! Amazon SNS events, sent to functions subscribed to a topic.
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::encodings::deserialize_lambda_map;
+
+/// An SNS event. SNS invokes functions with one notification per event.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SnsEvent {
+    /// The notification records.
+    #[serde(rename = "Records")]
+    pub records: Vec<SnsRecord>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The delivery of a notification to the subscription of the function.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SnsRecord {
+    /// Always `aws:sns`.
+    pub event_source: String,
+    /// The version of the event format, for example `1.0`.
+    pub event_version: String,
+    /// The ARN of the subscription of the function.
+    pub event_subscription_arn: String,
+    /// The notification.
+    pub sns: SnsMessage,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A notification published to a topic.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SnsMessage {
+    /// The type of the message, always `Notification` for Lambda subscriptions.
+    #[serde(rename = "Type")]
+    pub sns_message_type: String,
+    /// The id SNS assigned to the message.
+    pub message_id: String,
+    /// The ARN of the topic the message was published to.
+    pub topic_arn: String,
+    /// The subject of the message, if the publisher set one.
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// The message, as sent by the publisher.
+    pub message: String,
+    /// The time the message was published.
+    pub timestamp: DateTime<Utc>,
+    /// The version of the signature algorithm.
+    pub signature_version: String,
+    /// The base64 encoded signature of the message.
+    pub signature: String,
+    /// The URL of the certificate used to sign the message.
+    pub signing_cert_url: String,
+    /// The URL to unsubscribe the endpoint from the topic.
+    pub unsubscribe_url: String,
+    /// The attributes the publisher attached to the message.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub message_attributes: HashMap<String, SnsMessageAttribute>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SnsMessage {
+    /// Deserializes the JSON message.
+    ///
+    /// # Return
+    /// The message parsed as `T`, or the error if the message is not valid JSON for `T`.
+    pub fn deserialize_message<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.message)
+    }
+}
+
+/// An attribute the publisher attached to a message. SNS sends all values as strings,
+/// use the accessors to read them according to their type.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SnsMessageAttribute {
+    /// The data type of the attribute: `String`, `String.Array`, `Number`, or `Binary`.
+    #[serde(rename = "Type")]
+    pub data_type: String,
+    /// The value of the attribute, as sent by SNS.
+    #[serde(rename = "Value")]
+    pub value: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SnsMessageAttribute {
+    /// Returns the value of a `String` attribute.
+    pub fn as_str(&self) -> Option<&str> {
+        match self.data_type.as_str() {
+            "String" => Some(&self.value),
+            _ => None,
+        }
+    }
+
+    /// Returns the values of a `String.Array` attribute. Arrays may also contain numbers,
+    /// booleans, and `null`, which are returned as their JSON representation.
+    pub fn as_string_array(&self) -> Option<Vec<String>> {
+        if self.data_type != "String.Array" {
+            return None;
+        }
+        let values: Vec<serde_json::Value> = serde_json::from_str(&self.value).ok()?;
+        let strings = values
+            .into_iter()
+            .map(|value| match value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            })
+            .collect();
+        Some(strings)
+    }
+
+    /// Returns the value of a `Number` attribute parsed as `T`, or `None` if the
+    /// attribute is not a number or doesn't fit into `T`.
+    pub fn as_number<T: FromStr>(&self) -> Option<T> {
+        match self.data_type.as_str() {
+            "Number" => self.value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the decoded value of a `Binary` attribute, or `None` if the attribute is
+    /// not binary or is not valid base64.
+    pub fn as_bytes(&self) -> Option<Vec<u8>> {
+        match self.data_type.as_str() {
+            "Binary" => base64::decode(&self.value).ok(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        order_id: String,
+        quantity: u32,
+    }
+
+    #[test]
+    fn deserializes_notification() {
+        let input = include_str!("../tests/data/sns_event.json");
+        let event: SnsEvent = serde_json::from_str(input).expect("Could not parse SNS event");
+        let message = &event.records[0].sns;
+        assert_eq!(message.subject.as_deref(), Some("TestInvoke"));
+        assert_eq!(message.timestamp.timestamp(), 1_546_433_107);
+        assert_eq!(
+            message.deserialize_message::<Order>().unwrap(),
+            Order {
+                order_id: String::from("o-123"),
+                quantity: 2,
+            }
+        );
+
+        let json = serde_json::to_string(&event).expect("Could not serialize SNS event");
+        assert_eq!(serde_json::from_str::<SnsEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn message_attributes_are_typed() {
+        let input = include_str!("../tests/data/sns_event.json");
+        let event: SnsEvent = serde_json::from_str(input).expect("Could not parse SNS event");
+        let attributes = &event.records[0].sns.message_attributes;
+        assert_eq!(attributes["Priority"].as_number::<u8>(), Some(42));
+        assert_eq!(attributes["Priority"].as_str(), None);
+        assert_eq!(attributes["Source"].as_str(), Some("checkout"));
+        assert_eq!(
+            attributes["Regions"].as_string_array(),
+            Some(vec![String::from("us-east-1"), String::from("eu-west-1")])
+        );
+        assert_eq!(attributes["Signature"].as_bytes(), Some(b"signed".to_vec()));
+    }
+}
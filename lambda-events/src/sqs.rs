@@ -0,0 +1,394 @@
+//! Amazon SQS events, sent to functions that consume the messages of a queue in batches.
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    batch::BatchItemFailure,
+    encodings::{deserialize_lambda_map, Base64Data, MillisecondTimestamp},
+};
+
+/// A batch of messages received from a queue.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SqsEvent {
+    /// The messages of the batch.
+    #[serde(rename = "Records")]
+    pub records: Vec<SqsMessage>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SqsEvent {
+    /// Runs `handler` for each message of the batch and returns the response that reports
+    /// the failed messages to SQS, for functions whose event source mapping enables
+    /// `ReportBatchItemFailures`. Only the failed messages return to the queue.
+    ///
+    /// Messages of FIFO queues must be processed in order, so once a message of a FIFO
+    /// queue fails, the remaining messages of the batch are not passed to `handler` and
+    /// are reported as failed too.
+    ///
+    /// ```rust
+    /// use lambda_events::sqs::{SqsEvent, SqsMessage};
+    ///
+    /// let event = SqsEvent {
+    ///     records: vec![
+    ///         SqsMessage { message_id: String::from("1"), body: String::from("ok"), ..SqsMessage::default() },
+    ///         SqsMessage { message_id: String::from("2"), body: String::from("bad"), ..SqsMessage::default() },
+    ///     ],
+    ///     ..SqsEvent::default()
+    /// };
+    /// let response = event.process(|message| match message.body.as_str() {
+    ///     "ok" => Ok(()),
+    ///     _ => Err("Could not process message"),
+    /// });
+    /// assert_eq!(response.batch_item_failures[0].item_identifier, "2");
+    /// ```
+    pub fn process<F, T, E>(&self, mut handler: F) -> SqsBatchResponse
+    where
+        F: FnMut(&SqsMessage) -> Result<T, E>,
+    {
+        let mut fifo_failed = false;
+        let results = self.records.iter().map(|message| {
+            let fifo = message.message_group_id().is_some();
+            let result = if fifo && fifo_failed {
+                Err(())
+            } else {
+                handler(message).map_err(|_| ())
+            };
+            fifo_failed |= fifo && result.is_err();
+            (message, result)
+        });
+        SqsBatchResponse::from_results(results)
+    }
+}
+
+/// The response of a function that reports partial batch failures. The messages
+/// listed in the response return to the queue, all others are deleted.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqsBatchResponse {
+    /// The messages that failed.
+    pub batch_item_failures: Vec<BatchItemFailure>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SqsBatchResponse {
+    /// Creates the response for the results of processing each message of a batch.
+    /// The messages whose result is an error are reported as failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` Each message of the batch with the result of processing it.
+    ///
+    /// # Return
+    /// A response listing the message id of every failed message.
+    pub fn from_results<'a, I, T, E>(results: I) -> SqsBatchResponse
+    where
+        I: IntoIterator<Item = (&'a SqsMessage, Result<T, E>)>,
+    {
+        let batch_item_failures = results
+            .into_iter()
+            .filter(|(_, result)| result.is_err())
+            .map(|(message, _)| BatchItemFailure {
+                item_identifier: message.message_id.clone(),
+                #[cfg(feature = "catch-all-fields")]
+                other: Default::default(),
+            })
+            .collect();
+        SqsBatchResponse {
+            batch_item_failures,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+/// A message received from a queue.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqsMessage {
+    /// The id SQS assigned to the message.
+    pub message_id: String,
+    /// The handle used to delete the message or change its visibility.
+    pub receipt_handle: String,
+    /// The body of the message, as sent by the producer.
+    pub body: String,
+    /// The system attributes of the message, such as `ApproximateReceiveCount`. Use the
+    /// accessors of `SqsMessage` to read the common ones.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub attributes: HashMap<String, String>,
+    /// The attributes the producer attached to the message.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub message_attributes: HashMap<String, SqsMessageAttribute>,
+    /// The MD5 digest of the body.
+    pub md5_of_body: String,
+    /// The MD5 digest of the message attributes, if the message has any.
+    #[serde(default)]
+    pub md5_of_message_attributes: Option<String>,
+    /// Always `aws:sqs`.
+    pub event_source: String,
+    /// The ARN of the queue.
+    #[serde(rename = "eventSourceARN")]
+    pub event_source_arn: String,
+    /// The region of the queue.
+    pub aws_region: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl SqsMessage {
+    /// Deserializes the JSON body of the message.
+    ///
+    /// ```rust
+    /// use lambda_events::sqs::SqsMessage;
+    /// use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Order {
+    ///     quantity: u32,
+    /// }
+    ///
+    /// let message = SqsMessage {
+    ///     body: String::from(r#"{"quantity":2}"#),
+    ///     ..SqsMessage::default()
+    /// };
+    /// let order: Order = message.deserialize_body().expect("Could not parse order");
+    /// assert_eq!(order.quantity, 2);
+    /// ```
+    pub fn deserialize_body<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.body)
+    }
+
+    /// Returns the number of times the message has been received, including this time.
+    pub fn approximate_receive_count(&self) -> Option<u32> {
+        self.parsed_attribute("ApproximateReceiveCount")
+    }
+
+    /// Returns the time the message was sent to the queue.
+    pub fn sent_timestamp(&self) -> Option<DateTime<Utc>> {
+        self.timestamp_attribute("SentTimestamp")
+    }
+
+    /// Returns the time the message was first received from the queue.
+    pub fn approximate_first_receive_timestamp(&self) -> Option<DateTime<Utc>> {
+        self.timestamp_attribute("ApproximateFirstReceiveTimestamp")
+    }
+
+    /// Returns the id of the principal that sent the message.
+    pub fn sender_id(&self) -> Option<&str> {
+        self.attribute("SenderId")
+    }
+
+    /// Returns the message group id, for messages of FIFO queues.
+    pub fn message_group_id(&self) -> Option<&str> {
+        self.attribute("MessageGroupId")
+    }
+
+    /// Returns the deduplication id, for messages of FIFO queues.
+    pub fn message_deduplication_id(&self) -> Option<&str> {
+        self.attribute("MessageDeduplicationId")
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    fn parsed_attribute<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.attribute(name).and_then(|value| value.parse().ok())
+    }
+
+    fn timestamp_attribute(&self, name: &str) -> Option<DateTime<Utc>> {
+        let value = self.attribute(name)?;
+        serde_json::from_value::<MillisecondTimestamp>(value.into())
+            .ok()
+            .map(|timestamp| timestamp.0)
+    }
+}
+
+/// An attribute the producer attached to a message.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqsMessageAttribute {
+    /// The value of `String` and `Number` attributes.
+    #[serde(default)]
+    pub string_value: Option<String>,
+    /// The decoded value of `Binary` attributes.
+    #[serde(default)]
+    pub binary_value: Option<Base64Data>,
+    /// Reserved by SQS, always empty.
+    #[serde(default)]
+    pub string_list_values: Vec<String>,
+    /// Reserved by SQS, always empty.
+    #[serde(default)]
+    pub binary_list_values: Vec<Base64Data>,
+    /// The data type of the attribute, for example `String`, `Number.int`, or `Binary`.
+    pub data_type: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The base data type of a message attribute, without the custom suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageAttributeType {
+    /// A UTF-8 string.
+    String,
+    /// A number, sent as a string to keep its precision.
+    Number,
+    /// Binary data.
+    Binary,
+}
+
+impl SqsMessageAttribute {
+    /// Returns the base data type of the attribute, or `None` for types unknown to this
+    /// crate. Custom types like `Number.int` return their base type.
+    pub fn attribute_type(&self) -> Option<MessageAttributeType> {
+        let base = self.data_type.split('.').next().unwrap_or_default();
+        match base {
+            "String" => Some(MessageAttributeType::String),
+            "Number" => Some(MessageAttributeType::Number),
+            "Binary" => Some(MessageAttributeType::Binary),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `String` attribute.
+    pub fn as_str(&self) -> Option<&str> {
+        match self.attribute_type() {
+            Some(MessageAttributeType::String) => self.string_value.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `Number` attribute parsed as `T`, or `None` if the
+    /// attribute is not a number or doesn't fit into `T`.
+    pub fn as_number<T: FromStr>(&self) -> Option<T> {
+        match self.attribute_type() {
+            Some(MessageAttributeType::Number) => self.string_value.as_ref()?.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the decoded value of a `Binary` attribute.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self.attribute_type() {
+            Some(MessageAttributeType::Binary) => self.binary_value.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        order_id: String,
+        quantity: u32,
+    }
+
+    #[test]
+    fn deserializes_messages() {
+        let input = include_str!("../tests/data/sqs_event.json");
+        let event: SqsEvent = serde_json::from_str(input).expect("Could not parse SQS event");
+        let message = &event.records[0];
+        assert_eq!(message.approximate_receive_count(), Some(3));
+        assert_eq!(message.sent_timestamp().unwrap().timestamp_millis(), 1_545_082_649_183);
+        assert_eq!(message.sender_id(), Some("AIDAIENQZJOLO23YVJ4VO"));
+        assert_eq!(message.message_group_id(), None);
+        assert_eq!(
+            message.deserialize_body::<Order>().unwrap(),
+            Order {
+                order_id: String::from("o-123"),
+                quantity: 2,
+            }
+        );
+
+        let fifo = &event.records[1];
+        assert_eq!(fifo.message_group_id(), Some("group-1"));
+        assert_eq!(fifo.md5_of_message_attributes, None);
+        assert!(fifo.deserialize_body::<Order>().is_err());
+
+        let json = serde_json::to_string(&event).expect("Could not serialize SQS event");
+        assert_eq!(serde_json::from_str::<SqsEvent>(&json).unwrap(), event);
+    }
+
+    #[test]
+    fn message_attributes_are_typed() {
+        let input = include_str!("../tests/data/sqs_event.json");
+        let event: SqsEvent = serde_json::from_str(input).expect("Could not parse SQS event");
+        let attributes = &event.records[0].message_attributes;
+
+        let priority = &attributes["Priority"];
+        assert_eq!(priority.attribute_type(), Some(MessageAttributeType::Number));
+        assert_eq!(priority.as_number::<i32>(), Some(42));
+        assert_eq!(priority.as_str(), None);
+
+        assert_eq!(attributes["Source"].as_str(), Some("checkout"));
+        assert_eq!(attributes["Source"].as_number::<i32>(), None);
+        assert_eq!(attributes["Signature"].as_bytes(), Some(&b"signed"[..]));
+    }
+
+    #[test]
+    fn failed_messages_are_reported() {
+        let input = include_str!("../tests/data/sqs_event.json");
+        let event: SqsEvent = serde_json::from_str(input).expect("Could not parse SQS event");
+        let response = event.process(|message| message.deserialize_body::<Order>());
+        assert_eq!(response.batch_item_failures.len(), 1);
+        assert_eq!(
+            response.batch_item_failures[0].item_identifier,
+            event.records[1].message_id
+        );
+
+        let results = vec![(&event.records[0], Err("failed")), (&event.records[1], Ok(()))];
+        let response = SqsBatchResponse::from_results(results);
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/data/sqs_batch_response.json")).unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+    }
+
+    #[test]
+    fn fifo_messages_after_a_failure_are_not_processed() {
+        let fifo = |id: &str| SqsMessage {
+            message_id: String::from(id),
+            attributes: vec![(String::from("MessageGroupId"), String::from("group-1"))]
+                .into_iter()
+                .collect(),
+            ..SqsMessage::default()
+        };
+        let event = SqsEvent {
+            records: vec![fifo("1"), fifo("2"), fifo("3")],
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        };
+        let mut processed = Vec::new();
+        let response = event.process(|message| {
+            processed.push(message.message_id.clone());
+            if message.message_id == "2" {
+                Err("failed")
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(processed, vec!["1", "2"]);
+        let failed: Vec<_> = response
+            .batch_item_failures
+            .iter()
+            .map(|f| f.item_identifier.as_str())
+            .collect();
+        assert_eq!(failed, vec!["2", "3"]);
+    }
+}
@@ -0,0 +1,230 @@
+//! AWS Step Functions integration types: the context object of executions, and the
+//! payloads of tasks that wait for a callback with a task token.
+//!
+//! Step Functions doesn't send a fixed event to functions, it sends the input of the
+//! task state. States pass the context object to functions by selecting it with `$$`,
+//! and the task token of `.waitForTaskToken` integrations with `$$.Task.Token`. The
+//! `TaskTokenPayload` type matches these parameters of the task state:
+//!
+//! ```json
+//! "Parameters": {
+//!   "FunctionName": "my-function",
+//!   "Payload": {
+//!     "TaskToken.$": "$$.Task.Token",
+//!     "Payload.$": "$",
+//!     "Context.$": "$$"
+//!   }
+//! }
+//! ```
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The payload of a task that waits for a callback, with the input of the task
+/// deserialized as `T`. `Context` is optional in the parameters of the task state.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::stepfunctions::TaskTokenPayload;
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: TaskTokenPayload, _ctx: Context) -> Result<(), HandlerError> {
+///     // store the token, and call SendTaskSuccess with it once the task completes
+///     println!("{:?} waits for {}", e.execution_id(), e.task_token);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TaskTokenPayload<T = Value> {
+    /// The token to pass to `SendTaskSuccess`, `SendTaskFailure`, or
+    /// `SendTaskHeartbeat`.
+    pub task_token: String,
+    /// The input of the task.
+    pub payload: T,
+    /// The context object of the execution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<StepFunctionsContext>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<T> TaskTokenPayload<T> {
+    /// Returns the ARN of the execution, when the context object is included.
+    pub fn execution_id(&self) -> Option<&str> {
+        self.context.as_ref().map(|c| c.execution.id.as_str())
+    }
+
+    /// Returns the name of the task state, when the context object is included.
+    pub fn state_name(&self) -> Option<&str> {
+        self.context.as_ref().map(|c| c.state.name.as_str())
+    }
+}
+
+/// The context object of an execution, selected with `$$` in the parameters of a
+/// state.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StepFunctionsContext {
+    /// The execution.
+    pub execution: ExecutionContext,
+    /// The current state.
+    pub state: StateContext,
+    /// The state machine of the execution.
+    pub state_machine: StateMachineContext,
+    /// The current task, only set in task states that wait for a callback.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<TaskContext>,
+    /// The current item, only set in the `ItemSelector` of map states.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<MapContext>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An execution of a state machine.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExecutionContext {
+    /// The ARN of the execution.
+    pub id: String,
+    /// The input of the execution.
+    #[serde(default)]
+    pub input: Value,
+    /// The name of the execution.
+    pub name: String,
+    /// The ARN of the role the execution runs with.
+    pub role_arn: String,
+    /// The time the execution started.
+    pub start_time: Option<DateTime<Utc>>,
+    /// The number of times the execution was redriven.
+    #[serde(default)]
+    pub redrive_count: u32,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A state of an execution.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StateContext {
+    /// The time the execution entered the state.
+    pub entered_time: Option<DateTime<Utc>>,
+    /// The name of the state.
+    pub name: String,
+    /// The number of times the state was retried.
+    #[serde(default)]
+    pub retry_count: u32,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A state machine.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StateMachineContext {
+    /// The ARN of the state machine.
+    pub id: String,
+    /// The name of the state machine.
+    pub name: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A task state that waits for a callback.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TaskContext {
+    /// The task token of the callback.
+    pub token: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An iteration of a map state.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MapContext {
+    /// The item of the iteration.
+    pub item: MapItem,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// An item of a map state.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MapItem {
+    /// The index of the item in the array.
+    pub index: u64,
+    /// The item.
+    pub value: Value,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        order_id: String,
+        amount: u32,
+    }
+
+    #[test]
+    fn deserializes_task_token_payloads() {
+        let input = include_str!("../tests/data/stepfunctions_task_token_payload.json");
+        let payload: TaskTokenPayload<Order> = serde_json::from_str(input).expect("Could not parse payload");
+        assert_eq!(payload.payload.order_id, "o-123");
+        assert_eq!(payload.state_name(), Some("WaitForApproval"));
+        assert!(payload
+            .execution_id()
+            .unwrap()
+            .ends_with(":execution:OrderProcessing:5c2d0b3e-8f7a-4c1b-9e6d-2a1b3c4d5e6f"));
+
+        let context = payload.context.as_ref().unwrap();
+        assert_eq!(context.task.as_ref().map(|t| &t.token), Some(&payload.task_token));
+        assert_eq!(context.execution.input["amount"], 42);
+        assert_eq!(context.map, None);
+
+        let json = serde_json::to_string(&payload).expect("Could not serialize payload");
+        assert_eq!(serde_json::from_str::<TaskTokenPayload<Order>>(&json).unwrap(), payload);
+    }
+
+    #[test]
+    fn context_is_optional() {
+        let payload: TaskTokenPayload = serde_json::from_value(serde_json::json!({
+            "TaskToken": "token",
+            "Payload": {"orderId": "o-123"},
+        }))
+        .expect("Could not parse payload");
+        assert_eq!(payload.context, None);
+        assert_eq!(payload.execution_id(), None);
+    }
+}
@@ -0,0 +1,362 @@
+//! Amazon VPC Lattice events, sent to functions registered as the targets of a Lattice
+//! service, and the responses functions return to Lattice.
+//!
+//! Target groups send requests in the version 1.0 or 2.0 event format, depending on
+//! their configuration. Both formats use the same response.
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{alb::reason_phrase, apigw::body_bytes, encodings::deserialize_lambda_map};
+
+/// A request received by a Lattice service, in the version 1.0 event format.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct VpcLatticeRequestV1 {
+    /// The path of the request, including the query string.
+    pub raw_path: String,
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The headers of the request, with lower-case names.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, String>,
+    /// The query string parameters of the request.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// The body of the request, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl VpcLatticeRequestV1 {
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns the body of the request, decoded from base64 if needed.
+    ///
+    /// # Return
+    /// The body, empty if the request has none, or an error if a base64 encoded body is
+    /// not valid base64.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        body_bytes(self.body.as_deref(), self.is_base64_encoded)
+    }
+}
+
+/// A request received by a Lattice service, in the version 2.0 event format, which
+/// keeps all the values of repeated headers and query string parameters.
+///
+/// ```rust,no_run
+/// #[macro_use]
+/// extern crate lambda_runtime;
+///
+/// use lambda_events::vpc_lattice::{VpcLatticeRequestV2, VpcLatticeResponse};
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn main() {
+///     lambda!(my_handler);
+/// }
+///
+/// fn my_handler(e: VpcLatticeRequestV2, _ctx: Context) -> Result<VpcLatticeResponse, HandlerError> {
+///     let caller = e.request_context.identity.principal.unwrap_or_default();
+///     Ok(VpcLatticeResponse::builder()
+///         .header("Content-Type", "text/plain")
+///         .body(format!("Hello {}", caller))
+///         .build())
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VpcLatticeRequestV2 {
+    /// The version of the event format, always `2.0`.
+    pub version: String,
+    /// The path of the request.
+    pub path: String,
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The headers of the request, with lower-case names and all their values.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub headers: HashMap<String, Vec<String>>,
+    /// The query string parameters of the request, with all their values.
+    #[serde(default, deserialize_with = "deserialize_lambda_map")]
+    pub query_string_parameters: HashMap<String, Vec<String>>,
+    /// The body of the request, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// Information about the request added by Lattice.
+    pub request_context: VpcLatticeRequestContext,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl VpcLatticeRequestV2 {
+    /// Returns the first value of a header, ignoring the case of its name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| v.first())
+            .map(String::as_str)
+    }
+
+    /// Returns the first value of a query string parameter.
+    pub fn query_string_parameter(&self, name: &str) -> Option<&str> {
+        self.query_string_parameters
+            .get(name)
+            .and_then(|values| values.first())
+            .map(String::as_str)
+    }
+
+    /// Returns the body of the request, decoded from base64 if needed.
+    ///
+    /// # Return
+    /// The body, empty if the request has none, or an error if a base64 encoded body is
+    /// not valid base64.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        body_bytes(self.body.as_deref(), self.is_base64_encoded)
+    }
+}
+
+/// Information about a request added by Lattice.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VpcLatticeRequestContext {
+    /// The ARN of the service network the request came through.
+    pub service_network_arn: String,
+    /// The ARN of the service.
+    pub service_arn: String,
+    /// The ARN of the target group of the function.
+    pub target_group_arn: String,
+    /// The caller.
+    pub identity: VpcLatticeIdentity,
+    /// The region of the service.
+    pub region: String,
+    /// The time of the request in microseconds since the epoch, as a string. Use
+    /// `time()` to parse it.
+    pub time_epoch: String,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl VpcLatticeRequestContext {
+    /// Returns the time of the request, or `None` if `time_epoch` isn't a number.
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp_micros(self.time_epoch.parse().ok()?)
+    }
+}
+
+/// The caller of a Lattice service.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VpcLatticeIdentity {
+    /// The ARN of the VPC the request came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_vpc_arn: Option<String>,
+    /// The authentication type of the service, `AWS_IAM` or `NONE`.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub identity_type: Option<String>,
+    /// The ARN of the caller, for `AWS_IAM` services.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub principal: Option<String>,
+    /// The AWS Organizations id of the account of the caller.
+    #[serde(rename = "principalOrgID", default, skip_serializing_if = "Option::is_none")]
+    pub principal_org_id: Option<String>,
+    /// The name of the session of callers with temporary credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_name: Option<String>,
+    /// The subject common name of the client certificate, for Roles Anywhere
+    /// credentials.
+    #[serde(rename = "x509SubjectCn", default, skip_serializing_if = "Option::is_none")]
+    pub x509_subject_cn: Option<String>,
+    /// The organizational unit of the issuer of the client certificate.
+    #[serde(rename = "x509IssuerOu", default, skip_serializing_if = "Option::is_none")]
+    pub x509_issuer_ou: Option<String>,
+    /// The DNS name in the subject alternative name of the client certificate.
+    #[serde(rename = "x509SanDns", default, skip_serializing_if = "Option::is_none")]
+    pub x509_san_dns: Option<String>,
+    /// The name in the subject alternative name of the client certificate.
+    #[serde(rename = "x509SanNameCn", default, skip_serializing_if = "Option::is_none")]
+    pub x509_san_name_cn: Option<String>,
+    /// The URI in the subject alternative name of the client certificate.
+    #[serde(rename = "x509SanUri", default, skip_serializing_if = "Option::is_none")]
+    pub x509_san_uri: Option<String>,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A response to a Lattice request, in both event formats.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VpcLatticeResponse {
+    /// The HTTP status code.
+    pub status_code: u16,
+    /// The status line of the response, for example `200 OK`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_description: Option<String>,
+    /// The headers of the response.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// The body of the response, base64 encoded if `is_base64_encoded` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Whether the body is base64 encoded, for binary responses.
+    #[serde(default)]
+    pub is_base64_encoded: bool,
+    /// The payload fields that this type doesn't define.
+    #[cfg(feature = "catch-all-fields")]
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for VpcLatticeResponse {
+    fn default() -> Self {
+        VpcLatticeResponse {
+            status_code: 200,
+            status_description: None,
+            headers: HashMap::new(),
+            body: None,
+            is_base64_encoded: false,
+            #[cfg(feature = "catch-all-fields")]
+            other: Default::default(),
+        }
+    }
+}
+
+impl VpcLatticeResponse {
+    /// Returns a builder for a response, with status code `200` and no headers or body.
+    pub fn builder() -> VpcLatticeResponseBuilder {
+        VpcLatticeResponseBuilder::default()
+    }
+}
+
+/// Builder for `VpcLatticeResponse` objects, created with
+/// `VpcLatticeResponse::builder()`.
+#[derive(Debug, Default, Clone)]
+pub struct VpcLatticeResponseBuilder {
+    response: VpcLatticeResponse,
+}
+
+impl VpcLatticeResponseBuilder {
+    /// Sets the HTTP status code.
+    pub fn status_code(mut self, status_code: u16) -> Self {
+        self.response.status_code = status_code;
+        self
+    }
+
+    /// Sets the status line of the response. Defaults to the status code followed by
+    /// its reason phrase, when the code is a common one.
+    pub fn status_description(mut self, description: impl Into<String>) -> Self {
+        self.response.status_description = Some(description.into());
+        self
+    }
+
+    /// Adds a header. Only the last value of a header is sent.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.response.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets a text body.
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.response.body = Some(body.into());
+        self.response.is_base64_encoded = false;
+        self
+    }
+
+    /// Sets a binary body, which is sent to Lattice base64 encoded.
+    pub fn binary_body(mut self, body: impl AsRef<[u8]>) -> Self {
+        self.response.body = Some(base64::encode(body.as_ref()));
+        self.response.is_base64_encoded = true;
+        self
+    }
+
+    /// Returns the response.
+    pub fn build(self) -> VpcLatticeResponse {
+        let mut response = self.response;
+        if response.status_description.is_none() {
+            response.status_description =
+                reason_phrase(response.status_code).map(|reason| format!("{} {}", response.status_code, reason));
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn deserializes_v1_requests() {
+        let request: VpcLatticeRequestV1 = serde_json::from_value(serde_json::json!({
+            "raw_path": "/orders?tag=new",
+            "method": "GET",
+            "headers": {"user-agent": "curl/7.64.1", "x-forwarded-for": "10.0.0.12"},
+            "query_string_parameters": {"tag": "new"},
+            "body": "",
+            "is_base64_encoded": false
+        }))
+        .expect("Could not parse request");
+        assert_eq!(request.header("User-Agent"), Some("curl/7.64.1"));
+        assert_eq!(request.query_string_parameters["tag"], "new");
+        assert!(request.body_bytes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn deserializes_v2_requests() {
+        let input = include_str!("../tests/data/vpc_lattice_v2_request.json");
+        let request: VpcLatticeRequestV2 = serde_json::from_str(input).expect("Could not parse request");
+        assert_eq!(request.header("X-Forwarded-For"), Some("10.0.0.12"));
+        assert_eq!(request.headers["x-forwarded-for"], vec!["10.0.0.12", "10.0.1.5"]);
+        assert_eq!(request.query_string_parameter("tag"), Some("new"));
+        assert_eq!(request.query_string_parameters["tag"], vec!["new", "urgent"]);
+        assert_eq!(request.body_bytes().unwrap(), br#"{"quantity":2}"#);
+
+        let context = &request.request_context;
+        assert_eq!(context.identity.identity_type.as_deref(), Some("AWS_IAM"));
+        assert_eq!(context.identity.principal_org_id.as_deref(), Some("o-1234567890"));
+        assert_eq!(context.identity.x509_san_dns.as_deref(), Some("example.com"));
+        let expected = Utc.with_ymd_and_hms(2023, 7, 27, 22, 39, 59).unwrap() + chrono::Duration::microseconds(177_430);
+        assert_eq!(context.time(), Some(expected));
+
+        let json = serde_json::to_string(&request).expect("Could not serialize request");
+        assert_eq!(serde_json::from_str::<VpcLatticeRequestV2>(&json).unwrap(), request);
+    }
+
+    #[test]
+    fn serializes_responses() {
+        let response = VpcLatticeResponse::builder()
+            .status_code(404)
+            .header("Content-Type", "text/plain")
+            .body("not found")
+            .build();
+        let expected = serde_json::json!({
+            "statusCode": 404,
+            "statusDescription": "404 Not Found",
+            "headers": {"Content-Type": "text/plain"},
+            "body": "not found",
+            "isBase64Encoded": false,
+        });
+        assert_eq!(serde_json::to_value(&response).unwrap(), expected);
+    }
+}
@@ -0,0 +1,55 @@
+// Generated by lambda_events::schema_codegen. Do not edit.
+
+/// The `detail_type` of the events.
+pub const DETAIL_TYPE: &str = "Order Created";
+
+/// The source of the events.
+pub const SOURCE: &str = "com.example.orders";
+
+/// An event of the schema.
+pub type OrderCreatedEvent = lambda_events::eventbridge::EventBridgeEvent<OrderCreated>;
+
+/// An order placed on the store.
+#[derive(Debug, Default, Clone, PartialEq, serde_derive::Deserialize, serde_derive::Serialize)]
+pub struct OrderCreated {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<std::collections::HashMap<String, String>>,
+    #[serde(rename = "createdAt", default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub customer: Customer,
+    pub items: Vec<Item>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+    /// The id of the order.
+    #[serde(rename = "orderId")]
+    pub order_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde_derive::Deserialize, serde_derive::Serialize)]
+pub struct Customer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<CustomerAddress>,
+    /// The id of the customer.
+    #[serde(rename = "customerId")]
+    pub customer_id: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde_derive::Deserialize, serde_derive::Serialize)]
+pub struct Item {
+    #[serde(rename = "giftWrap", default, skip_serializing_if = "Option::is_none")]
+    pub gift_wrap: Option<bool>,
+    pub quantity: i64,
+    pub sku: String,
+    #[serde(rename = "unitPrice")]
+    pub unit_price: f64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, serde_derive::Deserialize, serde_derive::Serialize)]
+pub struct CustomerAddress {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(rename = "postalCode", default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+}
@@ -3,7 +3,6 @@ use std::error::Error;
 use lambda_http::{lambda, IntoResponse, Request, RequestExt, Response};
 use lambda_runtime::{error::HandlerError, Context};
 use log::{self, error};
-use simple_logger;
 
 fn main() -> Result<(), Box<dyn Error>> {
     simple_logger::init_with_level(log::Level::Debug).unwrap();
@@ -43,6 +43,8 @@ use serde::ser::{Error as SerError, Serialize, Serializer};
 /// ```
 ///
 /// `Binary` responses bodies will automatically get based64 encoded to meet API Gateway's response expectations.
+/// So will `Text` response bodies whose `Content-Type` isn't textual, such as `application/octet-stream`.
+/// Base64 encoded request bodies are decoded into `Binary` bodies.
 ///
 /// ## Empty
 ///
@@ -58,9 +60,10 @@ use serde::ser::{Error as SerError, Serialize, Serializer};
 ///
 /// For more information about API Gateway's body types,
 /// refer to [this documentation](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-payload-encodings.html).
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Default, PartialEq)]
 pub enum Body {
     /// An empty body
+    #[default]
     Empty,
     /// A body containing string data
     Text(String),
@@ -68,12 +71,6 @@ pub enum Body {
     Binary(Vec<u8>),
 }
 
-impl Default for Body {
-    fn default() -> Self {
-        Body::Empty
-    }
-}
-
 impl From<()> for Body {
     fn from(_: ()) -> Self {
         Body::Empty
@@ -144,7 +141,7 @@ impl AsRef<[u8]> for Body {
     }
 }
 
-impl<'a> Serialize for Body {
+impl Serialize for Body {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -162,7 +159,6 @@ impl<'a> Serialize for Body {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json;
     use std::collections::HashMap;
 
     #[test]
@@ -179,7 +175,7 @@ mod tests {
     fn from_str() {
         match Body::from(String::from("foo").as_str()) {
             Body::Text(_) => (),
-            not => assert!(false, "expected Body::Text(...) got {:?}", not),
+            not => panic!("expected Body::Text(...) got {:?}", not),
         }
     }
 
@@ -187,7 +183,7 @@ mod tests {
     fn from_string() {
         match Body::from(String::from("foo")) {
             Body::Text(_) => (),
-            not => assert!(false, "expected Body::Text(...) got {:?}", not),
+            not => panic!("expected Body::Text(...) got {:?}", not),
         }
     }
 
@@ -195,7 +191,7 @@ mod tests {
     fn from_cow_str() {
         match Body::from(Cow::from("foo")) {
             Body::Text(_) => (),
-            not => assert!(false, "expected Body::Text(...) got {:?}", not),
+            not => panic!("expected Body::Text(...) got {:?}", not),
         }
     }
 
@@ -203,7 +199,7 @@ mod tests {
     fn from_cow_bytes() {
         match Body::from(Cow::from("foo".as_bytes())) {
             Body::Binary(_) => (),
-            not => assert!(false, "expected Body::Binary(...) got {:?}", not),
+            not => panic!("expected Body::Binary(...) got {:?}", not),
         }
     }
 
@@ -211,7 +207,7 @@ mod tests {
     fn from_bytes() {
         match Body::from("foo".as_bytes()) {
             Body::Binary(_) => (),
-            not => assert!(false, "expected Body::Binary(...) got {:?}", not),
+            not => panic!("expected Body::Binary(...) got {:?}", not),
         }
     }
 
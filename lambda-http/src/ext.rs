@@ -1,12 +1,11 @@
 //! ALB and API Gateway extension methods for `http::Request` types
 
-use failure::Fail;
-use http::{header::CONTENT_TYPE, Request as HttpRequest};
+use std::{error::Error, fmt};
+
+use http::{header::CONTENT_TYPE, Request as HttpRequest, Response, StatusCode};
 use serde::{de::value::Error as SerdeError, Deserialize};
-use serde_json;
-use serde_urlencoded;
 
-use crate::{request::RequestContext, strmap::StrMap};
+use crate::{request::RequestContext, strmap::StrMap, Body, IntoResponse};
 
 /// ALB/API gateway pre-parsed http query string parameters
 pub(crate) struct QueryStringParameters(pub(crate) StrMap);
@@ -16,6 +15,9 @@ pub(crate) struct QueryStringParameters(pub(crate) StrMap);
 /// These will always be empty for ALB requests
 pub(crate) struct PathParameters(pub(crate) StrMap);
 
+/// Path segments captured by a `Router`'s route, in the order of the route's path
+pub(crate) struct RouteParameters(pub(crate) Vec<(String, String)>);
+
 /// API gateway configured
 /// [stage variables](https://docs.aws.amazon.com/apigateway/latest/developerguide/stage-variables.html)
 ///
@@ -23,16 +25,69 @@ pub(crate) struct PathParameters(pub(crate) StrMap);
 pub(crate) struct StageVariables(pub(crate) StrMap);
 
 /// Payload deserialization errors
-#[derive(Debug, Fail)]
+#[derive(Debug)]
 pub enum PayloadError {
     /// Returned when `application/json` bodies fail to deserialize a payload
-    #[fail(display = "failed to parse payload from application/json")]
     Json(serde_json::Error),
     /// Returned when `application/x-www-form-urlencoded` bodies fail to deserialize a payload
-    #[fail(display = "failed to parse payload application/x-www-form-urlencoded")]
     WwwFormUrlEncoded(SerdeError),
 }
 
+impl fmt::Display for PayloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayloadError::Json(e) => write!(f, "failed to parse payload from application/json: {}", e),
+            PayloadError::WwwFormUrlEncoded(e) => {
+                write!(f, "failed to parse payload application/x-www-form-urlencoded: {}", e)
+            }
+        }
+    }
+}
+
+/// Payload errors are the client's fault, they are returned as `400 Bad Request` responses
+/// describing the error
+///
+/// ```rust,no_run
+/// use lambda_http::{lambda, IntoResponse, Request, RequestExt, Response};
+/// use lambda_runtime::{Context, HandlerError};
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Greeting {
+///     name: String,
+/// }
+///
+/// fn main() {
+///     lambda!(hello)
+/// }
+///
+/// fn hello(request: Request, _ctx: Context) -> Result<Response<lambda_http::Body>, HandlerError> {
+///     Ok(match request.payload::<Greeting>() {
+///         Ok(Some(greeting)) => format!("hello {}", greeting.name).into_response(),
+///         Ok(None) => "hello stranger".into_response(),
+///         Err(e) => e.into_response(),
+///     })
+/// }
+/// ```
+impl IntoResponse for PayloadError {
+    fn into_response(self) -> Response<Body> {
+        Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .header(CONTENT_TYPE, "text/plain")
+            .body(self.to_string().into())
+            .expect("unable to build http::Response")
+    }
+}
+
+impl Error for PayloadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PayloadError::Json(e) => Some(e),
+            PayloadError::WwwFormUrlEncoded(e) => Some(e),
+        }
+    }
+}
+
 /// Extentions for `lambda_http::Request` structs that
 /// provide access to [API gateway](https://docs.aws.amazon.com/apigateway/latest/developerguide/set-up-lambda-proxy-integrations.html#api-gateway-simple-proxy-for-lambda-input-format)
 /// and [ALB](https://docs.aws.amazon.com/elasticloadbalancing/latest/application/lambda-functions.html)
@@ -111,6 +166,40 @@ pub trait RequestExt {
     /// These will always be empty for ALB triggered requests
     fn stage_variables(&self) -> StrMap;
 
+    /// Configures the query string parameters of the request, which API Gateway and
+    /// ALBs would otherwise provide
+    ///
+    /// This is intended for building requests in the tests of handlers
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use lambda_http::{Request, RequestExt};
+    ///
+    /// let mut query = HashMap::new();
+    /// query.insert("name".to_string(), "lambda".to_string());
+    /// let request = Request::default().with_query_string_parameters(query);
+    /// assert_eq!(request.query_string_parameters().get("name"), Some("lambda"));
+    /// ```
+    fn with_query_string_parameters<Q>(self, parameters: Q) -> Self
+    where
+        Q: Into<StrMap>;
+
+    /// Configures the path parameters of the request, which API Gateway would
+    /// otherwise provide
+    ///
+    /// This is intended for building requests in the tests of handlers
+    fn with_path_parameters<P>(self, parameters: P) -> Self
+    where
+        P: Into<StrMap>;
+
+    /// Configures the stage variables of the request, which API Gateway would
+    /// otherwise provide
+    ///
+    /// This is intended for building requests in the tests of handlers
+    fn with_stage_variables<V>(self, variables: V) -> Self
+    where
+        V: Into<StrMap>;
+
     /// Return request context data assocaited with the ALB or API gateway request
     fn request_context(&self) -> RequestContext;
 
@@ -119,10 +208,13 @@ pub trait RequestExt {
     ///
     /// Currently only `application/x-www-form-urlencoded`
     /// and `application/json` flavors of content type
-    /// are supported
+    /// are supported, including their parameters such as `charset`
+    /// and `+json` types such as `application/vnd.api+json`.
+    /// Base64 encoded bodies are decoded before they are parsed.
     ///
     /// A [PayloadError](enum.PayloadError.html) will be returned for undeserializable
-    /// payloads. If no body is provided, `Ok(None)` will be returned.
+    /// payloads, which can be returned as a `400 Bad Request` response.
+    /// If no body or no supported content type is provided, `Ok(None)` will be returned.
     fn payload<D>(&self) -> Result<Option<D>, PayloadError>
     where
         for<'de> D: Deserialize<'de>;
@@ -148,6 +240,33 @@ impl RequestExt for HttpRequest<super::Body> {
             .unwrap_or_default()
     }
 
+    fn with_query_string_parameters<Q>(self, parameters: Q) -> Self
+    where
+        Q: Into<StrMap>,
+    {
+        let mut s = self;
+        s.extensions_mut().insert(QueryStringParameters(parameters.into()));
+        s
+    }
+
+    fn with_path_parameters<P>(self, parameters: P) -> Self
+    where
+        P: Into<StrMap>,
+    {
+        let mut s = self;
+        s.extensions_mut().insert(PathParameters(parameters.into()));
+        s
+    }
+
+    fn with_stage_variables<V>(self, variables: V) -> Self
+    where
+        V: Into<StrMap>,
+    {
+        let mut s = self;
+        s.extensions_mut().insert(StageVariables(variables.into()));
+        s
+    }
+
     fn request_context(&self) -> RequestContext {
         self.extensions().get::<RequestContext>().cloned().unwrap_or_default()
     }
@@ -158,11 +277,12 @@ impl RequestExt for HttpRequest<super::Body> {
     {
         self.headers()
             .get(CONTENT_TYPE)
-            .map(|ct| match ct.to_str() {
-                Ok("application/x-www-form-urlencoded") => serde_urlencoded::from_bytes::<D>(self.body().as_ref())
+            .and_then(|ct| ct.to_str().ok())
+            .map(|ct| match media_type(ct).as_str() {
+                "application/x-www-form-urlencoded" => serde_urlencoded::from_bytes::<D>(self.body().as_ref())
                     .map_err(PayloadError::WwwFormUrlEncoded)
                     .map(Some),
-                Ok("application/json") => serde_json::from_slice::<D>(self.body().as_ref())
+                json if is_json(json) => serde_json::from_slice::<D>(self.body().as_ref())
                     .map_err(PayloadError::Json)
                     .map(Some),
                 _ => Ok(None),
@@ -171,13 +291,28 @@ impl RequestExt for HttpRequest<super::Body> {
     }
 }
 
+/// Return the lowercased media type of a content type, without its parameters
+pub(crate) fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Return true if a media type is `application/json` or a `+json` type
+pub(crate) fn is_json(media_type: &str) -> bool {
+    media_type == "application/json" || (media_type.starts_with("application/") && media_type.ends_with("+json"))
+}
+
 #[cfg(test)]
 mod tests {
     use http::{HeaderMap, Request as HttpRequest};
     use serde_derive::Deserialize;
     use std::collections::HashMap;
 
-    use crate::{LambdaRequest, RequestExt, StrMap};
+    use crate::{request::LambdaRequest, IntoResponse, Request, RequestExt, StrMap};
 
     #[test]
     fn requests_have_query_string_ext() {
@@ -195,6 +330,20 @@ mod tests {
         assert_eq!(actual.query_string_parameters(), StrMap(query.clone().into()));
     }
 
+    #[test]
+    fn requests_can_be_configured_for_tests() {
+        let mut path = HashMap::new();
+        path.insert("id".to_owned(), "42".to_owned());
+        let mut stage = HashMap::new();
+        stage.insert("env".to_owned(), vec!["prod".to_owned()]);
+        let request = Request::default()
+            .with_path_parameters(path)
+            .with_stage_variables(stage);
+        assert_eq!(request.path_parameters().get("id"), Some("42"));
+        assert_eq!(request.stage_variables().get("env"), Some("prod"));
+        assert!(request.query_string_parameters().is_empty());
+    }
+
     #[test]
     fn requests_have_form_post_parseable_payloads() {
         let mut headers = HeaderMap::new();
@@ -222,6 +371,59 @@ mod tests {
         )
     }
 
+    #[test]
+    fn requests_have_json_parseable_payloads_with_parameters() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Host", "www.rust-lang.org".parse().unwrap());
+        headers.insert("Content-Type", "Application/JSON; charset=utf-8".parse().unwrap());
+        let lambda_request = LambdaRequest {
+            path: "/foo".into(),
+            headers,
+            body: Some(r#"{"foo":"bar"}"#.into()),
+            ..LambdaRequest::default()
+        };
+        let actual = HttpRequest::from(lambda_request);
+        let payload: Option<HashMap<String, String>> = actual.payload().unwrap_or_default();
+        assert_eq!(payload.unwrap()["foo"], "bar");
+    }
+
+    #[test]
+    fn requests_have_base64_encoded_payloads() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Host", "www.rust-lang.org".parse().unwrap());
+        headers.insert("Content-Type", "application/vnd.api+json".parse().unwrap());
+        let lambda_request = LambdaRequest {
+            path: "/foo".into(),
+            headers,
+            body: Some("eyJmb28iOiJiYXIifQ==".into()),
+            is_base64_encoded: true,
+            ..LambdaRequest::default()
+        };
+        let actual = HttpRequest::from(lambda_request);
+        let payload: Option<HashMap<String, String>> = actual.payload().unwrap_or_default();
+        assert_eq!(payload.unwrap()["foo"], "bar");
+    }
+
+    #[test]
+    fn payload_errors_are_bad_requests() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Host", "www.rust-lang.org".parse().unwrap());
+        headers.insert("Content-Type", "application/json".parse().unwrap());
+        let lambda_request = LambdaRequest {
+            path: "/foo".into(),
+            headers,
+            body: Some("{".into()),
+            ..LambdaRequest::default()
+        };
+        let actual = HttpRequest::from(lambda_request);
+        let err = actual.payload::<HashMap<String, String>>().unwrap_err();
+        let response = err.into_response();
+        assert_eq!(response.status(), 400);
+        assert!(std::str::from_utf8(response.body())
+            .unwrap()
+            .starts_with("failed to parse payload from application/json"));
+    }
+
     #[test]
     fn requests_have_form_post_parseable_payloads_for_hashmaps() {
         let mut headers = HeaderMap::new();
@@ -0,0 +1,377 @@
+//! Typed extraction of handler arguments from requests
+//!
+//! Handler functions can take typed parts of the request, such as [Path](struct.Path.html),
+//! [Query](struct.Query.html) or [Json](../struct.Json.html), before their `Context`,
+//! rather than the request itself. Wrapping them with [handler](fn.handler.html) turns
+//! them into handlers, which respond with `400 Bad Request` when a part can't be extracted.
+//!
+//! ```rust,no_run
+//! use lambda_http::{extract::{self, Path, Query}, lambda, Json, Router};
+//! use lambda_runtime::{Context, HandlerError};
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize)]
+//! struct Filters {
+//!     active: bool,
+//! }
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! fn main() {
+//!     lambda!(Router::new()
+//!         .get("/users", extract::handler(list_users))
+//!         .get("/users/:id", extract::handler(get_user))
+//!         .post("/users", extract::handler(create_user)))
+//! }
+//!
+//! fn list_users(Query(filters): Query<Filters>, _ctx: Context) -> Result<String, HandlerError> {
+//!     Ok(format!("active users: {}", filters.active))
+//! }
+//!
+//! fn get_user(Path(id): Path<u64>, _ctx: Context) -> Result<String, HandlerError> {
+//!     Ok(format!("user {}", id))
+//! }
+//!
+//! fn create_user(Json(user): Json<User>, _ctx: Context) -> Result<Json<User>, HandlerError> {
+//!     Ok(Json(user))
+//! }
+//! ```
+
+use std::{error::Error, fmt};
+
+use http::{header::CONTENT_TYPE, Response, StatusCode};
+use lambda_runtime::{error::HandlerError, Context};
+use serde::de::{value::Error as SerdeError, DeserializeOwned};
+use serde_derive::Deserialize;
+
+use crate::{
+    ext::{is_json, media_type, RouteParameters},
+    Body, Handler, IntoResponse, Json, Request, RequestExt,
+};
+
+/// Types extracted from requests, as the arguments of handler functions
+pub trait FromRequest: Sized {
+    /// Extract the value from a request, or return the reason it couldn't be
+    fn from_request(request: &Request) -> Result<Self, Rejection>;
+}
+
+/// The reason a value could not be extracted from a request
+///
+/// Rejections are the client's fault, they are returned as `400 Bad Request`
+/// responses describing the reason
+#[derive(Debug)]
+pub enum Rejection {
+    /// Returned when path parameters fail to deserialize
+    Path(SerdeError),
+    /// Returned when query string parameters fail to deserialize
+    Query(SerdeError),
+    /// Returned when a json body is expected but the request has another content type
+    JsonContentType,
+    /// Returned when a json body fails to deserialize
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::Path(e) => write!(f, "failed to parse path parameters: {}", e),
+            Rejection::Query(e) => write!(f, "failed to parse query string parameters: {}", e),
+            Rejection::JsonContentType => write!(f, "expected a request with an application/json content type"),
+            Rejection::Json(e) => write!(f, "failed to parse payload from application/json: {}", e),
+        }
+    }
+}
+
+impl Error for Rejection {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Rejection::Path(e) | Rejection::Query(e) => Some(e),
+            Rejection::JsonContentType => None,
+            Rejection::Json(e) => Some(e),
+        }
+    }
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response<Body> {
+        Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .header(CONTENT_TYPE, "text/plain")
+            .body(self.to_string().into())
+            .expect("unable to build http::Response")
+    }
+}
+
+/// Deserialize parameters into a type, or their scalar value for types that aren't
+/// deserialized from a map
+fn deserialize_parameters<T>(pairs: &[(&str, &str)], scalar: Option<&str>) -> Result<T, SerdeError>
+where
+    T: DeserializeOwned,
+{
+    /// A single parameter, for types deserialized from a value rather than a map
+    #[derive(Deserialize)]
+    struct Single<T> {
+        value: T,
+    }
+
+    let encoded = serde_urlencoded::to_string(pairs).map_err(serde::de::Error::custom)?;
+    match (serde_urlencoded::from_str::<T>(&encoded), scalar) {
+        (Err(e), Some(scalar)) => {
+            let encoded = serde_urlencoded::to_string([("value", scalar)]).map_err(serde::de::Error::custom)?;
+            serde_urlencoded::from_str::<Single<T>>(&encoded)
+                .map(|single| single.value)
+                .map_err(|_| e)
+        }
+        (result, _) => result,
+    }
+}
+
+/// Path parameters, captured by API Gateway or a [Router](../struct.Router.html),
+/// deserialized into a type
+///
+/// Types such as structs and maps are deserialized from all the parameters, other types
+/// such as numbers from a single parameter. When a router's route captured segments, only
+/// those are deserialized, and single parameters are the last captured segment, so that
+/// `Path<u64>` works for a `/users/:id` route behind an API Gateway `{proxy+}` resource.
+/// Otherwise, single parameters are the only parameter captured by API Gateway.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Path<T>(pub T);
+
+impl<T> FromRequest for Path<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_request(request: &Request) -> Result<Self, Rejection> {
+        let result = match request.extensions().get::<RouteParameters>() {
+            Some(RouteParameters(captures)) if !captures.is_empty() => {
+                let pairs: Vec<(&str, &str)> = captures.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+                deserialize_parameters(&pairs, pairs.last().map(|(_, value)| *value))
+            }
+            _ => {
+                let parameters = request.path_parameters();
+                let pairs: Vec<(&str, &str)> = parameters.iter().collect();
+                let scalar = match pairs.as_slice() {
+                    [(_, value)] => Some(*value),
+                    _ => None,
+                };
+                deserialize_parameters(&pairs, scalar)
+            }
+        };
+        result.map(Path).map_err(Rejection::Path)
+    }
+}
+
+/// Query string parameters deserialized into a type
+///
+/// Only the first value of repeated parameters is deserialized
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Query<T>(pub T);
+
+impl<T> FromRequest for Query<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_request(request: &Request) -> Result<Self, Rejection> {
+        let parameters = request.query_string_parameters();
+        serde_urlencoded::to_string(parameters.iter().collect::<Vec<_>>())
+            .map_err(serde::de::Error::custom)
+            .and_then(|encoded| serde_urlencoded::from_str(&encoded))
+            .map(Query)
+            .map_err(Rejection::Query)
+    }
+}
+
+/// Json bodies are extracted from requests with an `application/json` or `+json`
+/// content type
+impl<T> FromRequest for Json<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_request(request: &Request) -> Result<Self, Rejection> {
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|ct| ct.to_str().ok())
+            .map(media_type)
+            .unwrap_or_default();
+        if !is_json(&content_type) {
+            return Err(Rejection::JsonContentType);
+        }
+        serde_json::from_slice(request.body().as_ref())
+            .map(Json)
+            .map_err(Rejection::Json)
+    }
+}
+
+/// Functions taking extracted arguments and a `Context`
+///
+/// This is implemented for functions of up to four arguments before their `Context`
+pub trait ExtractHandler<T, R> {
+    /// Extract the arguments from the request and run the function
+    fn run(&mut self, request: Request, ctx: Context) -> Result<Response<Body>, HandlerError>;
+}
+
+macro_rules! extract_handler {
+    ($($arg:ident),+) => {
+        impl<F, R, $($arg,)+> ExtractHandler<($($arg,)+), R> for F
+        where
+            F: FnMut($($arg,)+ Context) -> Result<R, HandlerError>,
+            R: IntoResponse,
+            $($arg: FromRequest,)+
+        {
+            #[allow(non_snake_case)]
+            fn run(&mut self, request: Request, ctx: Context) -> Result<Response<Body>, HandlerError> {
+                $(
+                    let $arg = match $arg::from_request(&request) {
+                        Ok(value) => value,
+                        Err(rejection) => return Ok(rejection.into_response()),
+                    };
+                )+
+                (self)($($arg,)+ ctx).map(IntoResponse::into_response)
+            }
+        }
+    };
+}
+
+extract_handler!(A);
+extract_handler!(A, B);
+extract_handler!(A, B, C);
+extract_handler!(A, B, C, D);
+
+/// Turn a function taking extracted arguments into a handler
+///
+/// Requests whose arguments can't be extracted get a `400 Bad Request` response,
+/// without running the function
+pub fn handler<T, R>(f: impl ExtractHandler<T, R>) -> impl Handler<Response<Body>> {
+    let mut f = f;
+    move |request: Request, ctx: Context| f.run(request, ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Router;
+    use serde_derive::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct User {
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Filters {
+        active: bool,
+        limit: Option<usize>,
+    }
+
+    fn parameters(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn body(response: &Response<Body>) -> &str {
+        std::str::from_utf8(response.body()).expect("invalid body")
+    }
+
+    #[test]
+    fn extracts_path_parameters() {
+        let request = Request::default().with_path_parameters(parameters(&[("id", "42")]));
+        assert_eq!(Path::<u64>::from_request(&request).unwrap(), Path(42));
+        assert_eq!(
+            Path::<HashMap<String, u64>>::from_request(&request).unwrap().0["id"],
+            42
+        );
+
+        let request = Request::default().with_path_parameters(parameters(&[("id", "ada")]));
+        assert!(matches!(Path::<u64>::from_request(&request), Err(Rejection::Path(_))));
+    }
+
+    #[test]
+    fn extracts_query_string_parameters() {
+        let request = Request::default().with_query_string_parameters(parameters(&[("active", "true")]));
+        assert_eq!(
+            Query::<Filters>::from_request(&request).unwrap(),
+            Query(Filters {
+                active: true,
+                limit: None
+            })
+        );
+
+        let request = Request::default().with_query_string_parameters(parameters(&[("limit", "10")]));
+        assert!(matches!(
+            Query::<Filters>::from_request(&request),
+            Err(Rejection::Query(_))
+        ));
+    }
+
+    #[test]
+    fn extracts_json_bodies() {
+        let request = http::Request::builder()
+            .header(CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(Body::from(r#"{"name":"ada"}"#))
+            .unwrap();
+        assert_eq!(
+            Json::<User>::from_request(&request).unwrap(),
+            Json(User { name: "ada".into() })
+        );
+
+        let request = http::Request::builder()
+            .header(CONTENT_TYPE, "text/plain")
+            .body(Body::from(r#"{"name":"ada"}"#))
+            .unwrap();
+        assert!(matches!(
+            Json::<User>::from_request(&request),
+            Err(Rejection::JsonContentType)
+        ));
+    }
+
+    #[test]
+    fn handlers_run_with_extracted_arguments() {
+        let mut router = Router::new().post(
+            "/teams/:team/users",
+            handler(
+                |Path(team): Path<String>, Json(user): Json<User>, _: Context| -> Result<_, HandlerError> {
+                    Ok((StatusCode::CREATED, format!("{} joined {}", user.name, team)))
+                },
+            ),
+        );
+        let request = http::Request::builder()
+            .method("POST")
+            .uri("/teams/rust/users")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"name":"ada"}"#))
+            .unwrap();
+        let response = router.run(request, Context::default()).unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(body(&response), "ada joined rust");
+
+        let request = http::Request::builder()
+            .method("POST")
+            .uri("/teams/rust/users")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from("{"))
+            .unwrap();
+        let response = router.run(request, Context::default()).unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(body(&response).starts_with("failed to parse payload from application/json"));
+    }
+
+    #[test]
+    fn extracts_route_parameters_behind_proxy_resources() {
+        let mut router = Router::new().get(
+            "/users/:id",
+            handler(|Path(id): Path<u64>, _: Context| -> Result<_, HandlerError> { Ok(format!("user {}", id)) }),
+        );
+        let request = http::Request::builder()
+            .uri("/users/42")
+            .body(Body::Empty)
+            .unwrap()
+            .with_path_parameters(parameters(&[("proxy", "users/42")]));
+        let response = router.run(request, Context::default()).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(&response), "user 42");
+    }
+}
@@ -1,11 +1,12 @@
 #![warn(missing_docs)]
 //#![deny(warnings)]
 //! Enriches the `lambda_runtime` crate with [http](https://github.com/hyperium/http)
-//! types targeting ALB and API Gateway proxy events.
+//! types targeting ALB, API Gateway (REST and HTTP APIs) and Lambda function URL events.
 //!
-//! Though ALB and API Gateway proxy events are separate Lambda triggers, they both share
+//! Though these events come from separate Lambda triggers, they all share
 //! similar shapes that contextually map to an http request handler. From a application perspective
-//! the differences shouldn't matter. This crate
+//! the differences shouldn't matter. The trigger of each event is detected from its shape,
+//! and responses are sent back in the format it expects. This crate
 //! abstracts over both using standard [http](https://github.com/hyperium/http) types allowing
 //! you to focus more on your application while giving you to the flexibility to
 //! transparently use whichever http trigger suits your application's needs best.
@@ -59,12 +60,20 @@ use tokio::runtime::Runtime as TokioRuntime;
 
 mod body;
 mod ext;
+pub mod extract;
 pub mod request;
 mod response;
+mod router;
 mod strmap;
 
-pub use crate::{body::Body, ext::RequestExt, response::IntoResponse, strmap::StrMap};
-use crate::{request::LambdaRequest, response::LambdaResponse};
+pub use crate::{
+    body::Body,
+    ext::RequestExt,
+    response::{IntoResponse, Json},
+    router::Router,
+    strmap::StrMap,
+};
+use crate::{request::LambdaEvent, response::LambdaResponse};
 
 /// Type alias for `http::Request`s with a fixed `lambda_http::Body` body
 pub type Request = http::Request<Body>;
@@ -99,10 +108,10 @@ where
     // handler requires a mutable ref
     let mut func = f;
     lambda::start(
-        |req: LambdaRequest<'_>, ctx: Context| {
-            let is_alb = req.request_context.is_alb();
+        |req: LambdaEvent<'_>, ctx: Context| {
+            let format = req.response_format();
             func.run(req.into(), ctx)
-                .map(|resp| LambdaResponse::from_response(is_alb, resp.into_response()))
+                .map(|resp| LambdaResponse::from_response(format, resp.into_response()))
         },
         runtime,
     )
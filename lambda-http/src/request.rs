@@ -1,18 +1,18 @@
-//! ALB andAPI Gateway request types.
+//! ALB, API Gateway and Lambda function URL request types.
 //!
 //! Typically these are exposed via the `request_context`
 //! request extension method provided by [lambda_http::RequestExt](../trait.RequestExt.html)
 //!
-use std::{borrow::Cow, collections::HashMap, fmt, mem};
+use std::{borrow::Cow, collections::HashMap, fmt};
 
 use http::{
     self,
-    header::{HeaderName, HeaderValue, HOST},
+    header::{HeaderName, HeaderValue, COOKIE, HOST},
     HeaderMap, Method, Request as HttpRequest,
 };
 use serde::{
     de::{Error as DeError, MapAccess, Visitor},
-    Deserialize, Deserializer,
+    Deserializer,
 };
 use serde_derive::Deserialize;
 use serde_json::Value;
@@ -20,6 +20,7 @@ use serde_json::Value;
 use crate::{
     body::Body,
     ext::{PathParameters, QueryStringParameters, StageVariables},
+    response::ResponseFormat,
     strmap::StrMap,
 };
 
@@ -56,29 +57,120 @@ pub(crate) struct LambdaRequest<'a> {
     pub(crate) request_context: RequestContext,
 }
 
+/// Internal representation of a Lambda http event in the 2.0 payload format, used
+/// by API Gateway HTTP APIs and Lambda function URLs
+#[doc(hidden)]
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LambdaRequestV2<'a> {
+    pub(crate) raw_path: Cow<'a, str>,
+    #[serde(default)]
+    pub(crate) raw_query_string: Cow<'a, str>,
+    /// Cookies are sent separately from the other headers
+    #[serde(default, deserialize_with = "nullable_default")]
+    pub(crate) cookies: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_headers")]
+    pub(crate) headers: HeaderMap<HeaderValue>,
+    /// Repeated parameters are joined with commas, `raw_query_string` is preferred
+    #[serde(default, deserialize_with = "nullable_default")]
+    pub(crate) query_string_parameters: StrMap,
+    #[serde(default, deserialize_with = "nullable_default")]
+    pub(crate) path_parameters: StrMap,
+    #[serde(default, deserialize_with = "nullable_default")]
+    pub(crate) stage_variables: StrMap,
+    pub(crate) body: Option<Cow<'a, str>>,
+    #[serde(default)]
+    pub(crate) is_base64_encoded: bool,
+    pub(crate) request_context: RequestContext,
+}
+
+/// Internal representation of the Lambda http events of any trigger, told apart
+/// by their shapes
+#[doc(hidden)]
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub(crate) enum LambdaEvent<'a> {
+    /// API Gateway HTTP API and Lambda function URL events
+    V2(LambdaRequestV2<'a>),
+    /// API Gateway REST API and ALB events
+    V1(LambdaRequest<'a>),
+}
+
 /// Event request context as an enumeration of request contexts
 /// for both ALB and API Gateway http events
 #[derive(Deserialize, Debug, Clone)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum RequestContext {
     /// Api Gateway request context
     #[serde(rename_all = "camelCase")]
     ApiGateway {
         //pub path: String,
+        /// The AWS account id owning the API Gateway
         account_id: String,
+        /// The API Gateway resource id
         resource_id: String,
+        /// The deployment stage of the API
         stage: String,
+        /// The API Gateway generated request id
         request_id: String,
+        /// The resource path as defined in API Gateway
         resource_path: String,
+        /// The http method of the request
         http_method: String,
+        /// Values returned by the API Gateway authorizer
         #[serde(default)]
         authorizer: HashMap<String, Value>,
+        /// The API Gateway rest API id
         api_id: String,
+        /// Identity information of the caller
         identity: Identity,
     },
     /// ALB request context
     #[serde(rename_all = "camelCase")]
-    Alb { elb: Elb },
+    Alb {
+        /// Elastic load balancer context information
+        elb: Elb,
+    },
+    /// Api Gateway HTTP API and Lambda function URL request context
+    #[serde(rename_all = "camelCase")]
+    ApiGatewayV2 {
+        /// The AWS account id owning the API Gateway or the function
+        account_id: String,
+        /// The API Gateway HTTP API id, or the function URL id
+        api_id: String,
+        /// The domain name the request was sent to
+        domain_name: String,
+        /// The route of the API that matched the request, `$default` for function URLs
+        #[serde(default)]
+        route_key: String,
+        /// The deployment stage of the API, `$default` for function URLs
+        #[serde(default)]
+        stage: String,
+        /// The generated request id
+        request_id: String,
+        /// The time of the request, in milliseconds since the epoch
+        #[serde(default)]
+        time_epoch: i64,
+        /// Values returned by the authorizer, such as JWT claims or IAM credentials
+        #[serde(default, deserialize_with = "nullable_default")]
+        authorizer: HashMap<String, Value>,
+        /// Http information about the request and its caller
+        http: Http,
+    },
+}
+
+/// The trigger of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestOrigin {
+    /// An API Gateway REST API
+    ApiGatewayV1,
+    /// An API Gateway HTTP API
+    ApiGatewayV2,
+    /// An application load balancer
+    Alb,
+    /// A Lambda function URL
+    FunctionUrl,
 }
 
 impl Default for RequestContext {
@@ -100,13 +192,40 @@ impl Default for RequestContext {
 impl RequestContext {
     /// Return true if this request context represents an ALB request
     pub fn is_alb(&self) -> bool {
+        matches!(self, RequestContext::Alb { .. })
+    }
+
+    /// Return the trigger of the request
+    pub fn origin(&self) -> RequestOrigin {
         match self {
-            RequestContext::Alb { .. } => true,
-            _ => false,
+            RequestContext::ApiGateway { .. } => RequestOrigin::ApiGatewayV1,
+            RequestContext::Alb { .. } => RequestOrigin::Alb,
+            // function URLs use the same payload format as HTTP APIs, on their own domain
+            RequestContext::ApiGatewayV2 { domain_name, .. } if domain_name.contains(".lambda-url.") => {
+                RequestOrigin::FunctionUrl
+            }
+            RequestContext::ApiGatewayV2 { .. } => RequestOrigin::ApiGatewayV2,
         }
     }
 }
 
+/// Http information of API Gateway HTTP API and Lambda function URL requests
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Http {
+    /// The http method of the request
+    #[serde(deserialize_with = "deserialize_method")]
+    pub method: Method,
+    /// The path of the request
+    pub path: String,
+    /// The protocol of the request, for example `HTTP/1.1`
+    pub protocol: String,
+    /// The source IP address of the caller
+    pub source_ip: String,
+    /// The user agent of the caller
+    pub user_agent: String,
+}
+
 /// Elastic load balancer context information
 #[derive(Deserialize, Debug, Default, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -119,17 +238,29 @@ pub struct Elb {
 #[derive(Deserialize, Debug, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Identity {
+    /// The source IP address of the caller
     pub source_ip: String,
+    /// The Cognito identity id of the caller
     pub cognito_identity_id: Option<String>,
+    /// The Cognito identity pool id of the caller
     pub cognito_identity_pool_id: Option<String>,
+    /// The Cognito authentication provider used by the caller
     pub cognito_authentication_provider: Option<String>,
+    /// The Cognito authentication type used by the caller
     pub cognito_authentication_type: Option<String>,
+    /// The AWS account id of the caller
     pub account_id: Option<String>,
+    /// The principal identifier of the caller
     pub caller: Option<String>,
+    /// The API key used by the caller
     pub api_key: Option<String>,
+    /// The access key used to sign the request
     pub access_key: Option<String>,
+    /// The principal identifier of the user
     pub user: Option<String>,
+    /// The user agent of the caller
     pub user_agent: Option<String>,
+    /// The ARN of the user
     pub user_arn: Option<String>,
 }
 
@@ -239,12 +370,45 @@ where
 fn nullable_default<'de, T, D>(deserializer: D) -> Result<T, D::Error>
 where
     D: Deserializer<'de>,
-    T: Default + Deserialize<'de>,
+    T: Default + serde::Deserialize<'de>,
 {
-    let opt = Option::deserialize(deserializer)?;
+    let opt = <Option<T> as serde::Deserialize>::deserialize(deserializer)?;
     Ok(opt.unwrap_or_else(T::default))
 }
 
+impl LambdaRequest<'_> {
+    /// Return the response format expected by the trigger of this request
+    ///
+    /// ALBs only send multi value headers when their target group expects them
+    /// in responses too
+    pub(crate) fn response_format(&self) -> ResponseFormat {
+        match self.request_context {
+            RequestContext::Alb { .. } if self.multi_value_headers.is_empty() => ResponseFormat::Alb,
+            RequestContext::Alb { .. } => ResponseFormat::AlbMultiValue,
+            _ => ResponseFormat::ApiGateway,
+        }
+    }
+}
+
+impl LambdaEvent<'_> {
+    /// Return the response format expected by the trigger of this event
+    pub(crate) fn response_format(&self) -> ResponseFormat {
+        match self {
+            LambdaEvent::V2(_) => ResponseFormat::ApiGatewayV2,
+            LambdaEvent::V1(request) => request.response_format(),
+        }
+    }
+}
+
+impl<'a> From<LambdaEvent<'a>> for HttpRequest<Body> {
+    fn from(value: LambdaEvent<'_>) -> Self {
+        match value {
+            LambdaEvent::V2(request) => request.into(),
+            LambdaEvent::V1(request) => request.into(),
+        }
+    }
+}
+
 impl<'a> From<LambdaRequest<'a>> for HttpRequest<Body> {
     fn from(value: LambdaRequest<'_>) -> Self {
         let LambdaRequest {
@@ -269,7 +433,7 @@ impl<'a> From<LambdaRequest<'a>> for HttpRequest<Body> {
                 "{}://{}{}",
                 headers
                     .get("X-Forwarded-Proto")
-                    .map(|val| val.to_str().unwrap_or_else(|_| "https"))
+                    .map(|val| val.to_str().unwrap_or("https"))
                     .unwrap_or_else(|| "https"),
                 headers
                     .get(HOST)
@@ -293,17 +457,7 @@ impl<'a> From<LambdaRequest<'a>> for HttpRequest<Body> {
         builder.extension(request_context);
 
         let mut req = builder
-            .body(match body {
-                Some(b) => {
-                    if is_base64_encoded {
-                        // todo: document failure behavior
-                        Body::from(::base64::decode(b.as_ref()).unwrap_or_default())
-                    } else {
-                        Body::from(b.into_owned())
-                    }
-                }
-                _ => Body::from(()),
-            })
+            .body(into_body(body, is_base64_encoded))
             .expect("failed to build request");
 
         // merge headers into multi_value_headers and make
@@ -319,7 +473,107 @@ impl<'a> From<LambdaRequest<'a>> for HttpRequest<Body> {
         }
 
         // no builder method that sets headers in batch
-        mem::replace(req.headers_mut(), multi_value_headers);
+        *req.headers_mut() = multi_value_headers;
+
+        req
+    }
+}
+
+/// Return the body of an event, decoding base64 encoded bodies
+fn into_body(body: Option<Cow<'_, str>>, is_base64_encoded: bool) -> Body {
+    match body {
+        Some(b) => {
+            if is_base64_encoded {
+                // bodies that fail to decode are passed along as is rather than dropped
+                match ::base64::decode(b.as_ref()) {
+                    Ok(bytes) => Body::from(bytes),
+                    Err(_) => Body::from(b.into_owned()),
+                }
+            } else {
+                Body::from(b.into_owned())
+            }
+        }
+        _ => Body::from(()),
+    }
+}
+
+impl<'a> From<LambdaRequestV2<'a>> for HttpRequest<Body> {
+    fn from(value: LambdaRequestV2<'_>) -> Self {
+        let LambdaRequestV2 {
+            raw_path,
+            raw_query_string,
+            cookies,
+            mut headers,
+            query_string_parameters,
+            path_parameters,
+            stage_variables,
+            body,
+            is_base64_encoded,
+            request_context,
+        } = value;
+
+        let (method, domain_name, stage) = match &request_context {
+            RequestContext::ApiGatewayV2 {
+                http,
+                domain_name,
+                stage,
+                ..
+            } => (http.method.clone(), domain_name.as_str(), stage.as_str()),
+            _ => (Method::default(), "", "$default"),
+        };
+        // http api paths start with the stage, unless it's the `$default` one,
+        // rest api paths never do
+        let path = match raw_path.strip_prefix('/').and_then(|path| path.strip_prefix(stage)) {
+            Some(path) if stage != "$default" && path.is_empty() => "/",
+            Some(path) if stage != "$default" && path.starts_with('/') => path,
+            _ => &raw_path,
+        };
+        let mut builder = HttpRequest::builder();
+        builder.method(method);
+        builder.uri({
+            format!(
+                "{}://{}{}{}{}",
+                headers
+                    .get("X-Forwarded-Proto")
+                    .map(|val| val.to_str().unwrap_or("https"))
+                    .unwrap_or_else(|| "https"),
+                headers
+                    .get(HOST)
+                    .map(|val| val.to_str().unwrap_or_default())
+                    .unwrap_or(domain_name),
+                path,
+                if raw_query_string.is_empty() { "" } else { "?" },
+                raw_query_string
+            )
+        });
+        // query_string_parameters joins repeated parameters with commas,
+        // the raw query string keeps them apart
+        builder.extension(QueryStringParameters(
+            match serde_urlencoded::from_str::<Vec<(String, String)>>(&raw_query_string) {
+                Ok(pairs) if !pairs.is_empty() => {
+                    let mut parameters = HashMap::<String, Vec<String>>::new();
+                    for (key, value) in pairs {
+                        parameters.entry(key).or_default().push(value);
+                    }
+                    parameters.into()
+                }
+                _ => query_string_parameters,
+            },
+        ));
+        builder.extension(PathParameters(path_parameters));
+        builder.extension(StageVariables(stage_variables));
+        builder.extension(request_context);
+
+        let mut req = builder
+            .body(into_body(body, is_base64_encoded))
+            .expect("failed to build request");
+
+        if !cookies.is_empty() {
+            if let Ok(cookie) = HeaderValue::from_shared(cookies.join("; ").into()) {
+                headers.insert(COOKIE, cookie);
+            }
+        }
+        *req.headers_mut() = headers;
 
         req
     }
@@ -348,13 +602,110 @@ mod tests {
         assert_eq!(expected.method(), actual.method());
     }
 
+    #[test]
+    fn requests_decode_base64_bodies() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Host", "www.rust-lang.org".parse().unwrap());
+        let lambda_request = LambdaRequest {
+            path: "/foo".into(),
+            headers: headers.clone(),
+            body: Some("iVBORw0KGgo=".into()),
+            is_base64_encoded: true,
+            ..LambdaRequest::default()
+        };
+        let actual = HttpRequest::from(lambda_request);
+        assert_eq!(actual.body(), &Body::Binary(b"\x89PNG\r\n\x1a\n".to_vec()));
+
+        let lambda_request = LambdaRequest {
+            path: "/foo".into(),
+            headers,
+            body: Some("not base64!".into()),
+            is_base64_encoded: true,
+            ..LambdaRequest::default()
+        };
+        let actual = HttpRequest::from(lambda_request);
+        assert_eq!(actual.body(), &Body::Text("not base64!".into()));
+    }
+
+    #[test]
+    fn deserializes_apigw_v2_request_events() {
+        // from the docs
+        // https://docs.aws.amazon.com/apigateway/latest/developerguide/http-api-develop-integrations-lambda.html
+        let input = include_str!("../tests/data/apigw_v2_proxy_request.json");
+        let event = serde_json::from_str::<LambdaEvent<'_>>(input).expect("failed to deserialize");
+        assert_eq!(event.response_format(), ResponseFormat::ApiGatewayV2);
+        let actual = HttpRequest::from(event);
+        assert_eq!(actual.request_context().origin(), RequestOrigin::ApiGatewayV2);
+        assert_eq!(actual.method(), Method::POST);
+        assert_eq!(
+            actual.uri(),
+            "https://r3pmxmplak.execute-api.us-east-2.amazonaws.com/my/path?parameter1=value1&parameter1=value2&parameter2=value"
+        );
+        assert_eq!(
+            actual.query_string_parameters().get_all("parameter1"),
+            Some(vec!["value1", "value2"])
+        );
+        assert_eq!(actual.path_parameters().get("parameter1"), Some("value1"));
+        assert_eq!(actual.stage_variables().get("stageVariable1"), Some("value1"));
+        assert_eq!(actual.headers()["cookie"], "cookie1; cookie2");
+        assert_eq!(actual.body(), &Body::from(r#"{"hello":"lambda"}"#));
+    }
+
+    #[test]
+    fn strips_named_stages_from_apigw_v2_paths() {
+        let input = include_str!("../tests/data/apigw_v2_proxy_request_named_stage.json");
+        let event = serde_json::from_str::<LambdaEvent<'_>>(input).expect("failed to deserialize");
+        let actual = HttpRequest::from(event);
+        assert_eq!(actual.uri().path(), "/my/path");
+        assert_eq!(actual.uri().query(), Some("parameter1=value1"));
+    }
+
+    #[test]
+    fn deserializes_function_url_request_events() {
+        // from the docs
+        // https://docs.aws.amazon.com/lambda/latest/dg/urls-invocation.html#urls-payloads
+        let input = include_str!("../tests/data/lambda_function_url_request.json");
+        let event = serde_json::from_str::<LambdaEvent<'_>>(input).expect("failed to deserialize");
+        assert_eq!(event.response_format(), ResponseFormat::ApiGatewayV2);
+        let actual = HttpRequest::from(event);
+        assert_eq!(actual.request_context().origin(), RequestOrigin::FunctionUrl);
+        assert_eq!(actual.method(), Method::POST);
+        assert!(actual.path_parameters().is_empty());
+        assert_eq!(actual.body(), &Body::from("Hello from client!"));
+    }
+
+    #[test]
+    fn rejects_apigw_v2_events_with_invalid_methods() {
+        let input = include_str!("../tests/data/apigw_v2_proxy_request.json")
+            .replace(r#""method": "POST""#, r#""method": "NOT A METHOD""#);
+        let result = serde_json::from_str::<LambdaRequestV2<'_>>(&input);
+        assert!(result.is_err(), "event with an invalid method was parsed: {:?}", result);
+        let result = serde_json::from_str::<LambdaEvent<'_>>(&input);
+        assert!(result.is_err(), "event with an invalid method was parsed: {:?}", result);
+    }
+
+    #[test]
+    fn deserializes_v1_events_as_v1() {
+        for (input, origin) in &[
+            (
+                include_str!("../tests/data/apigw_proxy_request.json"),
+                RequestOrigin::ApiGatewayV1,
+            ),
+            (include_str!("../tests/data/alb_request.json"), RequestOrigin::Alb),
+        ] {
+            let event = serde_json::from_str::<LambdaEvent<'_>>(input).expect("failed to deserialize");
+            assert!(matches!(event, LambdaEvent::V1(_)));
+            assert_eq!(HttpRequest::from(event).request_context().origin(), *origin);
+        }
+    }
+
     #[test]
     fn deserializes_apigw_request_events() {
         // from the docs
         // https://docs.aws.amazon.com/lambda/latest/dg/eventsources.html#eventsources-api-gateway-request
         let input = include_str!("../tests/data/apigw_proxy_request.json");
-        let result = serde_json::from_str::<LambdaRequest<'_>>(&input);
-        assert!(result.is_ok(), format!("event was not parsed as expected {:?}", result));
+        let result = serde_json::from_str::<LambdaRequest<'_>>(input);
+        assert!(result.is_ok(), "event was not parsed as expected {:?}", result);
     }
 
     #[test]
@@ -362,8 +713,8 @@ mod tests {
         // from the docs
         // https://docs.aws.amazon.com/elasticloadbalancing/latest/application/lambda-functions.html#multi-value-headers
         let input = include_str!("../tests/data/alb_request.json");
-        let result = serde_json::from_str::<LambdaRequest<'_>>(&input);
-        assert!(result.is_ok(), format!("event was not parsed as expected {:?}", result));
+        let result = serde_json::from_str::<LambdaRequest<'_>>(input);
+        assert!(result.is_ok(), "event was not parsed as expected {:?}", result);
     }
 
     #[test]
@@ -371,11 +722,8 @@ mod tests {
         // from docs
         // https://docs.aws.amazon.com/apigateway/latest/developerguide/set-up-lambda-proxy-integrations.html#api-gateway-simple-proxy-for-lambda-input-format
         let input = include_str!("../tests/data/apigw_multi_value_proxy_request.json");
-        let result = serde_json::from_str::<LambdaRequest<'_>>(&input);
-        assert!(
-            result.is_ok(),
-            format!("event is was not parsed as expected {:?}", result)
-        );
+        let result = serde_json::from_str::<LambdaRequest<'_>>(input);
+        assert!(result.is_ok(), "event is was not parsed as expected {:?}", result);
         let apigw = result.unwrap();
         assert!(!apigw.query_string_parameters.is_empty());
         assert!(!apigw.multi_value_query_string_parameters.is_empty());
@@ -388,16 +736,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn requests_have_response_formats() {
+        let input = include_str!("../tests/data/apigw_proxy_request.json");
+        let request = serde_json::from_str::<LambdaRequest<'_>>(input).unwrap();
+        assert_eq!(request.response_format(), ResponseFormat::ApiGateway);
+        let input = include_str!("../tests/data/alb_request.json");
+        let request = serde_json::from_str::<LambdaRequest<'_>>(input).unwrap();
+        assert_eq!(request.response_format(), ResponseFormat::Alb);
+        let input = include_str!("../tests/data/alb_multi_value_request.json");
+        let request = serde_json::from_str::<LambdaRequest<'_>>(input).unwrap();
+        assert_eq!(request.response_format(), ResponseFormat::AlbMultiValue);
+    }
+
+    #[test]
+    fn requests_keep_repeated_headers() {
+        let input = include_str!("../tests/data/alb_multi_value_request.json");
+        let request = HttpRequest::from(serde_json::from_str::<LambdaRequest<'_>>(input).unwrap());
+        assert_eq!(request.headers().get_all("cookie").iter().count(), 2);
+    }
+
     #[test]
     fn deserializes_alb_multi_value_request_events() {
         // from docs
         // https://docs.aws.amazon.com/apigateway/latest/developerguide/set-up-lambda-proxy-integrations.html#api-gateway-simple-proxy-for-lambda-input-format
         let input = include_str!("../tests/data/alb_multi_value_request.json");
-        let result = serde_json::from_str::<LambdaRequest<'_>>(&input);
-        assert!(
-            result.is_ok(),
-            format!("event is was not parsed as expected {:?}", result)
-        );
+        let result = serde_json::from_str::<LambdaRequest<'_>>(input);
+        assert!(result.is_ok(), "event is was not parsed as expected {:?}", result);
         let apigw = result.unwrap();
         assert!(!apigw.query_string_parameters.is_empty());
         assert!(!apigw.multi_value_query_string_parameters.is_empty());
@@ -423,5 +788,4 @@ mod tests {
             Test { foo: HashMap::new() }
         )
     }
-
 }
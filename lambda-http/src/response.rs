@@ -1,8 +1,8 @@
 //! Response types
 
 use http::{
-    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
-    Response,
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE, SET_COOKIE},
+    Response, StatusCode,
 };
 use serde::{
     ser::{Error as SerError, SerializeMap},
@@ -10,7 +10,25 @@ use serde::{
 };
 use serde_derive::Serialize;
 
-use crate::body::Body;
+use crate::{
+    body::Body,
+    ext::{is_json, media_type},
+};
+
+/// The response fields expected by the trigger of a request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ResponseFormat {
+    /// API Gateway proxy responses, with multi value headers
+    ApiGateway,
+    /// ALB responses, with single value headers
+    Alb,
+    /// ALB responses for target groups with the `lambda.multi_value_headers.enabled`
+    /// setting turned on, with multi value headers
+    AlbMultiValue,
+    /// API Gateway HTTP API and Lambda function URL responses, with single value
+    /// headers joined by commas and cookies apart
+    ApiGatewayV2,
+}
 
 /// Representation of API Gateway response
 #[derive(Serialize, Debug)]
@@ -21,10 +39,18 @@ pub(crate) struct LambdaResponse {
     // when one is provided. only populate this for ALB responses
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status_description: Option<String>,
-    #[serde(serialize_with = "serialize_headers")]
+    // only one of headers and multi_value_headers is populated, depending on
+    // the response format, as API Gateway merges them when both are provided
+    #[serde(serialize_with = "serialize_headers", skip_serializing_if = "HeaderMap::is_empty")]
     pub headers: HeaderMap<HeaderValue>,
-    #[serde(serialize_with = "serialize_multi_value_headers")]
+    #[serde(
+        serialize_with = "serialize_multi_value_headers",
+        skip_serializing_if = "HeaderMap::is_empty"
+    )]
     pub multi_value_headers: HeaderMap<HeaderValue>,
+    // the 2.0 payload format has no multi value headers, cookies are sent apart
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cookies: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body: Option<Body>,
     // This field is optional for API Gateway but required for ALB
@@ -39,6 +65,7 @@ impl Default for LambdaResponse {
             status_description: Default::default(),
             headers: Default::default(),
             multi_value_headers: Default::default(),
+            cookies: Default::default(),
             body: Default::default(),
             is_base64_encoded: Default::default(),
         }
@@ -76,50 +103,102 @@ where
 
 /// tranformation from http type to internal type
 impl LambdaResponse {
-    pub(crate) fn from_response<T>(is_alb: bool, value: Response<T>) -> Self
+    pub(crate) fn from_response<T>(format: ResponseFormat, value: Response<T>) -> Self
     where
         T: Into<Body>,
     {
         let (parts, bod) = value.into_parts();
+        let is_text = parts
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|ct| ct.to_str().ok())
+            .map(|ct| is_text_media_type(&media_type(ct)))
+            .unwrap_or(true);
         let (is_base64_encoded, body) = match bod.into() {
             Body::Empty => (false, None),
-            b @ Body::Text(_) => (false, Some(b)),
+            b @ Body::Text(_) if is_text => (false, Some(b)),
+            // text bodies of binary media types are encoded too, so that API Gateway
+            // decodes them for the binary media types it's configured with
+            Body::Text(text) => (true, Some(Body::Binary(text.into_bytes()))),
             b @ Body::Binary(_) => (true, Some(b)),
         };
+        let (headers, multi_value_headers, cookies) = match format {
+            ResponseFormat::ApiGateway | ResponseFormat::AlbMultiValue => (HeaderMap::new(), parts.headers, Vec::new()),
+            ResponseFormat::Alb => (parts.headers, HeaderMap::new(), Vec::new()),
+            ResponseFormat::ApiGatewayV2 => {
+                let (headers, cookies) = join_headers(parts.headers);
+                (headers, HeaderMap::new(), cookies)
+            }
+        };
         Self {
             status_code: parts.status.as_u16(),
-            status_description: if is_alb {
-                Some(format!(
+            status_description: match format {
+                ResponseFormat::Alb | ResponseFormat::AlbMultiValue => Some(format!(
                     "{} {}",
                     parts.status.as_u16(),
                     parts.status.canonical_reason().unwrap_or_default()
-                ))
-            } else {
-                None
+                )),
+                _ => None,
             },
             body,
-            headers: parts.headers.clone(),
-            multi_value_headers: parts.headers,
+            headers,
+            multi_value_headers,
+            cookies,
             is_base64_encoded,
         }
     }
 }
 
+/// Join the values of repeated headers with commas, except for `Set-Cookie` headers
+/// which are returned apart
+fn join_headers(headers: HeaderMap<HeaderValue>) -> (HeaderMap<HeaderValue>, Vec<String>) {
+    let mut joined = HeaderMap::with_capacity(headers.keys_len());
+    let mut cookies = Vec::new();
+    for key in headers.keys() {
+        let values = headers.get_all(key).iter().filter_map(|value| value.to_str().ok());
+        if key == SET_COOKIE {
+            cookies.extend(values.map(String::from));
+        } else if let Ok(value) = HeaderValue::from_shared(values.collect::<Vec<_>>().join(",").into()) {
+            joined.insert(key.clone(), value);
+        }
+    }
+    (joined, cookies)
+}
+
+/// Return true if a media type is textual, so its bodies don't need to be base64 encoded
+fn is_text_media_type(media_type: &str) -> bool {
+    media_type.starts_with("text/")
+        || is_json(media_type)
+        || media_type.ends_with("+xml")
+        || matches!(
+            media_type,
+            "application/xml" | "application/javascript" | "application/x-www-form-urlencoded" | "application/graphql"
+        )
+}
+
 /// A conversion of self into a `Response`
 ///
 /// Implementations for `Response<B> where B: Into<Body>`,
-/// `B where B: Into<Body>` and `serde_json::Value` are provided
+/// `B where B: Into<Body>`, `serde_json::Value`, [Json](struct.Json.html)
+/// and `(StatusCode, R) where R: IntoResponse` are provided
 /// by default
 ///
+/// Text bodies default to a `text/plain; charset=utf-8` content type, binary bodies
+/// to `application/octet-stream` and json values to `application/json`
+///
 /// # example
 ///
 /// ```rust
-/// use lambda_http::{Body, IntoResponse, Response};
+/// use lambda_http::{http::StatusCode, Body, IntoResponse, Response};
 ///
 /// assert_eq!(
 ///   "hello".into_response().body(),
 ///   Response::new(Body::from("hello")).body()
 /// );
+/// assert_eq!(
+///   (StatusCode::CREATED, "hello").into_response().status(),
+///   StatusCode::CREATED
+/// );
 /// ```
 pub trait IntoResponse {
     /// Return a translation of `self` into a `Response<Body>`
@@ -141,28 +220,86 @@ where
     B: Into<Body>,
 {
     fn into_response(self) -> Response<Body> {
-        Response::new(self.into())
+        let body = self.into();
+        let content_type = match body {
+            Body::Empty => None,
+            Body::Text(_) => Some("text/plain; charset=utf-8"),
+            Body::Binary(_) => Some("application/octet-stream"),
+        };
+        let mut response = Response::new(body);
+        if let Some(content_type) = content_type {
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        }
+        response
     }
 }
 
 impl IntoResponse for serde_json::Value {
     fn into_response(self) -> Response<Body> {
-        Response::builder()
-            .header(CONTENT_TYPE, "application/json")
-            .body(
-                serde_json::to_string(&self)
-                    .expect("unable to serialize serde_json::Value")
-                    .into(),
-            )
-            .expect("unable to build http::Response")
+        Json(self).into_response()
+    }
+}
+
+impl<R> IntoResponse for (StatusCode, R)
+where
+    R: IntoResponse,
+{
+    fn into_response(self) -> Response<Body> {
+        let (status, body) = self;
+        let mut response = body.into_response();
+        *response.status_mut() = status;
+        response
+    }
+}
+
+/// A value serialized as an `application/json` response
+///
+/// Values that fail to serialize produce `500 Internal Server Error` responses
+///
+/// # example
+///
+/// ```rust
+/// use lambda_http::{IntoResponse, Json};
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///   name: String,
+/// }
+///
+/// let response = Json(User { name: "lambda".into() }).into_response();
+/// assert_eq!(&**response.body(), br#"{"name":"lambda"}"#);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Json<T>(pub T);
+
+impl<T> IntoResponse for Json<T>
+where
+    T: serde::Serialize,
+{
+    fn into_response(self) -> Response<Body> {
+        match serde_json::to_string(&self.0) {
+            Ok(json) => Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(json.into())
+                .expect("unable to build http::Response"),
+            Err(e) => Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .header(CONTENT_TYPE, "text/plain")
+                .body(format!("failed to serialize json response: {}", e).into())
+                .expect("unable to build http::Response"),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Body, IntoResponse, LambdaResponse};
-    use http::{header::CONTENT_TYPE, Response};
+    use super::{Body, IntoResponse, Json, LambdaResponse, ResponseFormat};
+    use http::{header::CONTENT_TYPE, Response, StatusCode};
     use serde_json::{self, json};
+    use std::collections::HashMap;
 
     #[test]
     fn json_into_response() {
@@ -189,6 +326,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn text_into_response_has_content_type() {
+        let response = String::from("text").into_response();
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/plain; charset=utf-8");
+        let response = vec![0u8, 1].into_response();
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/octet-stream");
+        assert!(().into_response().headers().is_empty());
+    }
+
+    #[test]
+    fn status_into_response() {
+        let response = (StatusCode::NOT_FOUND, json!({ "error": "not found" })).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+
+        let response = (StatusCode::CREATED, Response::new("created")).into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    #[test]
+    fn typed_json_into_response() {
+        let mut map = HashMap::new();
+        map.insert("hello", "lambda");
+        let response = Json(map).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(response.body(), &Body::from(r#"{"hello":"lambda"}"#));
+
+        // json object keys must be strings
+        let mut map = HashMap::new();
+        map.insert(vec![1], "lambda");
+        let response = Json(map).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     #[test]
     fn default_response() {
         assert_eq!(LambdaResponse::default().status_code, 200)
@@ -198,34 +370,122 @@ mod tests {
     fn serialize_default() {
         assert_eq!(
             serde_json::to_string(&LambdaResponse::default()).expect("failed to serialize response"),
-            r#"{"statusCode":200,"headers":{},"multiValueHeaders":{},"isBase64Encoded":false}"#
+            r#"{"statusCode":200,"isBase64Encoded":false}"#
         );
     }
 
     #[test]
     fn serialize_body() {
-        let mut resp = LambdaResponse::default();
-        resp.body = Some("foo".into());
+        let resp = LambdaResponse {
+            body: Some("foo".into()),
+            ..LambdaResponse::default()
+        };
         assert_eq!(
             serde_json::to_string(&resp).expect("failed to serialize response"),
-            r#"{"statusCode":200,"headers":{},"multiValueHeaders":{},"body":"foo","isBase64Encoded":false}"#
+            r#"{"statusCode":200,"body":"foo","isBase64Encoded":false}"#
+        );
+    }
+
+    #[test]
+    fn serialize_binary_bodies() {
+        let res = LambdaResponse::from_response(
+            ResponseFormat::ApiGateway,
+            Response::builder()
+                .header(CONTENT_TYPE, "application/octet-stream")
+                .body(Body::from(vec![0, 159, 146, 150]))
+                .expect("failed to create response"),
+        );
+        assert!(res.is_base64_encoded);
+        assert_eq!(
+            serde_json::to_value(&res).expect("failed to serialize response")["body"],
+            "AJ+Slg=="
+        );
+    }
+
+    #[test]
+    fn serialize_text_bodies_by_content_type() {
+        let res = LambdaResponse::from_response(
+            ResponseFormat::ApiGateway,
+            Response::builder()
+                .header(CONTENT_TYPE, "image/svg+xml; charset=utf-8")
+                .body(Body::from("<svg/>"))
+                .expect("failed to create response"),
+        );
+        assert!(!res.is_base64_encoded);
+
+        let res = LambdaResponse::from_response(
+            ResponseFormat::ApiGateway,
+            Response::builder()
+                .header(CONTENT_TYPE, "application/octet-stream")
+                .body(Body::from("raw"))
+                .expect("failed to create response"),
+        );
+        assert!(res.is_base64_encoded);
+        assert_eq!(
+            serde_json::to_value(&res).expect("failed to serialize response")["body"],
+            "cmF3"
         );
     }
 
     #[test]
     fn serialize_multi_value_headers() {
         let res = LambdaResponse::from_response(
-            false,
+            ResponseFormat::ApiGateway,
+            Response::builder()
+                .header("multi", "a")
+                .header("multi", "b")
+                .body(Body::from(()))
+                .expect("failed to create response"),
+        );
+        let json = serde_json::to_string(&res).expect("failed to serialize to json");
+        assert_eq!(
+            json,
+            r#"{"statusCode":200,"multiValueHeaders":{"multi":["a","b"]},"isBase64Encoded":false}"#
+        )
+    }
+
+    #[test]
+    fn serialize_v2_headers() {
+        let res = LambdaResponse::from_response(
+            ResponseFormat::ApiGatewayV2,
             Response::builder()
                 .header("multi", "a")
                 .header("multi", "b")
+                .header("set-cookie", "a=1")
+                .header("set-cookie", "b=2")
                 .body(Body::from(()))
                 .expect("failed to create response"),
         );
         let json = serde_json::to_string(&res).expect("failed to serialize to json");
         assert_eq!(
             json,
-            r#"{"statusCode":200,"headers":{"multi":"a"},"multiValueHeaders":{"multi":["a","b"]},"isBase64Encoded":false}"#
+            r#"{"statusCode":200,"headers":{"multi":"a,b"},"cookies":["a=1","b=2"],"isBase64Encoded":false}"#
         )
     }
+
+    #[test]
+    fn serialize_alb_headers() {
+        let response = || {
+            Response::builder()
+                .header("set-cookie", "a=1")
+                .header("set-cookie", "b=2")
+                .body(Body::from(()))
+                .expect("failed to create response")
+        };
+        let json = serde_json::to_string(&LambdaResponse::from_response(ResponseFormat::Alb, response()))
+            .expect("failed to serialize to json");
+        assert_eq!(
+            json,
+            r#"{"statusCode":200,"statusDescription":"200 OK","headers":{"set-cookie":"a=1"},"isBase64Encoded":false}"#
+        );
+        let json = serde_json::to_string(&LambdaResponse::from_response(
+            ResponseFormat::AlbMultiValue,
+            response(),
+        ))
+        .expect("failed to serialize to json");
+        assert_eq!(
+            json,
+            r#"{"statusCode":200,"statusDescription":"200 OK","multiValueHeaders":{"set-cookie":["a=1","b=2"]},"isBase64Encoded":false}"#
+        );
+    }
 }
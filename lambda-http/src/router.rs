@@ -0,0 +1,421 @@
+//! Dispatch of requests to handlers by method and path
+
+use std::{collections::HashMap, future::Future};
+
+use http::{
+    header::{HeaderValue, ALLOW},
+    Method, Response, StatusCode,
+};
+use lambda_runtime::{error::HandlerError, Context};
+use percent_encoding::percent_decode_str;
+use tokio::runtime::{Builder, Runtime as TokioRuntime};
+
+use crate::{
+    ext::{PathParameters, RouteParameters},
+    Body, Handler, IntoResponse, Request, RequestExt,
+};
+
+type BoxedHandler = Box<dyn FnMut(Request, Context) -> Result<Response<Body>, HandlerError>>;
+
+thread_local! {
+    /// The runtime futures of async handlers are blocked on
+    ///
+    /// The lambda runtime calls handlers outside of its own tokio runtime, so they
+    /// can block on this one
+    static RUNTIME: TokioRuntime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build tokio runtime");
+}
+
+/// A segment of a route's path
+enum Segment {
+    /// A segment matched as is
+    Static(String),
+    /// A `:name` segment, capturing one segment of the path
+    Param(String),
+    /// A `*name` segment, capturing the rest of the path
+    Rest(String),
+}
+
+struct Route {
+    method: Method,
+    segments: Vec<Segment>,
+    handler: BoxedHandler,
+}
+
+impl Route {
+    /// Return the parameters captured from a path, if the path matches the route
+    fn matches(&self, path: &[&str]) -> Option<Vec<(String, String)>> {
+        let mut params = Vec::new();
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Rest(name) => {
+                    params.push((name.clone(), decode(&path[i.min(path.len())..].join("/"))));
+                    return Some(params);
+                }
+                Segment::Static(value) if path.get(i) == Some(&value.as_str()) => (),
+                Segment::Param(name) => params.push((name.clone(), decode(path.get(i)?))),
+                Segment::Static(_) => return None,
+            }
+        }
+        if path.len() == self.segments.len() {
+            Some(params)
+        } else {
+            None
+        }
+    }
+}
+
+fn decode(segment: &str) -> String {
+    percent_decode_str(segment).decode_utf8_lossy().into_owned()
+}
+
+fn split(path: &str) -> Vec<&str> {
+    path.split('/').filter(|segment| !segment.is_empty()).collect()
+}
+
+/// Dispatches requests to handlers by http method and path, so that one function
+/// can serve a small API
+///
+/// Paths are made of `/` separated segments. `:name` segments capture one segment of
+/// the request's path and `*name` segments capture the rest of it. Captured segments
+/// are available with `RequestExt::path_parameters`, along with API Gateway's own path
+/// parameters. Routes are matched in the order they were added.
+///
+/// Requests that match no route get a `404 Not Found` response, and requests whose
+/// path matches routes of other methods get a `405 Method Not Allowed` response.
+///
+/// Routes added with `route_async` and the `*_async` methods take handlers returning
+/// futures. Each request's future is run to completion on a current thread tokio runtime
+/// before the response is sent, so routers with async routes must not be run from
+/// within another tokio runtime.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lambda_http::{lambda, Request, RequestExt, Router};
+/// use lambda_runtime::{Context, HandlerError};
+///
+/// fn main() {
+///     lambda!(Router::new()
+///         .get("/users/:id", get_user)
+///         .post("/users", |_, _| Ok("created"))
+///         .delete_async("/users/:id", delete_user))
+/// }
+///
+/// fn get_user(request: Request, _ctx: Context) -> Result<String, HandlerError> {
+///     Ok(format!("user {}", request.path_parameters().get("id").unwrap_or_default()))
+/// }
+///
+/// async fn delete_user(request: Request, _ctx: Context) -> Result<String, HandlerError> {
+///     Ok(format!("deleted {}", request.path_parameters().get("id").unwrap_or_default()))
+/// }
+/// ```
+#[derive(Default)]
+pub struct Router {
+    routes: Vec<Route>,
+}
+
+impl Router {
+    /// Return a router without routes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a route for requests of a method to a path
+    pub fn route<R>(self, method: Method, path: &str, handler: impl Handler<R> + 'static) -> Self
+    where
+        R: IntoResponse,
+    {
+        let mut handler = handler;
+        self.add(
+            method,
+            path,
+            Box::new(move |req, ctx| handler.run(req, ctx).map(IntoResponse::into_response)),
+        )
+    }
+
+    /// Add a route for requests of a method to a path, with a handler returning a future
+    pub fn route_async<F, Fut, R>(self, method: Method, path: &str, handler: F) -> Self
+    where
+        F: FnMut(Request, Context) -> Fut + 'static,
+        Fut: Future<Output = Result<R, HandlerError>>,
+        R: IntoResponse,
+    {
+        let mut handler = handler;
+        self.add(
+            method,
+            path,
+            Box::new(move |req, ctx| {
+                RUNTIME
+                    .with(|runtime| runtime.block_on(handler(req, ctx)))
+                    .map(IntoResponse::into_response)
+            }),
+        )
+    }
+
+    fn add(mut self, method: Method, path: &str, handler: BoxedHandler) -> Self {
+        let segments = split(path)
+            .into_iter()
+            .map(|segment| {
+                if let Some(name) = segment.strip_prefix(':') {
+                    Segment::Param(name.to_string())
+                } else if let Some(name) = segment.strip_prefix('*') {
+                    Segment::Rest(name.to_string())
+                } else {
+                    Segment::Static(segment.to_string())
+                }
+            })
+            .collect();
+        self.routes.push(Route {
+            method,
+            segments,
+            handler,
+        });
+        self
+    }
+
+    /// Add a route for `GET` requests to a path
+    pub fn get<R>(self, path: &str, handler: impl Handler<R> + 'static) -> Self
+    where
+        R: IntoResponse,
+    {
+        self.route(Method::GET, path, handler)
+    }
+
+    /// Add a route for `POST` requests to a path
+    pub fn post<R>(self, path: &str, handler: impl Handler<R> + 'static) -> Self
+    where
+        R: IntoResponse,
+    {
+        self.route(Method::POST, path, handler)
+    }
+
+    /// Add a route for `PUT` requests to a path
+    pub fn put<R>(self, path: &str, handler: impl Handler<R> + 'static) -> Self
+    where
+        R: IntoResponse,
+    {
+        self.route(Method::PUT, path, handler)
+    }
+
+    /// Add a route for `PATCH` requests to a path
+    pub fn patch<R>(self, path: &str, handler: impl Handler<R> + 'static) -> Self
+    where
+        R: IntoResponse,
+    {
+        self.route(Method::PATCH, path, handler)
+    }
+
+    /// Add a route for `DELETE` requests to a path
+    pub fn delete<R>(self, path: &str, handler: impl Handler<R> + 'static) -> Self
+    where
+        R: IntoResponse,
+    {
+        self.route(Method::DELETE, path, handler)
+    }
+
+    /// Add a route for `GET` requests to a path, with a handler returning a future
+    pub fn get_async<F, Fut, R>(self, path: &str, handler: F) -> Self
+    where
+        F: FnMut(Request, Context) -> Fut + 'static,
+        Fut: Future<Output = Result<R, HandlerError>>,
+        R: IntoResponse,
+    {
+        self.route_async(Method::GET, path, handler)
+    }
+
+    /// Add a route for `POST` requests to a path, with a handler returning a future
+    pub fn post_async<F, Fut, R>(self, path: &str, handler: F) -> Self
+    where
+        F: FnMut(Request, Context) -> Fut + 'static,
+        Fut: Future<Output = Result<R, HandlerError>>,
+        R: IntoResponse,
+    {
+        self.route_async(Method::POST, path, handler)
+    }
+
+    /// Add a route for `PUT` requests to a path, with a handler returning a future
+    pub fn put_async<F, Fut, R>(self, path: &str, handler: F) -> Self
+    where
+        F: FnMut(Request, Context) -> Fut + 'static,
+        Fut: Future<Output = Result<R, HandlerError>>,
+        R: IntoResponse,
+    {
+        self.route_async(Method::PUT, path, handler)
+    }
+
+    /// Add a route for `PATCH` requests to a path, with a handler returning a future
+    pub fn patch_async<F, Fut, R>(self, path: &str, handler: F) -> Self
+    where
+        F: FnMut(Request, Context) -> Fut + 'static,
+        Fut: Future<Output = Result<R, HandlerError>>,
+        R: IntoResponse,
+    {
+        self.route_async(Method::PATCH, path, handler)
+    }
+
+    /// Add a route for `DELETE` requests to a path, with a handler returning a future
+    pub fn delete_async<F, Fut, R>(self, path: &str, handler: F) -> Self
+    where
+        F: FnMut(Request, Context) -> Fut + 'static,
+        Fut: Future<Output = Result<R, HandlerError>>,
+        R: IntoResponse,
+    {
+        self.route_async(Method::DELETE, path, handler)
+    }
+}
+
+impl Handler<Response<Body>> for Router {
+    fn run(&mut self, event: Request, ctx: Context) -> Result<Response<Body>, HandlerError> {
+        let path = event.uri().path().to_string();
+        let path = split(&path);
+        let mut allowed = Vec::new();
+        for route in &mut self.routes {
+            let params = match route.matches(&path) {
+                Some(params) => params,
+                None => continue,
+            };
+            if route.method != event.method() {
+                if !allowed.contains(&route.method.as_str()) {
+                    allowed.push(route.method.as_str());
+                }
+                continue;
+            }
+            let mut parameters: HashMap<String, Vec<String>> = event
+                .path_parameters()
+                .iter()
+                .map(|(key, value)| (key.to_string(), vec![value.to_string()]))
+                .collect();
+            for (key, value) in &params {
+                parameters.insert(key.clone(), vec![value.clone()]);
+            }
+            let mut event = event;
+            event.extensions_mut().insert(PathParameters(parameters.into()));
+            event.extensions_mut().insert(RouteParameters(params));
+            return (route.handler)(event, ctx);
+        }
+        if allowed.is_empty() {
+            return Ok((StatusCode::NOT_FOUND, Body::Empty).into_response());
+        }
+        let mut response = (StatusCode::METHOD_NOT_ALLOWED, Body::Empty).into_response();
+        if let Ok(allow) = HeaderValue::from_shared(allowed.join(", ").into()) {
+            response.headers_mut().insert(ALLOW, allow);
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, uri: &str) -> Request {
+        http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::Empty)
+            .expect("failed to build request")
+    }
+
+    fn router() -> Router {
+        Router::new()
+            .get("/users", |_, _| Ok("users"))
+            .get("/users/:id", |req: Request, _| {
+                Ok(format!("user {}", req.path_parameters().get("id").unwrap_or_default()))
+            })
+            .delete("/users/:id", |_, _| Ok((StatusCode::NO_CONTENT, Body::Empty)))
+            .get("/files/*path", |req: Request, _| {
+                Ok(format!(
+                    "file {}",
+                    req.path_parameters().get("path").unwrap_or_default()
+                ))
+            })
+    }
+
+    fn body(response: &Response<Body>) -> &str {
+        std::str::from_utf8(response.body()).expect("invalid body")
+    }
+
+    #[test]
+    fn routes_by_path() {
+        let mut router = router();
+        let response = router.run(request(Method::GET, "/users"), Context::default()).unwrap();
+        assert_eq!(body(&response), "users");
+        let response = router
+            .run(request(Method::GET, "/users/ada%20lovelace/"), Context::default())
+            .unwrap();
+        assert_eq!(body(&response), "user ada lovelace");
+        let response = router
+            .run(request(Method::GET, "/files/a/b.txt"), Context::default())
+            .unwrap();
+        assert_eq!(body(&response), "file a/b.txt");
+    }
+
+    #[test]
+    fn routes_by_method() {
+        let mut router = router();
+        let response = router
+            .run(request(Method::DELETE, "/users/1"), Context::default())
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = router
+            .run(request(Method::PUT, "/users/1"), Context::default())
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[ALLOW], "GET, DELETE");
+    }
+
+    #[test]
+    fn allows_each_method_once() {
+        let mut router = Router::new()
+            .get("/a/:x", |_, _| Ok("x"))
+            .post("/a/:y", |_, _| Ok("y"))
+            .get("/a/*rest", |_, _| Ok("rest"));
+        let response = router.run(request(Method::PUT, "/a/1"), Context::default()).unwrap();
+        assert_eq!(response.headers()[ALLOW], "GET, POST");
+    }
+
+    #[test]
+    fn routes_to_async_handlers() {
+        let mut router = Router::new().get_async("/users/:id", |req: Request, _| async move {
+            tokio::task::yield_now().await;
+            Ok::<_, HandlerError>(format!("user {}", req.path_parameters().get("id").unwrap_or_default()))
+        });
+        let response = router
+            .run(request(Method::GET, "/users/1"), Context::default())
+            .unwrap();
+        assert_eq!(body(&response), "user 1");
+    }
+
+    #[test]
+    fn unknown_paths_are_not_found() {
+        let mut router = router();
+        let response = router
+            .run(request(Method::GET, "/users/1/posts"), Context::default())
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn keeps_api_gateway_path_parameters() {
+        let mut proxy = HashMap::new();
+        proxy.insert("proxy".to_string(), "users/1".to_string());
+        let mut router = Router::new().get("/users/:id", |req: Request, _| {
+            let params = req.path_parameters();
+            Ok(format!(
+                "{} {}",
+                params.get("proxy").unwrap_or_default(),
+                params.get("id").unwrap_or_default()
+            ))
+        });
+        let response = router
+            .run(
+                request(Method::GET, "/users/1").with_path_parameters(proxy),
+                Context::default(),
+            )
+            .unwrap();
+        assert_eq!(body(&response), "users/1 1");
+    }
+}
@@ -59,6 +59,12 @@ impl From<HashMap<String, Vec<String>>> for StrMap {
     }
 }
 
+impl From<HashMap<String, String>> for StrMap {
+    fn from(inner: HashMap<String, String>) -> Self {
+        StrMap(Arc::new(inner.into_iter().map(|(k, v)| (k, vec![v])).collect()))
+    }
+}
+
 /// A read only reference to `StrMap` key and value slice pairings
 pub struct StrMapIter<'a> {
     data: &'a StrMap,
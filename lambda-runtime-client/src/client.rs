@@ -1,23 +1,56 @@
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    env, fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
+use bytes::Bytes;
+use futures_util::stream::{Stream, StreamExt};
+use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Full, StreamBody};
 use hyper::{
-    client::HttpConnector,
+    body::{Body, Frame, Incoming},
+    client::conn::http1::{self, SendRequest},
     header::{self, HeaderMap, HeaderValue},
-    rt::{Future, Stream},
-    Body, Client, Method, Request, Uri,
+    http::{uri::Authority, Extensions},
+    Method, Request, Response, StatusCode, Uri,
+};
+use hyper_util::{
+    client::legacy::{
+        connect::{dns::GaiResolver, HttpConnector},
+        Client,
+    },
+    rt::{TokioExecutor, TokioIo},
 };
-use serde_derive::Deserialize;
-use serde_json;
-use tokio::runtime::Runtime;
+use serde_derive::{Deserialize, Serialize};
+use tokio::{net::TcpStream, time};
 
-use crate::error::{ApiError, ErrorResponse, RuntimeApiError};
+#[cfg(feature = "wire-trace")]
+use crate::wire::{self, DefaultRedactor, TracedBody, WireRedactor};
+use crate::{
+    dns::Resolver,
+    error::{ApiError, EndpointError, ErrorResponse, RuntimeApiError, XRAY_ERROR_CAUSE_MAX_SIZE},
+    observer::{ApiCall, ApiCallMetrics, ApiObserver},
+    trace::TraceContext,
+};
 
 const RUNTIME_API_VERSION: &str = "2018-06-01";
+const RUNTIME_ENDPOINT_VAR: &str = "AWS_LAMBDA_RUNTIME_API";
 const API_CONTENT_TYPE: &str = "application/json";
 const API_ERROR_CONTENT_TYPE: &str = "application/vnd.aws.lambda.error+json";
 const RUNTIME_ERROR_HEADER: &str = "Lambda-Runtime-Function-Error-Type";
+const DEFAULT_RUNTIME_ERROR_TYPE: &str = "RuntimeError";
+const RUNTIME_XRAY_ERROR_CAUSE_HEADER: &str = "Lambda-Runtime-Function-XRay-Error-Cause";
+/// Upper bound on opening the pre-warmed connection, after which calls use the pool.
+const PREWARM_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Body of the requests sent to the Runtime APIs, either buffered or streamed.
+type RequestBody = UnsyncBoxBody<Bytes, Infallible>;
 
 /// Enum of the headers returned by Lambda's `/next` API call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LambdaHeaders {
     /// The AWS request ID
     RequestId,
@@ -34,11 +67,14 @@ pub enum LambdaHeaders {
     /// when the function is invoked with AWS credentials obtained from Cognito
     /// Identity.
     CognitoIdentity,
+    /// The tenant the invocation belongs to. This field is populated when the
+    /// function uses tenant isolation mode.
+    TenantId,
 }
 
 impl LambdaHeaders {
     /// Returns the `str` representation of the header.
-    fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             LambdaHeaders::RequestId => "Lambda-Runtime-Aws-Request-Id",
             LambdaHeaders::FunctionArn => "Lambda-Runtime-Invoked-Function-Arn",
@@ -46,6 +82,7 @@ impl LambdaHeaders {
             LambdaHeaders::Deadline => "Lambda-Runtime-Deadline-Ms",
             LambdaHeaders::ClientContext => "Lambda-Runtime-Client-Context",
             LambdaHeaders::CognitoIdentity => "Lambda-Runtime-Cognito-Identity",
+            LambdaHeaders::TenantId => "Lambda-Runtime-Aws-Tenant-Id",
         }
     }
 }
@@ -56,8 +93,10 @@ impl fmt::Display for LambdaHeaders {
     }
 }
 
-/// AWS Moble SDK client properties
-#[derive(Deserialize, Clone)]
+/// AWS Moble SDK client properties. Properties missing from the client context
+/// are left empty. The installation id is redacted from the `Debug` output.
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
 pub struct ClientApplication {
     /// The mobile app installation id
     #[serde(rename = "installationId")]
@@ -76,19 +115,106 @@ pub struct ClientApplication {
     pub app_package_name: String,
 }
 
-/// Client context sent by the AWS Mobile SDK.
-#[derive(Deserialize, Clone)]
+/// Client context sent by the AWS Mobile SDK. Sections missing from the header
+/// are left empty, and the context serializes back to the same JSON format. The
+/// values of custom properties are redacted from the `Debug` output.
+///
+/// Use `ClientContext::builder()` to fabricate a context, for example in tests or
+/// local emulators:
+///
+/// ```rust
+/// use lambda_runtime_client::ClientContext;
+///
+/// let ctx = ClientContext::builder()
+///     .app_title("My App")
+///     .custom("user", "alice")
+///     .build();
+/// assert_eq!(ctx.client.app_title, "My App");
+/// assert_eq!(ctx.custom["user"], "alice");
+/// ```
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
 pub struct ClientContext {
     /// Information about the mobile application invoking the function.
     pub client: ClientApplication,
     /// Custom properties attached to the mobile event context.
     pub custom: HashMap<String, String>,
     /// Environment settings from the mobile client.
+    #[serde(alias = "env")]
     pub environment: HashMap<String, String>,
 }
 
-#[derive(Deserialize, Clone)]
-/// Cognito identity information sent with the event
+impl ClientContext {
+    /// Returns a builder for a `ClientContext` with all of its properties empty.
+    pub fn builder() -> ClientContextBuilder {
+        ClientContextBuilder::default()
+    }
+}
+
+/// Builder for `ClientContext` objects, created with `ClientContext::builder()`.
+#[derive(Debug, Default, Clone)]
+pub struct ClientContextBuilder {
+    ctx: ClientContext,
+}
+
+impl ClientContextBuilder {
+    /// Sets all of the mobile application properties.
+    pub fn client(mut self, client: ClientApplication) -> Self {
+        self.ctx.client = client;
+        self
+    }
+
+    /// Sets the mobile app installation id.
+    pub fn installation_id(mut self, installation_id: impl Into<String>) -> Self {
+        self.ctx.client.installation_id = installation_id.into();
+        self
+    }
+
+    /// Sets the app title of the mobile app.
+    pub fn app_title(mut self, app_title: impl Into<String>) -> Self {
+        self.ctx.client.app_title = app_title.into();
+        self
+    }
+
+    /// Sets the version name of the mobile app.
+    pub fn app_version_name(mut self, app_version_name: impl Into<String>) -> Self {
+        self.ctx.client.app_version_name = app_version_name.into();
+        self
+    }
+
+    /// Sets the version code of the mobile app.
+    pub fn app_version_code(mut self, app_version_code: impl Into<String>) -> Self {
+        self.ctx.client.app_version_code = app_version_code.into();
+        self
+    }
+
+    /// Sets the package name of the mobile app.
+    pub fn app_package_name(mut self, app_package_name: impl Into<String>) -> Self {
+        self.ctx.client.app_package_name = app_package_name.into();
+        self
+    }
+
+    /// Adds a custom property.
+    pub fn custom(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.ctx.custom.insert(key.into(), value.into());
+        self
+    }
+
+    /// Adds an environment setting of the mobile client.
+    pub fn environment(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.ctx.environment.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the `ClientContext`.
+    pub fn build(self) -> ClientContext {
+        self.ctx
+    }
+}
+
+#[derive(Default, Deserialize, Serialize, Clone, PartialEq)]
+/// Cognito identity information sent with the event. The identity id is redacted
+/// from the `Debug` output unless the identity is wrapped with `unredacted()`.
 pub struct CognitoIdentity {
     /// The unique identity id for the Cognito credentials invoking the function.
     pub identity_id: String,
@@ -100,7 +226,14 @@ pub struct CognitoIdentity {
 /// are populated using the [Lambda environment variables](https://docs.aws.amazon.com/lambda/latest/dg/current-supported-versions.html)
 /// and the headers returned by the poll request to the Runtime APIs.
 /// A new instance of the `Context` object is passed to each handler invocation.
-#[derive(Clone)]
+///
+/// Use `EventContext::builder()` to create a context outside of Lambda, for example in
+/// the unit tests of a handler.
+///
+/// The `Debug` output redacts the Cognito identity id, the mobile installation id, the
+/// values of custom client context properties, and the raw context headers, so the
+/// context can be logged safely. Use `unredacted()` to print every value.
+#[derive(Default, Serialize, Clone)]
 pub struct EventContext {
     /// The ARN of the Lambda function being invoked.
     pub invoked_function_arn: String,
@@ -117,74 +250,468 @@ pub struct EventContext {
     /// unless the invocation request to the Lambda APIs was made using AWS
     /// credentials issues by Amazon Cognito Identity Pools.
     pub identity: Option<CognitoIdentity>,
+    /// The raw value of the `Lambda-Runtime-Client-Context` header. It is kept even when
+    /// the header cannot be parsed into `client_context`.
+    #[serde(skip)]
+    pub raw_client_context: Option<String>,
+    /// The raw value of the `Lambda-Runtime-Cognito-Identity` header. It is kept even when
+    /// the header cannot be parsed into `identity`.
+    #[serde(skip)]
+    pub raw_identity: Option<String>,
+    /// The id of the tenant the invocation belongs to. This field is empty unless the
+    /// function is configured with tenant isolation mode.
+    pub tenant_id: Option<String>,
+    /// The name of the Lambda function, from the `AWS_LAMBDA_FUNCTION_NAME` variable.
+    pub function_name: String,
+    /// The version of the function being executed, from the `AWS_LAMBDA_FUNCTION_VERSION`
+    /// variable.
+    pub function_version: String,
+    /// The amount of memory configured for the function in MB, from the
+    /// `AWS_LAMBDA_FUNCTION_MEMORY_SIZE` variable.
+    pub memory_limit_in_mb: i32,
+    /// The name of the CloudWatch log group for the function, from the
+    /// `AWS_LAMBDA_LOG_GROUP_NAME` variable.
+    pub log_group_name: String,
+    /// The name of the CloudWatch log stream for the execution environment, from the
+    /// `AWS_LAMBDA_LOG_STREAM_NAME` variable.
+    pub log_stream_name: String,
+    /// All of the headers of the `/next` response, including headers this crate does not
+    /// parse yet. Use these to read new Runtime API headers before typed support for them
+    /// is added. The headers are not serialized.
+    #[serde(skip)]
+    pub headers: HeaderMap,
+    /// Typed map of values attached to the invocation, for example by middleware that
+    /// authenticates the caller or assigns a correlation id. Values are retrieved by type
+    /// with `extensions.get::<T>()`. The map starts empty and is not serialized.
+    #[serde(skip)]
+    pub extensions: Extensions,
+}
+
+/// Builder for `EventContext` objects, created with `EventContext::builder()`.
+///
+/// ```rust
+/// use lambda_runtime_client::EventContext;
+/// use std::time::Duration;
+///
+/// let ctx = EventContext::builder()
+///     .aws_request_id("52fdfc07-2182-154f-163f-5f0f9a621d72")
+///     .function_name("my-function")
+///     .remaining_time(Duration::from_secs(3))
+///     .build();
+/// assert_eq!(ctx.function_name, "my-function");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct EventContextBuilder {
+    ctx: EventContext,
+}
+
+impl EventContextBuilder {
+    /// Sets the ARN of the Lambda function being invoked.
+    pub fn invoked_function_arn(mut self, arn: impl Into<String>) -> Self {
+        self.ctx.invoked_function_arn = arn.into();
+        self
+    }
+
+    /// Sets the AWS request ID of the invocation.
+    pub fn aws_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.ctx.aws_request_id = request_id.into();
+        self
+    }
+
+    /// Sets the X-Ray trace ID of the invocation.
+    pub fn xray_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.ctx.xray_trace_id = trace_id.into();
+        self
+    }
+
+    /// Sets the execution deadline in milliseconds since the epoch.
+    pub fn deadline(mut self, deadline: i64) -> Self {
+        self.ctx.deadline = deadline;
+        self
+    }
+
+    /// Sets the execution deadline to the given time from now.
+    pub fn remaining_time(mut self, remaining: Duration) -> Self {
+        let deadline = SystemTime::now() + remaining;
+        self.ctx.deadline = deadline
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+        self
+    }
+
+    /// Sets the client context sent by the AWS Mobile SDK.
+    pub fn client_context(mut self, client_context: ClientContext) -> Self {
+        self.ctx.client_context = Some(client_context);
+        self
+    }
+
+    /// Sets the Cognito identity that invoked the function.
+    pub fn identity(mut self, identity: CognitoIdentity) -> Self {
+        self.ctx.identity = Some(identity);
+        self
+    }
+
+    /// Sets the name of the Lambda function.
+    pub fn function_name(mut self, name: impl Into<String>) -> Self {
+        self.ctx.function_name = name.into();
+        self
+    }
+
+    /// Sets the version of the function being executed.
+    pub fn function_version(mut self, version: impl Into<String>) -> Self {
+        self.ctx.function_version = version.into();
+        self
+    }
+
+    /// Sets the amount of memory configured for the function in MB.
+    pub fn memory_limit_in_mb(mut self, memory: i32) -> Self {
+        self.ctx.memory_limit_in_mb = memory;
+        self
+    }
+
+    /// Sets the name of the CloudWatch log group for the function.
+    pub fn log_group_name(mut self, name: impl Into<String>) -> Self {
+        self.ctx.log_group_name = name.into();
+        self
+    }
+
+    /// Sets the name of the CloudWatch log stream for the execution environment.
+    pub fn log_stream_name(mut self, name: impl Into<String>) -> Self {
+        self.ctx.log_stream_name = name.into();
+        self
+    }
+
+    /// Sets the id of the tenant the invocation belongs to.
+    pub fn tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.ctx.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Sets the raw headers of the `/next` response.
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.ctx.headers = headers;
+        self
+    }
+
+    /// Attaches a value to the extensions of the context, replacing any value of the same type.
+    pub fn extension<T>(mut self, value: T) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.ctx.extensions.insert(value);
+        self
+    }
+
+    /// Returns the `EventContext` built from the values set on this builder.
+    pub fn build(self) -> EventContext {
+        self.ctx
+    }
 }
 
+/// Function settings read once from the Lambda environment variables when the client is
+/// created. Variables that are not set, for example when running outside of Lambda, are
+/// left empty.
+#[derive(Debug, Default, Clone)]
+struct FunctionSettings {
+    function_name: String,
+    function_version: String,
+    memory_limit_in_mb: i32,
+    log_group_name: String,
+    log_stream_name: String,
+}
+
+impl FunctionSettings {
+    fn from_env() -> Self {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        FunctionSettings {
+            function_name: var("AWS_LAMBDA_FUNCTION_NAME"),
+            function_version: var("AWS_LAMBDA_FUNCTION_VERSION"),
+            memory_limit_in_mb: var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE").parse().unwrap_or_default(),
+            log_group_name: var("AWS_LAMBDA_LOG_GROUP_NAME"),
+            log_stream_name: var("AWS_LAMBDA_LOG_STREAM_NAME"),
+        }
+    }
+}
+
+impl EventContext {
+    /// Returns a builder for an `EventContext`. Fields that are not set keep their default,
+    /// empty value.
+    pub fn builder() -> EventContextBuilder {
+        EventContextBuilder::default()
+    }
+
+    /// Returns the X-Ray trace context of the invocation, parsed from `xray_trace_id`.
+    ///
+    /// # Returns
+    /// The `TraceContext`, or `None` if the trace id is empty or cannot be parsed.
+    pub fn trace_context(&self) -> Option<TraceContext> {
+        TraceContext::parse(&self.xray_trace_id).ok()
+    }
+
+    /// Returns the execution deadline for the current invocation as a `SystemTime`.
+    pub fn deadline_system_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.deadline.max(0) as u64)
+    }
+
+    /// Returns the execution deadline for the current invocation on the monotonic clock,
+    /// for use with timers such as `tokio::time::sleep_until()`. The conversion from the
+    /// wall-clock deadline sent by Lambda is made when this method is called, so later
+    /// adjustments of the system clock do not move the returned `Instant`.
+    pub fn deadline_instant(&self) -> Instant {
+        let now = Instant::now();
+        match self.deadline_system_time().duration_since(SystemTime::now()) {
+            Ok(remaining) => now + remaining,
+            Err(e) => now.checked_sub(e.duration()).unwrap_or(now),
+        }
+    }
+
+    /// Returns the time left before the execution deadline, or a zero `Duration` if the
+    /// deadline has passed.
+    pub fn remaining_time(&self) -> Duration {
+        self.deadline_system_time()
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+    }
+}
+
+/// Timeouts applied to the calls a `RuntimeClient` makes to the Runtime APIs. All timeouts
+/// are disabled by default. A call that does not complete within its timeout fails with an
+/// `ApiError` for which `is_timeout()` returns `true`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Timeouts {
+    /// Upper bound on the `/next` long poll. Lambda freezes the execution environment while
+    /// there are no events, so this should be generous; a timed out poll is recoverable and
+    /// is retried by the runtime.
+    pub next_event: Option<Duration>,
+    /// Upper bound on posting a function response to the `/response` API.
+    pub response: Option<Duration>,
+    /// Upper bound on posting an error to the `/error` and `/init/error` APIs.
+    pub error: Option<Duration>,
+}
+
+/// Slot holding the pre-warmed connection to the Runtime APIs. The connection is taken
+/// out of the slot while a request is in flight, concurrent calls use the pool instead.
+type WarmConnection = Arc<Mutex<Option<SendRequest<RequestBody>>>>;
+
 /// Used by the Runtime to communicate with the internal endpoint.
+///
+/// The client is cheap to clone and can be shared across threads and tasks: clones share
+/// the same connection pool and configuration.
+#[derive(Clone)]
 pub struct RuntimeClient {
-    _runtime: Runtime,
-    http_client: Client<HttpConnector, Body>,
-    endpoint: String,
+    http_client: Client<HttpConnector<Resolver>, RequestBody>,
+    endpoint: Arc<str>,
+    timeouts: Timeouts,
+    content_type: HeaderValue,
+    observer: Option<Arc<dyn ApiObserver>>,
+    warm_connection: Option<WarmConnection>,
+    settings: Arc<FunctionSettings>,
+    #[cfg(feature = "wire-trace")]
+    wire_redactor: Arc<dyn WireRedactor>,
 }
 
 impl RuntimeClient {
     /// Creates a new instance of the Runtime APIclient SDK. The http client has timeouts disabled and
-    /// will always send a `Connection: keep-alive` header.
-    pub fn new(endpoint: String, runtime: Option<Runtime>) -> Result<Self, ApiError> {
+    /// will always send a `Connection: keep-alive` header. Use `with_timeouts()` to bound the
+    /// individual API calls.
+    ///
+    /// The client does not own an executor: the futures returned by its methods must be
+    /// driven by a tokio runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` The address of the Runtime APIs as found in the `AWS_LAMBDA_RUNTIME_API`
+    ///   variable, for example `127.0.0.1:9001`. An `http://` scheme, a missing port (80),
+    ///   and bracketed IPv6 literals such as `[::1]:9001` are also accepted.
+    ///
+    /// # Returns
+    /// A new `RuntimeClient`, or an `EndpointError` if the endpoint is not a valid address.
+    pub fn new(endpoint: String) -> Result<Self, EndpointError> {
         debug!("Starting new HttpRuntimeClient for {}", endpoint);
-        // start a tokio core main event loop for hyper
-        let runtime = match runtime {
-            Some(r) => r,
-            None => Runtime::new()?,
-        };
-
-        let http_client = Client::builder().executor(runtime.executor()).build_http();
+        let endpoint = parse_endpoint(&endpoint)?;
+        // the connector of `build_http`, with resolver errors that have their own type
+        let mut connector = HttpConnector::new_with_resolver(Resolver(GaiResolver::new()));
+        connector.set_keepalive(Some(Duration::from_secs(90)));
+        let http_client = Client::builder(TokioExecutor::new()).build(connector);
 
         Ok(RuntimeClient {
-            _runtime: runtime,
             http_client,
-            endpoint,
+            endpoint: Arc::from(endpoint),
+            timeouts: Timeouts::default(),
+            content_type: HeaderValue::from_static(API_CONTENT_TYPE),
+            observer: None,
+            warm_connection: None,
+            settings: Arc::new(FunctionSettings::from_env()),
+            #[cfg(feature = "wire-trace")]
+            wire_redactor: Arc::new(DefaultRedactor),
         })
     }
+
+    /// Creates a new instance of the Runtime APIclient SDK for the endpoint in the
+    /// `AWS_LAMBDA_RUNTIME_API` environment variable, which Lambda sets in the execution
+    /// environment of functions and extensions.
+    ///
+    /// # Returns
+    /// A new `RuntimeClient`, or an `EndpointError` if the variable is not set or does not
+    /// contain a valid address.
+    pub fn from_env() -> Result<Self, EndpointError> {
+        RuntimeClient::from_env_var(env::var(RUNTIME_ENDPOINT_VAR))
+    }
+
+    /// Creates a client for the value of the `AWS_LAMBDA_RUNTIME_API` environment
+    /// variable, kept apart from `from_env` so that it can be tested without changing
+    /// the environment of the process.
+    fn from_env_var(var: Result<String, env::VarError>) -> Result<Self, EndpointError> {
+        match var {
+            Ok(endpoint) => RuntimeClient::new(endpoint),
+            Err(env::VarError::NotPresent) => Err(EndpointError::new(
+                "",
+                &format!(
+                    "the {} environment variable is not set, is this running in Lambda?",
+                    RUNTIME_ENDPOINT_VAR
+                ),
+            )),
+            Err(e) => Err(EndpointError::new(
+                "",
+                &format!(
+                    "could not read the {} environment variable: {}",
+                    RUNTIME_ENDPOINT_VAR, e
+                ),
+            )),
+        }
+    }
+
+    /// Sets the timeouts applied to the Runtime API calls made by this client.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeouts` The `Timeouts` configuration for the `/next`, `/response`, and `/error` calls.
+    ///
+    /// # Returns
+    /// The `RuntimeClient` configured with the given timeouts.
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Keeps a persistent connection to the Runtime APIs open so that the `/next` poll that
+    /// follows a response does not pay for connection setup. The connection is opened by
+    /// `prewarm()`, which the runtime calls during init, and re-opened after each response
+    /// or error post if the Runtime APIs closed it.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` Whether to keep a pre-warmed connection.
+    ///
+    /// # Returns
+    /// The `RuntimeClient` configured with the given option.
+    pub fn with_prewarmed_connection(mut self, enabled: bool) -> Self {
+        self.warm_connection = if enabled { Some(WarmConnection::default()) } else { None };
+        self
+    }
+
+    /// Sets an observer that is notified with the timing and status of every call this
+    /// client makes to the Runtime APIs.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` The `ApiObserver` implementation to notify.
+    ///
+    /// # Returns
+    /// The `RuntimeClient` configured with the given observer.
+    pub fn with_observer<O>(mut self, observer: O) -> Self
+    where
+        O: ApiObserver + 'static,
+    {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Sets the `WireRedactor` that decides how headers and bodies appear in the wire trace.
+    /// The `DefaultRedactor` is used otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `redactor` The `WireRedactor` implementation to use.
+    ///
+    /// # Returns
+    /// The `RuntimeClient` configured with the given redactor.
+    #[cfg(feature = "wire-trace")]
+    pub fn with_wire_redactor<R>(mut self, redactor: R) -> Self
+    where
+        R: WireRedactor + 'static,
+    {
+        self.wire_redactor = Arc::new(redactor);
+        self
+    }
+
+    /// Sets the `Content-Type` of the responses posted by this client. Responses are sent as
+    /// `application/json` by default; functions returning binary or plain text payloads to
+    /// direct invocations can use this to describe them correctly.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` The media type of the responses, for example `application/octet-stream`.
+    ///
+    /// # Returns
+    /// The `RuntimeClient` configured with the given content type, or an `ApiError` if the
+    /// content type is not a valid header value.
+    pub fn with_response_content_type(mut self, content_type: &str) -> Result<Self, ApiError> {
+        self.content_type =
+            HeaderValue::from_str(content_type).map_err(|e| ApiError::invalid_header("Content-Type", e))?;
+        Ok(self)
+    }
 }
 
 impl RuntimeClient {
     /// Polls for new events to the Runtime APIs.
-    pub fn next_event(&self) -> Result<(Vec<u8>, EventContext), ApiError> {
-        let uri = format!(
+    pub async fn next_event(&self) -> Result<(Vec<u8>, EventContext), ApiError> {
+        let uri: Uri = format!(
             "http://{}/{}/runtime/invocation/next",
             self.endpoint, RUNTIME_API_VERSION
         )
         .parse()?;
         trace!("Polling for next event");
 
-        // We wait instead of processing the future asynchronously because AWS Lambda
-        // itself enforces only one event per container at a time. No point in taking on
-        // the additional complexity.
-        let out = self.http_client.get(uri).wait();
-        match out {
-            Ok(resp) => {
-                if resp.status().is_client_error() {
+        let started = Instant::now();
+        let poll = async {
+            let req = Request::builder()
+                .uri(uri)
+                .body(Full::new(Bytes::new()).boxed_unsync())
+                .unwrap();
+            let resp = self.request(req).await?;
+            let (parts, body) = resp.into_parts();
+            let body = body.collect().await?.to_bytes();
+            #[cfg(feature = "wire-trace")]
+            wire::trace_body(&*self.wire_redactor, "<", &body);
+            Ok::<_, ApiError>((parts, body))
+        };
+        let res = self
+            .wait(poll, self.timeouts.next_event, "polling for next event")
+            .await;
+        match &res {
+            Ok((parts, body)) => self.observe(ApiCall::NextEvent, started, Some(parts.status), Some(body.len() as u64)),
+            Err(_) => self.observe(ApiCall::NextEvent, started, None, None),
+        }
+        match res {
+            Ok((parts, body)) => {
+                if parts.status.is_client_error() {
                     error!(
                         "Runtime API returned client error when polling for new events: {}",
-                        resp.status()
+                        parts.status
                     );
-                    return Err(ApiError::new(&format!(
-                        "Error {} when polling for events",
-                        resp.status()
-                    )));
+                    return Err(ApiError::status("polling for next event", parts.status));
                 }
-                if resp.status().is_server_error() {
+                if parts.status.is_server_error() {
                     error!(
                         "Runtime API returned server error when polling for new events: {}",
-                        resp.status()
+                        parts.status
                     );
-                    return Err(ApiError::new("Server error when polling for new events")
-                        .unrecoverable()
-                        .clone());
+                    return Err(ApiError::status("polling for next event", parts.status));
                 }
-                let ctx = self.get_event_context(&resp.headers())?;
-                let out = resp.into_body().concat2().wait()?;
-                let buf: Vec<u8> = out.into_bytes().to_vec();
+                let ctx = self.get_event_context(&parts.headers)?;
+                let buf: Vec<u8> = body.to_vec();
 
                 trace!(
                     "Received new event for request id {}. Event length {} bytes",
@@ -195,7 +722,7 @@ impl RuntimeClient {
             }
             Err(e) => {
                 error!("Error when fetching next event from Runtime API: {}", e);
-                Err(ApiError::from(e))
+                Err(e)
             }
         }
     }
@@ -207,25 +734,262 @@ impl RuntimeClient {
     /// # Arguments
     ///
     /// * `request_id` The request id associated with the event we are serving the response for.
-    ///                This is returned as a header from the poll (`/next`) API.
+    ///   This is returned as a header from the poll (`/next`) API.
+    /// * `output` The object be sent back to the Runtime APIs as a response. Anything that
+    ///   converts into `Bytes`, such as a `Vec<u8>`, a `String` or static data, is accepted
+    ///   without copying the payload.
+    ///
+    /// # Returns
+    /// A `Result` object containing a bool return value for the call or an `error::ApiError` instance.
+    pub fn event_response<'a>(
+        &'a self,
+        request_id: &'a str,
+        output: impl Into<Bytes>,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send + 'a {
+        // convert the output up front so that the returned future does not capture its type
+        let output = output.into();
+        async move {
+            trace!(
+                "Posting response for request {} to Runtime API. Response length {} bytes",
+                request_id,
+                output.len()
+            );
+            self.post_response(request_id, Full::new(output).boxed_unsync(), self.content_type.clone())
+                .await
+        }
+    }
+
+    /// Calls the Lambda Runtime APIs to submit a response to an event with the given
+    /// `Content-Type`, overriding the content type configured on the client for this response
+    /// only. Errors are treated the same way as in `event_response()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` The request id associated with the event we are serving the response for.
+    ///   This is returned as a header from the poll (`/next`) API.
     /// * `output` The object be sent back to the Runtime APIs as a response.
+    /// * `content_type` The media type of the response, for example `text/plain`.
+    ///
+    /// # Returns
+    /// A `Result` object containing a bool return value for the call or an `error::ApiError` instance.
+    pub fn event_response_with_content_type<'a>(
+        &'a self,
+        request_id: &'a str,
+        output: impl Into<Bytes>,
+        content_type: &str,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send + 'a {
+        let output = output.into();
+        let content_type = HeaderValue::from_str(content_type);
+        async move {
+            trace!(
+                "Posting response for request {} to Runtime API. Response length {} bytes",
+                request_id,
+                output.len()
+            );
+            let content_type = content_type.map_err(|e| ApiError::invalid_header("Content-Type", e))?;
+            self.post_response(request_id, Full::new(output).boxed_unsync(), content_type)
+                .await
+        }
+    }
+
+    /// Calls the Lambda Runtime APIs to submit a response to an event, streaming the output
+    /// with a chunked request instead of buffering it. This keeps peak memory down when a
+    /// function returns a large payload that it can produce incrementally. Errors are treated
+    /// the same way as in `event_response()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` The request id associated with the event we are serving the response for.
+    ///   This is returned as a header from the poll (`/next`) API.
+    /// * `output` A stream of the chunks of the response, sent to the Runtime APIs as they
+    ///   are produced.
+    ///
+    /// # Returns
+    /// A `Result` object containing a bool return value for the call or an `error::ApiError` instance.
+    pub async fn event_response_stream<S>(&self, request_id: &str, output: S) -> Result<(), ApiError>
+    where
+        S: Stream<Item = Bytes> + Send + 'static,
+    {
+        trace!("Streaming response for request {} to Runtime API", request_id);
+        let body = StreamBody::new(output.map(|chunk| Ok(Frame::data(chunk))));
+        self.post_response(request_id, body.boxed_unsync(), self.content_type.clone())
+            .await
+    }
+
+    /// Calls Lambda's Runtime APIs to send an error generated by the `Handler`. Because it's rust,
+    /// the error type for lambda is always `handled`.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` The request id associated with the event we are serving the error for.
+    /// * `e` An instance of `errors::HandlerError` generated by the handler function. Handler
+    ///   functions can generate a new error using the `new_error(&str)` method of the `Context`
+    ///   object.
     ///
     /// # Returns
     /// A `Result` object containing a bool return value for the call or an `error::ApiError` instance.
-    pub fn event_response(&self, request_id: &str, output: Vec<u8>) -> Result<(), ApiError> {
+    pub fn event_error<'a>(
+        &'a self,
+        request_id: &'a str,
+        e: &dyn RuntimeApiError,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send + 'a {
+        // convert the error up front so that the returned future does not borrow it
+        let response = e.to_response();
+        async move {
+            let uri: Uri = format!(
+                "http://{}/{}/runtime/invocation/{}/error",
+                self.endpoint, RUNTIME_API_VERSION, request_id
+            )
+            .parse()?;
+            trace!(
+                "Posting error to runtime API for request {}: {}",
+                request_id,
+                response.error_message
+            );
+            let req = self.get_runtime_error_request(&uri, &response);
+
+            match self
+                .send(req, ApiCall::Error, self.timeouts.error, "posting error response")
+                .await
+            {
+                Ok(resp) => {
+                    if !resp.status().is_success() {
+                        error!(
+                            "Error from Runtime API when posting error response for request {}: {}",
+                            request_id,
+                            resp.status()
+                        );
+                        return Err(ApiError::status("posting error response", resp.status()));
+                    }
+                    trace!("Posted error response for request id {}", request_id);
+                    if let Err(e) = self.prewarm().await {
+                        warn!("Could not pre-warm connection to Runtime API: {}", e);
+                    }
+                    Ok(())
+                }
+                Err(e) => {
+                    error!("Error when calling runtime API for request {}: {}", request_id, e);
+                    Err(e)
+                }
+            }
+        }
+    }
+
+    /// Calls the Runtime APIs to report a failure during the init process.
+    /// The contents of the error (`e`) parmeter are passed to the Runtime APIs
+    /// using the private `to_response()` method.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` An instance of `errors::RuntimeError`.
+    ///
+    /// # Panics
+    /// If it cannot send the init error. In this case we panic to force the runtime
+    /// to restart.
+    pub fn fail_init<'a>(&'a self, e: &dyn RuntimeApiError) -> impl Future<Output = ()> + Send + 'a {
+        // convert the error up front so that the returned future does not borrow it
+        let response = e.to_response();
+        async move {
+            let uri: Uri = format!("http://{}/{}/runtime/init/error", self.endpoint, RUNTIME_API_VERSION)
+                .parse()
+                .expect("Could not generate Runtime URI");
+            error!("Calling fail_init Runtime API: {}", response.error_message);
+            let req = self.get_runtime_error_request(&uri, &response);
+
+            match self
+                .send(req, ApiCall::InitError, self.timeouts.error, "posting init error")
+                .await
+            {
+                Ok(resp) => info!("Successfully sent error response to the runtime API: {:?}", resp),
+                Err(e) => {
+                    error!("Error while sending init failed message: {}", e);
+                    panic!("Error while sending init failed message: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Opens the pre-warmed connection to the Runtime APIs if the client was configured with
+    /// `with_prewarmed_connection(true)` and the connection is not already open. This is a
+    /// no-op otherwise. Opening the connection times out after one second, so that a stalled
+    /// connection doesn't hold up the next call, which then uses the connection pool.
+    ///
+    /// # Returns
+    /// An `ApiError` if the connection could not be established in time.
+    pub async fn prewarm(&self) -> Result<(), ApiError> {
+        let slot = match &self.warm_connection {
+            Some(slot) => slot,
+            None => return Ok(()),
+        };
+        if slot.lock().unwrap().as_ref().is_some_and(|sender| !sender.is_closed()) {
+            return Ok(());
+        }
+
+        let connect = async {
+            let stream = match self.endpoint.parse::<Authority>() {
+                Ok(authority) => {
+                    let host = authority.host().trim_start_matches('[').trim_end_matches(']');
+                    TcpStream::connect((host, authority.port_u16().unwrap_or(80))).await?
+                }
+                Err(e) => return Err(ApiError::Transport(Box::new(e))),
+            };
+            stream.set_nodelay(true)?;
+            Ok(http1::handshake(TokioIo::new(stream)).await?)
+        };
+        let (sender, conn) = match time::timeout(PREWARM_TIMEOUT, connect).await {
+            Ok(res) => res?,
+            Err(_) => {
+                return Err(ApiError::Timeout {
+                    operation: "pre-warming the connection",
+                    after: PREWARM_TIMEOUT,
+                })
+            }
+        };
+        tokio::spawn(async move {
+            if let Err(e) = conn.await {
+                debug!("Pre-warmed Runtime API connection closed: {}", e);
+            }
+        });
+
+        debug!("Pre-warmed connection to Runtime API at {}", self.endpoint);
+        *slot.lock().unwrap() = Some(sender);
+        Ok(())
+    }
+
+    /// Returns the endpoint configured for this HTTP Runtime client, as a `host:port` authority.
+    pub fn get_endpoint(&self) -> String {
+        self.endpoint.to_string()
+    }
+}
+
+impl RuntimeClient {
+    /// Posts a response body to the `/response` API for the given request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` The request id associated with the event we are serving the response for.
+    /// * `body` The body of the response, buffered or streamed.
+    /// * `content_type` The `Content-Type` header value of the response.
+    ///
+    /// # Returns
+    /// A `Result` object containing a bool return value for the call or an `error::ApiError` instance.
+    async fn post_response(
+        &self,
+        request_id: &str,
+        body: RequestBody,
+        content_type: HeaderValue,
+    ) -> Result<(), ApiError> {
         let uri: Uri = format!(
             "http://{}/{}/runtime/invocation/{}/response",
             self.endpoint, RUNTIME_API_VERSION, request_id
         )
         .parse()?;
-        trace!(
-            "Posting response for request {} to Runtime API. Response length {} bytes",
-            request_id,
-            output.len()
-        );
-        let req = self.get_runtime_post_request(&uri, output);
+        let req = self.get_runtime_post_request(&uri, body, content_type);
 
-        match self.http_client.request(req).wait() {
+        match self
+            .send(req, ApiCall::Response, self.timeouts.response, "posting response")
+            .await
+        {
             Ok(resp) => {
                 if !resp.status().is_success() {
                     error!(
@@ -233,183 +997,297 @@ impl RuntimeClient {
                         request_id,
                         resp.status()
                     );
-                    return Err(ApiError::new(&format!(
-                        "Error {} while sending response",
-                        resp.status()
-                    )));
+                    return Err(ApiError::status("posting response", resp.status()));
                 }
                 trace!("Posted response to Runtime API for request {}", request_id);
+                if let Err(e) = self.prewarm().await {
+                    warn!("Could not pre-warm connection to Runtime API: {}", e);
+                }
                 Ok(())
             }
             Err(e) => {
                 error!("Error when calling runtime API for request {}: {}", request_id, e);
-                Err(ApiError::from(e))
+                Err(e)
             }
         }
     }
 
-    /// Calls Lambda's Runtime APIs to send an error generated by the `Handler`. Because it's rust,
-    /// the error type for lambda is always `handled`.
+    /// Sends a request to the Runtime APIs, tracing it on the wire if the `wire-trace`
+    /// feature is enabled.
     ///
     /// # Arguments
     ///
-    /// * `request_id` The request id associated with the event we are serving the error for.
-    /// * `e` An instance of `errors::HandlerError` generated by the handler function. Handler
-    ///       functions can generate a new error using the `new_error(&str)` method of the `Context`
-    ///       object.
+    /// * `req` The request to send
     ///
     /// # Returns
-    /// A `Result` object containing a bool return value for the call or an `error::ApiError` instance.
-    pub fn event_error(&self, request_id: &str, e: &dyn RuntimeApiError) -> Result<(), ApiError> {
-        let uri: Uri = format!(
-            "http://{}/{}/runtime/invocation/{}/error",
-            self.endpoint, RUNTIME_API_VERSION, request_id
-        )
-        .parse()?;
-        trace!(
-            "Posting error to runtime API for request {}: {}",
-            request_id,
-            e.to_response().error_message
-        );
-        let req = self.get_runtime_error_request(&uri, &e.to_response());
+    /// The response of the Runtime APIs or an `ApiError` if the request could not be sent.
+    async fn request(&self, req: Request<RequestBody>) -> Result<Response<Incoming>, ApiError> {
+        #[cfg(feature = "wire-trace")]
+        let req = {
+            let line = format!("{} {}", req.method(), req.uri());
+            wire::trace_head(&*self.wire_redactor, ">", &line, req.headers());
+            let redactor = self.wire_redactor.clone();
+            req.map(|body| TracedBody::new(body, redactor).boxed_unsync())
+        };
 
-        match self.http_client.request(req).wait() {
-            Ok(resp) => {
-                if !resp.status().is_success() {
-                    error!(
-                        "Error from Runtime API when posting error response for request {}: {}",
-                        request_id,
-                        resp.status()
+        let resp = self.dispatch(req).await?;
+
+        #[cfg(feature = "wire-trace")]
+        {
+            let line = format!("{:?} {}", resp.version(), resp.status());
+            wire::trace_head(&*self.wire_redactor, "<", &line, resp.headers());
+        }
+        Ok(resp)
+    }
+
+    /// Sends a request to the Runtime APIs over the pre-warmed connection if it is available,
+    /// or over a pooled connection otherwise. The request is also sent over a pooled
+    /// connection if the pre-warmed one fails before the request was written to it, for
+    /// example because the Runtime APIs closed it while it was idle.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` The request to send
+    ///
+    /// # Returns
+    /// The response of the Runtime APIs or an `ApiError` if the request could not be sent.
+    async fn dispatch(&self, mut req: Request<RequestBody>) -> Result<Response<Incoming>, ApiError> {
+        let warm = self
+            .warm_connection
+            .as_ref()
+            .and_then(|slot| slot.lock().unwrap().take());
+        let mut sender = match warm {
+            Some(sender) if !sender.is_closed() => sender,
+            _ => return Ok(self.http_client.request(req).await?),
+        };
+        if sender.ready().await.is_err() {
+            return Ok(self.http_client.request(req).await?);
+        }
+
+        // the connection is bound to the endpoint: send the path only, with a host header
+        let uri = req.uri().clone();
+        if let Some(path) = uri.path_and_query().cloned() {
+            *req.uri_mut() = Uri::from(path);
+        }
+        req.headers_mut().insert(
+            header::HOST,
+            HeaderValue::from_str(&self.endpoint).map_err(|e| ApiError::invalid_header("Host", e))?,
+        );
+        let resp = match sender.try_send_request(req).await {
+            Ok(resp) => resp,
+            Err(mut e) => match e.take_message() {
+                Some(mut req) => {
+                    debug!(
+                        "Pre-warmed Runtime API connection failed, using the pool: {}",
+                        e.error()
                     );
-                    return Err(ApiError::new(&format!(
-                        "Error {} while sending response",
-                        resp.status()
-                    )));
+                    *req.uri_mut() = uri;
+                    return Ok(self.http_client.request(req).await?);
                 }
-                trace!("Posted error response for request id {}", request_id);
-                Ok(())
-            }
-            Err(e) => {
-                error!("Error when calling runtime API for request {}: {}", request_id, e);
-                Err(ApiError::from(e))
-            }
+                None => return Err(e.into_error().into()),
+            },
+        };
+        if let Some(slot) = &self.warm_connection {
+            *slot.lock().unwrap() = Some(sender);
         }
+        Ok(resp)
     }
 
-    /// Calls the Runtime APIs to report a failure during the init process.
-    /// The contents of the error (`e`) parmeter are passed to the Runtime APIs
-    /// using the private `to_response()` method.
+    /// Sends a request to the Runtime APIs within the given timeout and notifies the
+    /// observer, if any, with the outcome of the call.
     ///
     /// # Arguments
     ///
-    /// * `e` An instance of `errors::RuntimeError`.
+    /// * `req` The request to send
+    /// * `call` The Runtime API call the request is for
+    /// * `timeout` The maximum time to wait for the response, if any
+    /// * `operation` A description of the call, used in the timeout error message
     ///
-    /// # Panics
-    /// If it cannot send the init error. In this case we panic to force the runtime
-    /// to restart.
-    pub fn fail_init(&self, e: &dyn RuntimeApiError) {
-        let uri: Uri = format!("http://{}/{}/runtime/init/error", self.endpoint, RUNTIME_API_VERSION)
-            .parse()
-            .expect("Could not generate Runtime URI");
-        error!("Calling fail_init Runtime API: {}", e.to_response().error_message);
-        let req = self.get_runtime_error_request(&uri, &e.to_response());
-
-        self.http_client
-            .request(req)
-            .wait()
-            .map_err(|e| {
-                error!("Error while sending init failed message: {}", e);
-                panic!("Error while sending init failed message: {}", e);
-            })
-            .map(|resp| {
-                info!("Successfully sent error response to the runtime API: {:?}", resp);
-            })
-            .expect("Could not complete init_fail request");
+    /// # Returns
+    /// The response of the Runtime APIs or an `ApiError` if the call failed or timed out.
+    async fn send(
+        &self,
+        req: Request<RequestBody>,
+        call: ApiCall,
+        timeout: Option<Duration>,
+        operation: &'static str,
+    ) -> Result<Response<Incoming>, ApiError> {
+        let bytes = req.body().size_hint().exact();
+        let started = Instant::now();
+        let res = self.wait(self.request(req), timeout, operation).await;
+        self.observe(call, started, res.as_ref().ok().map(Response::status), bytes);
+        res
     }
 
-    /// Returns the endpoint configured for this HTTP Runtime client.
-    pub fn get_endpoint(&self) -> String {
-        self.endpoint.clone()
+    /// Notifies the observer, if any, that a Runtime API call completed.
+    fn observe(&self, call: ApiCall, started: Instant, status: Option<StatusCode>, bytes: Option<u64>) {
+        if let Some(observer) = &self.observer {
+            observer.on_api_call(&ApiCallMetrics {
+                call,
+                duration: started.elapsed(),
+                status,
+                bytes,
+            });
+        }
+    }
+
+    /// Awaits the given Runtime API call. If a `timeout` is set and elapses before the
+    /// call completes, the pending request is dropped along with its connection and a
+    /// timeout `ApiError` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `fut` The future for the Runtime API call
+    /// * `timeout` The maximum time to wait for the call, if any
+    /// * `operation` A description of the call, used in the timeout error message
+    ///
+    /// # Returns
+    /// The output of the future or an `ApiError` if it failed or timed out.
+    async fn wait<F, T>(&self, fut: F, timeout: Option<Duration>, operation: &'static str) -> Result<T, ApiError>
+    where
+        F: Future<Output = Result<T, ApiError>>,
+    {
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => return fut.await,
+        };
+
+        match time::timeout(timeout, fut).await {
+            Ok(res) => res,
+            Err(_) => {
+                error!("Runtime API call timed out after {:?} while {}", timeout, operation);
+                Err(ApiError::Timeout {
+                    operation,
+                    after: timeout,
+                })
+            }
+        }
     }
-}
 
-impl RuntimeClient {
     /// Creates a Hyper `Request` object for the given `Uri` and `Body`. Sets the
-    /// HTTP method to `POST` and the `Content-Type` header value to `application/json`.
+    /// HTTP method to `POST` and the `Content-Type` header to the given value.
     ///
     /// # Arguments
     ///
     /// * `uri` A `Uri` reference target for the request
     /// * `body` The content of the post request. This parameter must not be null
+    /// * `content_type` The `Content-Type` header value for the request
     ///
     /// # Returns
     /// A Populated Hyper `Request` object.
-    fn get_runtime_post_request(&self, uri: &Uri, body: Vec<u8>) -> Request<Body> {
+    fn get_runtime_post_request(
+        &self,
+        uri: &Uri,
+        body: RequestBody,
+        content_type: HeaderValue,
+    ) -> Request<RequestBody> {
         Request::builder()
             .method(Method::POST)
             .uri(uri.clone())
-            .header(header::CONTENT_TYPE, header::HeaderValue::from_static(API_CONTENT_TYPE))
-            .body(Body::from(body))
+            .header(header::CONTENT_TYPE, content_type)
+            .body(body)
             .unwrap()
     }
 
-    fn get_runtime_error_request(&self, uri: &Uri, e: &ErrorResponse) -> Request<Body> {
+    /// Creates a Hyper `Request` object to post the given `ErrorResponse`. The
+    /// `Lambda-Runtime-Function-Error-Type` header is populated with the `error_type` of the
+    /// response, falling back to `RuntimeError` if it is empty or not a valid header value.
+    /// The `Lambda-Runtime-Function-XRay-Error-Cause` header carries the X-Ray exception
+    /// cause for the error, truncated to fit the maximum size accepted by the Runtime API.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` A `Uri` reference target for the request
+    /// * `e` The `ErrorResponse` to be serialized as the body of the request
+    ///
+    /// # Returns
+    /// A Populated Hyper `Request` object.
+    fn get_runtime_error_request(&self, uri: &Uri, e: &ErrorResponse) -> Request<RequestBody> {
         let body = serde_json::to_vec(e).expect("Could not turn error object into response JSON");
-        Request::builder()
+        let error_type = match HeaderValue::from_str(&e.error_type) {
+            Ok(value) if !e.error_type.is_empty() => value,
+            _ => {
+                warn!(
+                    "Invalid error type {:?}, reporting {}",
+                    e.error_type, DEFAULT_RUNTIME_ERROR_TYPE
+                );
+                HeaderValue::from_static(DEFAULT_RUNTIME_ERROR_TYPE)
+            }
+        };
+        let mut builder = Request::builder()
             .method(Method::POST)
             .uri(uri.clone())
             .header(
                 header::CONTENT_TYPE,
                 header::HeaderValue::from_static(API_ERROR_CONTENT_TYPE),
             )
-            .header(RUNTIME_ERROR_HEADER, HeaderValue::from_static("RuntimeError")) // TODO: We should add this code to the error object.
-            .body(Body::from(body))
-            .unwrap()
+            .header(RUNTIME_ERROR_HEADER, error_type);
+        match e
+            .to_xray_cause(XRAY_ERROR_CAUSE_MAX_SIZE)
+            .map(|cause| HeaderValue::from_bytes(cause.as_bytes()))
+        {
+            Some(Ok(cause)) => builder = builder.header(RUNTIME_XRAY_ERROR_CAUSE_HEADER, cause),
+            Some(Err(_)) => warn!("Could not encode X-Ray error cause as a header value"),
+            None => warn!("X-Ray error cause exceeds the maximum size, omitting it"),
+        }
+        builder.body(Full::new(Bytes::from(body)).boxed_unsync()).unwrap()
     }
 
-    /// Creates an `EventContext` object based on the response returned by the Runtime
-    /// API `/next` endpoint.
+    /// Creates an `EventContext` object based on the headers of the response returned by the
+    /// Runtime API `/next` endpoint. `next_event()` calls this method for you; it is public for
+    /// runtimes that poll the Runtime APIs with their own HTTP client.
     ///
     /// # Arguments
     ///
-    /// * `resp` The response returned by the Runtime APIs endpoint.
+    /// * `headers` The headers of the response returned by the Runtime APIs endpoint.
     ///
     /// # Returns
     /// A `Result` containing the populated `EventContext` or an `ApiError` if the required headers
-    /// were not present or the client context and cognito identity could not be parsed from the
-    /// JSON string.
-    fn get_event_context(&self, headers: &HeaderMap<HeaderValue>) -> Result<EventContext, ApiError> {
-        // let headers = resp.headers();
-
+    /// were not present. A client context or cognito identity that cannot be parsed is logged and
+    /// left empty, its raw value is still available in `raw_client_context` and `raw_identity`.
+    pub fn get_event_context(&self, headers: &HeaderMap<HeaderValue>) -> Result<EventContext, ApiError> {
         let aws_request_id = match headers.get(LambdaHeaders::RequestId.as_str()) {
-            Some(value) => value.to_str()?.to_owned(),
+            Some(value) => value
+                .to_str()
+                .map_err(|e| ApiError::invalid_header(LambdaHeaders::RequestId.as_str(), e))?
+                .to_owned(),
             None => {
                 error!("Response headers do not contain request id header");
-                return Err(ApiError::new(&format!("Missing {} header", LambdaHeaders::RequestId)));
+                return Err(ApiError::missing_header(LambdaHeaders::RequestId.as_str()));
             }
         };
 
         let invoked_function_arn = match headers.get(LambdaHeaders::FunctionArn.as_str()) {
-            Some(value) => value.to_str()?.to_owned(),
+            Some(value) => value
+                .to_str()
+                .map_err(|e| ApiError::invalid_header(LambdaHeaders::FunctionArn.as_str(), e))?
+                .to_owned(),
             None => {
                 error!("Response headers do not contain function arn header");
-                return Err(ApiError::new(&format!("Missing {} header", LambdaHeaders::FunctionArn)));
+                return Err(ApiError::missing_header(LambdaHeaders::FunctionArn.as_str()));
             }
         };
 
         let xray_trace_id = match headers.get(LambdaHeaders::TraceId.as_str()) {
-            Some(value) => value.to_str()?.to_owned(),
+            Some(value) => value
+                .to_str()
+                .map_err(|e| ApiError::invalid_header(LambdaHeaders::TraceId.as_str(), e))?
+                .to_owned(),
             None => {
                 error!("Response headers do not contain trace id header");
-                return Err(ApiError::new(&format!("Missing {} header", LambdaHeaders::TraceId)));
+                return Err(ApiError::missing_header(LambdaHeaders::TraceId.as_str()));
             }
         };
 
         let deadline = match headers.get(LambdaHeaders::Deadline.as_str()) {
-            Some(value) => value.to_str()?.parse()?,
+            Some(value) => value
+                .to_str()
+                .map_err(|e| ApiError::invalid_header(LambdaHeaders::Deadline.as_str(), e))?
+                .parse()
+                .map_err(|e| ApiError::invalid_header(LambdaHeaders::Deadline.as_str(), e))?,
             None => {
                 error!("Response headers do not contain deadline header");
-                return Err(ApiError::new(&format!("Missing {} header", LambdaHeaders::Deadline)));
+                return Err(ApiError::missing_header(LambdaHeaders::Deadline.as_str()));
             }
         };
 
@@ -420,22 +1298,546 @@ impl RuntimeClient {
             deadline,
             client_context: Option::default(),
             identity: Option::default(),
+            raw_client_context: Option::default(),
+            raw_identity: Option::default(),
+            tenant_id: optional_header(headers, LambdaHeaders::TenantId),
+            function_name: self.settings.function_name.clone(),
+            function_version: self.settings.function_version.clone(),
+            memory_limit_in_mb: self.settings.memory_limit_in_mb,
+            log_group_name: self.settings.log_group_name.clone(),
+            log_stream_name: self.settings.log_stream_name.clone(),
+            headers: headers.clone(),
+            extensions: Extensions::new(),
         };
 
-        if let Some(ctx_json) = headers.get(LambdaHeaders::ClientContext.as_str()) {
-            let ctx_json = ctx_json.to_str()?;
+        // the client context and identity are best-effort: a malformed header should not
+        // prevent the event from reaching the handler
+        ctx.raw_client_context = optional_header(headers, LambdaHeaders::ClientContext);
+        if let Some(ctx_json) = &ctx.raw_client_context {
             trace!("Found Client Context in response headers: {}", ctx_json);
-            let ctx_value: ClientContext = serde_json::from_str(&ctx_json)?;
-            ctx.client_context = Option::from(ctx_value);
+            match serde_json::from_str::<ClientContext>(ctx_json) {
+                Ok(ctx_value) => ctx.client_context = Option::from(ctx_value),
+                Err(e) => warn!("Could not parse Client Context for {}: {}", ctx.aws_request_id, e),
+            }
         };
 
-        if let Some(cognito_json) = headers.get(LambdaHeaders::CognitoIdentity.as_str()) {
-            let cognito_json = cognito_json.to_str()?;
+        ctx.raw_identity = optional_header(headers, LambdaHeaders::CognitoIdentity);
+        if let Some(cognito_json) = &ctx.raw_identity {
             trace!("Found Cognito Identity in response headers: {}", cognito_json);
-            let identity_value: CognitoIdentity = serde_json::from_str(&cognito_json)?;
-            ctx.identity = Option::from(identity_value);
+            match serde_json::from_str::<CognitoIdentity>(cognito_json) {
+                Ok(identity_value) => ctx.identity = Option::from(identity_value),
+                Err(e) => warn!("Could not parse Cognito Identity for {}: {}", ctx.aws_request_id, e),
+            }
         };
 
         Ok(ctx)
     }
 }
+
+/// Returns the value of an optional header as a string. Values that are not valid
+/// UTF-8 are logged and ignored.
+fn optional_header(headers: &HeaderMap<HeaderValue>, header: LambdaHeaders) -> Option<String> {
+    let value = headers.get(header.as_str())?;
+    match value.to_str() {
+        Ok(value) => Some(value.to_owned()),
+        Err(e) => {
+            warn!("Ignoring {} header: {}", header, e);
+            None
+        }
+    }
+}
+
+/// Validates the Runtime API endpoint and normalizes it to the `host[:port]` authority
+/// used to build the request URIs.
+fn parse_endpoint(endpoint: &str) -> Result<String, EndpointError> {
+    let endpoint = endpoint.trim();
+    if endpoint.is_empty() {
+        return Err(EndpointError::new(endpoint, "the endpoint is empty"));
+    }
+    let uri = if endpoint.contains("://") {
+        endpoint.parse::<Uri>()
+    } else {
+        format!("http://{}", endpoint).parse::<Uri>()
+    }
+    .map_err(|e| EndpointError::new(endpoint, &e.to_string()))?;
+
+    if uri.scheme_str() != Some("http") {
+        return Err(EndpointError::new(endpoint, "only the http scheme is supported"));
+    }
+    let authority = match uri.authority() {
+        Some(authority) if !authority.host().is_empty() => authority,
+        _ => return Err(EndpointError::new(endpoint, "the endpoint has no host")),
+    };
+    if authority.as_str().contains('@') {
+        return Err(EndpointError::new(endpoint, "the endpoint cannot contain credentials"));
+    }
+    let port = &authority.as_str()[authority.host().len()..];
+    if !port.is_empty() && port[1..].parse::<u16>().is_err() {
+        return Err(EndpointError::new(endpoint, "the endpoint has an invalid port"));
+    }
+    if !matches!(uri.path(), "" | "/") || uri.query().is_some() {
+        return Err(EndpointError::new(
+            endpoint,
+            "the endpoint cannot contain a path or query",
+        ));
+    }
+
+    Ok(authority.as_str().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    #[tokio::test]
+    async fn response_post_times_out() {
+        // accept connections but never answer them
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind listener");
+        let endpoint = listener
+            .local_addr()
+            .expect("Could not read listener address")
+            .to_string();
+        thread::spawn(move || {
+            let _conns: Vec<_> = listener.incoming().collect();
+        });
+
+        let client = RuntimeClient::new(endpoint)
+            .expect("Could not initialize client")
+            .with_timeouts(Timeouts {
+                response: Some(Duration::from_millis(100)),
+                ..Timeouts::default()
+            });
+        let err = client
+            .event_response("123", Bytes::from_static(b"{}"))
+            .await
+            .expect_err("Response post should time out");
+        assert!(err.is_timeout(), "Unexpected error: {}", err);
+        assert!(err.is_recoverable());
+    }
+
+    #[tokio::test]
+    async fn refused_connection_is_classified() {
+        // bind and release a port so that nothing is listening on it
+        let endpoint = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("Could not find a free port")
+            .to_string();
+
+        let client = RuntimeClient::new(endpoint).expect("Could not initialize client");
+        let err = match client.next_event().await {
+            Ok(_) => panic!("Poll should fail without a Runtime API"),
+            Err(e) => e,
+        };
+        assert!(
+            matches!(err, ApiError::ConnectionRefused(_)),
+            "Unexpected error: {}",
+            err
+        );
+        assert!(err.is_recoverable());
+    }
+
+    #[tokio::test]
+    async fn observer_is_notified_of_calls() {
+        struct Recorder(Arc<std::sync::Mutex<Vec<ApiCallMetrics>>>);
+
+        impl ApiObserver for Recorder {
+            fn on_api_call(&self, metrics: &ApiCallMetrics) {
+                self.0.lock().unwrap().push(metrics.clone());
+            }
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind listener");
+        let endpoint = listener
+            .local_addr()
+            .expect("Could not read listener address")
+            .to_string();
+        thread::spawn(move || {
+            let _conns: Vec<_> = listener.incoming().collect();
+        });
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let client = RuntimeClient::new(endpoint)
+            .expect("Could not initialize client")
+            .with_timeouts(Timeouts {
+                response: Some(Duration::from_millis(100)),
+                ..Timeouts::default()
+            })
+            .with_observer(Recorder(calls.clone()));
+        client
+            .event_response("123", Bytes::from_static(b"{}"))
+            .await
+            .expect_err("Response post should time out");
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].call, ApiCall::Response);
+        assert_eq!(calls[0].status, None);
+        assert_eq!(calls[0].bytes, Some(2));
+        assert!(calls[0].duration >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn prewarmed_connection_is_reused() {
+        // answers every request on a connection with a 202, counting accepted connections
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind listener");
+        let endpoint = listener
+            .local_addr()
+            .expect("Could not read listener address")
+            .to_string();
+        let accepted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = accepted.clone();
+        thread::spawn(move || {
+            for conn in listener.incoming() {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let mut conn = conn.expect("Could not accept connection");
+                thread::spawn(move || {
+                    let mut req = Vec::new();
+                    let mut buf = [0; 1024];
+                    loop {
+                        match conn.read(&mut buf) {
+                            Ok(0) | Err(_) => return,
+                            Ok(read) => req.extend_from_slice(&buf[..read]),
+                        }
+                        // every request sent in this test has a `{}` body
+                        while let Some(end) = req.windows(6).position(|w| w == b"\r\n\r\n{}") {
+                            req.drain(..end + 6);
+                            conn.write_all(b"HTTP/1.1 202 Accepted\r\ncontent-length: 0\r\n\r\n")
+                                .expect("Could not write response");
+                        }
+                    }
+                });
+            }
+        });
+
+        let client = RuntimeClient::new(endpoint)
+            .expect("Could not initialize client")
+            .with_prewarmed_connection(true);
+        client.prewarm().await.expect("Could not pre-warm connection");
+        // the server thread counts the connection asynchronously
+        for _ in 0..100 {
+            if accepted.load(std::sync::atomic::Ordering::SeqCst) > 0 {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(accepted.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        for _ in 0..3 {
+            client
+                .event_response("123", Bytes::from_static(b"{}"))
+                .await
+                .expect("Could not post response");
+        }
+        assert_eq!(accepted.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn dropped_prewarmed_connection_falls_back_to_pool() {
+        // closes the first connection without answering, answers a 202 on the others
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind listener");
+        let endpoint = listener
+            .local_addr()
+            .expect("Could not read listener address")
+            .to_string();
+        let (closed_tx, closed_rx) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            let mut incoming = listener.incoming();
+            drop(incoming.next());
+            closed_tx.send(()).expect("Could not signal closed connection");
+            for conn in incoming {
+                let mut conn = conn.expect("Could not accept connection");
+                thread::spawn(move || {
+                    let mut req = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !req.ends_with(b"\r\n\r\n{}") {
+                        match conn.read(&mut buf) {
+                            Ok(0) | Err(_) => return,
+                            Ok(read) => req.extend_from_slice(&buf[..read]),
+                        }
+                    }
+                    conn.write_all(b"HTTP/1.1 202 Accepted\r\ncontent-length: 0\r\n\r\n")
+                        .expect("Could not write response");
+                });
+            }
+        });
+
+        let client = RuntimeClient::new(endpoint)
+            .expect("Could not initialize client")
+            .with_prewarmed_connection(true);
+        client.prewarm().await.expect("Could not pre-warm connection");
+        // block without yielding, so that the client only finds out about the closed
+        // connection once the request is handed to it
+        closed_rx.recv().expect("Server thread stopped");
+
+        client
+            .event_response("123", Bytes::from_static(b"{}"))
+            .await
+            .expect("Could not post response");
+    }
+
+    #[test]
+    fn client_from_env() {
+        let err = RuntimeClient::from_env_var(Err(env::VarError::NotPresent))
+            .err()
+            .expect("Client should not be created without an endpoint");
+        assert!(
+            err.to_string().contains(RUNTIME_ENDPOINT_VAR),
+            "Unexpected error: {}",
+            err
+        );
+
+        let client =
+            RuntimeClient::from_env_var(Ok(String::from("127.0.0.1:9001"))).expect("Could not initialize client");
+        assert_eq!(client.get_endpoint(), "127.0.0.1:9001");
+    }
+
+    #[test]
+    fn endpoint_is_validated() {
+        assert_eq!(parse_endpoint("127.0.0.1:9001").unwrap(), "127.0.0.1:9001");
+        assert_eq!(parse_endpoint("http://localhost:9001/").unwrap(), "localhost:9001");
+        assert_eq!(parse_endpoint(" [::1]:9001 ").unwrap(), "[::1]:9001");
+        assert_eq!(parse_endpoint("localhost").unwrap(), "localhost");
+
+        for endpoint in &[
+            "",
+            "::1:9001",
+            "localhost:port",
+            "https://localhost:9001",
+            "user@localhost:9001",
+            "localhost:9001/runtime",
+        ] {
+            assert!(parse_endpoint(endpoint).is_err(), "Accepted {:?}", endpoint);
+        }
+    }
+
+    #[test]
+    fn event_context_from_headers() {
+        let mut client = RuntimeClient::new(String::from("localhost:8080")).expect("Could not initialize client");
+        client.settings = Arc::new(FunctionSettings {
+            function_name: String::from("test"),
+            memory_limit_in_mb: 128,
+            ..FunctionSettings::default()
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert(LambdaHeaders::RequestId.as_str(), HeaderValue::from_static("my-id"));
+        headers.insert(
+            LambdaHeaders::FunctionArn.as_str(),
+            HeaderValue::from_static("arn:aws:lambda:us-east-1:123456789012:function:test"),
+        );
+        headers.insert(
+            LambdaHeaders::TraceId.as_str(),
+            HeaderValue::from_static("Root=1-5bef4de7"),
+        );
+        headers.insert(
+            LambdaHeaders::Deadline.as_str(),
+            HeaderValue::from_static("1542409706888"),
+        );
+
+        let ctx = client.get_event_context(&headers).expect("Could not parse context");
+        assert_eq!(ctx.aws_request_id, "my-id");
+        assert_eq!(ctx.deadline, 1_542_409_706_888);
+        assert_eq!(ctx.function_name, "test");
+        assert_eq!(ctx.memory_limit_in_mb, 128);
+        let trace = ctx.trace_context().expect("Could not parse trace context");
+        assert_eq!(trace.trace_id(), "1-5bef4de7");
+        assert!(ctx.client_context.is_none());
+        assert!(ctx.identity.is_none());
+        assert!(ctx.tenant_id.is_none());
+        assert_eq!(ctx.headers.len(), 4);
+
+        headers.insert(
+            LambdaHeaders::ClientContext.as_str(),
+            HeaderValue::from_static("{\"client\": not json"),
+        );
+        headers.insert(LambdaHeaders::TenantId.as_str(), HeaderValue::from_static("tenant-1"));
+        headers.insert(
+            LambdaHeaders::CognitoIdentity.as_str(),
+            HeaderValue::from_static("{\"identity_id\":\"id\",\"identity_pool_id\":\"pool\"}"),
+        );
+        let ctx = client
+            .get_event_context(&headers)
+            .expect("Malformed client context should not fail the event");
+        assert!(ctx.client_context.is_none());
+        assert_eq!(ctx.raw_client_context.as_deref(), Some("{\"client\": not json"));
+        assert_eq!(ctx.tenant_id.as_deref(), Some("tenant-1"));
+        assert_eq!(ctx.identity.expect("Missing identity").identity_pool_id, "pool");
+        assert_eq!(
+            ctx.headers.get(LambdaHeaders::CognitoIdentity.as_str()),
+            headers.get(LambdaHeaders::CognitoIdentity.as_str())
+        );
+
+        headers.remove(LambdaHeaders::TraceId.as_str());
+        match client.get_event_context(&headers) {
+            Err(ApiError::InvalidHeader { name, source: None }) => assert_eq!(name, LambdaHeaders::TraceId.as_str()),
+            _ => panic!("Missing trace id header should be reported"),
+        }
+    }
+
+    #[test]
+    fn client_context_round_trip() {
+        let partial: ClientContext =
+            serde_json::from_str(r#"{"client":{"appTitle":"My App"},"env":{"locale":"en_US"}}"#)
+                .expect("Could not parse partial client context");
+        assert_eq!(partial.client.app_title, "My App");
+        assert_eq!(partial.client.installation_id, "");
+        assert!(partial.custom.is_empty());
+        assert_eq!(partial.environment["locale"], "en_US");
+
+        let ctx = ClientContext::builder()
+            .installation_id("install")
+            .app_package_name("com.example.app")
+            .custom("user", "alice")
+            .environment("platform", "Android")
+            .build();
+        let json = serde_json::to_string(&ctx).expect("Could not serialize client context");
+        let parsed: ClientContext = serde_json::from_str(&json).expect("Could not parse client context");
+        assert_eq!(parsed, ctx);
+    }
+
+    #[test]
+    fn deadline_helpers() {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let mut ctx = EventContext::builder()
+            .deadline((now + Duration::from_secs(10)).as_millis() as i64)
+            .build();
+        let remaining = ctx.remaining_time();
+        assert!(remaining > Duration::from_secs(9) && remaining <= Duration::from_secs(10));
+        let until_deadline = ctx.deadline_instant() - Instant::now();
+        assert!(until_deadline > Duration::from_secs(9) && until_deadline <= Duration::from_secs(10));
+
+        ctx.deadline = (now - Duration::from_secs(1)).as_millis() as i64;
+        assert_eq!(ctx.remaining_time(), Duration::from_secs(0));
+        assert!(ctx.deadline_instant() <= Instant::now());
+    }
+
+    #[test]
+    fn event_context_builder() {
+        let ctx = EventContext::builder()
+            .aws_request_id("my-id")
+            .identity(CognitoIdentity {
+                identity_id: String::from("identity"),
+                identity_pool_id: String::from("pool"),
+            })
+            .memory_limit_in_mb(128)
+            .remaining_time(Duration::from_secs(5))
+            .extension(42u32)
+            .build();
+        assert_eq!(ctx.aws_request_id, "my-id");
+        assert_eq!(ctx.extensions.get::<u32>(), Some(&42));
+        assert!(ctx.extensions.get::<String>().is_none());
+        assert!(ctx.remaining_time() > Duration::from_secs(4));
+
+        let json = serde_json::to_value(&ctx).expect("Could not serialize context");
+        assert_eq!(json["aws_request_id"], "my-id");
+        assert_eq!(json["identity"]["identity_pool_id"], "pool");
+        assert_eq!(json["memory_limit_in_mb"], 128);
+        assert!(json["client_context"].is_null());
+        assert!(json.get("extensions").is_none());
+    }
+
+    #[test]
+    fn error_type_header_from_response() {
+        let client = RuntimeClient::new(String::from("localhost:8080")).expect("Could not initialize client");
+        let uri: Uri = "http://localhost:8080/error".parse().expect("Could not parse uri");
+
+        let err = ErrorResponse::handled(String::from("invalid input")).with_error_type("MyApp.ValidationError");
+        let req = client.get_runtime_error_request(&uri, &err);
+        assert_eq!(req.headers()[RUNTIME_ERROR_HEADER], "MyApp.ValidationError");
+
+        let err = ErrorResponse::handled(String::from("invalid input")).with_error_type("");
+        let req = client.get_runtime_error_request(&uri, &err);
+        assert_eq!(req.headers()[RUNTIME_ERROR_HEADER], DEFAULT_RUNTIME_ERROR_TYPE);
+        assert!(req.headers().contains_key(RUNTIME_XRAY_ERROR_CAUSE_HEADER));
+    }
+
+    #[test]
+    fn response_content_type_is_configurable() {
+        let uri: Uri = "http://localhost:8080/response".parse().expect("Could not parse uri");
+        let client = RuntimeClient::new(String::from("localhost:8080")).expect("Could not initialize client");
+        assert_eq!(client.content_type, API_CONTENT_TYPE);
+
+        let client = client
+            .with_response_content_type("application/octet-stream")
+            .expect("Could not set content type");
+        let req = client.get_runtime_post_request(
+            &uri,
+            Full::new(Bytes::new()).boxed_unsync(),
+            client.content_type.clone(),
+        );
+        assert_eq!(req.headers()[header::CONTENT_TYPE], "application/octet-stream");
+
+        assert!(client.with_response_content_type("text/plain\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn response_is_streamed_in_chunks() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind listener");
+        let endpoint = listener
+            .local_addr()
+            .expect("Could not read listener address")
+            .to_string();
+        let server = thread::spawn(move || {
+            let (mut conn, _) = listener.accept().expect("Could not accept connection");
+            let mut req = Vec::new();
+            let mut buf = [0; 1024];
+            while !req.ends_with(b"0\r\n\r\n") {
+                let read = conn.read(&mut buf).expect("Could not read request");
+                assert!(read > 0, "Connection closed before the end of the body");
+                req.extend_from_slice(&buf[..read]);
+            }
+            conn.write_all(b"HTTP/1.1 202 Accepted\r\ncontent-length: 0\r\n\r\n")
+                .expect("Could not write response");
+            String::from_utf8(req).expect("Request is not valid UTF-8")
+        });
+
+        let client = RuntimeClient::new(endpoint).expect("Could not initialize client");
+        let chunks = vec![Bytes::from_static(b"{\"hello\":"), Bytes::from_static(b"\"world\"}")];
+        client
+            .event_response_stream("123", futures_util::stream::iter(chunks))
+            .await
+            .expect("Could not stream response");
+
+        let req = server.join().expect("Server thread panicked");
+        assert!(req.starts_with("POST /2018-06-01/runtime/invocation/123/response"));
+        assert!(req.to_lowercase().contains("transfer-encoding: chunked"));
+        assert!(req.contains("{\"hello\":"));
+        assert!(req.contains("\"world\"}"));
+    }
+
+    #[tokio::test]
+    async fn client_is_shareable_across_tasks() {
+        fn assert_clone_send_sync<T: Clone + Send + Sync>() {}
+        assert_clone_send_sync::<RuntimeClient>();
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind listener");
+        let endpoint = listener
+            .local_addr()
+            .expect("Could not read listener address")
+            .to_string();
+        thread::spawn(move || {
+            let _conns: Vec<_> = listener.incoming().collect();
+        });
+
+        let client = RuntimeClient::new(endpoint)
+            .expect("Could not initialize client")
+            .with_timeouts(Timeouts {
+                error: Some(Duration::from_millis(100)),
+                ..Timeouts::default()
+            });
+        let shared = client.clone();
+        let err = tokio::spawn(async move {
+            shared
+                .event_error("123", &ApiError::status("testing", hyper::StatusCode::BAD_REQUEST))
+                .await
+        })
+        .await
+        .expect("Background task panicked")
+        .expect_err("Error post should time out");
+        assert!(err.is_timeout(), "Unexpected error: {}", err);
+    }
+}
@@ -0,0 +1,59 @@
+//! Resolution of the Runtime API host, with errors that can be told apart from the other
+//! errors of the http connector.
+use std::{
+    error::Error,
+    fmt,
+    task::{Context, Poll},
+};
+
+use futures_util::future::{MapErr, TryFutureExt};
+use hyper_util::client::legacy::connect::dns::{GaiResolver, Name};
+use tower_service::Service;
+
+/// An error resolving the host of the Runtime APIs.
+#[derive(Debug)]
+pub(crate) struct DnsError(Box<dyn Error + Send + Sync>);
+
+impl DnsError {
+    pub(crate) fn new<E>(cause: E) -> DnsError
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        DnsError(cause.into())
+    }
+}
+
+impl fmt::Display for DnsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to resolve host: {}", self.0)
+    }
+}
+
+impl Error for DnsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.0.as_ref())
+    }
+}
+
+/// A resolver whose errors are `DnsError`s, so that `ApiError::transport` can classify
+/// them by type rather than by the message of the connector's error.
+#[derive(Debug, Clone)]
+pub(crate) struct Resolver<R = GaiResolver>(pub(crate) R);
+
+impl<R> Service<Name> for Resolver<R>
+where
+    R: Service<Name>,
+    R::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    type Response = R::Response;
+    type Error = DnsError;
+    type Future = MapErr<R::Future, fn(R::Error) -> DnsError>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), DnsError>> {
+        self.0.poll_ready(cx).map_err(DnsError::new)
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        self.0.call(name).map_err(DnsError::new as fn(R::Error) -> DnsError)
+    }
+}
@@ -1,13 +1,19 @@
 //! This module defines the `RuntimeApiError` trait that developers should implement
 //! to send their custom errors to the AWS Lambda Runtime Client SDK. The module also
 //! defines the `ApiError` type returned by the `RuntimeClient` implementations.
-use std::{env, error::Error, fmt, io, num::ParseIntError, option::Option};
+use std::{env, error::Error, fmt, io, option::Option, time::Duration};
 
-use backtrace;
-use http::{header::ToStrError, uri::InvalidUri};
+use http::{uri::InvalidUri, StatusCode};
 use hyper;
+use hyper_util::client::legacy;
+use serde::ser::{self, SerializeMap};
 use serde_derive::Serialize;
-use serde_json;
+use serde_json::{self, Map, Value};
+
+use crate::dns::DnsError;
+
+#[cfg(feature = "derive")]
+pub use lambda_runtime_errors_derive::LambdaError;
 
 /// Error type description for the `ErrorResponse` event. This type should be returned
 /// for errors that were handled by the function code or framework.
@@ -15,25 +21,197 @@ pub const ERROR_TYPE_HANDLED: &str = "Handled";
 /// Error type description for the `ErrorResponse` event. This type is used for unhandled,
 /// unexpcted errors.
 pub const ERROR_TYPE_UNHANDLED: &str = "Unhandled";
+/// Maximum size in bytes of the X-Ray error cause sent along with error responses.
+/// Causes larger than this are truncated by dropping stack frames.
+pub(crate) const XRAY_ERROR_CAUSE_MAX_SIZE: usize = 1024 * 1024;
+/// Names of the `ErrorResponse` fields, which cannot be overridden by details.
+const RESERVED_FIELDS: [&str; 4] = ["errorMessage", "errorType", "stackTrace", "causes"];
 
 /// This object is used to generate requests to the Lambda Runtime APIs.
 /// It is used for both the error response APIs and fail init calls.
 /// custom error types should implement the `RuntimeError` trait and return
 /// this object to be compatible with the APIs.
-#[derive(Serialize)]
+///
+/// Use `ErrorResponse::builder()` or `ErrorResponse::from_error()` to report wrapped
+/// errors together with their causes, and `details` to add fields of your own to the
+/// JSON body received by Destinations and dead-letter queue consumers.
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
 pub struct ErrorResponse {
     /// The error message generated by the application.
     #[serde(rename = "errorMessage")]
     pub error_message: String,
-    /// The error type for Lambda. This can be `Handled` or `Unhandled`.
-    /// Developers can use the `ERROR_TYPE_HANDLED` and `ERROR_TYPE_UNHANDLED`
-    /// constants to populate this field.
+    /// The error type for Lambda. This can be `Handled` or `Unhandled`, or the
+    /// class of the error such as `MyApp.ValidationError`. Developers can use the
+    /// `ERROR_TYPE_HANDLED` and `ERROR_TYPE_UNHANDLED` constants to populate this field.
+    /// The value is also sent in the `Lambda-Runtime-Function-Error-Type` header, which
+    /// is what shows up in Lambda metrics and Destinations.
     #[serde(rename = "errorType")]
     pub error_type: String,
     /// The stack trace for the exception as vector of strings. In the framework,
     /// this value is automatically populated using the `backtrace` crate.
     #[serde(rename = "stackTrace")]
     pub stack_trace: Option<Vec<String>>,
+    /// The underlying causes of the error, starting with the direct cause. Omitted from
+    /// the serialized response when empty.
+    #[serde(rename = "causes", skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<ErrorCause>,
+    /// Additional fields serialized at the top level of the error body, such as
+    /// correlation ids or diagnostic values. Details named like one of the fields
+    /// above are not serialized.
+    #[serde(flatten, serialize_with = "serialize_details")]
+    pub details: Map<String, Value>,
+}
+
+fn serialize_details<S>(details: &Map<String, Value>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    let mut map = serializer.serialize_map(None)?;
+    for (name, value) in details {
+        if !RESERVED_FIELDS.contains(&name.as_str()) {
+            map.serialize_entry(name, value)?;
+        }
+    }
+    map.end()
+}
+
+/// Serializes `value` into the fields of a details map. Values that do not serialize
+/// to a JSON object are logged and ignored.
+fn details_of<T: ser::Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(details)) => details,
+        Ok(other) => {
+            warn!("Error details must serialize to a JSON object, ignoring {}", other);
+            Map::new()
+        }
+        Err(e) => {
+            warn!("Could not serialize error details: {}", e);
+            Map::new()
+        }
+    }
+}
+
+/// An underlying cause of an `ErrorResponse`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ErrorCause {
+    /// The message of the cause.
+    #[serde(rename = "errorMessage")]
+    pub error_message: String,
+}
+
+/// Builder for `ErrorResponse` objects, created with `ErrorResponse::builder()`.
+///
+/// The causes added to the builder are appended to the error message, listed in the
+/// `causes` field, and prepended to the stack trace as `caused by:` lines, so that the
+/// root cause of a wrapped error shows up wherever the error is reported.
+///
+/// ```rust
+/// use lambda_runtime_client::error::ErrorResponse;
+///
+/// let response = ErrorResponse::builder("Could not load order")
+///     .error_type("OrderError")
+///     .cause("connection reset by peer")
+///     .build();
+/// assert_eq!(response.error_message, "Could not load order: connection reset by peer");
+/// assert_eq!(response.causes[0].error_message, "connection reset by peer");
+/// ```
+///
+/// Details are added to the top level of the JSON body next to the required fields:
+///
+/// ```rust
+/// use lambda_runtime_client::error::ErrorResponse;
+/// use serde_json::json;
+///
+/// let response = ErrorResponse::builder("Payment declined")
+///     .error_type("Payments.Declined")
+///     .detail("correlationId", "c-42")
+///     .details(&json!({ "retryable": false }))
+///     .build();
+/// let body = serde_json::to_value(&response).unwrap();
+/// assert_eq!(body["errorType"], "Payments.Declined");
+/// assert_eq!(body["correlationId"], "c-42");
+/// assert_eq!(body["retryable"], false);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ErrorResponseBuilder {
+    message: String,
+    error_type: String,
+    stack_trace: Option<Vec<String>>,
+    causes: Vec<String>,
+    details: Map<String, Value>,
+}
+
+impl ErrorResponseBuilder {
+    /// Sets the error type, `Handled` by default.
+    pub fn error_type(mut self, error_type: impl Into<String>) -> Self {
+        self.error_type = error_type.into();
+        self
+    }
+
+    /// Sets the stack trace frames.
+    pub fn stack_trace(mut self, stack_trace: Vec<String>) -> Self {
+        self.stack_trace = Some(stack_trace);
+        self
+    }
+
+    /// Adds a cause after the causes already added.
+    pub fn cause(mut self, message: impl Into<String>) -> Self {
+        self.causes.push(message.into());
+        self
+    }
+
+    /// Adds the chain of errors returned by `source()`, starting with the direct
+    /// cause of `e`.
+    pub fn source_chain(mut self, e: &(dyn Error + 'static)) -> Self {
+        self.causes.extend(source_chain(e));
+        self
+    }
+
+    /// Adds a field to the serialized error body, replacing a detail with the same name.
+    pub fn detail(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.details.insert(name.into(), value.into());
+        self
+    }
+
+    /// Adds the fields of a value that serializes to a JSON object to the serialized
+    /// error body. Other values are logged and ignored.
+    pub fn details<T: ser::Serialize>(mut self, details: &T) -> Self {
+        self.details.extend(details_of(details));
+        self
+    }
+
+    /// Returns the `ErrorResponse`.
+    pub fn build(self) -> ErrorResponse {
+        if self.causes.is_empty() {
+            return ErrorResponse {
+                error_message: self.message,
+                error_type: self.error_type,
+                stack_trace: self.stack_trace,
+                causes: Vec::new(),
+                details: self.details,
+            };
+        }
+
+        let mut error_message = self.message;
+        let mut stack_trace = Vec::new();
+        for cause in &self.causes {
+            error_message.push_str(": ");
+            error_message.push_str(cause);
+            stack_trace.push(format!("caused by: {}", cause));
+        }
+        stack_trace.extend(self.stack_trace.into_iter().flatten());
+        ErrorResponse {
+            error_message,
+            error_type: self.error_type,
+            stack_trace: Some(stack_trace),
+            causes: self
+                .causes
+                .into_iter()
+                .map(|error_message| ErrorCause { error_message })
+                .collect(),
+            details: self.details,
+        }
+    }
 }
 
 impl ErrorResponse {
@@ -49,7 +227,7 @@ impl ErrorResponse {
         ErrorResponse {
             error_message: message,
             error_type: String::from(ERROR_TYPE_HANDLED),
-            stack_trace: Option::default(),
+            ..ErrorResponse::default()
         }
     }
 
@@ -65,14 +243,134 @@ impl ErrorResponse {
         ErrorResponse {
             error_message: message,
             error_type: String::from(ERROR_TYPE_UNHANDLED),
-            stack_trace: Option::default(),
+            ..ErrorResponse::default()
         }
     }
+
+    /// Returns a builder for an `ErrorResponse` with the handled error type.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` The error message for the Lambda Runtime APIs.
+    pub fn builder(message: impl Into<String>) -> ErrorResponseBuilder {
+        ErrorResponseBuilder {
+            message: message.into(),
+            error_type: String::from(ERROR_TYPE_HANDLED),
+            stack_trace: None,
+            causes: Vec::new(),
+            details: Map::new(),
+        }
+    }
+
+    /// Creates an `ErrorResponse` with the handled error type from an error and the
+    /// chain of errors returned by its `source()` method.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` The error to report.
+    ///
+    /// # Return
+    /// A populated `ErrorResponse` object.
+    pub fn from_error(e: &(dyn Error + 'static)) -> ErrorResponse {
+        ErrorResponse::builder(e.to_string()).source_chain(e).build()
+    }
+
+    /// Sets the error type reported to the Lambda Runtime APIs.
+    ///
+    /// # Arguments
+    ///
+    /// * `error_type` The class of the error, for example `MyApp.ValidationError`.
+    ///
+    /// # Return
+    /// The `ErrorResponse` with the given error type.
+    pub fn with_error_type(mut self, error_type: &str) -> ErrorResponse {
+        self.error_type = String::from(error_type);
+        self
+    }
+
+    /// Adds the fields of a value that serializes to a JSON object to the serialized
+    /// error body, next to the error message and type.
+    ///
+    /// # Arguments
+    ///
+    /// * `details` The additional fields, for example a struct with a correlation id.
+    ///
+    /// # Return
+    /// The `ErrorResponse` with the given details.
+    pub fn with_details<T: ser::Serialize>(mut self, details: &T) -> ErrorResponse {
+        self.details.extend(details_of(details));
+        self
+    }
+
+    /// Serializes this error as an X-Ray exception cause, used to attribute the
+    /// error in the X-Ray trace of the invocation. Stack frames are dropped from
+    /// the end of the trace until the cause fits in `max_size` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_size` The maximum size of the serialized cause in bytes.
+    ///
+    /// # Return
+    /// The JSON cause, or `None` if it cannot fit in `max_size` even without a stack.
+    pub(crate) fn to_xray_cause(&self, max_size: usize) -> Option<String> {
+        let mut cause = XRayErrorCause {
+            working_directory: env::current_dir()
+                .map(|d| d.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            exceptions: vec![XRayException {
+                message: &self.error_message,
+                error_type: &self.error_type,
+                stack: self
+                    .stack_trace
+                    .iter()
+                    .flatten()
+                    .map(|label| XRayStackFrame { label })
+                    .collect(),
+            }],
+            paths: vec![],
+        };
+        loop {
+            let json = serde_json::to_string(&cause).ok()?;
+            if json.len() <= max_size {
+                return Some(json);
+            }
+            let stack = &mut cause.exceptions[0].stack;
+            if stack.is_empty() {
+                return None;
+            }
+            // drop frames proportionally to how far over the limit we are
+            let excess = json.len() - max_size;
+            let keep = stack.len().saturating_sub(excess * stack.len() / json.len() + 1);
+            stack.truncate(keep);
+        }
+    }
+}
+
+/// The error cause format understood by AWS X-Ray.
+#[derive(Serialize)]
+struct XRayErrorCause<'a> {
+    working_directory: String,
+    exceptions: Vec<XRayException<'a>>,
+    paths: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct XRayException<'a> {
+    message: &'a str,
+    #[serde(rename = "type")]
+    error_type: &'a str,
+    stack: Vec<XRayStackFrame<'a>>,
+}
+
+#[derive(Serialize)]
+struct XRayStackFrame<'a> {
+    label: &'a str,
 }
 
 /// Custom errors for the framework should implement this trait. The client calls
 /// the `to_response()` method automatically to produce an object that can be serialized
-/// and sent to the Lambda Runtime APIs.
+/// and sent to the Lambda Runtime APIs. With the `derive` feature enabled, the
+/// `#[derive(LambdaError)]` macro implements the trait for types that implement `Display`.
 pub trait RuntimeApiError {
     /// Creates a `RuntimeError` object for the current error. This is
     /// then serialized and sent to the Lambda runtime APIs.
@@ -82,103 +380,516 @@ pub trait RuntimeApiError {
     fn to_response(&self) -> ErrorResponse;
 }
 
-/// Represents an error generated by the Lambda Runtime API client.
-#[derive(Debug, Clone)]
-pub struct ApiError {
-    msg: String,
-    /// The `Backtrace` object from the `backtrace` crate used to store
-    /// the stack trace of the error.
-    pub backtrace: Option<backtrace::Backtrace>,
-    /// Whether the current error is recoverable. If the error is not
-    /// recoverable a runtime should panic to force the Lambda service
-    /// to restart the execution environment.
-    pub recoverable: bool,
+/// Returned by `RuntimeClient::new()` when the Runtime API endpoint is not a valid
+/// `host:port` address, and by `RuntimeClient::from_env()` when the endpoint is not set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointError {
+    endpoint: String,
+    reason: String,
+}
+
+impl EndpointError {
+    pub(crate) fn new(endpoint: &str, reason: &str) -> EndpointError {
+        EndpointError {
+            endpoint: String::from(endpoint),
+            reason: String::from(reason),
+        }
+    }
+
+    /// Returns the endpoint that failed validation.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+}
+
+impl fmt::Display for EndpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.endpoint.is_empty() {
+            write!(f, "Invalid Runtime API endpoint: {}", self.reason)
+        } else {
+            write!(f, "Invalid Runtime API endpoint {:?}: {}", self.endpoint, self.reason)
+        }
+    }
+}
+
+impl Error for EndpointError {}
+
+/// Returned by `TraceContext::parse()` when an X-Ray trace header cannot be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceContextError {
+    header: String,
+    reason: String,
+}
+
+impl TraceContextError {
+    pub(crate) fn new(header: &str, reason: &str) -> TraceContextError {
+        TraceContextError {
+            header: String::from(header),
+            reason: String::from(reason),
+        }
+    }
+}
+
+impl fmt::Display for TraceContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid trace header {:?}: {}", self.header, self.reason)
+    }
+}
+
+impl Error for TraceContextError {}
+
+/// Represents an error generated by the Lambda Runtime API client. The variants
+/// describe what went wrong so that callers can branch on the kind of failure, and
+/// `is_recoverable()` tells the runtime whether it can keep serving events.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ApiError {
+    /// The Runtime API endpoint configured for the client is not valid.
+    InvalidEndpoint(EndpointError),
+    /// The host name of the Runtime APIs could not be resolved.
+    Dns(Box<dyn Error + Send + Sync>),
+    /// The Runtime APIs refused the connection, usually because they are not up yet.
+    ConnectionRefused(Box<dyn Error + Send + Sync>),
+    /// The connection to the Runtime APIs was closed or reset while a call was in flight.
+    ConnectionReset(Box<dyn Error + Send + Sync>),
+    /// The request could not be sent or the connection to the Runtime APIs failed for
+    /// another reason.
+    Transport(Box<dyn Error + Send + Sync>),
+    /// A Runtime API call did not complete within the timeout configured with
+    /// `RuntimeClient::with_timeouts()`, or the pre-warmed connection could not be opened
+    /// in time.
+    Timeout {
+        /// The call that timed out, for example `posting response`.
+        operation: &'static str,
+        /// The timeout that elapsed.
+        after: Duration,
+    },
+    /// The Runtime APIs answered with an unexpected status code.
+    Http {
+        /// The call that failed, for example `polling for next event`.
+        operation: &'static str,
+        /// The status code returned by the Runtime APIs.
+        status: StatusCode,
+    },
+    /// The Runtime APIs rejected a payload because it exceeds the maximum size.
+    PayloadTooLarge {
+        /// The call that failed, for example `posting response`.
+        operation: &'static str,
+    },
+    /// A JSON value sent by the Runtime APIs could not be deserialized.
+    Serde(serde_json::Error),
+    /// A header was missing or could not be parsed.
+    InvalidHeader {
+        /// The name of the header.
+        name: &'static str,
+        /// Why the header could not be parsed, or `None` if it was missing.
+        source: Option<Box<dyn Error + Send + Sync>>,
+    },
 }
 
 impl ApiError {
-    pub(crate) fn new(description: &str) -> ApiError {
-        let mut trace: Option<backtrace::Backtrace> = None;
-        let is_backtrace = env::var("RUST_BACKTRACE");
-        if is_backtrace.is_ok() && is_backtrace.unwrap() == "1" {
-            trace!("Begin backtrace collection");
-            trace = Option::from(backtrace::Backtrace::new());
-            trace!("Completed backtrace collection");
+    /// Creates the error for a call that returned a non-success status code.
+    pub(crate) fn status(operation: &'static str, status: StatusCode) -> ApiError {
+        if status == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge { operation }
+        } else {
+            ApiError::Http { operation, status }
+        }
+    }
+
+    /// Creates the error for a failed connection to the Runtime APIs, classified by
+    /// looking for the underlying cause in the chain of sources.
+    pub(crate) fn transport<E>(e: E) -> ApiError
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let e = e.into();
+        let mut source: Option<&(dyn Error + 'static)> = Some(e.as_ref());
+        while let Some(cause) = source {
+            if let Some(io_err) = cause.downcast_ref::<io::Error>() {
+                match io_err.kind() {
+                    io::ErrorKind::ConnectionRefused => return ApiError::ConnectionRefused(e),
+                    io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::UnexpectedEof => return ApiError::ConnectionReset(e),
+                    _ => {}
+                }
+                // io errors skip their payload when reporting their source
+                if let Some(inner) = io_err.get_ref() {
+                    source = Some(inner);
+                    continue;
+                }
+            }
+            if let Some(hyper_err) = cause.downcast_ref::<hyper::Error>() {
+                if hyper_err.is_incomplete_message() || hyper_err.is_closed() {
+                    return ApiError::ConnectionReset(e);
+                }
+            }
+            if is_dns_error(cause) {
+                return ApiError::Dns(e);
+            }
+            source = cause.source();
         }
-        ApiError {
-            msg: String::from(description),
-            backtrace: trace,
-            recoverable: true,
+        ApiError::Transport(e)
+    }
+
+    /// Creates the error for a missing header.
+    pub(crate) fn missing_header(name: &'static str) -> ApiError {
+        ApiError::InvalidHeader { name, source: None }
+    }
+
+    /// Creates the error for a header that could not be parsed.
+    pub(crate) fn invalid_header<E>(name: &'static str, e: E) -> ApiError
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        ApiError::InvalidHeader {
+            name,
+            source: Some(e.into()),
         }
     }
 
-    pub(crate) fn unrecoverable(&mut self) -> &ApiError {
-        self.recoverable = false;
+    /// Returns `true` if the runtime can keep serving events after this error. Server
+    /// errors from the Runtime APIs and invalid endpoints are not recoverable: the runtime
+    /// should report the failure and exit to force the Lambda service to restart the
+    /// execution environment.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            ApiError::InvalidEndpoint(_) => false,
+            ApiError::Http { status, .. } => !status.is_server_error(),
+            _ => true,
+        }
+    }
 
-        self
+    /// Returns `true` if this error was caused by a Runtime API call exceeding
+    /// the timeout configured with `RuntimeClient::with_timeouts()`.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, ApiError::Timeout { .. })
     }
 }
 
+/// Returns whether an error is a resolver error of the http connector.
+///
+/// The resolver of `RuntimeClient` returns `DnsError`s. hyper-util wraps resolver errors
+/// in a private type, so its "dns error" message, which is not a stable API, is only a
+/// fallback for resolvers that don't.
+fn is_dns_error(cause: &(dyn Error + 'static)) -> bool {
+    cause.is::<DnsError>() || cause.to_string() == "dns error"
+}
+
 impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.msg)
+        match self {
+            ApiError::InvalidEndpoint(e) => write!(f, "{}", e),
+            ApiError::Dns(e) => write!(f, "Could not resolve the Runtime API host: {}", e),
+            ApiError::ConnectionRefused(e) => write!(f, "Connection refused by the Runtime API: {}", e),
+            ApiError::ConnectionReset(e) => write!(f, "Connection to the Runtime API was reset: {}", e),
+            ApiError::Transport(e) => write!(f, "Could not communicate with the Runtime API: {}", e),
+            ApiError::Timeout { operation, after } => write!(f, "Timed out after {:?} while {}", after, operation),
+            ApiError::Http { operation, status } => write!(f, "Error {} while {}", status, operation),
+            ApiError::PayloadTooLarge { operation } => write!(f, "Payload too large while {}", operation),
+            ApiError::Serde(e) => write!(f, "Could not parse JSON: {}", e),
+            ApiError::InvalidHeader { name, source: None } => write!(f, "Missing {} header", name),
+            ApiError::InvalidHeader { name, source: Some(e) } => write!(f, "Invalid {} header: {}", name, e),
+        }
     }
 }
 
-// This is important for other errors to wrap this one.
 impl Error for ApiError {
-    fn description(&self) -> &str {
-        &self.msg
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ApiError::InvalidEndpoint(e) => Some(e),
+            ApiError::Dns(e)
+            | ApiError::ConnectionRefused(e)
+            | ApiError::ConnectionReset(e)
+            | ApiError::Transport(e) => Some(e.as_ref()),
+            ApiError::Serde(e) => Some(e),
+            ApiError::InvalidHeader { source: Some(e), .. } => Some(e.as_ref()),
+            _ => None,
+        }
     }
+}
 
-    fn cause(&self) -> Option<&dyn Error> {
-        // Generic error, underlying cause isn't tracked.
-        None
+impl From<EndpointError> for ApiError {
+    fn from(e: EndpointError) -> Self {
+        ApiError::InvalidEndpoint(e)
     }
 }
 
 impl From<serde_json::Error> for ApiError {
     fn from(e: serde_json::Error) -> Self {
-        ApiError::new(e.description())
+        ApiError::Serde(e)
     }
 }
 
 impl From<InvalidUri> for ApiError {
     fn from(e: InvalidUri) -> Self {
-        ApiError::new(e.description())
+        ApiError::Transport(Box::new(e))
     }
 }
 
 impl From<hyper::Error> for ApiError {
     fn from(e: hyper::Error) -> Self {
-        ApiError::new(e.description())
-    }
-}
-
-impl From<ToStrError> for ApiError {
-    fn from(e: ToStrError) -> Self {
-        ApiError::new(e.description())
+        ApiError::transport(e)
     }
 }
 
-impl From<ParseIntError> for ApiError {
-    fn from(e: ParseIntError) -> Self {
-        ApiError::new(e.description())
+impl From<legacy::Error> for ApiError {
+    fn from(e: legacy::Error) -> Self {
+        ApiError::transport(e)
     }
 }
 
 impl From<io::Error> for ApiError {
     fn from(e: io::Error) -> Self {
-        ApiError::new(e.description())
+        ApiError::transport(e)
     }
 }
 
 impl RuntimeApiError for ApiError {
     fn to_response(&self) -> ErrorResponse {
-        let backtrace = format!("{:?}", self.backtrace);
-        let trace_vec = backtrace.lines().map(|s| s.to_string()).collect::<Vec<String>>();
-        let mut err = ErrorResponse::unhandled(self.msg.clone());
-        err.stack_trace = Option::from(trace_vec);
+        let mut err = ErrorResponse::unhandled(self.to_string());
+        err.stack_trace = Option::from(source_chain(self));
 
         err
     }
 }
+
+/// Boxed errors, as used by applications with anyhow-style error handling, can be sent
+/// to the Runtime APIs directly. The errors returned by `source()` are reported as the
+/// causes of the error, see `ErrorResponse::from_error()`.
+impl RuntimeApiError for Box<dyn Error + Send + Sync> {
+    fn to_response(&self) -> ErrorResponse {
+        ErrorResponse::from_error(self.as_ref())
+    }
+}
+
+impl RuntimeApiError for Box<dyn Error> {
+    fn to_response(&self) -> ErrorResponse {
+        ErrorResponse::from_error(self.as_ref())
+    }
+}
+
+/// Returns the messages of the chain of errors returned by `source()`, starting with
+/// the direct cause of `e`. This is the format of the causes of error responses.
+pub fn source_chain(e: &dyn Error) -> Vec<String> {
+    let mut causes = Vec::new();
+    let mut source = e.source();
+    while let Some(cause) = source {
+        causes.push(cause.to_string());
+        source = cause.source();
+    }
+    causes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxed_errors_report_source_chain() {
+        let io_err = io::Error::other(ApiError::missing_header("Lambda-Runtime-Aws-Request-Id"));
+        let boxed: Box<dyn Error + Send + Sync> = Box::new(ApiError::from(io_err));
+        let response = boxed.to_response();
+        assert_eq!(response.error_type, ERROR_TYPE_HANDLED);
+        assert_eq!(
+            response.error_message,
+            format!("{}: Missing Lambda-Runtime-Aws-Request-Id header", boxed)
+        );
+        assert_eq!(
+            response.stack_trace,
+            Some(vec![String::from(
+                "caused by: Missing Lambda-Runtime-Aws-Request-Id header"
+            )])
+        );
+        let json = serde_json::to_value(&response).expect("Could not serialize error response");
+        assert_eq!(
+            json["causes"][0]["errorMessage"],
+            "Missing Lambda-Runtime-Aws-Request-Id header"
+        );
+        assert!(serde_json::to_value(ErrorResponse::handled(String::from("test")))
+            .expect("Could not serialize error response")
+            .get("causes")
+            .is_none());
+    }
+
+    #[test]
+    fn api_error_kinds() {
+        let err = ApiError::status("polling for next event", StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!err.is_recoverable());
+        assert_eq!(
+            err.to_string(),
+            "Error 500 Internal Server Error while polling for next event"
+        );
+
+        let err = ApiError::status("posting response", StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(matches!(err, ApiError::PayloadTooLarge { .. }));
+        assert!(err.is_recoverable());
+
+        let parse_err = "abc".parse::<i64>().unwrap_err();
+        let err = ApiError::invalid_header("Lambda-Runtime-Deadline-Ms", parse_err);
+        assert!(err.source().is_some());
+        assert_eq!(
+            err.to_response().stack_trace,
+            Some(vec![String::from("invalid digit found in string")])
+        );
+
+        let err = ApiError::missing_header("Lambda-Runtime-Aws-Request-Id");
+        assert!(err.source().is_none());
+        assert_eq!(err.to_string(), "Missing Lambda-Runtime-Aws-Request-Id header");
+    }
+
+    #[test]
+    fn transport_errors_are_classified() {
+        let refused = io::Error::from(io::ErrorKind::ConnectionRefused);
+        assert!(matches!(ApiError::from(refused), ApiError::ConnectionRefused(_)));
+
+        let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert!(matches!(ApiError::from(reset), ApiError::ConnectionReset(_)));
+
+        // the cause is found when wrapped in another error
+        let wrapped = io::Error::other(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert!(matches!(ApiError::from(wrapped), ApiError::ConnectionReset(_)));
+
+        let other = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert!(matches!(ApiError::from(other), ApiError::Transport(_)));
+    }
+
+    /// An error with a message and a source, like the errors of the http connector.
+    #[derive(Debug)]
+    struct Wrapped(&'static str, Box<dyn Error + Send + Sync>);
+
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(self.1.as_ref())
+        }
+    }
+
+    #[test]
+    fn dns_errors_are_classified() {
+        let resolver_error = || io::Error::other("failed to lookup address information");
+
+        // by the type of the client's resolver errors, whatever the connector says
+        let err = Wrapped("connect failed", Box::new(DnsError::new(resolver_error())));
+        assert!(matches!(ApiError::transport(err), ApiError::Dns(_)));
+
+        // by the message of the connector, for other resolvers
+        let err = Wrapped("dns error", Box::new(resolver_error()));
+        assert!(matches!(ApiError::transport(err), ApiError::Dns(_)));
+
+        let err = Wrapped("tcp connect error", Box::new(resolver_error()));
+        assert!(matches!(ApiError::transport(err), ApiError::Transport(_)));
+    }
+
+    #[test]
+    fn resolver_errors_reach_the_client_as_dns_errors() {
+        use crate::dns::Resolver;
+        use http_body_util::Empty;
+        use hyper_util::{
+            client::legacy::{
+                connect::{dns::Name, HttpConnector},
+                Client,
+            },
+            rt::TokioExecutor,
+        };
+        use std::{
+            future::{ready, Ready},
+            net::SocketAddr,
+            task::{Context, Poll},
+            vec,
+        };
+
+        /// A resolver that fails to resolve every name, without looking anything up.
+        #[derive(Clone)]
+        struct Failing;
+
+        impl tower_service::Service<Name> for Failing {
+            type Response = vec::IntoIter<SocketAddr>;
+            type Error = io::Error;
+            type Future = Ready<Result<Self::Response, io::Error>>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _name: Name) -> Self::Future {
+                ready(Err(io::Error::other("no such host")))
+            }
+        }
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Could not create tokio runtime");
+        let connector = HttpConnector::new_with_resolver(Resolver(Failing));
+        let client = Client::builder(TokioExecutor::new()).build::<_, Empty<bytes::Bytes>>(connector);
+        let err = rt
+            .block_on(client.get(http::Uri::from_static("http://runtime.test/")))
+            .expect_err("Resolving should fail");
+        assert!(matches!(ApiError::transport(err), ApiError::Dns(_)));
+    }
+
+    #[test]
+    fn xray_cause_contains_exception() {
+        let mut err = ErrorResponse::handled(String::from("invalid input")).with_error_type("MyApp.ValidationError");
+        err.stack_trace = Some(vec![String::from("frame one"), String::from("frame two")]);
+        let cause: serde_json::Value =
+            serde_json::from_str(&err.to_xray_cause(XRAY_ERROR_CAUSE_MAX_SIZE).expect("Missing cause"))
+                .expect("Invalid cause JSON");
+
+        let exception = &cause["exceptions"][0];
+        assert_eq!(exception["message"], "invalid input");
+        assert_eq!(exception["type"], "MyApp.ValidationError");
+        assert_eq!(exception["stack"][1]["label"], "frame two");
+    }
+
+    #[test]
+    fn xray_cause_is_truncated() {
+        let mut err = ErrorResponse::unhandled(String::from("boom"));
+        err.stack_trace = Some((0..1000).map(|i| format!("frame {}", i)).collect());
+
+        let cause = err.to_xray_cause(2048).expect("Missing cause");
+        assert!(cause.len() <= 2048);
+        assert!(cause.contains("frame 0"));
+        assert!(!cause.contains("frame 999"));
+
+        let err = ErrorResponse::unhandled("x".repeat(4096));
+        assert!(err.to_xray_cause(2048).is_none());
+    }
+
+    #[test]
+    fn details_are_serialized_next_to_required_fields() {
+        #[derive(Serialize)]
+        struct Details {
+            #[serde(rename = "correlationId")]
+            correlation_id: &'static str,
+            #[serde(rename = "errorType")]
+            error_type: &'static str,
+        }
+
+        let err = ErrorResponse::handled(String::from("declined"))
+            .with_error_type("Payments.Declined")
+            .with_details(&Details {
+                correlation_id: "c-42",
+                error_type: "Overridden",
+            })
+            .with_details(&"not an object");
+        let body: Value = serde_json::to_value(&err).expect("Could not serialize error");
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "errorMessage": "declined",
+                "errorType": "Payments.Declined",
+                "stackTrace": null,
+                "correlationId": "c-42",
+            })
+        );
+    }
+}
@@ -2,7 +2,8 @@
 #![deny(warnings)]
 //! Rust client SDK for the AWS Lambda Runtime APIs. This crate defines
 //! a `RuntimeClient` that encapsulates interactions with AWS Lambda's Runtime
-//! APIs.
+//! APIs. The client methods are `async` and must be driven by a [tokio](https://tokio.rs)
+//! runtime.
 //!
 //! To return errors to the Runtime APIs through the `event_error()` or
 //! `fail_init()` methods the `Error` objects must implement the `error::RuntimeApiError`
@@ -10,6 +11,20 @@
 //! called `to_response()`. The method must return an `error::RuntimeError` object.
 //! See the `error::ApiError` object in this crate for an example.
 //!
+//! # Low-level API
+//!
+//! Projects that build their own event loop, shim, or alternative runtime can rely on
+//! the following methods of `RuntimeClient`, which follow semantic versioning: breaking
+//! changes to them only ship in a new major version.
+//!
+//! * `next_event()` polls for the next event and returns its payload with an `EventContext`.
+//! * `event_response()`, `event_response_with_content_type()`, and `event_response_stream()`
+//!   post the output of an invocation.
+//! * `event_error()` posts a handler error and `fail_init()` reports an initialization failure.
+//! * `get_event_context()` builds an `EventContext` from the headers of a `/next` response,
+//!   using the header names defined by `LambdaHeaders`, for runtimes that poll with their
+//!   own HTTP client. The `http` crate is re-exported for its `HeaderMap` type.
+//!
 //! # Examples
 //!
 //! ```rust,no_run
@@ -30,12 +45,13 @@
 //!     surname: String,
 //! }
 //!
-//! fn main() {
-//!     let runtime_endpoint = String::from("http://localhost:8080");
-//!     let client = RuntimeClient::new(runtime_endpoint, None)
+//! #[tokio::main]
+//! async fn main() {
+//!     // reads the endpoint from the AWS_LAMBDA_RUNTIME_API environment variable
+//!     let client = RuntimeClient::from_env()
 //!         .expect("Could not initialize client");
 //!
-//!     let (event_data, event_context) = client.next_event()
+//!     let (event_data, event_context) = client.next_event().await
 //!         .expect("Could not retrieve next event");
 //!     let custom_event: CustomEvent = serde_json::from_slice(&event_data)
 //!         .expect("Could not turn Vec<u8> into CustomEvent object");
@@ -45,12 +61,12 @@
 //!         let resp_object = CustomResponse{ surname: String::from("Doe")};
 //!         let resp_vec = serde_json::to_vec(&resp_object)
 //!             .expect("Could not serialize CustomResponse to Vec<u8>");
-//!         client.event_response(&event_context.aws_request_id, resp_vec)
+//!         client.event_response(&event_context.aws_request_id, resp_vec).await
 //!             .expect("Response sent successfully");
 //!     } else {
 //!         // return a custom error by implementing the RuntimeApiError trait.
 //!         // See the error module for examples.
-//!         //client.event_error(&event_context.aws_request_id, CustomErrorType::new("Invalid first name"))
+//!         //client.event_error(&event_context.aws_request_id, &CustomErrorType::new("Invalid first name")).await
 //!         //    .expect("Could not send error response");
 //!     }
 //! }
@@ -59,6 +75,14 @@
 #[macro_use]
 extern crate log;
 
+pub use http;
+
 mod client;
+mod dns;
 pub mod error;
-pub use crate::client::*;
+mod observer;
+mod redact;
+mod trace;
+#[cfg(feature = "wire-trace")]
+pub mod wire;
+pub use crate::{client::*, observer::*, redact::Unredacted, trace::TraceContext};
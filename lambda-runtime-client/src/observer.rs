@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use hyper::StatusCode;
+
+/// The Runtime API calls made by the `RuntimeClient`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiCall {
+    /// The `/next` long poll for a new event.
+    NextEvent,
+    /// A function response posted to the `/response` API.
+    Response,
+    /// A handler error posted to the `/error` API.
+    Error,
+    /// An initialization failure posted to the `/init/error` API.
+    InitError,
+}
+
+/// Timing and outcome of a single Runtime API call, passed to `ApiObserver::on_api_call()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiCallMetrics {
+    /// The Runtime API call that was made.
+    pub call: ApiCall,
+    /// How long the call took. For `ApiCall::NextEvent` this is the time spent waiting
+    /// for an event, for the other calls it is the latency of the post.
+    pub duration: Duration,
+    /// The status code returned by the Runtime APIs, or `None` if the call failed or
+    /// timed out before a response was received.
+    pub status: Option<StatusCode>,
+    /// The size of the event received for `ApiCall::NextEvent`, or of the body posted for
+    /// the other calls. `None` if the size is not known, for example for streamed responses.
+    pub bytes: Option<u64>,
+}
+
+/// Observers are notified after each call the `RuntimeClient` makes to the Runtime APIs.
+/// Implement this trait to export runtime overhead metrics; observers are called inline
+/// from the client, so they should return quickly.
+pub trait ApiObserver: Send + Sync {
+    /// Called once a Runtime API call completes, whether it succeeded or not.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` The timing and outcome of the call.
+    fn on_api_call(&self, metrics: &ApiCallMetrics);
+}
@@ -0,0 +1,221 @@
+//! `Debug` implementations for the context types that keep sensitive values out of
+//! logs. Cognito identity ids, the mobile app installation id, the values of custom
+//! client context properties, and the raw context headers, including their copies in
+//! the header map, are printed as `<redacted>`.
+//! Wrap a value with its `unredacted()` method to print every field.
+use std::fmt;
+
+use crate::client::{ClientApplication, ClientContext, CognitoIdentity, EventContext, LambdaHeaders};
+
+/// Prints the full `Debug` representation of a context value, including the values
+/// that its own `Debug` implementation redacts. Created with the `unredacted()` method
+/// of `EventContext`, `ClientContext`, `ClientApplication`, and `CognitoIdentity`.
+///
+/// ```rust
+/// use lambda_runtime_client::CognitoIdentity;
+///
+/// let identity = CognitoIdentity {
+///     identity_id: String::from("us-east-1:1234"),
+///     identity_pool_id: String::from("us-east-1:pool"),
+/// };
+/// assert!(!format!("{:?}", identity).contains("us-east-1:1234"));
+/// assert!(format!("{:?}", identity.unredacted()).contains("us-east-1:1234"));
+/// ```
+pub struct Unredacted<'a, T>(&'a T);
+
+/// Placeholder printed instead of a redacted value.
+struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// Returns `value`, or the redaction placeholder when `redact` is set.
+fn redacted<T: fmt::Debug>(value: &T, redact: bool) -> &dyn fmt::Debug {
+    if redact {
+        &Redacted
+    } else {
+        value
+    }
+}
+
+fn fmt_identity(identity: &CognitoIdentity, f: &mut fmt::Formatter<'_>, redact: bool) -> fmt::Result {
+    f.debug_struct("CognitoIdentity")
+        .field("identity_id", redacted(&identity.identity_id, redact))
+        .field("identity_pool_id", &identity.identity_pool_id)
+        .finish()
+}
+
+fn fmt_application(client: &ClientApplication, f: &mut fmt::Formatter<'_>, redact: bool) -> fmt::Result {
+    f.debug_struct("ClientApplication")
+        .field("installation_id", redacted(&client.installation_id, redact))
+        .field("app_title", &client.app_title)
+        .field("app_version_name", &client.app_version_name)
+        .field("app_version_code", &client.app_version_code)
+        .field("app_package_name", &client.app_package_name)
+        .finish()
+}
+
+fn fmt_client_context(ctx: &ClientContext, f: &mut fmt::Formatter<'_>, redact: bool) -> fmt::Result {
+    let custom = DebugFn(|f: &mut fmt::Formatter<'_>| {
+        f.debug_map()
+            .entries(ctx.custom.iter().map(|(k, v)| (k, redacted(v, redact))))
+            .finish()
+    });
+    f.debug_struct("ClientContext")
+        .field("client", &DebugWith(&ctx.client, fmt_application, redact))
+        .field("custom", &custom)
+        .field("environment", &ctx.environment)
+        .finish()
+}
+
+fn fmt_event_context(ctx: &EventContext, f: &mut fmt::Formatter<'_>, redact: bool) -> fmt::Result {
+    let client_context = ctx
+        .client_context
+        .as_ref()
+        .map(|c| DebugWith(c, fmt_client_context, redact));
+    let identity = ctx.identity.as_ref().map(|i| DebugWith(i, fmt_identity, redact));
+    let headers = DebugFn(|f: &mut fmt::Formatter<'_>| {
+        f.debug_map()
+            .entries(ctx.headers.iter().map(|(name, value)| {
+                let sensitive =
+                    name == LambdaHeaders::ClientContext.as_str() || name == LambdaHeaders::CognitoIdentity.as_str();
+                (name, redacted(value, redact && sensitive))
+            }))
+            .finish()
+    });
+    f.debug_struct("EventContext")
+        .field("invoked_function_arn", &ctx.invoked_function_arn)
+        .field("aws_request_id", &ctx.aws_request_id)
+        .field("xray_trace_id", &ctx.xray_trace_id)
+        .field("deadline", &ctx.deadline)
+        .field("client_context", &client_context)
+        .field("identity", &identity)
+        .field(
+            "raw_client_context",
+            &ctx.raw_client_context.as_ref().map(|c| redacted(c, redact)),
+        )
+        .field("raw_identity", &ctx.raw_identity.as_ref().map(|i| redacted(i, redact)))
+        .field("tenant_id", &ctx.tenant_id)
+        .field("function_name", &ctx.function_name)
+        .field("function_version", &ctx.function_version)
+        .field("memory_limit_in_mb", &ctx.memory_limit_in_mb)
+        .field("log_group_name", &ctx.log_group_name)
+        .field("log_stream_name", &ctx.log_stream_name)
+        .field("headers", &headers)
+        .field("extensions", &ctx.extensions)
+        .finish()
+}
+
+/// Formats a value with one of the functions above.
+struct DebugWith<'a, T>(&'a T, fn(&T, &mut fmt::Formatter<'_>, bool) -> fmt::Result, bool);
+
+impl<T> fmt::Debug for DebugWith<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.1)(self.0, f, self.2)
+    }
+}
+
+/// Formats a value with a closure.
+struct DebugFn<F>(F);
+
+impl<F: Fn(&mut fmt::Formatter<'_>) -> fmt::Result> fmt::Debug for DebugFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.0)(f)
+    }
+}
+
+macro_rules! redacted_debug {
+    ($ty:ty, $fmt:ident) => {
+        impl $ty {
+            /// Returns a wrapper whose `Debug` implementation prints all of the values of
+            /// this object, including the ones redacted by default.
+            pub fn unredacted(&self) -> Unredacted<'_, $ty> {
+                Unredacted(self)
+            }
+        }
+
+        impl fmt::Debug for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                $fmt(self, f, true)
+            }
+        }
+
+        impl fmt::Debug for Unredacted<'_, $ty> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                $fmt(self.0, f, false)
+            }
+        }
+    };
+}
+
+redacted_debug!(CognitoIdentity, fmt_identity);
+redacted_debug!(ClientApplication, fmt_application);
+redacted_debug!(ClientContext, fmt_client_context);
+redacted_debug!(EventContext, fmt_event_context);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderMap;
+
+    fn event_context() -> EventContext {
+        EventContext {
+            aws_request_id: String::from("req-1"),
+            client_context: Some(
+                ClientContext::builder()
+                    .installation_id("install-secret")
+                    .app_title("My App")
+                    .custom("token", "custom-secret")
+                    .build(),
+            ),
+            identity: Some(CognitoIdentity {
+                identity_id: String::from("identity-secret"),
+                identity_pool_id: String::from("pool-1"),
+            }),
+            raw_identity: Some(String::from("raw-secret")),
+            headers: {
+                let mut headers = HeaderMap::new();
+                headers.insert("lambda-runtime-cognito-identity", "header-secret".parse().unwrap());
+                headers.insert("lambda-runtime-aws-request-id", "req-1".parse().unwrap());
+                headers
+            },
+            ..EventContext::default()
+        }
+    }
+
+    #[test]
+    fn sensitive_values_are_redacted() {
+        let debug = format!("{:?}", event_context());
+        for secret in &[
+            "install-secret",
+            "custom-secret",
+            "identity-secret",
+            "raw-secret",
+            "header-secret",
+        ] {
+            assert!(!debug.contains(secret), "{} leaked in {}", secret, debug);
+        }
+        assert!(debug.contains("req-1"));
+        assert!(debug.contains("My App"));
+        assert!(debug.contains("pool-1"));
+        assert!(debug.contains("\"token\": <redacted>"));
+    }
+
+    #[test]
+    fn unredacted_prints_all_values() {
+        let ctx = event_context();
+        let debug = format!("{:?}", ctx.unredacted());
+        for secret in &[
+            "install-secret",
+            "custom-secret",
+            "identity-secret",
+            "raw-secret",
+            "header-secret",
+        ] {
+            assert!(debug.contains(secret), "{} missing from {}", secret, debug);
+        }
+    }
+}
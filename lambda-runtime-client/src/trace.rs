@@ -0,0 +1,170 @@
+use std::{fmt, str::FromStr};
+
+use crate::error::TraceContextError;
+
+/// The X-Ray trace context of an invocation, parsed from the `Lambda-Runtime-Trace-Id`
+/// header, for example `Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1`.
+///
+/// The `Display` implementation serializes the context back to the header format,
+/// including any fields this type does not know about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: String,
+    parent_id: Option<String>,
+    sampled: Option<bool>,
+    others: Vec<(String, String)>,
+}
+
+impl TraceContext {
+    /// Creates a new trace context for the given root trace id.
+    ///
+    /// # Arguments
+    ///
+    /// * `trace_id` The root trace id, for example `1-5759e988-bd862e3fe1be46a994272793`.
+    ///
+    /// # Return
+    /// A `TraceContext` without a parent and without a sampling decision.
+    pub fn new(trace_id: &str) -> TraceContext {
+        TraceContext {
+            trace_id: String::from(trace_id),
+            parent_id: None,
+            sampled: None,
+            others: Vec::new(),
+        }
+    }
+
+    /// Parses the value of an X-Ray trace header.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` The value of the header.
+    ///
+    /// # Return
+    /// The parsed `TraceContext`, or a `TraceContextError` if the header has no `Root` field
+    /// or a field is not a `key=value` pair.
+    pub fn parse(header: &str) -> Result<TraceContext, TraceContextError> {
+        let mut trace_id = None;
+        let mut parent_id = None;
+        let mut sampled = None;
+        let mut others = Vec::new();
+        for field in header.split(';').map(str::trim).filter(|f| !f.is_empty()) {
+            let (key, value) = match field.split_once('=') {
+                Some(pair) => pair,
+                None => return Err(TraceContextError::new(header, "fields must be key=value pairs")),
+            };
+            match key {
+                "Root" => trace_id = Some(String::from(value)),
+                "Parent" => parent_id = Some(String::from(value)),
+                "Sampled" => {
+                    sampled = match value {
+                        "1" => Some(true),
+                        "0" => Some(false),
+                        // "?" defers the sampling decision to the next service
+                        _ => None,
+                    }
+                }
+                _ => others.push((String::from(key), String::from(value))),
+            }
+        }
+
+        match trace_id {
+            Some(trace_id) if !trace_id.is_empty() => Ok(TraceContext {
+                trace_id,
+                parent_id,
+                sampled,
+                others,
+            }),
+            _ => Err(TraceContextError::new(header, "the Root field is missing")),
+        }
+    }
+
+    /// Returns the root trace id.
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// Returns the id of the parent segment, if any.
+    pub fn parent_id(&self) -> Option<&str> {
+        self.parent_id.as_deref()
+    }
+
+    /// Returns the sampling decision, or `None` if it has not been made.
+    pub fn sampled(&self) -> Option<bool> {
+        self.sampled
+    }
+
+    /// Returns the value of another field of the header, such as `Lineage`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.others.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// Returns a copy of this context with the given parent segment id, for propagating
+    /// the trace to downstream calls.
+    pub fn with_parent_id(mut self, parent_id: &str) -> TraceContext {
+        self.parent_id = Some(String::from(parent_id));
+        self
+    }
+
+    /// Returns a copy of this context with the given sampling decision.
+    pub fn with_sampled(mut self, sampled: bool) -> TraceContext {
+        self.sampled = Some(sampled);
+        self
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Root={}", self.trace_id)?;
+        if let Some(parent_id) = &self.parent_id {
+            write!(f, ";Parent={}", parent_id)?;
+        }
+        if let Some(sampled) = self.sampled {
+            write!(f, ";Sampled={}", if sampled { 1 } else { 0 })?;
+        }
+        for (key, value) in &self.others {
+            write!(f, ";{}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TraceContext {
+    type Err = TraceContextError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TraceContext::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_serialize() {
+        let header = "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=a87bd80c:0";
+        let ctx = TraceContext::parse(header).expect("Could not parse trace header");
+        assert_eq!(ctx.trace_id(), "1-5759e988-bd862e3fe1be46a994272793");
+        assert_eq!(ctx.parent_id(), Some("53995c3f42cd8ad8"));
+        assert_eq!(ctx.sampled(), Some(true));
+        assert_eq!(ctx.get("Lineage"), Some("a87bd80c:0"));
+        assert_eq!(ctx.to_string(), header);
+
+        let ctx: TraceContext = "Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=?"
+            .parse()
+            .expect("Could not parse trace header");
+        assert_eq!(ctx.parent_id(), None);
+        assert_eq!(ctx.sampled(), None);
+        assert_eq!(
+            ctx.with_parent_id("53995c3f42cd8ad8").with_sampled(false).to_string(),
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0"
+        );
+    }
+
+    #[test]
+    fn invalid_headers() {
+        assert!(TraceContext::parse("").is_err());
+        assert!(TraceContext::parse("Parent=53995c3f42cd8ad8;Sampled=1").is_err());
+        assert!(TraceContext::parse("Root=1-5759e988-bd862e3fe1be46a994272793;garbage").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Wire-level tracing of the Runtime API calls, enabled with the `wire-trace` feature.
+//! Request and response headers and the start of their bodies are logged at `trace`
+//! level under the `lambda_runtime_client::wire` target.
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use hyper::{
+    body::{Body, Frame, SizeHint},
+    header::HeaderMap,
+};
+
+/// Maximum number of bytes of a body included in the wire trace.
+const MAX_TRACED_BODY: usize = 1024;
+
+/// Headers that the `DefaultRedactor` hides from the wire trace.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "set-cookie",
+    "x-amz-security-token",
+    "lambda-runtime-client-context",
+    "lambda-runtime-cognito-identity",
+];
+
+/// Controls what the wire trace logs for headers and bodies, so that secrets do not
+/// end up in the logs. Set a custom implementation with `RuntimeClient::with_wire_redactor()`.
+pub trait WireRedactor: Send + Sync {
+    /// Returns the value to log for a header.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` The lowercase name of the header.
+    /// * `value` The value of the header.
+    ///
+    /// # Return
+    /// The value to log in place of `value`.
+    fn redact_header(&self, name: &str, value: &str) -> String {
+        if SENSITIVE_HEADERS.contains(&name) {
+            String::from("<redacted>")
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Returns the body to log. The body has already been truncated.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` The start of the body, decoded as UTF-8 with invalid sequences replaced.
+    ///
+    /// # Return
+    /// The text to log in place of `body`.
+    fn redact_body(&self, body: &str) -> String {
+        body.to_string()
+    }
+}
+
+/// The redactor used unless another one is configured. It hides authorization headers,
+/// cookies, session tokens, and the client context and Cognito identity sent by the
+/// Runtime APIs, and logs bodies as they are.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultRedactor;
+
+impl WireRedactor for DefaultRedactor {}
+
+/// Logs the start line and headers of a request or response.
+pub(crate) fn trace_head(redactor: &dyn WireRedactor, direction: &str, line: &str, headers: &HeaderMap) {
+    if !log_enabled!(target: "lambda_runtime_client::wire", log::Level::Trace) {
+        return;
+    }
+    let mut head = format!("{} {}", direction, line);
+    for (name, value) in headers {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        head.push_str(&format!(
+            "\n{} {}: {}",
+            direction,
+            name,
+            redactor.redact_header(name.as_str(), &value)
+        ));
+    }
+    trace!(target: "lambda_runtime_client::wire", "{}", head);
+}
+
+/// Logs the body of a request or response, truncated to `MAX_TRACED_BODY` bytes.
+pub(crate) fn trace_body(redactor: &dyn WireRedactor, direction: &str, body: &[u8]) {
+    if !log_enabled!(target: "lambda_runtime_client::wire", log::Level::Trace) {
+        return;
+    }
+    trace!(
+        target: "lambda_runtime_client::wire",
+        "{} {}",
+        direction,
+        format_body(redactor, body)
+    );
+}
+
+/// Request body that traces its data frames as they are sent, so that streamed bodies
+/// are traced too. The size of the wrapped body is preserved.
+pub(crate) struct TracedBody<B> {
+    inner: B,
+    redactor: Arc<dyn WireRedactor>,
+}
+
+impl<B> TracedBody<B> {
+    pub(crate) fn new(inner: B, redactor: Arc<dyn WireRedactor>) -> Self {
+        TracedBody { inner, redactor }
+    }
+}
+
+impl<B> Body for TracedBody<B>
+where
+    B: Body<Data = Bytes, Error = Infallible> + Unpin,
+{
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        let frame = Pin::new(&mut self.inner).poll_frame(cx);
+        if let Poll::Ready(Some(Ok(frame))) = &frame {
+            if let Some(data) = frame.data_ref() {
+                trace_body(&*self.redactor, ">", data);
+            }
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+fn format_body(redactor: &dyn WireRedactor, body: &[u8]) -> String {
+    let end = body.len().min(MAX_TRACED_BODY);
+    let mut text = redactor.redact_body(&String::from_utf8_lossy(&body[..end]));
+    if end < body.len() {
+        text.push_str(&format!("... ({} bytes)", body.len()));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_redactor_hides_secrets() {
+        let redactor = DefaultRedactor;
+        assert_eq!(redactor.redact_header("authorization", "Bearer abc"), "<redacted>");
+        assert_eq!(
+            redactor.redact_header("content-type", "application/json"),
+            "application/json"
+        );
+    }
+
+    #[test]
+    fn bodies_are_truncated() {
+        struct Masking;
+        impl WireRedactor for Masking {
+            fn redact_body(&self, body: &str) -> String {
+                body.replace("secret", "******")
+            }
+        }
+
+        assert_eq!(
+            format_body(&Masking, b"{\"password\":\"secret\"}"),
+            "{\"password\":\"******\"}"
+        );
+
+        let body = vec![b'a'; MAX_TRACED_BODY + 1];
+        let traced = format_body(&DefaultRedactor, &body);
+        assert!(traced.ends_with(&format!("... ({} bytes)", MAX_TRACED_BODY + 1)));
+    }
+}
@@ -0,0 +1,139 @@
+#![warn(missing_docs)]
+#![deny(warnings)]
+//! Derive macro for the `RuntimeApiError` trait of the `lambda_runtime_client` crate.
+//! Enable the `derive` feature of `lambda_runtime_client` and use the macro through
+//! `lambda_runtime_client::error::LambdaError` rather than depending on this crate
+//! directly.
+//!
+//! The generated implementation uses the `Display` representation of the error as the
+//! error message. The error type is the name of the struct, or the name of the variant
+//! for enums, and can be overridden with `#[lambda_error(error_type = "...")]` on the
+//! type or on a variant. With `#[lambda_error(source_chain)]` on the type, the errors
+//! returned by `Error::source()` are reported as the causes of the error, see
+//! `ErrorResponse::builder()`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use lambda_runtime_client::error::{LambdaError, RuntimeApiError};
+//! use std::fmt;
+//!
+//! #[derive(Debug, LambdaError)]
+//! enum OrderError {
+//!     NotFound(String),
+//!     #[lambda_error(error_type = "Orders.Validation")]
+//!     Invalid { field: String },
+//! }
+//!
+//! impl fmt::Display for OrderError {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         match self {
+//!             OrderError::NotFound(id) => write!(f, "Order {} not found", id),
+//!             OrderError::Invalid { field } => write!(f, "Invalid field {}", field),
+//!         }
+//!     }
+//! }
+//!
+//! let response = OrderError::NotFound(String::from("123")).to_response();
+//! assert_eq!(response.error_type, "NotFound");
+//! assert_eq!(response.error_message, "Order 123 not found");
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, LitStr};
+
+/// Implements `lambda_runtime_client::error::RuntimeApiError` for a struct or enum.
+/// See the crate documentation for the supported attributes.
+#[proc_macro_derive(LambdaError, attributes(lambda_error))]
+pub fn derive_lambda_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// The options set with `#[lambda_error(...)]` attributes.
+#[derive(Default)]
+struct Options {
+    error_type: Option<String>,
+    source_chain: bool,
+}
+
+impl Options {
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Options> {
+        let mut options = Options::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("lambda_error")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("error_type") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    options.error_type = Some(value.value());
+                    Ok(())
+                } else if meta.path.is_ident("source_chain") {
+                    options.source_chain = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `error_type = \"...\"` or `source_chain`"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let options = Options::from_attrs(&input.attrs)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let error_type = match &input.data {
+        Data::Struct(_) => {
+            let error_type = options.error_type.clone().unwrap_or_else(|| name.to_string());
+            quote!(#error_type)
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let variant_options = Options::from_attrs(&variant.attrs)?;
+                if variant_options.source_chain {
+                    return Err(syn::Error::new_spanned(
+                        &variant.ident,
+                        "`source_chain` can only be set on the type",
+                    ));
+                }
+                let ident = &variant.ident;
+                let error_type = variant_options
+                    .error_type
+                    .or_else(|| options.error_type.clone())
+                    .unwrap_or_else(|| ident.to_string());
+                arms.push(quote!(#name::#ident { .. } => #error_type));
+            }
+            quote!(match self { #(#arms,)* })
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "LambdaError cannot be derived for unions",
+            ))
+        }
+    };
+
+    let source_chain = if options.source_chain {
+        quote!(let response = response.source_chain(self);)
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::lambda_runtime_client::error::RuntimeApiError for #name #ty_generics #where_clause {
+            fn to_response(&self) -> ::lambda_runtime_client::error::ErrorResponse {
+                let error_type: &str = #error_type;
+                let response = ::lambda_runtime_client::error::ErrorResponse::builder(
+                    ::std::string::ToString::to_string(self),
+                )
+                .error_type(error_type);
+                #source_chain
+                response.build()
+            }
+        }
+    })
+}
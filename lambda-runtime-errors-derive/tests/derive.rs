@@ -0,0 +1,69 @@
+use std::{error::Error, fmt};
+
+use lambda_runtime_client::error::{LambdaError, RuntimeApiError};
+
+#[derive(Debug, LambdaError)]
+struct ValidationError {
+    field: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid value for {}", self.field)
+    }
+}
+
+impl Error for ValidationError {}
+
+#[derive(Debug, LambdaError)]
+#[lambda_error(source_chain)]
+enum OrderError {
+    Timeout,
+    #[lambda_error(error_type = "Orders.Validation")]
+    Invalid(ValidationError),
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderError::Timeout => write!(f, "Order service timed out"),
+            OrderError::Invalid(_) => write!(f, "Invalid order"),
+        }
+    }
+}
+
+impl Error for OrderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OrderError::Timeout => None,
+            OrderError::Invalid(e) => Some(e),
+        }
+    }
+}
+
+#[test]
+fn struct_uses_type_name() {
+    let response = ValidationError {
+        field: String::from("name"),
+    }
+    .to_response();
+    assert_eq!(response.error_type, "ValidationError");
+    assert_eq!(response.error_message, "Invalid value for name");
+    assert!(response.stack_trace.is_none());
+}
+
+#[test]
+fn enum_uses_variant_name() {
+    let response = OrderError::Timeout.to_response();
+    assert_eq!(response.error_type, "Timeout");
+    assert_eq!(response.error_message, "Order service timed out");
+    assert!(response.stack_trace.is_none());
+
+    let response = OrderError::Invalid(ValidationError {
+        field: String::from("quantity"),
+    })
+    .to_response();
+    assert_eq!(response.error_type, "Orders.Validation");
+    assert_eq!(response.error_message, "Invalid order: Invalid value for quantity");
+    assert_eq!(response.causes[0].error_message, "Invalid value for quantity");
+}
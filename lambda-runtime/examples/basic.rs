@@ -3,7 +3,6 @@ use std::error::Error;
 use lambda_runtime::{error::HandlerError, lambda, Context};
 use log::{self, error};
 use serde_derive::{Deserialize, Serialize};
-use simple_logger;
 
 #[derive(Deserialize)]
 struct CustomEvent {
@@ -24,7 +23,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 fn my_handler(e: CustomEvent, c: Context) -> Result<CustomOutput, HandlerError> {
-    if e.first_name == "" {
+    if e.first_name.is_empty() {
         error!("Empty first name in request {}", c.aws_request_id);
         return Err(c.new_error("Empty first name"));
     }
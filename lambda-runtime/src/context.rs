@@ -1,10 +1,15 @@
-use std::env;
+use std::{future::Future, time::Duration};
 
-use backtrace;
 use chrono::Utc;
-use lambda_runtime_client;
+use lambda_runtime_client::{
+    http::{Extensions, HeaderMap},
+    TraceContext,
+};
 
-use crate::{env as lambda_env, error::HandlerError};
+use crate::{
+    env as lambda_env,
+    error::{BudgetExceeded, HandlerError},
+};
 
 /// The Lambda function execution context. The values in this struct
 /// are populated using the [Lambda environment variables](https://docs.aws.amazon.com/lambda/latest/dg/current-supported-versions.html)
@@ -57,10 +62,29 @@ pub struct Context {
     /// request was performed with AWS credentials federated through the Cognito
     /// identity service.
     pub identity: Option<lambda_runtime_client::CognitoIdentity>,
+    /// The raw client context header, available even when it could not be
+    /// parsed into `client_context`.
+    pub raw_client_context: Option<String>,
+    /// The raw Cognito identity header, available even when it could not be
+    /// parsed into `identity`.
+    pub raw_identity: Option<String>,
+    /// The id of the tenant the invocation belongs to. This value is returned
+    /// by the Lambda Runtime APIs as a header and it's only populated if the
+    /// function uses tenant isolation mode.
+    pub tenant_id: Option<String>,
 
     /// The deadline for the current handler execution in milliseconds, based
     /// on a unix `MONOTONIC` clock.
     pub deadline: i64,
+
+    /// All of the headers returned by the Runtime APIs with the event, including
+    /// headers that do not have a typed field in this struct yet.
+    pub headers: HeaderMap,
+
+    /// Typed map of values attached to the invocation, for example by middleware that
+    /// authenticates the caller or assigns a correlation id. Values are retrieved by type
+    /// with `extensions.get::<T>()`.
+    pub extensions: Extensions,
 }
 
 impl Context {
@@ -89,14 +113,7 @@ impl Context {
     /// clients should use to retrieve an initialized `RuntimeError` with the populated
     /// stack trace.
     pub fn new_error(&self, msg: &str) -> HandlerError {
-        let mut trace: Option<backtrace::Backtrace> = None;
-        let is_backtrace = env::var("RUST_BACKTRACE");
-        if is_backtrace.is_ok() && is_backtrace.unwrap() == "1" {
-            trace!("Begin backtrace collection");
-            trace = Option::from(backtrace::Backtrace::new());
-            trace!("Completed backtrace collection");
-        }
-        HandlerError::new(msg, trace)
+        HandlerError::new(msg)
     }
 
     /// Returns the remaining time in the execution in milliseconds. This is based on the
@@ -104,6 +121,82 @@ impl Context {
     pub fn get_time_remaining_millis(&self) -> i64 {
         self.deadline - Utc::now().timestamp_millis()
     }
+
+    /// Returns the X-Ray trace context of the invocation, parsed from `xray_trace_id`.
+    ///
+    /// # Return
+    /// The `TraceContext`, or `None` if the trace id is empty or cannot be parsed.
+    pub fn trace_context(&self) -> Option<TraceContext> {
+        TraceContext::parse(&self.xray_trace_id).ok()
+    }
+
+    /// Returns the remaining time in the execution as a `Duration`, or a zero `Duration`
+    /// if the deadline has passed. Use this to budget the timeouts of downstream calls.
+    pub fn remaining_time(&self) -> Duration {
+        Duration::from_millis(self.get_time_remaining_millis().max(0) as u64)
+    }
+
+    /// Wraps a future with a timeout that expires after `duration`, or when the
+    /// invocation runs out of time if that is sooner. The returned future must be
+    /// polled within a tokio runtime with the time driver enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` The maximum time the future may take.
+    /// * `future` The future to run.
+    ///
+    /// # Return
+    /// A future that resolves to the output of `future`, or to a `BudgetExceeded`
+    /// error if it did not complete in time.
+    pub fn timeout<F: Future>(
+        &self,
+        duration: Duration,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, BudgetExceeded>> {
+        let budget = duration.min(self.remaining_time());
+        async move {
+            tokio::time::timeout(budget, future)
+                .await
+                .map_err(|_| BudgetExceeded::new(budget))
+        }
+    }
+
+    /// Wraps a future with a timeout that expires after a fraction of the remaining
+    /// invocation time, leaving the rest of the time for cleanup before Lambda stops
+    /// the invocation. The returned future must be polled within a tokio runtime with
+    /// the time driver enabled.
+    ///
+    /// ```rust,no_run
+    /// # use lambda_runtime::{error::HandlerError, Context};
+    /// # async fn fetch_order() -> String { String::new() }
+    /// # async fn handle(ctx: Context) -> Result<String, HandlerError> {
+    /// // give the call 80% of the remaining time
+    /// let order = ctx.with_budget(0.8, fetch_order()).await.map_err(HandlerError::from_err)?;
+    /// # Ok(order)
+    /// # }
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` The share of the remaining time the future may take, between `0.0`
+    ///   and `1.0`. Values outside this range are clamped.
+    /// * `future` The future to run.
+    ///
+    /// # Return
+    /// A future that resolves to the output of `future`, or to a `BudgetExceeded`
+    /// error if it did not complete in time.
+    pub fn with_budget<F: Future>(
+        &self,
+        fraction: f64,
+        future: F,
+    ) -> impl Future<Output = Result<F::Output, BudgetExceeded>> {
+        let fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        self.timeout(self.remaining_time().mul_f64(fraction), future)
+    }
 }
 
 #[cfg(test)]
@@ -129,7 +222,12 @@ pub(crate) mod tests {
             log_group_name: "logGroup".to_string(),
             client_context: Option::default(),
             identity: Option::default(),
+            raw_client_context: Option::default(),
+            raw_identity: Option::default(),
+            tenant_id: Option::default(),
             deadline: get_deadline(timeout_secs),
+            headers: HeaderMap::new(),
+            extensions: Extensions::new(),
         }
     }
 
@@ -147,5 +245,47 @@ pub(crate) mod tests {
             "Remaining time in millis outside the expected range: {}",
             remaining
         );
+        assert!(ctx.remaining_time() <= time::Duration::from_millis(8200));
+
+        ctx.deadline = get_deadline(-1);
+        assert_eq!(ctx.remaining_time(), time::Duration::from_millis(0));
+    }
+
+    #[test]
+    fn parses_trace_context() {
+        let mut ctx = test_context(10);
+        ctx.xray_trace_id = "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1".to_string();
+        let trace = ctx.trace_context().expect("Trace context should parse");
+        assert_eq!(trace.trace_id(), "1-5759e988-bd862e3fe1be46a994272793");
+        assert_eq!(trace.parent_id(), Some("53995c3f42cd8ad8"));
+        assert_eq!(trace.sampled(), Some(true));
+
+        ctx.xray_trace_id = String::new();
+        assert_eq!(ctx.trace_context(), None);
+    }
+
+    #[test]
+    fn futures_are_bounded_by_remaining_time() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("Could not create tokio runtime");
+        let ctx = test_context(10);
+
+        let result = rt.block_on(ctx.timeout(time::Duration::from_secs(1), async { 42 }));
+        assert_eq!(result, Ok(42));
+
+        let slow = async { tokio::time::sleep(time::Duration::from_secs(5)).await };
+        let err = rt
+            .block_on(ctx.timeout(time::Duration::from_millis(10), slow))
+            .expect_err("Sleep should time out");
+        assert_eq!(err.budget(), time::Duration::from_millis(10));
+
+        let ctx = test_context(-1);
+        let slow = async { tokio::time::sleep(time::Duration::from_secs(5)).await };
+        let err = rt
+            .block_on(ctx.with_budget(0.5, slow))
+            .expect_err("Sleep should time out");
+        assert_eq!(err.budget(), time::Duration::from_millis(0));
     }
 }
@@ -7,6 +7,11 @@ use crate::error::RuntimeError;
 /// variable is read once as the runtime starts.
 pub const RUNTIME_ENDPOINT_VAR: &str = "AWS_LAMBDA_RUNTIME_API";
 
+/// The name of the environment variable that AWS SDKs and X-Ray clients read
+/// the trace header of the current invocation from. The runtime updates it
+/// at the start of each invocation, as the managed runtimes do.
+pub const TRACE_ID_VAR: &str = "_X_AMZN_TRACE_ID";
+
 /// Clone-able generic function settings object. The data is loaded
 /// from environment variables during the init process. The data
 /// for the object is cloned in the `Context` for each invocation.
@@ -55,9 +60,8 @@ impl ConfigProvider for EnvConfigProvider {
         let log_group = env::var("AWS_LAMBDA_LOG_GROUP_NAME")?;
         let memory_str = env::var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE")?;
         let parsed_memory_str = memory_str.parse::<i32>();
-        let memory_size: i32;
-        match parsed_memory_str {
-            Ok(int_value) => memory_size = int_value,
+        let memory_size: i32 = match parsed_memory_str {
+            Ok(int_value) => int_value,
             Err(_parse_err) => {
                 error!(
                     "Memory value from environment is not i32: {}",
@@ -86,10 +90,22 @@ impl ConfigProvider for EnvConfigProvider {
     }
 }
 
+/// Sets the `_X_AMZN_TRACE_ID` environment variable to the trace header of the
+/// invocation that is about to start. The variable is removed when the Runtime
+/// APIs did not send a trace header, so that the previous invocation's trace is
+/// not picked up.
+pub(crate) fn set_trace_id(trace_id: &str) {
+    if trace_id.is_empty() {
+        env::remove_var(TRACE_ID_VAR);
+    } else {
+        env::set_var(TRACE_ID_VAR, trace_id);
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use crate::{env::*, error};
-    use std::{env, error::Error};
+    use std::env;
 
     pub(crate) struct MockConfigProvider {
         pub(crate) error: bool,
@@ -147,11 +163,10 @@ pub(crate) mod tests {
         set_lambda_env_vars();
         let config_provider: &dyn ConfigProvider = &EnvConfigProvider {};
         let env_settings = config_provider.get_function_settings();
-        assert_eq!(
-            env_settings.is_err(),
-            false,
+        assert!(
+            env_settings.is_ok(),
             "Env settings returned an error: {}",
-            env_settings.err().unwrap().description()
+            env_settings.err().unwrap()
         );
         let settings = env_settings.unwrap();
         assert_eq!(
@@ -160,11 +175,10 @@ pub(crate) mod tests {
             settings.memory_size
         );
         let endpoint = config_provider.get_runtime_api_endpoint();
-        assert_eq!(
-            endpoint.is_err(),
-            false,
+        assert!(
+            endpoint.is_ok(),
             "Env endpoint returned an error: {}",
-            endpoint.err().unwrap().description()
+            endpoint.err().unwrap()
         );
 
         unset_env_vars();
@@ -179,4 +193,15 @@ pub(crate) mod tests {
             "Env endpoint did not return error without variables"
         );
     }
+
+    #[test]
+    fn trace_id_is_propagated() {
+        set_trace_id("Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=1");
+        assert_eq!(
+            env::var(TRACE_ID_VAR).expect("Trace id was not set"),
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=1"
+        );
+        set_trace_id("");
+        assert!(env::var(TRACE_ID_VAR).is_err(), "Trace id was not cleared");
+    }
 }
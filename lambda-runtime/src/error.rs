@@ -1,10 +1,88 @@
 //! The error module defines the error types that can be returned
 //! by custom handlers as well as the runtime itself.
-use std::{cmp, env, error::Error, fmt};
+use std::{cmp, env, error::Error, fmt, time::Duration};
 
 use backtrace;
 use lambda_runtime_client::error;
-use serde_json;
+
+use crate::panic::PanicReport;
+use serde::Serialize;
+use serde_json::{self, Map, Value};
+
+/// Error type reported when the event payload cannot be deserialized into the
+/// event type of the handler.
+pub const ERROR_TYPE_DESERIALIZATION: &str = "Runtime.DeserializationError";
+/// Error type reported for errors returned by the handler that are not classified
+/// by their type, such as the errors created with `Context::new_error()`.
+pub const ERROR_TYPE_HANDLER: &str = "Runtime.HandlerError";
+/// Error type reported when the handler panics.
+pub const ERROR_TYPE_PANIC: &str = "Runtime.HandlerPanic";
+/// Error type reported instead of invoking the handler when an event repeatedly
+/// crashed the runtime process.
+pub const ERROR_TYPE_POISON_EVENT: &str = "Runtime.PoisonEvent";
+/// Error type reported when the runtime cannot communicate with the Runtime APIs.
+pub const ERROR_TYPE_TRANSPORT: &str = "Runtime.TransportError";
+
+/// Collects a backtrace of the current thread when the `RUST_BACKTRACE` environment
+/// variable is set to anything other than `0`, following the convention of the standard
+/// library.
+pub(crate) fn collect_backtrace() -> Option<backtrace::Backtrace> {
+    match env::var("RUST_BACKTRACE") {
+        Ok(ref value) if value != "0" => {
+            trace!("Begin backtrace collection");
+            let trace = backtrace::Backtrace::new();
+            trace!("Completed backtrace collection");
+            Some(trace)
+        }
+        _ => None,
+    }
+}
+
+/// Renders the resolved frames of a backtrace into the lines of the `stack_trace` of an
+/// `ErrorResponse`, one line per symbol formatted as `function (file:line)`. Frames of the
+/// backtrace collection itself are skipped, as are frames that could not be resolved.
+fn stack_trace_lines(trace: &backtrace::Backtrace) -> Vec<String> {
+    trace
+        .frames()
+        .iter()
+        .flat_map(|frame| frame.symbols())
+        .filter_map(|symbol| {
+            let name = symbol.name()?.to_string();
+            let location = match (symbol.filename(), symbol.lineno()) {
+                (Some(file), Some(line)) => format!(" ({}:{})", file.display(), line),
+                _ => String::new(),
+            };
+            Some(format!("{}{}", name, location))
+        })
+        .skip_while(|line| line.starts_with("backtrace::") || line.contains("collect_backtrace"))
+        .collect()
+}
+
+/// Returned by the futures created with `Context::timeout()` and `Context::with_budget()`
+/// when the wrapped future does not complete within its time budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetExceeded {
+    budget: Duration,
+}
+
+impl BudgetExceeded {
+    pub(crate) fn new(budget: Duration) -> BudgetExceeded {
+        BudgetExceeded { budget }
+    }
+
+    /// Returns the time budget the future did not complete in.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Operation did not complete within {}ms", self.budget.as_millis())
+    }
+}
+
+impl Error for BudgetExceeded {}
 
 /// The `RuntimeError` object is returned by the custom runtime as it polls
 /// for new events and tries to execute the handler function. The error
@@ -14,6 +92,7 @@ use serde_json;
 #[derive(Debug, Clone)]
 pub struct RuntimeError {
     msg: String,
+    error_type: &'static str,
     stack_trace: Option<backtrace::Backtrace>,
     /// The request id that generated this error
     pub(crate) request_id: Option<String>,
@@ -39,6 +118,23 @@ impl RuntimeError {
         new_error
     }
 
+    /// Creates the error reported for an event that crashed the runtime process more
+    /// times than the crash limit allows. The handler is not invoked for the event.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` The request id of the event.
+    /// * `crashes` The number of times the event crashed the runtime.
+    pub(crate) fn poison_event(request_id: &str, crashes: u32) -> RuntimeError {
+        let mut err = RuntimeError::new(&format!(
+            "Event {} crashed the runtime {} times and was not processed again",
+            request_id, crashes
+        ));
+        err.error_type = ERROR_TYPE_POISON_EVENT;
+        err.request_id = Some(String::from(request_id));
+        err
+    }
+
     /// Creates a new `RuntimeError` with the given properties. The stack trace for the
     /// error is collected automatically using the `backtrace` crate.
     ///
@@ -49,16 +145,10 @@ impl RuntimeError {
     /// # Returns
     /// A new `RuntimeError` instance.
     pub(crate) fn new(msg: &str) -> RuntimeError {
-        let mut trace: Option<backtrace::Backtrace> = None;
-        let is_backtrace = env::var("RUST_BACKTRACE");
-        if is_backtrace.is_ok() && is_backtrace.unwrap() == "1" {
-            trace!("Begin backtrace collection");
-            trace = Option::from(backtrace::Backtrace::new());
-            trace!("Completed backtrace collection");
-        }
         RuntimeError {
             msg: String::from(msg),
-            stack_trace: trace,
+            error_type: error::ERROR_TYPE_HANDLED,
+            stack_trace: collect_backtrace(),
             recoverable: true,
             request_id: None,
         }
@@ -67,11 +157,11 @@ impl RuntimeError {
 
 impl error::RuntimeApiError for RuntimeError {
     fn to_response(&self) -> error::ErrorResponse {
-        let backtrace = format!("{:?}", self.stack_trace);
         error::ErrorResponse {
-            error_message: String::from(self.description()),
-            error_type: String::from(error::ERROR_TYPE_HANDLED),
-            stack_trace: Option::from(backtrace.lines().map(|s| s.to_string()).collect::<Vec<String>>()),
+            error_message: self.msg.clone(),
+            error_type: String::from(self.error_type),
+            stack_trace: self.stack_trace.as_ref().map(stack_trace_lines),
+            ..Default::default()
         }
     }
 }
@@ -96,35 +186,62 @@ impl Error for RuntimeError {
 
 impl From<env::VarError> for RuntimeError {
     fn from(e: env::VarError) -> Self {
-        RuntimeError::unrecoverable(e.description())
+        RuntimeError::unrecoverable(&e.to_string())
     }
 }
 
 impl From<serde_json::Error> for RuntimeError {
     fn from(e: serde_json::Error) -> Self {
-        RuntimeError::unrecoverable(e.description())
+        let mut err = RuntimeError::unrecoverable(&e.to_string());
+        err.error_type = ERROR_TYPE_DESERIALIZATION;
+        err
     }
 }
 
 impl From<error::ApiError> for RuntimeError {
     fn from(e: error::ApiError) -> Self {
-        let mut err = RuntimeError::new(e.description());
-        err.recoverable = e.recoverable;
-        err.stack_trace = e.backtrace;
+        let mut err = RuntimeError::new(&e.to_string());
+        err.error_type = ERROR_TYPE_TRANSPORT;
+        err.recoverable = e.is_recoverable();
         err
     }
 }
 
 /// The error type for functions that are used as the `Handler` type. New errors
 /// should be instantiated using the `new_error()` method  of the `runtime::Context`
-/// object passed to the handler function.
+/// object passed to the handler function, or converted from any other error with
+/// `HandlerError::from_err()`:
+///
+/// ```rust
+/// use lambda_runtime::{error::HandlerError, Context};
+///
+/// fn my_handler(e: String, _c: Context) -> Result<i32, HandlerError> {
+///     let value: i32 = e.parse().map_err(HandlerError::from_err)?;
+///     Ok(value * 2)
+/// }
+/// ```
+///
+/// `HandlerError` implements `std::error::Error` so that other errors can wrap it,
+/// which rules out a blanket `From` implementation for all error types.
 ///
 /// An implementation of `PartialEq` is provided and based it's comparison on the `msg`
 /// field.
 #[derive(Debug, Clone)]
 pub struct HandlerError {
     msg: String,
-    backtrace: Option<backtrace::Backtrace>,
+    error_type: String,
+    causes: Vec<String>,
+    backtrace: Option<StackTrace>,
+    details: Map<String, Value>,
+}
+
+/// The stack trace attached to a `HandlerError`.
+#[derive(Debug, Clone)]
+enum StackTrace {
+    /// A backtrace collected by this crate, resolved when the error is reported.
+    Captured(backtrace::Backtrace),
+    /// A backtrace collected by another error library, already rendered into lines.
+    Rendered(Vec<String>),
 }
 
 impl cmp::PartialEq for HandlerError {
@@ -152,48 +269,489 @@ impl Error for HandlerError {
 }
 
 impl HandlerError {
-    /// Creates a new handler error. This method is used by the `new_error()` method
-    /// of the `runtime::Context` object.
+    /// Creates a new handler error with the `ERROR_TYPE_HANDLER` error type. A backtrace
+    /// is collected for the stack trace of the error response when `RUST_BACKTRACE` is set.
+    /// Use `with_error_type()` to classify the error.
+    ///
+    /// ```rust
+    /// use lambda_runtime::error::HandlerError;
+    ///
+    /// let err = HandlerError::new("Order 123 not found").with_error_type("Orders.NotFound");
+    /// assert_eq!(err.error_type(), "Orders.NotFound");
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` The error message for the new error
+    ///
+    /// # Returns
+    /// A new `HandlerError` instance.
+    pub fn new(msg: &str) -> HandlerError {
+        HandlerError::with_trace(msg, collect_backtrace())
+    }
+
+    /// Creates a new handler error with the given backtrace.
     ///
     /// # Arguments
     ///
     /// * `msg` The error message for the new error
     /// * `trace` A `Backtrace` object to generate the stack trace for the error
-    ///           response. This is provided by the `Context` object.
-    pub(crate) fn new(msg: &str, trace: Option<backtrace::Backtrace>) -> HandlerError {
+    ///   response.
+    pub(crate) fn with_trace(msg: &str, trace: Option<backtrace::Backtrace>) -> HandlerError {
         HandlerError {
             msg: msg.to_string(),
-            backtrace: trace,
+            error_type: String::from(ERROR_TYPE_HANDLER),
+            causes: Vec::new(),
+            backtrace: trace.map(StackTrace::Captured),
+            details: Map::new(),
+        }
+    }
+
+    /// Converts any error into a handler error. The message of the handler error is the
+    /// `Display` representation of `e` and the error type reported to the Runtime APIs is
+    /// the name of the type of `e`, for example `ParseIntError`. The messages of the errors
+    /// returned by `source()` are kept as the cause chain, and a backtrace is collected
+    /// when `RUST_BACKTRACE` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` The error to convert
+    ///
+    /// # Returns
+    /// A new `HandlerError` instance.
+    pub fn from_err<E: Error>(e: E) -> HandlerError {
+        HandlerError {
+            msg: e.to_string(),
+            error_type: short_type_name(std::any::type_name::<E>()),
+            causes: error::source_chain(&e),
+            backtrace: collect_backtrace().map(StackTrace::Captured),
+            details: Map::new(),
+        }
+    }
+
+    /// Converts an error that classifies itself with the `ErrorType` trait into a handler
+    /// error. This works like `from_err()`, except that the error type reported to the
+    /// Runtime APIs is the one returned by `ErrorType::error_type()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` The error to convert
+    ///
+    /// # Returns
+    /// A new `HandlerError` instance.
+    pub fn from_classified_err<E: Error + ErrorType>(e: E) -> HandlerError {
+        let error_type = String::from(e.error_type());
+        let mut err = HandlerError::from_err(e);
+        err.error_type = error_type;
+        err
+    }
+
+    /// Creates the error reported to the Runtime APIs when the handler panics. The
+    /// stack trace starts with the location of the panic, followed by the backtrace
+    /// recorded by the panic hook.
+    pub(crate) fn from_panic(report: PanicReport) -> HandlerError {
+        let mut lines: Vec<String> = report
+            .location
+            .iter()
+            .map(|location| format!("panicked at {}", location))
+            .collect();
+        if let Some(trace) = &report.backtrace {
+            lines.extend(stack_trace_lines(trace));
+        }
+        HandlerError {
+            msg: report.message,
+            error_type: String::from(ERROR_TYPE_PANIC),
+            causes: Vec::new(),
+            backtrace: Some(StackTrace::Rendered(lines)),
+            details: Map::new(),
+        }
+    }
+
+    /// Adds the fields of a value that serializes to a JSON object to the error body
+    /// posted to the Runtime APIs, next to the error message and type. Lambda passes the
+    /// body on to Destinations and dead-letter queues, so the details can carry
+    /// correlation ids or other diagnostic values for their consumers.
+    ///
+    /// # Arguments
+    ///
+    /// * `details` The additional fields, for example a struct with a correlation id.
+    ///
+    /// # Returns
+    /// The `HandlerError` with the given details.
+    pub fn with_details<T: Serialize>(mut self, details: &T) -> HandlerError {
+        match serde_json::to_value(details) {
+            Ok(Value::Object(details)) => self.details.extend(details),
+            Ok(other) => warn!("Error details must serialize to a JSON object, ignoring {}", other),
+            Err(e) => warn!("Could not serialize error details: {}", e),
+        }
+        self
+    }
+
+    /// Sets the error type reported to the Runtime APIs, for example `ValidationError` or
+    /// `MyApp.ValidationError`.
+    ///
+    /// # Arguments
+    ///
+    /// * `error_type` The class of the error.
+    ///
+    /// # Returns
+    /// The `HandlerError` with the given error type.
+    pub fn with_error_type(mut self, error_type: &str) -> HandlerError {
+        self.error_type = String::from(error_type);
+        self
+    }
+
+    /// Returns the error type reported to the Runtime APIs for this error.
+    pub fn error_type(&self) -> &str {
+        &self.error_type
+    }
+}
+
+#[cfg(feature = "rusoto")]
+impl<E: Error + 'static> From<rusoto_core::RusotoError<E>> for HandlerError {
+    /// Converts an error returned by a rusoto client into a handler error, so that handlers
+    /// calling AWS services can use `?`. The error type tells apart the kinds of failures:
+    /// service errors are reported with the name of the service error type, for example
+    /// `GetItemError`, throttled requests as `Rusoto.ThrottlingError`, and credential,
+    /// dispatch, validation, and parsing failures as `Rusoto.CredentialsError`,
+    /// `Rusoto.HttpDispatchError`, `Rusoto.ValidationError`, and `Rusoto.ParseError`.
+    fn from(e: rusoto_core::RusotoError<E>) -> Self {
+        use rusoto_core::RusotoError;
+
+        let error_type = match &e {
+            RusotoError::Service(_) => short_type_name(std::any::type_name::<E>()),
+            RusotoError::Unknown(response) if is_throttling(response.status.as_u16(), response.body_as_str()) => {
+                String::from("Rusoto.ThrottlingError")
+            }
+            RusotoError::Unknown(_) => String::from("Rusoto.UnknownError"),
+            RusotoError::HttpDispatch(_) => String::from("Rusoto.HttpDispatchError"),
+            RusotoError::Credentials(_) => String::from("Rusoto.CredentialsError"),
+            RusotoError::Validation(_) => String::from("Rusoto.ValidationError"),
+            RusotoError::ParseError(_) => String::from("Rusoto.ParseError"),
+            RusotoError::Blocking => String::from("Rusoto.BlockingError"),
+        };
+        HandlerError {
+            msg: e.to_string(),
+            error_type,
+            causes: error::source_chain(&e),
+            backtrace: collect_backtrace().map(StackTrace::Captured),
+            details: Map::new(),
         }
     }
 }
 
+/// Returns whether an AWS response that rusoto could not parse reports that the
+/// request was throttled.
+#[cfg(feature = "rusoto")]
+fn is_throttling(status: u16, body: &str) -> bool {
+    const THROTTLING_CODES: &[&str] = &[
+        "Throttling",
+        "ThrottlingException",
+        "ThrottledException",
+        "TooManyRequestsException",
+        "RequestLimitExceeded",
+        "ProvisionedThroughputExceededException",
+        "SlowDown",
+    ];
+    status == 429 || THROTTLING_CODES.iter().any(|code| body.contains(code))
+}
+
+/// Classifies errors for Lambda's error metrics and filters. The error type is sent in the
+/// `errorType` field of the error response and in the `Lambda-Runtime-Function-Error-Type`
+/// header. Errors converted with `HandlerError::from_err()` are classified by the name of
+/// their type; implement this trait and use `HandlerError::from_classified_err()` to
+/// report a different type, for example one per enum variant:
+///
+/// ```rust
+/// use lambda_runtime::error::{ErrorType, HandlerError};
+/// use std::{error::Error, fmt};
+///
+/// #[derive(Debug)]
+/// enum OrderError {
+///     Timeout,
+///     Validation(String),
+/// }
+///
+/// impl fmt::Display for OrderError {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         match self {
+///             OrderError::Timeout => write!(f, "Order service timed out"),
+///             OrderError::Validation(field) => write!(f, "Invalid field {}", field),
+///         }
+///     }
+/// }
+///
+/// impl Error for OrderError {}
+///
+/// impl ErrorType for OrderError {
+///     fn error_type(&self) -> &str {
+///         match self {
+///             OrderError::Timeout => "TimeoutError",
+///             OrderError::Validation(_) => "ValidationError",
+///         }
+///     }
+/// }
+///
+/// let err = HandlerError::from_classified_err(OrderError::Timeout);
+/// assert_eq!(err.error_type(), "TimeoutError");
+/// ```
+pub trait ErrorType {
+    /// Returns the error type reported to the Runtime APIs, for example `ValidationError`
+    /// or `MyApp.ValidationError`.
+    fn error_type(&self) -> &str;
+}
+
+/// Boxed errors are converted with their cause chain, so that handlers using anyhow-style
+/// error handling can return them with `?`. The error type is `ERROR_TYPE_HANDLER`
+/// because the concrete type of the error is not known.
+impl From<Box<dyn Error + Send + Sync>> for HandlerError {
+    fn from(e: Box<dyn Error + Send + Sync>) -> Self {
+        HandlerError {
+            msg: e.to_string(),
+            error_type: String::from(ERROR_TYPE_HANDLER),
+            causes: error::source_chain(e.as_ref()),
+            backtrace: collect_backtrace().map(StackTrace::Captured),
+            details: Map::new(),
+        }
+    }
+}
+
+impl From<Box<dyn Error>> for HandlerError {
+    fn from(e: Box<dyn Error>) -> Self {
+        HandlerError {
+            msg: e.to_string(),
+            error_type: String::from(ERROR_TYPE_HANDLER),
+            causes: error::source_chain(e.as_ref()),
+            backtrace: collect_backtrace().map(StackTrace::Captured),
+            details: Map::new(),
+        }
+    }
+}
+
+impl From<String> for HandlerError {
+    fn from(msg: String) -> Self {
+        HandlerError::new(&msg)
+    }
+}
+
+impl From<&str> for HandlerError {
+    fn from(msg: &str) -> Self {
+        HandlerError::new(msg)
+    }
+}
+
+/// Returns the name of a type without its module path or generic parameters,
+/// for example `ParseIntError` for `core::num::error::ParseIntError`.
+fn short_type_name(full_name: &str) -> String {
+    let base_name = full_name.split('<').next().unwrap_or(full_name);
+    String::from(base_name.rsplit("::").next().unwrap_or(base_name))
+}
+
 impl error::RuntimeApiError for HandlerError {
     fn to_response(&self) -> error::ErrorResponse {
-        let backtrace = format!("{:?}", self.backtrace);
-        error::ErrorResponse {
-            error_message: String::from(self.description()),
-            error_type: String::from(error::ERROR_TYPE_HANDLED),
-            stack_trace: Option::from(backtrace.lines().map(|s| s.to_string()).collect::<Vec<String>>()),
+        let mut response = error::ErrorResponse::builder(self.msg.as_str()).error_type(self.error_type.as_str());
+        for cause in &self.causes {
+            response = response.cause(cause.as_str());
+        }
+        match &self.backtrace {
+            Some(StackTrace::Captured(trace)) => response = response.stack_trace(stack_trace_lines(trace)),
+            Some(StackTrace::Rendered(lines)) => response = response.stack_trace(lines.clone()),
+            None => {}
+        }
+        for (name, value) in &self.details {
+            response = response.detail(name.as_str(), value.clone());
+        }
+        response.build()
+    }
+}
+
+#[cfg(feature = "failure")]
+impl From<failure::Error> for HandlerError {
+    /// Converts a `failure::Error` into a handler error. The error type is the name of
+    /// the type of the underlying `Fail`, the messages of its causes are kept in the
+    /// stack trace of the error response, followed by the backtrace collected by
+    /// `failure` when `RUST_BACKTRACE` is set.
+    fn from(e: failure::Error) -> Self {
+        let backtrace = e.backtrace().to_string();
+        HandlerError {
+            msg: e.to_string(),
+            error_type: e
+                .as_fail()
+                .name()
+                .map(short_type_name)
+                .unwrap_or_else(|| String::from(ERROR_TYPE_HANDLER)),
+            causes: e.iter_causes().map(|c| c.to_string()).collect(),
+            backtrace: if backtrace.is_empty() {
+                None
+            } else {
+                Some(StackTrace::Rendered(backtrace.lines().map(String::from).collect()))
+            },
+            details: Map::new(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::HandlerError;
+    use super::{
+        HandlerError, RuntimeError, StackTrace, ERROR_TYPE_DESERIALIZATION, ERROR_TYPE_HANDLER, ERROR_TYPE_TRANSPORT,
+    };
+    use lambda_runtime_client::error::{ApiError, RuntimeApiError, ERROR_TYPE_HANDLED};
 
     #[test]
     fn handler_error_impls_partialeq() {
         assert_eq!(
             HandlerError {
                 msg: "test".into(),
-                backtrace: Default::default()
+                error_type: ERROR_TYPE_HANDLED.into(),
+                causes: Vec::new(),
+                backtrace: Default::default(),
+                details: Default::default()
             },
             HandlerError {
                 msg: "test".into(),
-                backtrace: Some(Default::default())
+                error_type: ERROR_TYPE_HANDLED.into(),
+                causes: Vec::new(),
+                backtrace: Some(StackTrace::Captured(Default::default())),
+                details: Default::default()
             }
         )
     }
+
+    #[test]
+    fn handler_error_with_details() {
+        let err = HandlerError::from("declined")
+            .with_details(&serde_json::json!({ "correlationId": "c-42", "retryable": false }));
+        let body = serde_json::to_value(err.to_response()).expect("Could not serialize error");
+        assert_eq!(body["errorMessage"], "declined");
+        assert_eq!(body["correlationId"], "c-42");
+        assert_eq!(body["retryable"], false);
+    }
+
+    #[test]
+    fn handler_error_with_error_type() {
+        let err = HandlerError::new("Order 123 not found").with_error_type("Orders.NotFound");
+        let response = err.to_response();
+        assert_eq!(response.error_message, "Order 123 not found");
+        assert_eq!(response.error_type, "Orders.NotFound");
+        assert_eq!(HandlerError::from("Order 123 not found"), err);
+    }
+
+    #[test]
+    fn handler_error_from_err() {
+        let parse_err = "abc".parse::<i32>().expect_err("Parsing should fail");
+        let err = HandlerError::from_err(parse_err.clone());
+        assert_eq!(err.to_string(), parse_err.to_string());
+        assert_eq!(err.error_type(), "ParseIntError");
+        assert_eq!(err.to_response().error_type, "ParseIntError");
+    }
+
+    #[test]
+    fn handler_error_from_boxed_error() {
+        #[derive(Debug)]
+        struct ConfigError(std::num::ParseIntError);
+
+        impl std::fmt::Display for ConfigError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "Invalid timeout setting")
+            }
+        }
+
+        impl std::error::Error for ConfigError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        fn parse(value: &str) -> Result<i32, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(value.parse::<i32>().map_err(ConfigError)?)
+        }
+
+        fn handler(value: &str) -> Result<i32, HandlerError> {
+            Ok(parse(value)?)
+        }
+
+        let err = handler("abc").expect_err("Handler should fail");
+        assert_eq!(err.error_type(), ERROR_TYPE_HANDLER);
+        assert_eq!(err.to_string(), "Invalid timeout setting");
+        assert_eq!(
+            err.to_response().stack_trace.expect("Missing stack trace")[0],
+            "caused by: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn runtime_errors_are_categorized() {
+        let parse_err = serde_json::from_str::<i32>("\"abc\"").expect_err("Parsing should fail");
+        assert_eq!(
+            RuntimeError::from(parse_err).to_response().error_type,
+            ERROR_TYPE_DESERIALIZATION
+        );
+        let api_err = ApiError::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert_eq!(
+            RuntimeError::from(api_err).to_response().error_type,
+            ERROR_TYPE_TRANSPORT
+        );
+        assert_eq!(
+            HandlerError::from(String::from("test")).to_response().error_type,
+            ERROR_TYPE_HANDLER
+        );
+    }
+
+    #[test]
+    fn backtrace_is_resolved_into_stack_trace() {
+        let err = HandlerError::with_trace("test", Some(backtrace::Backtrace::new()));
+        let stack_trace = err.to_response().stack_trace.expect("Missing stack trace");
+        assert!(stack_trace
+            .iter()
+            .any(|line| line.contains("backtrace_is_resolved_into_stack_trace")));
+
+        let err = HandlerError::with_trace("test", None);
+        assert_eq!(err.to_response().stack_trace, None);
+    }
+
+    #[cfg(feature = "failure")]
+    #[test]
+    fn handler_error_from_failure() {
+        use failure::{Fail, ResultExt};
+
+        #[derive(Debug)]
+        struct ConfigError;
+
+        impl std::fmt::Display for ConfigError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "Missing configuration")
+            }
+        }
+
+        impl Fail for ConfigError {}
+
+        let result: Result<(), failure::Error> = Err(ConfigError)
+            .context("Could not start the service")
+            .map_err(failure::Error::from);
+        let err = HandlerError::from(result.expect_err("Result should be an error"));
+        assert_eq!(err.to_string(), "Could not start the service");
+        let response = err.to_response();
+        let stack_trace = response.stack_trace.expect("Missing stack trace");
+        assert_eq!(stack_trace[0], "caused by: Missing configuration");
+    }
+
+    #[cfg(feature = "rusoto")]
+    #[test]
+    fn handler_error_from_rusoto() {
+        use rusoto_core::{credential::CredentialsError, RusotoError};
+
+        let err = HandlerError::from(RusotoError::<std::num::ParseIntError>::Credentials(
+            CredentialsError::new("No credentials found"),
+        ));
+        assert_eq!(err.error_type(), "Rusoto.CredentialsError");
+
+        let parse_err = "abc".parse::<i32>().expect_err("Parsing should fail");
+        let err = HandlerError::from(RusotoError::Service(parse_err));
+        assert_eq!(err.error_type(), "ParseIntError");
+
+        assert!(super::is_throttling(429, ""));
+        assert!(super::is_throttling(400, "{\"__type\":\"ThrottlingException\"}"));
+        assert!(!super::is_throttling(400, "{\"__type\":\"ValidationException\"}"));
+    }
 }
@@ -0,0 +1,132 @@
+//! Guards against crash loops caused by poison events. Before the handler runs, the
+//! request id of the invocation is written to a file in `/tmp`, which survives restarts
+//! of the runtime process within the same execution environment. The file is removed
+//! once the handler returns. If the process crashes, for example because the handler
+//! aborts or runs out of memory, the file is still there when the runtime restarts and
+//! the invocation is redelivered, so the guard can count the crashes caused by the event.
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The environment variable that sets the number of crashes an event may cause
+/// before it is reported as a poison event. `0` disables the guard.
+pub(crate) const CRASH_LIMIT_VAR: &str = "LAMBDA_RUNTIME_CRASH_LIMIT";
+
+const DEFAULT_CRASH_LIMIT: u32 = 2;
+const DEFAULT_INFLIGHT_FILE: &str = "/tmp/.lambda-runtime-inflight";
+
+/// Records the in-flight invocation and detects events that repeatedly crash the runtime.
+pub(crate) struct CrashGuard {
+    path: PathBuf,
+    limit: u32,
+}
+
+impl CrashGuard {
+    /// Creates a guard that records the in-flight invocation in `path` and reports
+    /// events once they crashed the runtime `limit` times.
+    pub(crate) fn new(path: &Path, limit: u32) -> CrashGuard {
+        CrashGuard {
+            path: path.to_path_buf(),
+            limit,
+        }
+    }
+
+    /// Creates a guard with the crash limit from the `LAMBDA_RUNTIME_CRASH_LIMIT`
+    /// environment variable, or the default limit of 2 crashes.
+    pub(crate) fn from_env() -> CrashGuard {
+        let limit = match env::var(CRASH_LIMIT_VAR) {
+            Ok(value) => value.parse().unwrap_or_else(|_| {
+                warn!(
+                    "Invalid {} value {:?}, using {}",
+                    CRASH_LIMIT_VAR, value, DEFAULT_CRASH_LIMIT
+                );
+                DEFAULT_CRASH_LIMIT
+            }),
+            Err(_) => DEFAULT_CRASH_LIMIT,
+        };
+        CrashGuard::new(Path::new(DEFAULT_INFLIGHT_FILE), limit)
+    }
+
+    /// Records that the handler is about to run for `request_id`.
+    ///
+    /// # Return
+    /// The number of times the event already crashed the runtime if it reached the
+    /// limit, in which case the event should not be passed to the handler again.
+    pub(crate) fn begin(&self, request_id: &str) -> Option<u32> {
+        if self.limit == 0 {
+            return None;
+        }
+        let crashes = match fs::read_to_string(&self.path) {
+            Ok(contents) => match contents.split_once('\n') {
+                Some((id, count)) if id == request_id => count.trim().parse::<u32>().unwrap_or(0),
+                _ => 0,
+            },
+            Err(_) => 0,
+        };
+        if crashes > 0 {
+            warn!("Event {} crashed the runtime {} time(s)", request_id, crashes);
+        }
+        if crashes >= self.limit {
+            self.end();
+            return Some(crashes);
+        }
+        if let Err(e) = self.write(request_id, crashes + 1) {
+            warn!("Could not record in-flight invocation {}: {}", request_id, e);
+        }
+        None
+    }
+
+    /// Records that the handler returned, so the in-flight invocation did not crash
+    /// the runtime.
+    pub(crate) fn end(&self) {
+        if self.limit == 0 {
+            return;
+        }
+        if let Err(e) = fs::remove_file(&self.path) {
+            if e.kind() != io::ErrorKind::NotFound {
+                warn!("Could not clear in-flight invocation: {}", e);
+            }
+        }
+    }
+
+    fn write(&self, request_id: &str, attempts: u32) -> io::Result<()> {
+        fs::write(&self.path, format!("{}\n{}", request_id, attempts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(name: &str, limit: u32) -> CrashGuard {
+        let path = env::temp_dir().join(format!("lambda-runtime-guard-{}-{}", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        CrashGuard::new(&path, limit)
+    }
+
+    #[test]
+    fn poison_event_is_detected() {
+        let guard = guard("poison", 2);
+        // every begin() without an end() simulates a crash of the runtime
+        assert_eq!(guard.begin("req-1"), None);
+        assert_eq!(guard.begin("req-1"), None);
+        assert_eq!(guard.begin("req-1"), Some(2));
+        // the record is cleared once the event is reported
+        assert_eq!(guard.begin("req-1"), None);
+        guard.end();
+    }
+
+    #[test]
+    fn completed_invocations_are_cleared() {
+        let guard = guard("cleared", 1);
+        assert_eq!(guard.begin("req-1"), None);
+        guard.end();
+        assert_eq!(guard.begin("req-1"), None);
+        assert_eq!(guard.begin("req-2"), None);
+        guard.end();
+
+        let disabled = super::CrashGuard::new(Path::new("/nonexistent/inflight"), 0);
+        assert_eq!(disabled.begin("req-1"), None);
+    }
+}
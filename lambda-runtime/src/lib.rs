@@ -81,6 +81,10 @@ extern crate log;
 mod context;
 mod env;
 pub mod error;
+mod guard;
+mod panic;
 mod runtime;
+mod watchdog;
 
-pub use crate::{context::*, error::HandlerError, runtime::*};
+pub use crate::{context::*, error::HandlerError, panic::PanicPolicy, runtime::*};
+pub use lambda_runtime_client::TraceContext;
@@ -0,0 +1,150 @@
+//! Captures the details of handler panics so that they can be reported to the
+//! Runtime APIs. The hook installed by `install_hook()` records the message,
+//! location, and backtrace of a panic in a thread-local, which the runtime reads
+//! back after catching the unwind.
+use std::{any::Any, cell::RefCell, env, fmt, panic, str::FromStr, sync::Once};
+
+use crate::error::collect_backtrace;
+
+/// The environment variable that sets the `PanicPolicy` of the runtime, either
+/// `continue` or `exit`.
+pub(crate) const PANIC_POLICY_VAR: &str = "LAMBDA_RUNTIME_PANIC_POLICY";
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// What the runtime does after reporting a handler panic to the Runtime APIs. Set the
+/// policy with `RuntimeBuilder::panic_policy()` or the `LAMBDA_RUNTIME_PANIC_POLICY`
+/// environment variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicPolicy {
+    /// Keep polling for events in the same process. This is the default.
+    #[default]
+    Continue,
+    /// Exit the process, so that Lambda starts a fresh execution environment for the
+    /// next event. Use this when a panic may leave global state, such as caches or
+    /// connection pools, corrupted.
+    Exit,
+}
+
+impl PanicPolicy {
+    /// Returns the policy set with the `LAMBDA_RUNTIME_PANIC_POLICY` environment
+    /// variable, or `None` if the variable is not set or invalid.
+    pub(crate) fn from_env() -> Option<PanicPolicy> {
+        let value = env::var(PANIC_POLICY_VAR).ok()?;
+        match value.parse() {
+            Ok(policy) => Some(policy),
+            Err(e) => {
+                warn!("{}", e);
+                None
+            }
+        }
+    }
+}
+
+impl FromStr for PanicPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "continue" => Ok(PanicPolicy::Continue),
+            "exit" => Ok(PanicPolicy::Exit),
+            _ => Err(format!(
+                "Invalid panic policy {:?}, expected \"continue\" or \"exit\"",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for PanicPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PanicPolicy::Continue => f.write_str("continue"),
+            PanicPolicy::Exit => f.write_str("exit"),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+}
+
+/// The details of a panic recorded by the runtime's panic hook.
+#[derive(Debug)]
+pub(crate) struct PanicReport {
+    /// The panic message, or a placeholder if the payload is not a string.
+    pub(crate) message: String,
+    /// The `file:line:column` the panic originated from, if known.
+    pub(crate) location: Option<String>,
+    /// The backtrace of the panicking thread, collected when `RUST_BACKTRACE` is set.
+    pub(crate) backtrace: Option<backtrace::Backtrace>,
+}
+
+/// Installs the runtime's panic hook. The hook records each panic for the current
+/// thread and then calls the hook that was previously installed, so panics are still
+/// printed to stderr. Installing the hook more than once has no effect.
+pub(crate) fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let report = PanicReport {
+                message: payload_message(info.payload()),
+                location: info.location().map(|l| l.to_string()),
+                backtrace: collect_backtrace(),
+            };
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(report));
+            previous(info);
+        }));
+    });
+}
+
+/// Returns the report of the panic that produced `payload`. When the panic hook is not
+/// installed only the message can be recovered from the payload.
+///
+/// # Arguments
+///
+/// * `payload` The payload returned by `catch_unwind()`.
+pub(crate) fn take_report(payload: Box<dyn Any + Send>) -> PanicReport {
+    LAST_PANIC
+        .with(|last| last.borrow_mut().take())
+        .unwrap_or_else(|| PanicReport {
+            message: payload_message(payload.as_ref()),
+            location: None,
+            backtrace: None,
+        })
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        String::from(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Box<dyn Any>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_policy_is_parsed() {
+        assert_eq!("continue".parse(), Ok(PanicPolicy::Continue));
+        assert_eq!(" Exit ".parse(), Ok(PanicPolicy::Exit));
+        assert!("abort".parse::<PanicPolicy>().is_err());
+        assert_eq!(PanicPolicy::Exit.to_string(), "exit");
+    }
+
+    #[test]
+    fn panic_is_recorded() {
+        install_hook();
+        let payload = panic::catch_unwind(|| panic!("handler failed with {}", 42)).expect_err("Closure should panic");
+        let report = take_report(payload);
+        assert_eq!(report.message, "handler failed with 42");
+        assert!(report
+            .location
+            .expect("Missing panic location")
+            .starts_with("lambda-runtime/src/panic.rs"));
+    }
+}
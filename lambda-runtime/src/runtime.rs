@@ -1,14 +1,21 @@
-use std::{error::Error, marker::PhantomData, result};
+use std::{
+    marker::PhantomData,
+    panic::{catch_unwind, AssertUnwindSafe},
+    process, result, thread,
+    time::{Duration, Instant},
+};
 
-use lambda_runtime_client::RuntimeClient;
-use serde;
-use serde_json;
-use tokio::runtime::Runtime as TokioRuntime;
+use lambda_runtime_client::{error::ApiError, RuntimeClient};
+use serde_derive::Serialize;
+use tokio::runtime::{Builder as TokioBuilder, Runtime as TokioRuntime};
 
 use crate::{
     context::Context,
-    env::{ConfigProvider, EnvConfigProvider, FunctionSettings},
-    error::{HandlerError, RuntimeError},
+    env::{self, ConfigProvider, EnvConfigProvider, FunctionSettings},
+    error::{HandlerError, RuntimeError, ERROR_TYPE_DESERIALIZATION, ERROR_TYPE_PANIC},
+    guard::CrashGuard,
+    panic::{self, PanicPolicy},
+    watchdog::Watchdog,
 };
 
 const MAX_RETRIES: i8 = 3;
@@ -28,11 +35,82 @@ where
     }
 }
 
+/// Post-processes the JSON responses of the handler before they are posted to the Runtime
+/// APIs, for example to stamp a schema version, echo the request id, or add timing
+/// metadata to every response of a service. Set the hook with
+/// `RuntimeBuilder::response_hook()`. Error responses are not passed to the hook.
+pub trait ResponseHook {
+    /// Modifies the serialized response.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` The response returned by the handler, serialized to JSON.
+    /// * `ctx` The context of the invocation.
+    /// * `elapsed` The time the handler took to produce the response.
+    fn process(&self, response: &mut serde_json::Value, ctx: &Context, elapsed: Duration);
+}
+
+impl<F> ResponseHook for F
+where
+    F: Fn(&mut serde_json::Value, &Context, Duration),
+{
+    fn process(&self, response: &mut serde_json::Value, ctx: &Context, elapsed: Duration) {
+        (*self)(response, ctx, elapsed)
+    }
+}
+
+/// Handles the events that cannot be deserialized into the event type of the handler.
+/// The fallback handler receives the raw payload and the deserialization error, so that
+/// functions can log or dead-letter unexpected payloads instead of failing the
+/// invocation. Its response is posted to the Runtime APIs like the response of the
+/// handler. Set the fallback handler with `RuntimeBuilder::fallback_handler()`.
+pub trait FallbackHandler {
+    /// Run the fallback handler.
+    fn run(
+        &mut self,
+        payload: &[u8],
+        error: &serde_json::Error,
+        ctx: Context,
+    ) -> Result<serde_json::Value, HandlerError>;
+}
+
+impl<F> FallbackHandler for F
+where
+    F: FnMut(&[u8], &serde_json::Error, Context) -> Result<serde_json::Value, HandlerError>,
+{
+    fn run(
+        &mut self,
+        payload: &[u8],
+        error: &serde_json::Error,
+        ctx: Context,
+    ) -> Result<serde_json::Value, HandlerError> {
+        (*self)(payload, error, ctx)
+    }
+}
+
+/// An event received from the Runtime APIs.
+pub(super) enum Event<E> {
+    /// An event deserialized into the event type of the handler.
+    Typed(E),
+    /// The payload of an event that could not be deserialized, for the fallback handler.
+    Raw(Vec<u8>, serde_json::Error),
+}
+
+/// The response of the handler or of the fallback handler.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(super) enum Response<O> {
+    Typed(O),
+    Raw(serde_json::Value),
+}
+
 /// Creates a new runtime and begins polling for events using Lambda's Runtime APIs.
 ///
 /// # Arguments
 ///
 /// * `f` A function pointer that conforms to the `Handler` type.
+/// * `runtime` An optional tokio `Runtime` used to drive the Runtime API calls. When `None`,
+///   a single threaded runtime is created.
 ///
 /// # Panics
 /// The function panics if the Lambda environment variables are not set.
@@ -41,9 +119,160 @@ where
     E: serde::de::DeserializeOwned,
     O: serde::Serialize,
 {
-    start_with_config(f, &EnvConfigProvider::new(), runtime)
+    let builder = RuntimeBuilder::new();
+    match runtime {
+        Some(runtime) => builder.tokio_runtime(runtime).start(f),
+        None => builder.start(f),
+    }
+}
+
+/// Configures the runtime before it starts polling for events. The `start()` function
+/// and the `lambda!` macro start the runtime with the default settings.
+///
+/// ```rust,no_run
+/// use lambda_runtime::{error::HandlerError, Context, PanicPolicy, RuntimeBuilder};
+///
+/// fn my_handler(e: String, _ctx: Context) -> Result<String, HandlerError> {
+///     Ok(e)
+/// }
+///
+/// fn main() {
+///     RuntimeBuilder::new().panic_policy(PanicPolicy::Exit).start(my_handler);
+/// }
+/// ```
+#[derive(Default)]
+pub struct RuntimeBuilder {
+    tokio_runtime: Option<TokioRuntime>,
+    panic_policy: Option<PanicPolicy>,
+    response_hook: Option<Box<dyn ResponseHook>>,
+    watchdog_headroom: Option<Duration>,
+    fallback_handler: Option<Box<dyn FallbackHandler>>,
+}
+
+impl RuntimeBuilder {
+    /// Creates a builder with the default settings.
+    pub fn new() -> RuntimeBuilder {
+        RuntimeBuilder::default()
+    }
+
+    /// Sets the tokio `Runtime` used to drive the Runtime API calls. By default a single
+    /// threaded runtime is created.
+    pub fn tokio_runtime(mut self, runtime: TokioRuntime) -> Self {
+        self.tokio_runtime = Some(runtime);
+        self
+    }
+
+    /// Sets what the runtime does after reporting a handler panic. This overrides the
+    /// `LAMBDA_RUNTIME_PANIC_POLICY` environment variable, and defaults to
+    /// `PanicPolicy::Continue` when neither is set.
+    pub fn panic_policy(mut self, policy: PanicPolicy) -> Self {
+        self.panic_policy = Some(policy);
+        self
+    }
+
+    /// Sets a hook that modifies every successful response of the handler before it
+    /// is posted to the Runtime APIs.
+    ///
+    /// ```rust,no_run
+    /// use lambda_runtime::{error::HandlerError, Context, RuntimeBuilder};
+    /// use serde_json::{json, Value};
+    /// use std::time::Duration;
+    ///
+    /// fn my_handler(e: Value, _ctx: Context) -> Result<Value, HandlerError> {
+    ///     Ok(e)
+    /// }
+    ///
+    /// fn main() {
+    ///     RuntimeBuilder::new()
+    ///         .response_hook(|response: &mut Value, ctx: &Context, elapsed: Duration| {
+    ///             response["meta"] = json!({
+    ///                 "schemaVersion": 2,
+    ///                 "requestId": ctx.aws_request_id,
+    ///                 "elapsedMs": elapsed.as_millis() as u64,
+    ///             });
+    ///         })
+    ///         .start(my_handler);
+    /// }
+    /// ```
+    pub fn response_hook(mut self, hook: impl ResponseHook + 'static) -> Self {
+        self.response_hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Starts a watchdog thread that logs an error with the request id and timings of
+    /// the invocation when the handler is still running `headroom` before the deadline,
+    /// so that invocations that time out leave a trace in the logs. The handler is not
+    /// interrupted.
+    pub fn watchdog(mut self, headroom: Duration) -> Self {
+        self.watchdog_headroom = Some(headroom);
+        self
+    }
+
+    /// Sets a handler for the events that cannot be deserialized into the event type of
+    /// the handler. Without a fallback handler, such events are reported to the Runtime
+    /// APIs as `Runtime.DeserializationError` errors.
+    ///
+    /// ```rust,no_run
+    /// use lambda_runtime::{error::HandlerError, Context, RuntimeBuilder};
+    /// use serde_json::{json, Value};
+    ///
+    /// fn my_handler(e: u32, _ctx: Context) -> Result<u32, HandlerError> {
+    ///     Ok(e * 2)
+    /// }
+    ///
+    /// fn main() {
+    ///     RuntimeBuilder::new()
+    ///         .fallback_handler(|payload: &[u8], err: &serde_json::Error, _ctx: Context| {
+    ///             eprintln!("Skipping unexpected payload ({}): {}", err, String::from_utf8_lossy(payload));
+    ///             Ok::<Value, HandlerError>(json!({ "skipped": true }))
+    ///         })
+    ///         .start(my_handler);
+    /// }
+    /// ```
+    pub fn fallback_handler(mut self, handler: impl FallbackHandler + 'static) -> Self {
+        self.fallback_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Creates the runtime and begins polling for events using Lambda's Runtime APIs.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` A function pointer that conforms to the `Handler` type.
+    ///
+    /// # Panics
+    /// The function panics if the Lambda environment variables are not set.
+    pub fn start<E, O>(self, f: impl Handler<E, O>)
+    where
+        E: serde::de::DeserializeOwned,
+        O: serde::Serialize,
+    {
+        start_with_config(f, &EnvConfigProvider::new(), self)
+    }
+
+    /// Returns the tokio runtime and the settings of the runtime, resolving the ones
+    /// that were not set from the environment.
+    fn into_parts(self) -> (Option<TokioRuntime>, RuntimeOptions) {
+        let options = RuntimeOptions {
+            panic_policy: self.panic_policy.or_else(PanicPolicy::from_env).unwrap_or_default(),
+            response_hook: self.response_hook,
+            watchdog_headroom: self.watchdog_headroom,
+            fallback_handler: self.fallback_handler,
+        };
+        (self.tokio_runtime, options)
+    }
+}
+
+/// The settings of a `Runtime`, resolved from the `RuntimeBuilder` and the environment.
+#[derive(Default)]
+pub(crate) struct RuntimeOptions {
+    panic_policy: PanicPolicy,
+    response_hook: Option<Box<dyn ResponseHook>>,
+    watchdog_headroom: Option<Duration>,
+    fallback_handler: Option<Box<dyn FallbackHandler>>,
 }
 
+/// A macro for starting new handler's poll for Lambda events
 #[macro_export]
 macro_rules! lambda {
     ($handler:ident) => {
@@ -68,40 +297,51 @@ macro_rules! lambda {
 ///
 /// * `f` A function pointer that conforms to the `Handler` type.
 /// * `config` An implementation of the `ConfigProvider` trait with static lifetime.
+/// * `builder` The settings of the runtime.
 ///
 /// # Panics
 /// The function panics if the `ConfigProvider` returns an error from the `get_runtime_api_endpoint()`
 /// or `get_function_settings()` methods. The panic forces AWS Lambda to terminate the environment
 /// and spin up a new one for the next invocation.
-pub(crate) fn start_with_config<E, O, C>(f: impl Handler<E, O>, config: &C, runtime: Option<TokioRuntime>)
+pub(crate) fn start_with_config<E, O, C>(f: impl Handler<E, O>, config: &C, builder: RuntimeBuilder)
 where
     E: serde::de::DeserializeOwned,
     O: serde::Serialize,
     C: ConfigProvider,
 {
     // if we cannot find the endpoint we panic, nothing else we can do.
-    let endpoint: String;
-    match config.get_runtime_api_endpoint() {
-        Ok(value) => endpoint = value,
+    let endpoint: String = match config.get_runtime_api_endpoint() {
+        Ok(value) => value,
         Err(e) => {
             panic!("Could not find runtime API env var: {}", e);
         }
-    }
+    };
 
     // if we can't get the settings from the environment variable
     // we also panic.
-    let function_config: FunctionSettings;
     let settings = config.get_function_settings();
-    match settings {
-        Ok(env_settings) => function_config = env_settings,
+    let function_config: FunctionSettings = match settings {
+        Ok(env_settings) => env_settings,
         Err(e) => {
             panic!("Could not find runtime API env var: {}", e);
         }
-    }
+    };
+
+    let (runtime, options) = builder.into_parts();
+    let runtime = match runtime {
+        Some(r) => r,
+        None => match TokioBuilder::new_current_thread().enable_all().build() {
+            Ok(r) => r,
+            Err(e) => {
+                panic!("Could not create tokio runtime: {}", e);
+            }
+        },
+    };
 
-    match RuntimeClient::new(endpoint, runtime) {
+    match RuntimeClient::new(endpoint) {
         Ok(client) => {
-            start_with_runtime_client(f, function_config, client);
+            let client = client.with_prewarmed_connection(true);
+            start_with_runtime_client(f, function_config, client, runtime, options);
         }
         Err(e) => {
             panic!("Could not create runtime client SDK: {}", e);
@@ -115,8 +355,10 @@ where
 ///
 /// * `f` A function pointer that conforms to the `Handler` type.
 /// * `client` An implementation of the `lambda_runtime_client::RuntimeClient`
-///            trait with a lifetime that matches that of the environment,
-///            in this case expressed as `'env`.
+///   trait with a lifetime that matches that of the environment,
+///   in this case expressed as `'env`.
+/// * `runtime` The tokio `Runtime` that drives the client's calls.
+/// * `options` The settings of the runtime.
 ///
 /// # Panics
 /// The function panics if we cannot instantiate a new `RustRuntime` object.
@@ -124,13 +366,15 @@ pub(crate) fn start_with_runtime_client<E, O>(
     f: impl Handler<E, O>,
     func_settings: FunctionSettings,
     client: RuntimeClient,
+    runtime: TokioRuntime,
+    options: RuntimeOptions,
 ) where
     E: serde::de::DeserializeOwned,
     O: serde::Serialize,
 {
     let mut lambda_runtime: Runtime<_, E, O>;
-    match Runtime::new(f, func_settings, MAX_RETRIES, client) {
-        Ok(r) => lambda_runtime = r,
+    match Runtime::new(f, func_settings, MAX_RETRIES, client, runtime) {
+        Ok(r) => lambda_runtime = r.with_options(options),
         Err(e) => {
             panic!("Error while starting runtime: {}", e);
         }
@@ -144,9 +388,12 @@ pub(crate) fn start_with_runtime_client<E, O>(
 /// with the Runtime APIs
 pub(super) struct Runtime<F, E, O> {
     runtime_client: RuntimeClient,
+    tokio_runtime: TokioRuntime,
     handler: F,
     max_retries: i8,
     settings: FunctionSettings,
+    crash_guard: CrashGuard,
+    options: RuntimeOptions,
     _phan: PhantomData<(E, O)>,
 }
 
@@ -159,7 +406,8 @@ impl<F, E, O> Runtime<F, E, O> {
     ///
     /// * `f` A function pointer that conforms to the `Handler` type.
     /// * `retries` The maximum number of times we should retry calling the Runtime APIs
-    ///             for recoverable errors while polling for new events.
+    ///   for recoverable errors while polling for new events.
+    /// * `tokio_runtime` The tokio `Runtime` used to drive the calls to the Runtime APIs.
     ///
     /// # Return
     /// A `Result` for the `Runtime` object or a `errors::RuntimeSerror`. The runtime
@@ -170,20 +418,34 @@ impl<F, E, O> Runtime<F, E, O> {
         config: FunctionSettings,
         retries: i8,
         client: RuntimeClient,
+        tokio_runtime: TokioRuntime,
     ) -> result::Result<Self, RuntimeError> {
         debug!(
             "Creating new runtime with {} max retries for endpoint {}",
             retries,
             client.get_endpoint()
         );
+        // open the connection to the Runtime APIs during init so the first poll doesn't pay for it
+        if let Err(e) = tokio_runtime.block_on(client.prewarm()) {
+            warn!("Could not pre-warm connection to Runtime API: {}", e);
+        }
         Ok(Runtime {
             runtime_client: client,
+            tokio_runtime,
             settings: config,
             handler: f,
             max_retries: retries,
+            crash_guard: CrashGuard::from_env(),
+            options: RuntimeOptions::default(),
             _phan: PhantomData,
         })
     }
+
+    /// Replaces the default settings of the runtime.
+    pub(super) fn with_options(mut self, options: RuntimeOptions) -> Self {
+        self.options = options;
+        self
+    }
 }
 
 // implementation of methods that require the Event and Output types
@@ -198,32 +460,68 @@ where
     /// Runtime APIs returns an unrecoverable error this method calls the init failed
     /// API and then panics.
     fn start(&mut self) {
+        // record the message, location, and backtrace of handler panics so they can be reported
+        panic::install_hook();
+        let watchdog = self.options.watchdog_headroom.map(Watchdog::start);
         debug!("Beginning main event loop");
         loop {
             let (event, ctx) = self.get_next_event(0, None);
             let request_id = ctx.aws_request_id.clone();
             info!("Received new event with AWS request id: {}", request_id);
-            let function_outcome = self.invoke(event, ctx);
+            if let Some(crashes) = self.crash_guard.begin(&request_id) {
+                error!("Not invoking the handler for poison event {}", request_id);
+                let err = RuntimeError::poison_event(&request_id, crashes);
+                if let Err(e) = self
+                    .tokio_runtime
+                    .block_on(self.runtime_client.event_error(&request_id, &err))
+                {
+                    error!(
+                        "Unable to send poison event error for {} to Runtime API: {}",
+                        request_id, e
+                    );
+                }
+                continue;
+            }
+            // the hook needs the context after the handler consumed it
+            let hook_ctx = self.options.response_hook.as_ref().map(|_| ctx.clone());
+            if let Some(watchdog) = &watchdog {
+                watchdog.arm(&request_id, ctx.remaining_time());
+            }
+            let started = Instant::now();
+            let function_outcome = match event {
+                Event::Typed(event) => self.invoke(event, ctx).map(Response::Typed),
+                Event::Raw(payload, e) => self.invoke_fallback(&payload, &e, ctx).map(Response::Raw),
+            };
+            let elapsed = started.elapsed();
+            if let Some(watchdog) = &watchdog {
+                watchdog.disarm();
+            }
+            self.crash_guard.end();
+            let panicked = matches!(&function_outcome, Err(e) if e.error_type() == ERROR_TYPE_PANIC);
             match function_outcome {
                 Ok(response) => {
                     debug!(
                         "Function executed succesfully for {}, pushing response to Runtime API",
                         request_id
                     );
-                    match serde_json::to_vec(&response) {
+                    match self.serialize_response(&response, hook_ctx.as_ref(), elapsed) {
                         Ok(response_bytes) => {
-                            match self.runtime_client.event_response(&request_id, response_bytes) {
+                            match self
+                                .tokio_runtime
+                                .block_on(self.runtime_client.event_response(&request_id, response_bytes))
+                            {
                                 Ok(_) => info!("Response for {} accepted by Runtime API", request_id),
                                 // unrecoverable error while trying to communicate with the endpoint.
                                 // we let the Lambda Runtime API know that we have died
                                 Err(e) => {
                                     error!("Could not send response for {} to Runtime API: {}", request_id, e);
-                                    if !e.recoverable {
+                                    if !e.is_recoverable() {
                                         error!(
                                             "Error for {} is not recoverable, sending fail_init signal and panicking.",
                                             request_id
                                         );
-                                        self.runtime_client.fail_init(&e);
+                                        self.tokio_runtime
+                                            .block_on(self.runtime_client.fail_init(&RuntimeError::from(e)));
                                         panic!("Could not send response");
                                     }
                                 }
@@ -234,8 +532,10 @@ where
                                 "Could not marshal output object to Vec<u8> JSON represnetation for request {}: {}",
                                 request_id, e
                             );
-                            self.runtime_client
-                                .fail_init(&RuntimeError::unrecoverable(e.description()));
+                            self.tokio_runtime.block_on(
+                                self.runtime_client
+                                    .fail_init(&RuntimeError::unrecoverable(&e.to_string())),
+                            );
                             panic!("Failed to marshal handler output, panic");
                         }
                     }
@@ -243,48 +543,101 @@ where
                 Err(e) => {
                     debug!("Handler returned an error for {}: {}", request_id, e);
                     debug!("Attempting to send error response to Runtime API for {}", request_id);
-                    match self.runtime_client.event_error(&request_id, &e) {
+                    match self
+                        .tokio_runtime
+                        .block_on(self.runtime_client.event_error(&request_id, &e))
+                    {
                         Ok(_) => info!("Error response for {} accepted by Runtime API", request_id),
                         Err(e) => {
                             error!("Unable to send error response for {} to Runtime API: {}", request_id, e);
-                            if !e.recoverable {
+                            if !e.is_recoverable() {
                                 error!(
                                     "Error for {} is not recoverable, sending fail_init signal and panicking",
                                     request_id
                                 );
-                                self.runtime_client.fail_init(&e);
+                                self.tokio_runtime
+                                    .block_on(self.runtime_client.fail_init(&RuntimeError::from(e)));
                                 panic!("Could not send error response");
                             }
                         }
                     }
                 }
             }
+            if panicked && self.options.panic_policy == PanicPolicy::Exit {
+                error!(
+                    "Handler panicked for {}, exiting to get a fresh execution environment",
+                    request_id
+                );
+                process::exit(1);
+            }
+        }
+    }
+
+    /// Serializes the response of the handler, passing it through the response hook
+    /// when one is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` The response returned by the handler.
+    /// * `ctx` The context of the invocation, cloned before the handler ran if a hook is set.
+    /// * `elapsed` The time the handler took to produce the response.
+    pub(super) fn serialize_response(
+        &self,
+        response: &Response<O>,
+        ctx: Option<&Context>,
+        elapsed: Duration,
+    ) -> serde_json::Result<Vec<u8>> {
+        match (&self.options.response_hook, ctx) {
+            (Some(hook), Some(ctx)) => {
+                let mut value = serde_json::to_value(response)?;
+                hook.process(&mut value, ctx, elapsed);
+                serde_json::to_vec(&value)
+            }
+            _ => serde_json::to_vec(response),
         }
     }
 
     /// Invoke the handler function. This method is split out of the main loop to
-    /// make it testable.
+    /// make it testable. If the handler panics, the panic is caught and returned as
+    /// a `HandlerError` so that it can be reported to the Runtime APIs.
     pub(super) fn invoke(&mut self, e: E, ctx: Context) -> Result<O, HandlerError> {
-        (&mut self.handler).run(e, ctx)
+        let handler = &mut self.handler;
+        catch_panics(|| handler.run(e, ctx))
+    }
+
+    /// Invoke the fallback handler with an event that could not be deserialized. Panics
+    /// are caught like the panics of the handler.
+    pub(super) fn invoke_fallback(
+        &mut self,
+        payload: &[u8],
+        e: &serde_json::Error,
+        ctx: Context,
+    ) -> Result<serde_json::Value, HandlerError> {
+        match self.options.fallback_handler.as_mut() {
+            Some(fallback) => catch_panics(|| fallback.run(payload, e, ctx)),
+            // raw events are only returned by `get_next_event()` when a fallback is set
+            None => Err(HandlerError::new(&e.to_string()).with_error_type(ERROR_TYPE_DESERIALIZATION)),
+        }
     }
 
     /// Attempts to get the next event from the Runtime APIs and keeps retrying
     /// unless the error throws is not recoverable.
     ///
     /// # Return
-    /// The next `Event` object to be processed.
-    pub(super) fn get_next_event(&self, retries: i8, e: Option<RuntimeError>) -> (E, Context) {
+    /// The next `Event` object to be processed, in its raw form if it cannot be
+    /// deserialized and a fallback handler is set.
+    pub(super) fn get_next_event(&self, retries: i8, e: Option<RuntimeError>) -> (Event<E>, Context) {
         if let Some(err) = e {
             if retries > self.max_retries {
                 error!("Unrecoverable error while fetching next event: {}", err);
                 match err.request_id.clone() {
                     Some(req_id) => {
-                        self.runtime_client
-                            .event_error(&req_id, &err)
+                        self.tokio_runtime
+                            .block_on(self.runtime_client.event_error(&req_id, &err))
                             .expect("Could not send event error response");
                     }
                     None => {
-                        self.runtime_client.fail_init(&err);
+                        self.tokio_runtime.block_on(self.runtime_client.fail_init(&err));
                     }
                 }
 
@@ -294,30 +647,59 @@ where
             }
         }
 
-        match self.runtime_client.next_event() {
+        match self.tokio_runtime.block_on(self.runtime_client.next_event()) {
             Ok((ev_data, invocation_ctx)) => {
-                let parse_result = serde_json::from_slice(&ev_data);
-                match parse_result {
-                    Ok(ev) => {
-                        let mut handler_ctx = Context::new(self.settings.clone());
-                        handler_ctx.invoked_function_arn = invocation_ctx.invoked_function_arn;
-                        handler_ctx.aws_request_id = invocation_ctx.aws_request_id;
-                        handler_ctx.xray_trace_id = invocation_ctx.xray_trace_id;
-                        handler_ctx.client_context = invocation_ctx.client_context;
-                        handler_ctx.identity = invocation_ctx.identity;
-                        handler_ctx.deadline = invocation_ctx.deadline;
-
-                        (ev, handler_ctx)
+                env::set_trace_id(&invocation_ctx.xray_trace_id);
+                let mut handler_ctx = Context::new(self.settings.clone());
+                handler_ctx.invoked_function_arn = invocation_ctx.invoked_function_arn;
+                handler_ctx.aws_request_id = invocation_ctx.aws_request_id;
+                handler_ctx.xray_trace_id = invocation_ctx.xray_trace_id;
+                handler_ctx.client_context = invocation_ctx.client_context;
+                handler_ctx.identity = invocation_ctx.identity;
+                handler_ctx.raw_client_context = invocation_ctx.raw_client_context;
+                handler_ctx.raw_identity = invocation_ctx.raw_identity;
+                handler_ctx.tenant_id = invocation_ctx.tenant_id;
+                handler_ctx.deadline = invocation_ctx.deadline;
+                handler_ctx.headers = invocation_ctx.headers;
+                handler_ctx.extensions = invocation_ctx.extensions;
+
+                match serde_json::from_slice(&ev_data) {
+                    Ok(ev) => (Event::Typed(ev), handler_ctx),
+                    Err(e) if self.options.fallback_handler.is_some() => {
+                        warn!("Could not parse event to type, invoking fallback handler: {}", e);
+                        (Event::Raw(ev_data, e), handler_ctx)
                     }
                     Err(e) => {
                         error!("Could not parse event to type: {}", e);
                         let mut runtime_err = RuntimeError::from(e);
-                        runtime_err.request_id = Option::from(invocation_ctx.aws_request_id);
+                        runtime_err.request_id = Option::from(handler_ctx.aws_request_id);
                         self.get_next_event(retries + 1, Option::from(runtime_err))
                     }
                 }
             }
-            Err(e) => self.get_next_event(retries + 1, Option::from(RuntimeError::from(e))),
+            Err(e) => {
+                if let ApiError::ConnectionRefused(_) = e {
+                    // the Runtime API may not be up yet, e.g. when testing against a local
+                    // emulator: back off instead of burning through the retries
+                    let backoff = Duration::from_millis(100 * 2u64.pow(retries.max(0) as u32));
+                    warn!("Runtime API refused the connection, retrying in {:?}", backoff);
+                    thread::sleep(backoff);
+                }
+                self.get_next_event(retries + 1, Option::from(RuntimeError::from(e)))
+            }
+        }
+    }
+}
+
+/// Runs a handler, converting its panics into a `HandlerError` so that they can be
+/// reported to the Runtime APIs.
+fn catch_panics<T>(run: impl FnOnce() -> Result<T, HandlerError>) -> Result<T, HandlerError> {
+    match catch_unwind(AssertUnwindSafe(run)) {
+        Ok(outcome) => outcome,
+        Err(payload) => {
+            let report = panic::take_report(payload);
+            error!("Handler panicked: {}", report.message);
+            Err(HandlerError::from_panic(report))
         }
     }
 }
@@ -326,7 +708,7 @@ where
 pub(crate) mod tests {
     use super::*;
     use crate::{context, env};
-    use lambda_runtime_client::RuntimeClient;
+    use lambda_runtime_client::{error::RuntimeApiError, RuntimeClient};
 
     #[test]
     fn runtime_invokes_handler() {
@@ -335,9 +717,12 @@ pub(crate) mod tests {
             config
                 .get_runtime_api_endpoint()
                 .expect("Could not get runtime endpoint"),
-            None,
         )
         .expect("Could not initialize client");
+        let tokio_runtime = TokioBuilder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Could not create tokio runtime");
         let handler = |_e: String, _c: context::Context| -> Result<String, HandlerError> { Ok("hello".to_string()) };
         let retries: i8 = 3;
         let runtime = Runtime::new(
@@ -347,23 +732,98 @@ pub(crate) mod tests {
                 .expect("Could not load environment config"),
             retries,
             client,
+            tokio_runtime,
         );
-        assert_eq!(
-            runtime.is_err(),
-            false,
+        assert!(
+            runtime.is_ok(),
             "Runtime threw an unexpected error: {}",
             runtime.err().unwrap()
         );
         let output = runtime
             .unwrap()
             .invoke(String::from("test"), context::tests::test_context(10));
-        assert_eq!(
-            output.is_err(),
-            false,
+        assert!(
+            output.is_ok(),
             "Handler threw an unexpected error: {}",
             output.err().unwrap()
         );
         let output_string = output.unwrap();
         assert_eq!(output_string, "hello", "Unexpected output message: {}", output_string);
     }
+
+    /// Returns a runtime for the mock environment, with a handler and options.
+    fn test_runtime<F, E, O>(handler: F, options: RuntimeOptions) -> Runtime<F, E, O> {
+        let config = env::tests::MockConfigProvider { error: false };
+        let client =
+            RuntimeClient::new(config.get_runtime_api_endpoint().unwrap()).expect("Could not initialize client");
+        let tokio_runtime = TokioBuilder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Could not create tokio runtime");
+        Runtime::new(
+            handler,
+            config.get_function_settings().unwrap(),
+            3,
+            client,
+            tokio_runtime,
+        )
+        .expect("Could not create runtime")
+        .with_options(options)
+    }
+
+    #[test]
+    fn runtime_reports_handler_panics() {
+        let handler = |e: String, _c: context::Context| -> Result<String, HandlerError> { panic!("Unexpected {}", e) };
+        let mut runtime = test_runtime(handler, RuntimeOptions::default());
+
+        panic::install_hook();
+        let err = runtime
+            .invoke(String::from("event"), context::tests::test_context(10))
+            .expect_err("Handler panic should be returned as an error");
+        assert_eq!(err.to_string(), "Unexpected event");
+        let stack_trace = err.to_response().stack_trace.expect("Missing stack trace");
+        assert!(stack_trace[0].starts_with("panicked at lambda-runtime/src/runtime.rs"));
+    }
+
+    #[test]
+    fn response_hook_modifies_responses() {
+        let handler = |e: String, _c: context::Context| -> Result<String, HandlerError> { Ok(e) };
+        let (_, options) = RuntimeBuilder::new()
+            .response_hook(|response: &mut serde_json::Value, ctx: &Context, _elapsed: Duration| {
+                *response = serde_json::json!({ "body": response.take(), "requestId": ctx.aws_request_id });
+            })
+            .into_parts();
+        let runtime = test_runtime(handler, options);
+
+        let ctx = context::tests::test_context(10);
+        let bytes = runtime
+            .serialize_response(
+                &Response::Typed(String::from("hello")),
+                Some(&ctx),
+                Duration::from_millis(5),
+            )
+            .expect("Could not serialize response");
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+            serde_json::json!({ "body": "hello", "requestId": "123" })
+        );
+    }
+
+    #[test]
+    fn fallback_handler_receives_raw_events() {
+        let handler = |e: u32, _c: context::Context| -> Result<u32, HandlerError> { Ok(e) };
+        let (_, options) = RuntimeBuilder::new()
+            .fallback_handler(|payload: &[u8], _e: &serde_json::Error, _c: Context| {
+                Ok(serde_json::json!({ "skipped": String::from_utf8_lossy(payload) }))
+            })
+            .into_parts();
+        let mut runtime = test_runtime(handler, options);
+
+        let payload = b"\"not a number\"";
+        let e = serde_json::from_slice::<u32>(payload).expect_err("Parsing should fail");
+        let output = runtime
+            .invoke_fallback(payload, &e, context::tests::test_context(10))
+            .expect("Fallback handler should succeed");
+        assert_eq!(output, serde_json::json!({ "skipped": "\"not a number\"" }));
+    }
 }
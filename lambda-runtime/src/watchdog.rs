@@ -0,0 +1,169 @@
+//! Watchdog that reports handlers that are about to run into the invocation deadline.
+//! Lambda stops timed out invocations without giving the runtime a chance to log
+//! anything, so the watchdog thread logs the request id and timings of the invocation
+//! shortly before the deadline. The watchdog only observes the handler: the stack of
+//! the handler thread is not sampled, and the handler keeps running.
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Called with the request id, the time the handler has been running, and the time
+/// left before the deadline when the watchdog fires.
+type Reporter = Box<dyn Fn(&str, Duration, Duration) + Send>;
+
+/// Handle to the watchdog thread. The thread stops when the handle is dropped.
+pub(crate) struct Watchdog {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    headroom: Duration,
+    state: Mutex<State>,
+    wakeup: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    invocation: Option<Invocation>,
+    stopped: bool,
+}
+
+/// The invocation the watchdog is waiting on.
+struct Invocation {
+    request_id: String,
+    started: Instant,
+    alarm: Instant,
+    deadline: Instant,
+}
+
+impl Watchdog {
+    /// Starts a watchdog thread that logs an error when a handler is still running
+    /// `headroom` before its deadline.
+    pub(crate) fn start(headroom: Duration) -> Watchdog {
+        Watchdog::start_with(
+            headroom,
+            Box::new(|request_id, elapsed, remaining| {
+                error!(
+                    "Handler for {} is still running after {}ms with {}ms left before the deadline",
+                    request_id,
+                    elapsed.as_millis(),
+                    remaining.as_millis()
+                );
+            }),
+        )
+    }
+
+    fn start_with(headroom: Duration, report: Reporter) -> Watchdog {
+        let shared = Arc::new(Shared {
+            headroom,
+            state: Mutex::new(State::default()),
+            wakeup: Condvar::new(),
+        });
+        let watched = Arc::clone(&shared);
+        let spawned = thread::Builder::new()
+            .name(String::from("lambda-watchdog"))
+            .spawn(move || watch(&watched, &report));
+        if let Err(e) = spawned {
+            warn!("Could not start watchdog thread: {}", e);
+        }
+        Watchdog { shared }
+    }
+
+    /// Starts watching the invocation the handler is about to run. Invocations with
+    /// less time left than the headroom are reported right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` The request id of the invocation.
+    /// * `remaining` The time left before the deadline of the invocation.
+    pub(crate) fn arm(&self, request_id: &str, remaining: Duration) {
+        let now = Instant::now();
+        let deadline = now + remaining;
+        let mut state = self.shared.lock();
+        state.invocation = Some(Invocation {
+            request_id: String::from(request_id),
+            started: now,
+            alarm: deadline.checked_sub(self.shared.headroom).unwrap_or(now).max(now),
+            deadline,
+        });
+        self.shared.wakeup.notify_one();
+    }
+
+    /// Stops watching the current invocation, once the handler returned.
+    pub(crate) fn disarm(&self) {
+        self.shared.lock().invocation = None;
+        self.shared.wakeup.notify_one();
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.shared.lock().stopped = true;
+        self.shared.wakeup.notify_one();
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // the state stays consistent even if a reporter panicked while holding the lock
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The loop of the watchdog thread. Each invocation is reported at most once.
+fn watch(shared: &Shared, report: &Reporter) {
+    let mut state = shared.lock();
+    while !state.stopped {
+        let alarm = match &state.invocation {
+            Some(invocation) => invocation.alarm,
+            None => {
+                state = shared.wakeup.wait(state).unwrap_or_else(|e| e.into_inner());
+                continue;
+            }
+        };
+        let now = Instant::now();
+        if now < alarm {
+            state = shared
+                .wakeup
+                .wait_timeout(state, alarm - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+            continue;
+        }
+        if let Some(invocation) = state.invocation.take() {
+            report(
+                &invocation.request_id,
+                now - invocation.started,
+                invocation.deadline.saturating_duration_since(now),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn slow_handlers_are_reported() {
+        let (tx, rx) = mpsc::channel();
+        let watchdog = Watchdog::start_with(
+            Duration::from_millis(100),
+            Box::new(move |request_id, _elapsed, remaining| {
+                tx.send((String::from(request_id), remaining)).unwrap();
+            }),
+        );
+
+        watchdog.arm("fast", Duration::from_secs(10));
+        watchdog.disarm();
+        watchdog.arm("slow", Duration::from_millis(150));
+        let (request_id, remaining) = rx.recv_timeout(Duration::from_secs(5)).expect("Watchdog did not fire");
+        assert_eq!(request_id, "slow");
+        assert!(remaining <= Duration::from_millis(100));
+        watchdog.disarm();
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+}